@@ -0,0 +1,42 @@
+// Backtest the Technical Trading Score for one symbol against buy-and-hold SPY
+// Usage: cargo run -p buenotea-backtest --example tts_backtest -- AAPL [config.toml]
+
+use buenotea_backtest::{BacktestConfig, Backtester, TTSStrategy};
+use buenotea_core::market_data::{MarketDataProvider, ProviderChain};
+use buenotea_core::Result;
+use buenotea_timing::TTSConfig;
+
+/// Roughly four years of sessions
+const HISTORY_BARS: usize = 1000;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv::dotenv().ok();
+    tracing_subscriber::fmt::init();
+
+    let mut args = std::env::args().skip(1);
+    let symbol = args.next().unwrap_or_else(|| "AAPL".to_string());
+    let config = match args.next() {
+        Some(path) => TTSConfig::from_file(path)?,
+        None => TTSConfig::default(),
+    };
+
+    let provider = ProviderChain::from_env();
+    let bars = provider.daily_bars(&symbol, HISTORY_BARS).await?.data;
+    let spy = provider.daily_bars("SPY", HISTORY_BARS).await?.data;
+
+    let strategy = TTSStrategy::try_with_config(config)?;
+    let report = Backtester::new(BacktestConfig::default()).run(&symbol, &bars, &spy, &strategy).await?;
+
+    println!("📊 {} backtest: {} → {} ({} sessions)", report.strategy, report.start_date, report.end_date, report.trading_days);
+    println!("{:<12} {:>10} {:>10}", "", &report.symbol, &report.benchmark_symbol);
+    println!("{:<12} {:>9.2}% {:>9.2}%", "CAGR", report.performance.cagr * 100.0, report.benchmark.cagr * 100.0);
+    println!("{:<12} {:>10.2} {:>10.2}", "Sharpe", report.performance.sharpe_ratio, report.benchmark.sharpe_ratio);
+    println!("{:<12} {:>9.2}% {:>9.2}%", "Max DD", report.performance.max_drawdown * 100.0, report.benchmark.max_drawdown * 100.0);
+    println!("{:<12} {:>9.1}%", "Hit rate", report.performance.hit_rate.unwrap_or(0.0) * 100.0);
+    println!("{:<12} {:>10.2}", "Turnover", report.performance.turnover);
+    println!("{:<12} {:>10}", "Trades", report.performance.trade_count);
+    println!("Excess CAGR vs {}: {:+.2}%", report.benchmark_symbol, report.excess_cagr() * 100.0);
+
+    Ok(())
+}
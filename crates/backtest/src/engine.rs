@@ -0,0 +1,425 @@
+// Day-by-day backtest engine
+// Decisions are taken at each close and held into the next session
+
+use buenotea_core::market_data::DailyBar;
+use buenotea_core::{Error, Result};
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+use tracing::{info, warn};
+
+use super::metrics::summarize;
+use super::models::*;
+use super::strategy::SignalStrategy;
+
+/// Replays a strategy over a price history and compares it with buy-and-hold
+pub struct Backtester {
+    config: BacktestConfig,
+    benchmark_symbol: String,
+}
+
+/// Trade that is still open while walking the history
+struct OpenTrade {
+    entry_date: NaiveDate,
+    entry_equity: f64,
+    direction: f64,
+}
+
+impl Backtester {
+    /// Create a backtester benchmarked against SPY
+    pub fn new(config: BacktestConfig) -> Self {
+        Self {
+            config,
+            benchmark_symbol: "SPY".to_string(),
+        }
+    }
+
+    /// Use a different benchmark symbol in reports
+    pub fn with_benchmark_symbol(mut self, symbol: &str) -> Self {
+        self.benchmark_symbol = symbol.to_string();
+        self
+    }
+
+    /// Run `strategy` over `bars` and compare it with buy-and-hold of `benchmark_bars`
+    ///
+    /// Both series are daily bars; they are sorted oldest first before use.
+    pub async fn run(
+        &self,
+        symbol: &str,
+        bars: &[DailyBar],
+        benchmark_bars: &[DailyBar],
+        strategy: &dyn SignalStrategy,
+    ) -> Result<BacktestReport> {
+        let mut bars = bars.to_vec();
+        bars.sort_by_key(|b| b.date);
+
+        let first_decision = self.config.warmup_bars.saturating_sub(1);
+        if bars.len() < first_decision + 2 {
+            return Err(Error::ValidationError {
+                message: format!(
+                    "Backtest for {} needs more than {} bars, got {}",
+                    symbol, self.config.warmup_bars, bars.len()
+                ),
+            });
+        }
+
+        let benchmark_closes: BTreeMap<NaiveDate, f64> =
+            benchmark_bars.iter().map(|b| (b.date, b.close)).collect();
+        let benchmark_close = |date: NaiveDate| benchmark_closes.range(..=date).next_back().map(|(_, c)| *c);
+        let benchmark_start = benchmark_close(bars[first_decision].date)
+            .filter(|c| *c > 0.0)
+            .ok_or_else(|| Error::ValidationError {
+                message: format!(
+                    "No {} data on or before {}",
+                    self.benchmark_symbol, bars[first_decision].date
+                ),
+            })?;
+
+        info!(
+            "Backtesting {} with {} from {} to {}",
+            symbol,
+            strategy.name(),
+            bars[first_decision].date,
+            bars[bars.len() - 1].date
+        );
+
+        let cost_rate = self.config.transaction_cost_bps / 10_000.0;
+        let mut equity = 1.0;
+        let mut position = 0.0;
+        let mut stop: Option<f64> = None;
+        let mut stopped_target: Option<f64> = None;
+        let mut traded = 0.0;
+        let mut open_trade: Option<OpenTrade> = None;
+        let mut trades = Vec::new();
+        let mut equity_curve = Vec::new();
+
+        for i in first_decision..bars.len() {
+            let today = &bars[i];
+
+            // Decide with only the bars known at today's close
+            let window_start = (i + 1).saturating_sub(self.config.history_window.max(1));
+            let decision = match strategy.decide(symbol, &bars[window_start..=i]).await {
+                Ok(decision) => decision,
+                Err(e) => {
+                    warn!("{} decision failed for {} on {}: {}", strategy.name(), symbol, today.date, e);
+                    Decision { target_position: position, stop_loss: stop }
+                }
+            };
+
+            let mut target = decision.target_position.clamp(-1.0, 1.0);
+            if !self.config.allow_short {
+                target = target.max(0.0);
+            }
+
+            // After a stop-out, wait for the signal to change before re-entering
+            if stopped_target == Some(target) {
+                target = 0.0;
+            } else {
+                stopped_target = None;
+            }
+
+            if target != position {
+                let change = (target - position).abs();
+                let equity_before = equity;
+                traded += change;
+                equity *= 1.0 - cost_rate * change;
+
+                let direction_changed = target == 0.0 || position == 0.0 || target.signum() != position.signum();
+                if direction_changed {
+                    if let Some(trade) = open_trade.take() {
+                        trades.push(close_trade(trade, today.date, equity, ExitReason::Signal));
+                    }
+                    if target != 0.0 {
+                        open_trade = Some(OpenTrade {
+                            entry_date: today.date,
+                            entry_equity: equity_before,
+                            direction: target.signum(),
+                        });
+                    }
+                }
+                position = target;
+            }
+
+            stop = if position > 0.0 {
+                decision.stop_loss.filter(|s| *s < today.close)
+            } else if position < 0.0 {
+                // Stops are expressed for longs; mirror the distance above the close
+                decision.stop_loss.filter(|s| *s < today.close).map(|s| 2.0 * today.close - s)
+            } else {
+                None
+            };
+
+            equity_curve.push(EquityPoint {
+                date: today.date,
+                equity,
+                benchmark_equity: benchmark_close(today.date).unwrap_or(benchmark_start) / benchmark_start,
+                position,
+            });
+
+            let next = match bars.get(i + 1) {
+                Some(next) => next,
+                None => break,
+            };
+            if position == 0.0 || today.close <= 0.0 {
+                continue;
+            }
+
+            // Hold into the next session, exiting at the stop (or the open if it gaps through)
+            let stop_price = match stop {
+                Some(s) if position > 0.0 && next.low <= s => Some(next.open.min(s)),
+                Some(s) if position < 0.0 && next.high >= s => Some(next.open.max(s)),
+                _ => None,
+            };
+            let exit_price = stop_price.unwrap_or(next.close);
+            equity *= 1.0 + position * (exit_price / today.close - 1.0);
+
+            if stop_price.is_some() {
+                traded += position.abs();
+                equity *= 1.0 - cost_rate * position.abs();
+                if let Some(trade) = open_trade.take() {
+                    trades.push(close_trade(trade, next.date, equity, ExitReason::StopLoss));
+                }
+                stopped_target = Some(position);
+                position = 0.0;
+                stop = None;
+            }
+        }
+
+        let end_date = bars[bars.len() - 1].date;
+        if let Some(trade) = open_trade.take() {
+            trades.push(close_trade(trade, end_date, equity, ExitReason::EndOfData));
+        }
+
+        let strategy_equity: Vec<f64> = equity_curve.iter().map(|p| p.equity).collect();
+        let benchmark_equity: Vec<f64> = equity_curve.iter().map(|p| p.benchmark_equity).collect();
+
+        Ok(BacktestReport {
+            symbol: symbol.to_string(),
+            strategy: strategy.name().to_string(),
+            benchmark_symbol: self.benchmark_symbol.clone(),
+            start_date: bars[first_decision].date,
+            end_date,
+            trading_days: equity_curve.len(),
+            performance: summarize(&strategy_equity, &trades, traded, self.config.risk_free_rate),
+            benchmark: summarize(&benchmark_equity, &[], 0.0, self.config.risk_free_rate),
+            trades,
+            equity_curve,
+        })
+    }
+}
+
+impl Default for Backtester {
+    fn default() -> Self {
+        Self::new(BacktestConfig::default())
+    }
+}
+
+fn close_trade(trade: OpenTrade, exit_date: NaiveDate, equity: f64, exit_reason: ExitReason) -> Trade {
+    Trade {
+        entry_date: trade.entry_date,
+        exit_date,
+        direction: trade.direction,
+        return_pct: equity / trade.entry_equity - 1.0,
+        exit_reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::{HistoricalSignals, QSSStrategy, TTSStrategy};
+    use async_trait::async_trait;
+    use buenotea_core::market_data::{CompanyProfile, IncomeStatement, MarketDataProvider, ProviderResponse, Quote, StatementPeriod};
+    use buenotea_sentiment::{FinraShortVolume, LocalOptionsChainProvider, QSSCalculator, ShortVolumeSource};
+    use buenotea_timing::TTSConfig;
+    use std::sync::Arc;
+
+    fn bars(closes: &[f64]) -> Vec<DailyBar> {
+        let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, &close)| DailyBar {
+                date: start + chrono::Duration::days(i as i64),
+                open: close,
+                high: close * 1.01,
+                low: close * 0.99,
+                close,
+                adjusted_close: None,
+                volume: 1_000_000,
+            })
+            .collect()
+    }
+
+    fn no_cost_config(warmup_bars: usize) -> BacktestConfig {
+        BacktestConfig {
+            warmup_bars,
+            transaction_cost_bps: 0.0,
+            ..BacktestConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_always_long_matches_buy_and_hold() {
+        let closes: Vec<f64> = (0..300).map(|i| 100.0 * 1.001f64.powi(i) + if i % 3 == 0 { 1.0 } else { 0.0 }).collect();
+        let history = bars(&closes);
+        let always_long = HistoricalSignals::from_positions("Always long", [(history[0].date, 1.0)]);
+
+        let report = Backtester::new(no_cost_config(10)).run("SPY", &history, &history, &always_long).await.unwrap();
+
+        assert!((report.performance.cagr - report.benchmark.cagr).abs() < 1e-9);
+        assert!((report.performance.max_drawdown - report.benchmark.max_drawdown).abs() < 1e-9);
+        assert_eq!(report.trades.len(), 1);
+        assert_eq!(report.trades[0].exit_reason, ExitReason::EndOfData);
+        assert_eq!(report.performance.hit_rate, Some(1.0));
+    }
+
+    #[tokio::test]
+    async fn test_stop_loss_exits_and_waits_for_new_signal() {
+        let mut history = bars(&[100.0, 100.0, 100.0, 100.0, 100.0, 100.0]);
+        // Gap down through the stop on day 3
+        history[3].open = 90.0;
+        history[3].low = 88.0;
+        history[3].close = 89.0;
+        let signals = HistoricalSignals::new(
+            "Long with stop",
+            [(history[0].date, Decision { target_position: 1.0, stop_loss: Some(95.0) })],
+        );
+
+        let report = Backtester::new(no_cost_config(1)).run("TEST", &history, &history, &signals).await.unwrap();
+
+        assert_eq!(report.trades.len(), 1);
+        assert_eq!(report.trades[0].exit_reason, ExitReason::StopLoss);
+        // Filled at the open because the bar gapped below the stop
+        assert!((report.trades[0].return_pct - -0.10).abs() < 1e-9);
+        assert!(report.equity_curve[3..].iter().all(|p| p.position == 0.0));
+    }
+
+    #[tokio::test]
+    async fn test_shorts_disabled_by_default_and_turnover_counted() {
+        let history = bars(&(0..60).map(|i| 100.0 + i as f64).collect::<Vec<_>>());
+        let signals = HistoricalSignals::from_positions(
+            "Flip",
+            [(history[0].date, 1.0), (history[20].date, -1.0), (history[40].date, 0.5)],
+        );
+
+        let report = Backtester::new(no_cost_config(1)).run("TEST", &history, &history, &signals).await.unwrap();
+
+        assert!(report.equity_curve.iter().all(|p| p.position >= 0.0));
+        assert_eq!(report.trades.len(), 2);
+        assert!(report.performance.turnover > 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_insufficient_history_errors() {
+        let history = bars(&[100.0; 50]);
+        let signals = HistoricalSignals::from_positions("Long", [(history[0].date, 1.0)]);
+        assert!(Backtester::default().run("TEST", &history, &history, &signals).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_tts_strategy_runs_point_in_time() {
+        let closes: Vec<f64> = (0..260).map(|i| 100.0 + (i as f64 / 10.0).sin() * 5.0 + i as f64 * 0.05).collect();
+        let history = bars(&closes);
+        let strategy = TTSStrategy::try_with_config(TTSConfig::default()).unwrap();
+
+        let report = Backtester::new(BacktestConfig { warmup_bars: 220, ..BacktestConfig::default() })
+            .run("TEST", &history, &history, &strategy)
+            .await
+            .unwrap();
+
+        assert_eq!(report.trading_days, 41);
+        assert!(report.strategy.starts_with("TTS"));
+    }
+
+    /// Range-bound "TEST" closes, flat benchmarks and annual EPS that doubles in a report filed on `filed`
+    struct EpsReport {
+        filed: NaiveDate,
+    }
+
+    fn annual_eps(year: i32, eps: f64, filing_date: NaiveDate) -> IncomeStatement {
+        IncomeStatement {
+            date: NaiveDate::from_ymd_opt(year, 12, 31).unwrap(),
+            filing_date: Some(filing_date),
+            period: "FY".to_string(),
+            fiscal_year: Some(year),
+            reported_currency: None,
+            revenue: None,
+            cost_of_revenue: None,
+            gross_profit: None,
+            research_and_development: None,
+            selling_general_admin: None,
+            depreciation_and_amortization: None,
+            operating_income: None,
+            interest_expense: None,
+            income_before_tax: None,
+            income_tax_expense: None,
+            net_income: None,
+            ebitda: None,
+            eps: Some(eps),
+            eps_diluted: None,
+            weighted_average_shares: None,
+            weighted_average_shares_diluted: None,
+        }
+    }
+
+    #[async_trait]
+    impl MarketDataProvider for EpsReport {
+        fn name(&self) -> &str {
+            "EpsReport"
+        }
+
+        async fn daily_bars(&self, symbol: &str, limit: usize) -> Result<ProviderResponse<Vec<DailyBar>>> {
+            let closes: Vec<f64> = (0..300)
+                .map(|i| if symbol == "TEST" && i % 2 == 1 { 101.0 } else { 100.0 })
+                .collect();
+            let mut data = bars(&closes);
+            data.drain(..data.len().saturating_sub(limit));
+            Ok(ProviderResponse { data, source: "EpsReport".to_string(), url: String::new(), raw: serde_json::Value::Null })
+        }
+
+        async fn quote(&self, _symbol: &str) -> Result<ProviderResponse<Quote>> {
+            Err(Error::ApiError("EpsReport".to_string(), "no quotes".to_string()))
+        }
+
+        async fn company_profile(&self, _symbol: &str) -> Result<ProviderResponse<CompanyProfile>> {
+            Err(Error::ApiError("EpsReport".to_string(), "no profiles".to_string()))
+        }
+
+        async fn income_statements(
+            &self,
+            _symbol: &str,
+            _period: StatementPeriod,
+            limit: usize,
+        ) -> Result<ProviderResponse<Vec<IncomeStatement>>> {
+            let early = NaiveDate::from_ymd_opt(2023, 3, 1).unwrap();
+            let mut data = vec![annual_eps(2023, 2.0, self.filed), annual_eps(2022, 1.0, early), annual_eps(2021, 1.0, early)];
+            data.truncate(limit);
+            Ok(ProviderResponse { data, source: "EpsReport".to_string(), url: String::new(), raw: serde_json::Value::Null })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_qss_strategy_scores_each_session_as_of() {
+        let history = bars(&[100.0; 300]);
+        let filed = history[290].date;
+        let provider = Arc::new(EpsReport { filed });
+        let empty = std::env::temp_dir().join("buenotea-backtest-no-data");
+        let calculator = QSSCalculator::with_provider(provider)
+            .with_short_volume(Arc::new(FinraShortVolume::new(ShortVolumeSource::Directory(empty.clone()))))
+            .with_options_provider(Box::new(LocalOptionsChainProvider::new(empty)));
+        let strategy = QSSStrategy::new(calculator);
+
+        let report = Backtester::new(no_cost_config(280))
+            .run("TEST", &history, &history, &strategy)
+            .await
+            .unwrap();
+
+        assert_eq!(report.trading_days, 21);
+        assert_eq!(report.strategy, "QSS");
+        // Hold until the doubled EPS is filed, then a WeakBuy held at half size
+        let positions: Vec<f64> = report.equity_curve.iter().map(|p| p.position).collect();
+        let entry = positions.iter().position(|p| *p > 0.0).unwrap();
+        assert_eq!(report.equity_curve[entry].date, filed);
+        assert!(positions[entry..].iter().all(|p| *p == 0.5));
+    }
+}
@@ -0,0 +1,11 @@
+// Backtest Module
+// Replays study signals point-in-time over a price history and measures them against SPY
+
+pub mod models;
+pub mod strategy;
+pub mod metrics;
+pub mod engine;
+
+pub use models::*;
+pub use strategy::{SignalStrategy, TTSStrategy, QSSStrategy, HistoricalSignals};
+pub use engine::Backtester;
@@ -0,0 +1,111 @@
+// Performance statistics for equity curves
+
+use super::models::{PerformanceMetrics, Trade};
+
+/// Trading sessions per year used for annualization
+pub const TRADING_DAYS_PER_YEAR: f64 = 252.0;
+
+/// Daily simple returns of an equity curve
+pub fn daily_returns(equity: &[f64]) -> Vec<f64> {
+    equity
+        .windows(2)
+        .map(|pair| if pair[0] != 0.0 { pair[1] / pair[0] - 1.0 } else { 0.0 })
+        .collect()
+}
+
+/// Compound annual growth rate of an equity curve with one point per session
+pub fn cagr(equity: &[f64]) -> f64 {
+    let (first, last) = match (equity.first(), equity.last()) {
+        (Some(first), Some(last)) if *first > 0.0 && equity.len() > 1 => (*first, *last),
+        _ => return 0.0,
+    };
+    let years = (equity.len() - 1) as f64 / TRADING_DAYS_PER_YEAR;
+    if last <= 0.0 {
+        return -1.0;
+    }
+    (last / first).powf(1.0 / years) - 1.0
+}
+
+/// Annualized Sharpe ratio of daily returns
+pub fn sharpe_ratio(returns: &[f64], risk_free_rate: f64) -> f64 {
+    if returns.len() < 2 {
+        return 0.0;
+    }
+
+    let daily_rf = risk_free_rate / TRADING_DAYS_PER_YEAR;
+    let excess: Vec<f64> = returns.iter().map(|r| r - daily_rf).collect();
+    let mean = excess.iter().sum::<f64>() / excess.len() as f64;
+    let variance = excess.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (excess.len() - 1) as f64;
+    let std_dev = variance.sqrt();
+
+    if std_dev == 0.0 {
+        return 0.0;
+    }
+    mean / std_dev * TRADING_DAYS_PER_YEAR.sqrt()
+}
+
+/// Largest peak-to-trough decline as a positive fraction
+pub fn max_drawdown(equity: &[f64]) -> f64 {
+    let mut peak = f64::NEG_INFINITY;
+    let mut worst: f64 = 0.0;
+    for &value in equity {
+        peak = peak.max(value);
+        if peak > 0.0 {
+            worst = worst.max((peak - value) / peak);
+        }
+    }
+    worst
+}
+
+/// Share of trades that made money
+pub fn hit_rate(trades: &[Trade]) -> Option<f64> {
+    if trades.is_empty() {
+        return None;
+    }
+    let winners = trades.iter().filter(|t| t.return_pct > 0.0).count();
+    Some(winners as f64 / trades.len() as f64)
+}
+
+/// Build the full metric set for an equity curve
+///
+/// `traded` is the sum of absolute position changes over the period.
+pub fn summarize(equity: &[f64], trades: &[Trade], traded: f64, risk_free_rate: f64) -> PerformanceMetrics {
+    let returns = daily_returns(equity);
+    let years = returns.len() as f64 / TRADING_DAYS_PER_YEAR;
+
+    PerformanceMetrics {
+        total_return: match (equity.first(), equity.last()) {
+            (Some(first), Some(last)) if *first != 0.0 => last / first - 1.0,
+            _ => 0.0,
+        },
+        cagr: cagr(equity),
+        sharpe_ratio: sharpe_ratio(&returns, risk_free_rate),
+        max_drawdown: max_drawdown(equity),
+        hit_rate: hit_rate(trades),
+        turnover: if years > 0.0 { traded / years } else { 0.0 },
+        trade_count: trades.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cagr_and_drawdown() {
+        // Doubling over exactly one year of sessions
+        let equity: Vec<f64> = (0..=252).map(|i| 2f64.powf(i as f64 / 252.0)).collect();
+        assert!((cagr(&equity) - 1.0).abs() < 1e-9);
+        assert_eq!(max_drawdown(&equity), 0.0);
+
+        assert!((max_drawdown(&[1.0, 1.2, 0.9, 1.3, 1.04]) - 0.25).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_sharpe_ratio() {
+        assert_eq!(sharpe_ratio(&[0.01, 0.01, 0.01], 0.0), 0.0);
+        let returns = [0.01, -0.005, 0.02, 0.0, 0.004];
+        assert!(sharpe_ratio(&returns, 0.0) > 0.0);
+        assert!(sharpe_ratio(&returns, 0.5) < sharpe_ratio(&returns, 0.0));
+    }
+}
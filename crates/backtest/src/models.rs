@@ -0,0 +1,128 @@
+// Data models for backtest configuration and results
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// What a strategy wants to hold after the close of a trading day
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Decision {
+    /// Target position as a fraction of equity (+1.0 fully long, -1.0 fully short)
+    pub target_position: f64,
+    /// Stop loss price for the position, if any
+    pub stop_loss: Option<f64>,
+}
+
+impl Decision {
+    /// Stay out of the market
+    pub fn flat() -> Self {
+        Self {
+            target_position: 0.0,
+            stop_loss: None,
+        }
+    }
+}
+
+/// Backtest settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestConfig {
+    /// Bars required before the first decision is taken
+    pub warmup_bars: usize,
+    /// Maximum number of bars handed to the strategy on each day
+    pub history_window: usize,
+    /// Allow negative target positions; otherwise sells go flat
+    pub allow_short: bool,
+    /// Cost per unit of position traded, in basis points
+    pub transaction_cost_bps: f64,
+    /// Annual risk-free rate used for the Sharpe ratio
+    pub risk_free_rate: f64,
+}
+
+impl Default for BacktestConfig {
+    fn default() -> Self {
+        Self {
+            warmup_bars: 200,
+            history_window: 250,
+            allow_short: false,
+            transaction_cost_bps: 5.0,
+            risk_free_rate: 0.0,
+        }
+    }
+}
+
+/// Equity at the close of one trading day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EquityPoint {
+    pub date: NaiveDate,
+    /// Strategy equity, starting at 1.0
+    pub equity: f64,
+    /// Buy-and-hold benchmark equity, starting at 1.0
+    pub benchmark_equity: f64,
+    /// Position held into the next session
+    pub position: f64,
+}
+
+/// Why a trade was closed
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ExitReason {
+    /// The strategy changed its target
+    Signal,
+    /// The stop loss was hit
+    StopLoss,
+    /// The backtest ran out of data
+    EndOfData,
+}
+
+/// One round trip in the same direction
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Trade {
+    pub entry_date: NaiveDate,
+    pub exit_date: NaiveDate,
+    /// Positive for long trades, negative for short trades
+    pub direction: f64,
+    /// Return on equity while the trade was open
+    pub return_pct: f64,
+    pub exit_reason: ExitReason,
+}
+
+/// Performance statistics for an equity curve
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PerformanceMetrics {
+    /// Total return over the period (0.25 = +25%)
+    pub total_return: f64,
+    /// Compound annual growth rate
+    pub cagr: f64,
+    /// Annualized Sharpe ratio of daily returns
+    pub sharpe_ratio: f64,
+    /// Largest peak-to-trough loss (0.2 = -20%)
+    pub max_drawdown: f64,
+    /// Share of closed trades with a positive return, when any trades were made
+    pub hit_rate: Option<f64>,
+    /// Annualized sum of absolute position changes (1.0 = equity traded once per year)
+    pub turnover: f64,
+    /// Number of closed trades
+    pub trade_count: usize,
+}
+
+/// Full backtest result for one symbol and strategy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestReport {
+    pub symbol: String,
+    pub strategy: String,
+    pub benchmark_symbol: String,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub trading_days: usize,
+    /// Strategy performance
+    pub performance: PerformanceMetrics,
+    /// Buy-and-hold benchmark performance over the same dates
+    pub benchmark: PerformanceMetrics,
+    pub trades: Vec<Trade>,
+    pub equity_curve: Vec<EquityPoint>,
+}
+
+impl BacktestReport {
+    /// Strategy CAGR minus benchmark CAGR
+    pub fn excess_cagr(&self) -> f64 {
+        self.performance.cagr - self.benchmark.cagr
+    }
+}
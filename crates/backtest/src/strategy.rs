@@ -0,0 +1,154 @@
+// Signal strategies replayed by the backtester
+
+use async_trait::async_trait;
+use buenotea_core::market_data::DailyBar;
+use buenotea_core::Result;
+use buenotea_sentiment::{QSSCalculator, TradingSignal};
+use buenotea_timing::{PricePoint, TTSCalculator, TTSConfig};
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+use super::models::Decision;
+
+/// Turns the price history known at a day's close into a position decision
+///
+/// `history` is oldest first and ends with the decision day, so a strategy
+/// cannot see bars from the future.
+#[async_trait]
+pub trait SignalStrategy: Send + Sync {
+    /// Strategy name used in reports
+    fn name(&self) -> &str;
+
+    /// Decide what to hold after the close of the last bar in `history`
+    async fn decide(&self, symbol: &str, history: &[DailyBar]) -> Result<Decision>;
+}
+
+/// Replays the Technical Trading Score
+///
+/// Position size comes from `TTSSignal::position_size` and the stop from
+/// `RiskAssessment::stop_loss`.
+pub struct TTSStrategy {
+    calculator: TTSCalculator,
+    name: String,
+}
+
+impl TTSStrategy {
+    /// Create a TTS strategy from a configured calculator
+    pub fn new(calculator: TTSCalculator) -> Self {
+        let name = format!("TTS ({})", calculator.config().config_hash());
+        Self { calculator, name }
+    }
+
+    /// Create a TTS strategy with a custom configuration, rejecting invalid ones
+    pub fn try_with_config(config: TTSConfig) -> Result<Self> {
+        Ok(Self::new(TTSCalculator::new().try_with_config(config)?))
+    }
+}
+
+#[async_trait]
+impl SignalStrategy for TTSStrategy {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn decide(&self, symbol: &str, history: &[DailyBar]) -> Result<Decision> {
+        let prices: Vec<PricePoint> = history.iter().map(PricePoint::from).collect();
+        let as_of = match prices.last() {
+            Some(last) => last.date,
+            None => return Ok(Decision::flat()),
+        };
+
+        let result = self.calculator.score_prices(symbol, &prices, as_of)?;
+        Ok(Decision {
+            target_position: result.trading_signal.position_size(),
+            stop_loss: Some(result.risk_assessment.stop_loss).filter(|stop| *stop > 0.0),
+        })
+    }
+}
+
+/// Replays the Quantitative Sentiment Score
+///
+/// Each session is scored with `QSSCalculator::calculate_qss_as_of`, which reads
+/// its own point-in-time data through the calculator's providers. Signals are
+/// sized like `TTSSignal::position_size` so both studies trade on the same scale.
+pub struct QSSStrategy {
+    calculator: QSSCalculator,
+}
+
+impl QSSStrategy {
+    /// Create a QSS strategy from a configured calculator
+    pub fn new(calculator: QSSCalculator) -> Self {
+        Self { calculator }
+    }
+}
+
+/// Target position for a QSS signal: full or half long, flat, half or full short
+fn qss_position(signal: TradingSignal) -> f64 {
+    match signal {
+        TradingSignal::StrongBuy => 1.0,
+        TradingSignal::WeakBuy => 0.5,
+        TradingSignal::Hold => 0.0,
+        TradingSignal::WeakSell => -0.5,
+        TradingSignal::StrongSell => -1.0,
+    }
+}
+
+#[async_trait]
+impl SignalStrategy for QSSStrategy {
+    fn name(&self) -> &str {
+        "QSS"
+    }
+
+    async fn decide(&self, symbol: &str, history: &[DailyBar]) -> Result<Decision> {
+        let Some(as_of) = history.last().map(|bar| bar.date) else {
+            return Ok(Decision::flat());
+        };
+
+        let result = self.calculator.calculate_qss_as_of(symbol, as_of).await?;
+        Ok(Decision { target_position: qss_position(result.trading_signal), stop_loss: None })
+    }
+}
+
+/// Replays decisions recorded ahead of time, e.g. QSS signals from `sentiment_history`
+///
+/// Each day uses the most recent decision dated on or before it.
+pub struct HistoricalSignals {
+    name: String,
+    decisions: BTreeMap<NaiveDate, Decision>,
+}
+
+impl HistoricalSignals {
+    /// Create from dated decisions
+    pub fn new(name: &str, decisions: impl IntoIterator<Item = (NaiveDate, Decision)>) -> Self {
+        Self {
+            name: name.to_string(),
+            decisions: decisions.into_iter().collect(),
+        }
+    }
+
+    /// Create from dated position sizes without stops (e.g. `TradingSignal::position_size`)
+    pub fn from_positions(name: &str, positions: impl IntoIterator<Item = (NaiveDate, f64)>) -> Self {
+        Self::new(
+            name,
+            positions.into_iter().map(|(date, target_position)| {
+                (date, Decision { target_position, stop_loss: None })
+            }),
+        )
+    }
+}
+
+#[async_trait]
+impl SignalStrategy for HistoricalSignals {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn decide(&self, _symbol: &str, history: &[DailyBar]) -> Result<Decision> {
+        let decision = history
+            .last()
+            .and_then(|bar| self.decisions.range(..=bar.date).next_back())
+            .map(|(_, decision)| *decision)
+            .unwrap_or_else(Decision::flat);
+        Ok(decision)
+    }
+}
@@ -0,0 +1,445 @@
+// Historical backfill CLI binary
+// Recomputes each study as of past trading days and bulk-inserts them into the *_history tables
+
+use anyhow::{bail, Context};
+use buenotea_core::market_data::point_in_time::weekdays_between;
+use buenotea_core::market_data::{
+    earnings_calendar_from_env, CachedEarningsCalendar, CachedProvider, EarningsCalendarProvider, MarketDataProvider,
+    ProviderChain,
+};
+use buenotea_fundamentals::{create_fundamentals_record_with_tracking, FundamentalsCalculator, FundamentalsStorage, PeerStock};
+use buenotea_infrastructure::sentiment_models::ApiUrls;
+use buenotea_invite_list::{ApiConfig, InviteListCalculator, InviteListStorage, SP500Fetcher, SP500Stock};
+use buenotea_regime::{create_market_regime_record_with_tracking, MarketRegimeCalculator, MarketRegimeStorage};
+use buenotea_sentiment::estimate_revisions::estimate_history_from_env;
+use buenotea_sentiment::{
+    create_sentiment_record_with_tracking, EstimateHistory, FinraShortVolume, QSSCalculator, SectorClassification,
+    SentimentStorage,
+};
+use buenotea_timing::{create_timing_record_with_tracking, TTSCalculator, TimingStorage};
+use chrono::{Duration, NaiveDate, Utc};
+use clap::{Parser, ValueEnum};
+use std::collections::{BTreeSet, HashMap};
+use std::future::Future;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Sessions of history the studies need before the first backfilled day (two years for post-earnings drift)
+const HISTORY_SESSIONS: usize = 530;
+
+/// Extra sessions fetched to absorb market holidays
+const HOLIDAY_SLACK: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Study {
+    Timing,
+    Sentiment,
+    InviteList,
+    Regime,
+    Fundamentals,
+}
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// First trading day to backfill (YYYY-MM-DD)
+    #[arg(long)]
+    from: NaiveDate,
+
+    /// Last trading day to backfill (YYYY-MM-DD), defaults to yesterday
+    #[arg(long)]
+    to: Option<NaiveDate>,
+
+    /// Comma-separated symbols, defaults to every symbol on the invite list
+    #[arg(short, long, value_delimiter = ',')]
+    symbols: Vec<String>,
+
+    /// Comma-separated studies to backfill
+    #[arg(long, value_enum, value_delimiter = ',', default_values_t = [Study::Timing, Study::Sentiment, Study::InviteList, Study::Regime, Study::Fundamentals])]
+    studies: Vec<Study>,
+
+    /// Records per bulk insert
+    #[arg(long, default_value_t = 50)]
+    batch_size: usize,
+
+    /// Compute records without reading or writing the database
+    #[arg(long, default_value_t = false)]
+    dry_run: bool,
+}
+
+/// Shared state for one backfill run
+struct Backfill {
+    provider: Arc<CachedProvider>,
+    earnings: Option<Arc<dyn EarningsCalendarProvider>>,
+    estimate_history: Option<Arc<dyn EstimateHistory>>,
+    short_volume: Arc<FinraShortVolume>,
+    trading_days: Vec<NaiveDate>,
+    batch_size: usize,
+    dry_run: bool,
+}
+
+impl Backfill {
+    fn from(&self) -> NaiveDate {
+        self.trading_days[0]
+    }
+
+    fn to(&self) -> NaiveDate {
+        self.trading_days[self.trading_days.len() - 1]
+    }
+
+    /// Trading days that are not stored yet
+    fn pending(&self, existing: &BTreeSet<NaiveDate>) -> Vec<NaiveDate> {
+        self.trading_days.iter().copied().filter(|day| !existing.contains(day)).collect()
+    }
+
+    /// Insert records in batches, returning how many were stored
+    async fn flush<'a, R, F, Fut>(&self, study: &str, records: &'a [R], store: F) -> anyhow::Result<usize>
+    where
+        F: Fn(&'a [R]) -> Fut,
+        Fut: Future<Output = buenotea_core::Result<Vec<i64>>>,
+    {
+        if self.dry_run {
+            info!("Dry run: computed {} {} records", records.len(), study);
+            return Ok(records.len());
+        }
+
+        let mut stored = 0;
+        for batch in records.chunks(self.batch_size.max(1)) {
+            stored += store(batch).await.with_context(|| format!("Failed to store {} batch", study))?.len();
+        }
+        Ok(stored)
+    }
+
+    async fn timing(&self, symbol: &str) -> anyhow::Result<usize> {
+        let storage = if self.dry_run { None } else { Some(TimingStorage::from_env()?) };
+        let existing = match &storage {
+            Some(storage) => storage.get_analysis_dates(symbol, self.from(), self.to()).await?,
+            None => BTreeSet::new(),
+        };
+
+        let mut calculator = TTSCalculator::with_provider(self.provider.clone());
+        if let Some(earnings) = &self.earnings {
+            calculator = calculator.with_earnings_calendar(earnings.clone());
+        }
+        let mut records = Vec::new();
+        for day in self.pending(&existing) {
+            match calculator.calculate_tts_as_of(symbol, day).await {
+                Ok((result, tracking)) => records.push(create_timing_record_with_tracking(result, tracking)),
+                Err(e) => warn!("Timing failed for {} on {}: {}", symbol, day, e),
+            }
+        }
+
+        self.flush("timing", &records, |batch| async {
+            match &storage {
+                Some(storage) => storage.store_multiple_records(batch).await,
+                None => Ok(Vec::new()),
+            }
+        })
+        .await
+    }
+
+    async fn sentiment(&self, symbol: &str, classifications: &HashMap<String, SectorClassification>) -> anyhow::Result<usize> {
+        let storage = if self.dry_run { None } else { Some(SentimentStorage::from_env()?) };
+        let existing = match &storage {
+            Some(storage) => storage.get_analysis_dates(symbol, self.from(), self.to()).await?,
+            None => BTreeSet::new(),
+        };
+
+        let mut calculator = QSSCalculator::with_provider(self.provider.clone())
+            .with_classifications(classifications.clone())
+            .with_short_volume(self.short_volume.clone());
+        if let Some(earnings) = &self.earnings {
+            calculator = calculator.with_earnings_calendar(earnings.clone());
+        }
+        if let Some(estimate_history) = &self.estimate_history {
+            calculator = calculator.with_estimate_history(estimate_history.clone());
+        }
+        let mut records = Vec::new();
+        for day in self.pending(&existing) {
+            match calculator.calculate_qss_as_of(symbol, day).await {
+                Ok(result) => records.push(create_sentiment_record_with_tracking(result, ApiUrls::default(), String::new())),
+                Err(e) => warn!("Sentiment failed for {} on {}: {}", symbol, day, e),
+            }
+        }
+
+        self.flush("sentiment", &records, |batch| async {
+            match &storage {
+                Some(storage) => storage.store_multiple_records(batch).await,
+                None => Ok(Vec::new()),
+            }
+        })
+        .await
+    }
+
+    async fn invite_list(&self, stock: &SP500Stock, api_config: &ApiConfig) -> anyhow::Result<usize> {
+        let storage = if self.dry_run { None } else { Some(InviteListStorage::from_env()?) };
+        let existing = match &storage {
+            Some(storage) => storage.get_analysis_dates(&stock.symbol, self.from(), self.to()).await?,
+            None => BTreeSet::new(),
+        };
+        let pending = self.pending(&existing);
+        if pending.is_empty() {
+            return Ok(0);
+        }
+
+        let mut fetcher = SP500Fetcher::with_provider(api_config.clone(), self.provider.clone());
+        if let Some(earnings) = &self.earnings {
+            fetcher = fetcher.with_earnings_calendar(earnings.clone());
+        }
+        let calculator = InviteListCalculator::new(api_config.clone());
+        let company_data = fetcher.fetch_company_data(&stock.symbol).await?;
+        let financial_data = fetcher.fetch_financial_data(&stock.symbol).await?;
+        let earnings = match fetcher.fetch_earnings_calendar(&stock.symbol).await {
+            Ok(calendar) => Some(calendar),
+            Err(e) => {
+                warn!("Earnings calendar unavailable for {}: {}", stock.symbol, e);
+                None
+            }
+        };
+
+        let mut records = Vec::new();
+        for day in pending {
+            let price_data = match fetcher.fetch_price_data_as_of(&stock.symbol, day).await {
+                Ok(price_data) => price_data,
+                Err(e) => {
+                    warn!("Invite list prices failed for {} on {}: {}", stock.symbol, day, e);
+                    continue;
+                }
+            };
+            let known_financials = calculator.financial_data_as_of(&financial_data, day);
+            let safety =
+                calculator.analyze_stock_safety(stock, &company_data, &known_financials, &price_data, earnings.as_ref());
+            records.push(calculator.create_invite_list_record_as_of(
+                stock,
+                &safety,
+                &company_data,
+                &known_financials,
+                &price_data,
+                day,
+            ));
+        }
+
+        self.flush("invite list", &records, |batch| async {
+            match &storage {
+                Some(storage) => storage.store_multiple_records(batch).await,
+                None => Ok(Vec::new()),
+            }
+        })
+        .await
+    }
+
+    async fn regime(&self, breadth_universe: Vec<String>) -> anyhow::Result<usize> {
+        let storage = if self.dry_run { None } else { Some(MarketRegimeStorage::from_env()?) };
+        let existing = match &storage {
+            Some(storage) => storage.get_analysis_dates(self.from(), self.to()).await?,
+            None => BTreeSet::new(),
+        };
+
+        let mut calculator =
+            MarketRegimeCalculator::with_provider(self.provider.clone()).with_breadth_universe(breadth_universe);
+        let mut records = Vec::new();
+        for day in self.pending(&existing) {
+            match calculator.calculate_market_regime_as_of(day).await {
+                Ok(result) => records.push(create_market_regime_record_with_tracking(result, None)),
+                Err(e) => warn!("Market regime failed on {}: {}", day, e),
+            }
+        }
+
+        self.flush("market regime", &records, |batch| async {
+            match &storage {
+                Some(storage) => storage.store_multiple_records(batch).await,
+                None => Ok(Vec::new()),
+            }
+        })
+        .await
+    }
+
+    /// Score the whole universe each day so valuation and profitability are ranked against peers,
+    /// storing the days each of `symbols` is missing
+    async fn fundamentals(&self, universe: &[PeerStock], symbols: &[String]) -> anyhow::Result<usize> {
+        let storage = if self.dry_run { None } else { Some(FundamentalsStorage::from_env()?) };
+        let mut existing: HashMap<&str, BTreeSet<NaiveDate>> = HashMap::new();
+        if let Some(storage) = &storage {
+            for symbol in symbols {
+                existing.insert(symbol, storage.get_analysis_dates(symbol, self.from(), self.to()).await?);
+            }
+        }
+        let stored = |symbol: &str, day: NaiveDate| existing.get(symbol).is_some_and(|dates| dates.contains(&day));
+
+        let calculator = FundamentalsCalculator::with_provider(self.provider.clone());
+        let mut records = Vec::new();
+        for day in self.trading_days.iter().copied() {
+            if symbols.iter().all(|symbol| stored(symbol, day)) {
+                continue;
+            }
+            for (result, api_urls) in calculator.calculate_universe_as_of(universe, day).await {
+                if symbols.contains(&result.symbol) && !stored(&result.symbol, day) {
+                    records.push(create_fundamentals_record_with_tracking(result, api_urls, None, None));
+                }
+            }
+        }
+
+        self.flush("fundamentals", &records, |batch| async {
+            match &storage {
+                Some(storage) => storage.store_multiple_records(batch).await,
+                None => Ok(Vec::new()),
+            }
+        })
+        .await
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    // Initialize logging
+    tracing_subscriber::fmt::init();
+
+    // Load environment variables
+    dotenv::dotenv().ok();
+
+    let args = Args::parse();
+
+    let today = Utc::now().date_naive();
+    let to = args.to.unwrap_or(today - Duration::days(1)).min(today - Duration::days(1));
+    if args.from > to {
+        bail!("--from {} must be before {}", args.from, to);
+    }
+
+    // Fetch every symbol's history once; each day is served from memory
+    let sessions = weekdays_between(args.from, today) + HISTORY_SESSIONS + HOLIDAY_SLACK;
+    let provider = Arc::new(CachedProvider::new(Arc::new(ProviderChain::from_env()), sessions));
+
+    // SPY sessions define the trading calendar
+    let trading_days: Vec<NaiveDate> = provider
+        .daily_bars("SPY", sessions)
+        .await
+        .context("Failed to fetch SPY history for the trading calendar")?
+        .data
+        .iter()
+        .map(|bar| bar.date)
+        .filter(|date| *date >= args.from && *date <= to)
+        .collect();
+    if trading_days.is_empty() {
+        bail!("No trading days between {} and {}", args.from, to);
+    }
+    info!("Backfilling {} trading days from {} to {}", trading_days.len(), args.from, to);
+
+    // Each symbol's earnings calendar is fetched once and shared by all studies
+    let earnings = earnings_calendar_from_env()
+        .map(|calendar| Arc::new(CachedEarningsCalendar::new(calendar)) as Arc<dyn EarningsCalendarProvider>);
+
+    let backfill = Backfill {
+        provider,
+        earnings,
+        estimate_history: estimate_history_from_env(),
+        // FINRA daily files cover every ticker, so each is downloaded once per run
+        short_volume: Arc::new(FinraShortVolume::from_env()),
+        trading_days,
+        batch_size: args.batch_size,
+        dry_run: args.dry_run,
+    };
+
+    if args.studies.contains(&Study::Regime) {
+        // Breadth always covers the whole invite list, not just --symbols
+        let breadth_universe = match InviteListStorage::from_env() {
+            Ok(storage) => storage.get_all_stock_symbols().await.unwrap_or_else(|e| {
+                warn!("Invite list unavailable, market breadth will be empty: {}", e);
+                Vec::new()
+            }),
+            Err(e) => {
+                warn!("Invite list unavailable, market breadth will be empty: {}", e);
+                Vec::new()
+            }
+        };
+        let stored = backfill.regime(breadth_universe).await?;
+        println!("market regime: {} records", stored);
+    }
+
+    if args.studies.contains(&Study::Fundamentals) {
+        // Peer groups always cover the whole invite list; only the requested symbols are stored
+        let universe: Vec<PeerStock> = InviteListStorage::from_env()?
+            .get_all_stocks()
+            .await
+            .context("Fundamentals peer groups need the invite list")?
+            .into_iter()
+            .map(|record| PeerStock { symbol: record.symbol, sector: record.sector, industry: record.industry })
+            .collect();
+        let symbols: Vec<String> = if args.symbols.is_empty() {
+            universe.iter().map(|stock| stock.symbol.clone()).collect()
+        } else {
+            args.symbols.iter().map(|s| s.trim().to_uppercase()).collect()
+        };
+        let stored = backfill.fundamentals(&universe, &symbols).await?;
+        println!("fundamentals: {} records", stored);
+    }
+
+    let symbols: Vec<String> = if args.symbols.is_empty() {
+        InviteListStorage::from_env()?
+            .get_all_stock_symbols()
+            .await
+            .context("No --symbols given and the invite list could not be read")?
+    } else {
+        args.symbols.iter().map(|s| s.trim().to_uppercase()).collect()
+    };
+
+    // Invite list sectors pick the sentiment sector benchmark; missing symbols fall back to profiles
+    let classifications: HashMap<String, SectorClassification> = if args.studies.contains(&Study::Sentiment) {
+        match InviteListStorage::from_env() {
+            Ok(storage) => match storage.get_all_stocks().await {
+                Ok(records) => records
+                    .into_iter()
+                    .map(|record| (record.symbol, SectorClassification::new(record.sector, record.industry)))
+                    .filter(|(_, classification)| !classification.is_empty())
+                    .collect(),
+                Err(e) => {
+                    warn!("Invite list unavailable, sector benchmarks will use company profiles: {}", e);
+                    HashMap::new()
+                }
+            },
+            Err(e) => {
+                warn!("Invite list unavailable, sector benchmarks will use company profiles: {}", e);
+                HashMap::new()
+            }
+        }
+    } else {
+        HashMap::new()
+    };
+
+    let invite_list = if args.studies.contains(&Study::InviteList) {
+        let api_config = ApiConfig::from_env().context("FMP_API_KEY is required for the invite list")?;
+        let constituents = SP500Fetcher::with_provider(api_config.clone(), backfill.provider.clone())
+            .fetch_sp500_list()
+            .await?;
+        Some((api_config, constituents))
+    } else {
+        None
+    };
+
+    for symbol in &symbols {
+        if args.studies.contains(&Study::Timing) {
+            match backfill.timing(symbol).await {
+                Ok(stored) => println!("{} timing: {} records", symbol, stored),
+                Err(e) => warn!("Timing backfill failed for {}: {}", symbol, e),
+            }
+        }
+
+        if args.studies.contains(&Study::Sentiment) {
+            match backfill.sentiment(symbol, &classifications).await {
+                Ok(stored) => println!("{} sentiment: {} records", symbol, stored),
+                Err(e) => warn!("Sentiment backfill failed for {}: {}", symbol, e),
+            }
+        }
+
+        if let Some((api_config, constituents)) = &invite_list {
+            match constituents.iter().find(|stock| &stock.symbol == symbol) {
+                Some(stock) => match backfill.invite_list(stock, api_config).await {
+                    Ok(stored) => println!("{} invite list: {} records", symbol, stored),
+                    Err(e) => warn!("Invite list backfill failed for {}: {}", symbol, e),
+                },
+                None => warn!("{} is not an S&P 500 constituent, skipping invite list", symbol),
+            }
+        }
+    }
+
+    Ok(())
+}
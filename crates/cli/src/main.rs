@@ -9,6 +9,7 @@ fn main() {
     println!("  regime         - Run regime analysis");
     println!("  fundamentals   - Run fundamentals analysis");
     println!("  invite-list    - Analyze invite list");
+    println!("  backfill       - Backfill history tables for past trading days");
     println!("\nExample: cargo run --bin timing -- --symbol AAPL --save");
 }
 
@@ -16,6 +16,8 @@ struct ChatGPTResponse {
 #[derive(Debug, Serialize, Deserialize)]
 struct Choice {
     message: Message,
+    #[serde(default)]
+    finish_reason: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -23,6 +25,9 @@ struct Message {
     content: String,
 }
 
+/// Model used for structured JSON output (JSON schema response formats need a recent model)
+const JSON_MODEL: &str = "gpt-4o-mini";
+
 /// ChatGPT service for generating AI explanations
 pub struct ChatGPTService {
     client: Client,
@@ -61,10 +66,45 @@ impl ChatGPTService {
         }
     }
 
+    /// Generate a response constrained to a strict JSON schema
+    ///
+    /// Returns the raw JSON text for the caller to deserialize and validate.
+    /// A response cut off by the token limit is an error rather than partial JSON.
+    pub async fn generate_json(&self, prompt: &str, schema_name: &str, schema: serde_json::Value, max_tokens: u32) -> Result<String> {
+        info!("Generating ChatGPT JSON response ({})", schema_name);
+
+        let request_body = json!({
+            "model": JSON_MODEL,
+            "messages": [
+                {
+                    "role": "user",
+                    "content": prompt
+                }
+            ],
+            "max_tokens": max_tokens,
+            "temperature": 0.0,
+            "response_format": {
+                "type": "json_schema",
+                "json_schema": {
+                    "name": schema_name,
+                    "strict": true,
+                    "schema": schema
+                }
+            }
+        });
+
+        let choice = self.send(request_body).await?;
+        if choice.finish_reason.as_deref() == Some("length") {
+            return Err(crate::error::Error::InvalidResponseFormat(
+                "ChatGPT".to_string(),
+                format!("JSON response for {} was truncated at {} tokens", schema_name, max_tokens),
+            ));
+        }
+        Ok(choice.message.content)
+    }
+
     /// Call ChatGPT API with a prompt
     async fn call_chatgpt_api(&self, prompt: &str) -> Result<String> {
-        let url = "https://api.openai.com/v1/chat/completions";
-        
         let request_body = json!({
             "model": "gpt-3.5-turbo",
             "messages": [
@@ -77,6 +117,13 @@ impl ChatGPTService {
             "temperature": 0.7
         });
 
+        Ok(self.send(request_body).await?.message.content)
+    }
+
+    /// Post a chat completion request and return the first choice
+    async fn send(&self, request_body: serde_json::Value) -> Result<Choice> {
+        let url = "https://api.openai.com/v1/chat/completions";
+
         let response = self.client
             .post(url)
             .header("Authorization", format!("Bearer {}", self.api_key))
@@ -96,8 +143,8 @@ impl ChatGPTService {
 
         let chatgpt_response: ChatGPTResponse = response.json().await?;
         
-        if let Some(choice) = chatgpt_response.choices.first() {
-            Ok(choice.message.content.clone())
+        if let Some(choice) = chatgpt_response.choices.into_iter().next() {
+            Ok(choice)
         } else {
             Err(crate::error::Error::ApiError(
                 "ChatGPT".to_string(),
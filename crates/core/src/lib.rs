@@ -1,6 +1,7 @@
 pub mod error;
 pub mod ai;
+pub mod market_data;
 
 pub use error::{Error, Result};
 pub use ai::ChatGPTService;
-
+pub use market_data::{MarketDataProvider, ProviderChain, ProviderResponse};
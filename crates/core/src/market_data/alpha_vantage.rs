@@ -0,0 +1,360 @@
+// Alpha Vantage market data provider
+
+use super::*;
+
+const BASE_URL: &str = "https://www.alphavantage.co/query";
+
+/// Market data from Alpha Vantage
+#[derive(Clone)]
+pub struct AlphaVantageProvider {
+    client: Client,
+    api_key: String,
+}
+
+impl AlphaVantageProvider {
+    /// Create a new Alpha Vantage provider
+    pub fn new(api_key: String) -> Self {
+        Self {
+            client: Client::new(),
+            api_key,
+        }
+    }
+
+    /// Create from the ALPHA_VANTAGE_API_KEY environment variable
+    pub fn from_env() -> Result<Self> {
+        let api_key = std::env::var("ALPHA_VANTAGE_API_KEY")
+            .map_err(|_| Error::MissingApiKey("Alpha Vantage".to_string()))?;
+        Ok(Self::new(api_key))
+    }
+
+    async fn fetch(&self, function: &str, symbol: &str, extra: &str) -> Result<(String, Value)> {
+        let url = format!(
+            "{}?function={}&symbol={}{}&apikey={}",
+            BASE_URL, function, symbol, extra, self.api_key
+        );
+        let json = get_json(&self.client, self.name(), &url).await?;
+
+        // Alpha Vantage reports errors and throttling with a 200 status
+        if let Some(message) = json.get("Error Message").and_then(|v| v.as_str()) {
+            return Err(Error::ApiError(self.name().to_string(), message.to_string()));
+        }
+        if json.get("Note").is_some() || json.get("Information").is_some() {
+            return Err(Error::RateLimitExceeded(self.name().to_string()));
+        }
+
+        Ok((url, json))
+    }
+
+    fn respond<T>(&self, data: T, url: String, raw: Value) -> ProviderResponse<T> {
+        ProviderResponse {
+            data,
+            source: self.name().to_string(),
+            url,
+            raw,
+        }
+    }
+}
+
+fn reports_key(period: StatementPeriod) -> &'static str {
+    match period {
+        StatementPeriod::Annual => "annualReports",
+        StatementPeriod::Quarterly => "quarterlyReports",
+    }
+}
+
+fn period_label(period: StatementPeriod) -> String {
+    match period {
+        StatementPeriod::Annual => "FY".to_string(),
+        StatementPeriod::Quarterly => "Q".to_string(),
+    }
+}
+
+fn reports(json: &Value, period: StatementPeriod) -> Vec<&Value> {
+    json[reports_key(period)]
+        .as_array()
+        .map(|items| items.iter().collect())
+        .unwrap_or_default()
+}
+
+/// Parse `TIME_SERIES_DAILY` into bars (oldest first)
+pub(crate) fn parse_daily_bars(json: &Value, limit: usize) -> Vec<DailyBar> {
+    let bars = json["Time Series (Daily)"]
+        .as_object()
+        .map(|series| {
+            series
+                .iter()
+                .filter_map(|(date, item)| {
+                    Some(DailyBar {
+                        date: parse_date(&Value::String(date.clone()))?,
+                        open: parse_f64(&item["1. open"])?,
+                        high: parse_f64(&item["2. high"])?,
+                        low: parse_f64(&item["3. low"])?,
+                        close: parse_f64(&item["4. close"])?,
+                        adjusted_close: None,
+                        volume: parse_u64(&item["5. volume"])?,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    finalize_bars(bars, limit)
+}
+
+pub(crate) fn parse_quote(json: &Value, symbol: &str) -> Option<Quote> {
+    let item = &json["Global Quote"];
+    Some(Quote {
+        symbol: item["01. symbol"].as_str().unwrap_or(symbol).to_string(),
+        price: parse_f64(&item["05. price"])?,
+        open: parse_f64(&item["02. open"]),
+        high: parse_f64(&item["03. high"]),
+        low: parse_f64(&item["04. low"]),
+        previous_close: parse_f64(&item["08. previous close"]),
+        change: parse_f64(&item["09. change"]),
+        change_percent: parse_f64(&item["10. change percent"]),
+        volume: parse_u64(&item["06. volume"]),
+        timestamp: parse_date(&item["07. latest trading day"])
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .map(|dt| dt.and_utc())
+            .unwrap_or_else(chrono::Utc::now),
+    })
+}
+
+pub(crate) fn parse_profile(json: &Value, symbol: &str) -> CompanyProfile {
+    let text = |key: &str| {
+        json[key]
+            .as_str()
+            .filter(|s| !s.is_empty() && *s != "None")
+            .map(|s| s.to_string())
+    };
+    CompanyProfile {
+        symbol: json["Symbol"].as_str().unwrap_or(symbol).to_string(),
+        name: text("Name"),
+        exchange: text("Exchange"),
+        sector: text("Sector"),
+        industry: text("Industry"),
+        country: text("Country"),
+        currency: text("Currency"),
+        description: text("Description"),
+        cik: text("CIK"),
+        market_cap: parse_f64(&json["MarketCapitalization"]),
+        beta: parse_f64(&json["Beta"]),
+        shares_outstanding: parse_f64(&json["SharesOutstanding"]),
+        average_volume: None,
+    }
+}
+
+pub(crate) fn parse_income_statements(json: &Value, period: StatementPeriod) -> Vec<IncomeStatement> {
+    reports(json, period)
+        .into_iter()
+        .filter_map(|item| {
+            let date = parse_date(&item["fiscalDateEnding"])?;
+            Some(IncomeStatement {
+                date,
+                filing_date: None,
+                period: period_label(period),
+                fiscal_year: Some(chrono::Datelike::year(&date)),
+                reported_currency: item["reportedCurrency"].as_str().map(|s| s.to_string()),
+                revenue: parse_f64(&item["totalRevenue"]),
+                cost_of_revenue: parse_f64(&item["costOfRevenue"]),
+                gross_profit: parse_f64(&item["grossProfit"]),
+                research_and_development: parse_f64(&item["researchAndDevelopment"]),
+                selling_general_admin: parse_f64(&item["sellingGeneralAndAdministrative"]),
+                depreciation_and_amortization: parse_f64(&item["depreciationAndAmortization"]),
+                operating_income: parse_f64(&item["operatingIncome"]),
+                interest_expense: parse_f64(&item["interestExpense"]),
+                income_before_tax: parse_f64(&item["incomeBeforeTax"]),
+                income_tax_expense: parse_f64(&item["incomeTaxExpense"]),
+                net_income: parse_f64(&item["netIncome"]),
+                ebitda: parse_f64(&item["ebitda"]),
+                eps: None,
+                eps_diluted: None,
+                weighted_average_shares: None,
+                weighted_average_shares_diluted: None,
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn parse_balance_sheets(json: &Value, period: StatementPeriod) -> Vec<BalanceSheet> {
+    reports(json, period)
+        .into_iter()
+        .filter_map(|item| {
+            let date = parse_date(&item["fiscalDateEnding"])?;
+            Some(BalanceSheet {
+                date,
+                filing_date: None,
+                period: period_label(period),
+                fiscal_year: Some(chrono::Datelike::year(&date)),
+                reported_currency: item["reportedCurrency"].as_str().map(|s| s.to_string()),
+                cash_and_equivalents: parse_f64(&item["cashAndCashEquivalentsAtCarryingValue"]),
+                short_term_investments: parse_f64(&item["shortTermInvestments"]),
+                net_receivables: parse_f64(&item["currentNetReceivables"]),
+                inventory: parse_f64(&item["inventory"]),
+                total_current_assets: parse_f64(&item["totalCurrentAssets"]),
+                property_plant_equipment_net: parse_f64(&item["propertyPlantEquipment"]),
+                goodwill: parse_f64(&item["goodwill"]),
+                intangible_assets: parse_f64(&item["intangibleAssets"]),
+                total_assets: parse_f64(&item["totalAssets"]),
+                accounts_payable: parse_f64(&item["currentAccountsPayable"]),
+                short_term_debt: parse_f64(&item["shortTermDebt"]),
+                total_current_liabilities: parse_f64(&item["totalCurrentLiabilities"]),
+                long_term_debt: parse_f64(&item["longTermDebt"]),
+                total_debt: parse_f64(&item["shortLongTermDebtTotal"]),
+                total_liabilities: parse_f64(&item["totalLiabilities"]),
+                retained_earnings: parse_f64(&item["retainedEarnings"]),
+                total_equity: parse_f64(&item["totalShareholderEquity"]),
+                shares_outstanding: parse_f64(&item["commonStockSharesOutstanding"]),
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn parse_cash_flow_statements(json: &Value, period: StatementPeriod) -> Vec<CashFlowStatement> {
+    reports(json, period)
+        .into_iter()
+        .filter_map(|item| {
+            let date = parse_date(&item["fiscalDateEnding"])?;
+            let operating_cash_flow = parse_f64(&item["operatingCashflow"]);
+            let capital_expenditure = parse_f64(&item["capitalExpenditures"]).map(f64::abs);
+            Some(CashFlowStatement {
+                date,
+                filing_date: None,
+                period: period_label(period),
+                fiscal_year: Some(chrono::Datelike::year(&date)),
+                reported_currency: item["reportedCurrency"].as_str().map(|s| s.to_string()),
+                net_income: parse_f64(&item["netIncome"]),
+                depreciation_and_amortization: parse_f64(&item["depreciationDepletionAndAmortization"]),
+                stock_based_compensation: None,
+                operating_cash_flow,
+                capital_expenditure,
+                free_cash_flow: match (operating_cash_flow, capital_expenditure) {
+                    (Some(ocf), Some(capex)) => Some(ocf - capex),
+                    _ => None,
+                },
+                dividends_paid: parse_f64(&item["dividendPayout"]).map(f64::abs),
+                common_stock_repurchased: parse_f64(&item["paymentsForRepurchaseOfCommonStock"]).map(f64::abs),
+            })
+        })
+        .collect()
+}
+
+#[async_trait]
+impl MarketDataProvider for AlphaVantageProvider {
+    fn name(&self) -> &str {
+        "Alpha Vantage"
+    }
+
+    async fn daily_bars(&self, symbol: &str, limit: usize) -> Result<ProviderResponse<Vec<DailyBar>>> {
+        // Compact output only covers the last 100 sessions
+        let output_size = if limit <= 100 { "compact" } else { "full" };
+        let (url, json) = self
+            .fetch("TIME_SERIES_DAILY", symbol, &format!("&outputsize={}", output_size))
+            .await?;
+        let bars = parse_daily_bars(&json, limit);
+
+        if bars.is_empty() {
+            return Err(Error::InvalidResponseFormat(
+                self.name().to_string(),
+                format!("No daily time series for {}", symbol),
+            ));
+        }
+
+        Ok(self.respond(bars, url, json))
+    }
+
+    async fn quote(&self, symbol: &str) -> Result<ProviderResponse<Quote>> {
+        let (url, json) = self.fetch("GLOBAL_QUOTE", symbol, "").await?;
+        let quote = parse_quote(&json, symbol).ok_or_else(|| {
+            Error::InvalidResponseFormat(self.name().to_string(), format!("Quote for {} has no price", symbol))
+        })?;
+        Ok(self.respond(quote, url, json))
+    }
+
+    async fn company_profile(&self, symbol: &str) -> Result<ProviderResponse<CompanyProfile>> {
+        let (url, json) = self.fetch("OVERVIEW", symbol, "").await?;
+        if json.get("Symbol").is_none() {
+            return Err(Error::InvalidResponseFormat(
+                self.name().to_string(),
+                format!("No overview for {}", symbol),
+            ));
+        }
+        let profile = parse_profile(&json, symbol);
+        Ok(self.respond(profile, url, json))
+    }
+
+    async fn income_statements(
+        &self,
+        symbol: &str,
+        period: StatementPeriod,
+        limit: usize,
+    ) -> Result<ProviderResponse<Vec<IncomeStatement>>> {
+        let (url, json) = self.fetch("INCOME_STATEMENT", symbol, "").await?;
+        let mut statements = parse_income_statements(&json, period);
+        statements.truncate(limit);
+        Ok(self.respond(statements, url, json))
+    }
+
+    async fn balance_sheets(
+        &self,
+        symbol: &str,
+        period: StatementPeriod,
+        limit: usize,
+    ) -> Result<ProviderResponse<Vec<BalanceSheet>>> {
+        let (url, json) = self.fetch("BALANCE_SHEET", symbol, "").await?;
+        let mut statements = parse_balance_sheets(&json, period);
+        statements.truncate(limit);
+        Ok(self.respond(statements, url, json))
+    }
+
+    async fn cash_flow_statements(
+        &self,
+        symbol: &str,
+        period: StatementPeriod,
+        limit: usize,
+    ) -> Result<ProviderResponse<Vec<CashFlowStatement>>> {
+        let (url, json) = self.fetch("CASH_FLOW", symbol, "").await?;
+        let mut statements = parse_cash_flow_statements(&json, period);
+        statements.truncate(limit);
+        Ok(self.respond(statements, url, json))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_daily_bars_keeps_latest_sessions() {
+        let json = json!({
+            "Time Series (Daily)": {
+                "2024-01-04": {"1. open": "4", "2. high": "4", "3. low": "4", "4. close": "4", "5. volume": "400"},
+                "2024-01-02": {"1. open": "2", "2. high": "2", "3. low": "2", "4. close": "2", "5. volume": "200"},
+                "2024-01-03": {"1. open": "3", "2. high": "3", "3. low": "3", "4. close": "3", "5. volume": "300"}
+            }
+        });
+        let bars = parse_daily_bars(&json, 2);
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[0].close, 3.0);
+        assert_eq!(bars[1].close, 4.0);
+        assert_eq!(bars[1].volume, 400);
+    }
+
+    #[test]
+    fn test_parse_quote() {
+        let json = json!({
+            "Global Quote": {
+                "01. symbol": "IBM",
+                "05. price": "180.50",
+                "06. volume": "3000000",
+                "07. latest trading day": "2024-05-01",
+                "10. change percent": "1.25%"
+            }
+        });
+        let quote = parse_quote(&json, "IBM").unwrap();
+        assert_eq!(quote.price, 180.5);
+        assert_eq!(quote.change_percent, Some(1.25));
+        assert_eq!(quote.volume, Some(3_000_000));
+    }
+}
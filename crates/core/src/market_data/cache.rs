@@ -0,0 +1,291 @@
+// In-memory caching provider
+// Fetches each series once and serves later (smaller) requests from memory
+
+use super::models::*;
+use super::{MarketDataProvider, ProviderResponse};
+use crate::error::Result;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+
+/// Cached series together with the limit it was fetched with
+type SeriesCache<K, T> = Mutex<HashMap<K, (usize, ProviderResponse<Vec<T>>)>>;
+
+/// Wraps a provider and keeps bars, profiles, statements and vendor ratios in memory
+///
+/// Meant for backfills, where every trading day asks for the same history
+/// truncated to a different date. The first request for a symbol fetches at
+/// least `min_sessions` bars so later `daily_bars_as_of` calls are served
+/// without another API call. Quotes are always passed through.
+pub struct CachedProvider {
+    inner: Arc<dyn MarketDataProvider>,
+    min_sessions: usize,
+    bars: SeriesCache<String, DailyBar>,
+    profiles: Mutex<HashMap<String, ProviderResponse<CompanyProfile>>>,
+    income: SeriesCache<(String, StatementPeriod), IncomeStatement>,
+    balance: SeriesCache<(String, StatementPeriod), BalanceSheet>,
+    cash_flow: SeriesCache<(String, StatementPeriod), CashFlowStatement>,
+    ratios: SeriesCache<(String, StatementPeriod), FinancialRatios>,
+    key_metrics: SeriesCache<(String, StatementPeriod), KeyMetrics>,
+    growth: SeriesCache<(String, StatementPeriod), FinancialGrowth>,
+    treasury: Mutex<HashMap<NaiveDate, ProviderResponse<TreasuryYields>>>,
+}
+
+impl CachedProvider {
+    /// Cache `inner`, fetching at least `min_sessions` bars per symbol
+    pub fn new(inner: Arc<dyn MarketDataProvider>, min_sessions: usize) -> Self {
+        Self {
+            inner,
+            min_sessions,
+            bars: Mutex::default(),
+            profiles: Mutex::default(),
+            income: Mutex::default(),
+            balance: Mutex::default(),
+            cash_flow: Mutex::default(),
+            ratios: Mutex::default(),
+            key_metrics: Mutex::default(),
+            growth: Mutex::default(),
+            treasury: Mutex::default(),
+        }
+    }
+
+    /// Fetch and cache bars for a symbol ahead of time
+    pub async fn preload_bars(&self, symbol: &str) -> Result<usize> {
+        let response = self.daily_bars(symbol, self.min_sessions).await?;
+        Ok(response.data.len())
+    }
+}
+
+/// Serve a cached series if it was fetched with at least `limit` entries
+fn lookup<K: Eq + Hash, T: Clone>(
+    cache: &SeriesCache<K, T>,
+    key: &K,
+    limit: usize,
+    keep: fn(Vec<T>, usize) -> Vec<T>,
+) -> Option<ProviderResponse<Vec<T>>> {
+    let cache = cache.lock().unwrap_or_else(|e| e.into_inner());
+    cache
+        .get(key)
+        .filter(|(fetched, _)| *fetched >= limit)
+        .map(|(_, response)| response.clone().map(|data| keep(data, limit)))
+}
+
+fn store<K: Eq + Hash, T: Clone>(
+    cache: &SeriesCache<K, T>,
+    key: K,
+    fetched: usize,
+    response: ProviderResponse<Vec<T>>,
+    limit: usize,
+    keep: fn(Vec<T>, usize) -> Vec<T>,
+) -> ProviderResponse<Vec<T>> {
+    let served = response.clone().map(|data| keep(data, limit));
+    cache.lock().unwrap_or_else(|e| e.into_inner()).insert(key, (fetched, response));
+    served
+}
+
+/// Bars are oldest first: keep the last `limit`
+fn keep_last<T>(mut data: Vec<T>, limit: usize) -> Vec<T> {
+    if data.len() > limit {
+        data.drain(..data.len() - limit);
+    }
+    data
+}
+
+/// Statements are newest first: keep the first `limit`
+fn keep_first<T>(mut data: Vec<T>, limit: usize) -> Vec<T> {
+    data.truncate(limit);
+    data
+}
+
+#[async_trait]
+impl MarketDataProvider for CachedProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn daily_bars(&self, symbol: &str, limit: usize) -> Result<ProviderResponse<Vec<DailyBar>>> {
+        let key = symbol.to_string();
+        if let Some(hit) = lookup(&self.bars, &key, limit, keep_last) {
+            return Ok(hit);
+        }
+        let fetched = limit.max(self.min_sessions);
+        let response = self.inner.daily_bars(symbol, fetched).await?;
+        Ok(store(&self.bars, key, fetched, response, limit, keep_last))
+    }
+
+    async fn quote(&self, symbol: &str) -> Result<ProviderResponse<Quote>> {
+        self.inner.quote(symbol).await
+    }
+
+    async fn company_profile(&self, symbol: &str) -> Result<ProviderResponse<CompanyProfile>> {
+        if let Some(hit) = self.profiles.lock().unwrap_or_else(|e| e.into_inner()).get(symbol) {
+            return Ok(hit.clone());
+        }
+        let response = self.inner.company_profile(symbol).await?;
+        self.profiles
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(symbol.to_string(), response.clone());
+        Ok(response)
+    }
+
+    async fn income_statements(
+        &self,
+        symbol: &str,
+        period: StatementPeriod,
+        limit: usize,
+    ) -> Result<ProviderResponse<Vec<IncomeStatement>>> {
+        let key = (symbol.to_string(), period);
+        if let Some(hit) = lookup(&self.income, &key, limit, keep_first) {
+            return Ok(hit);
+        }
+        let response = self.inner.income_statements(symbol, period, limit).await?;
+        Ok(store(&self.income, key, limit, response, limit, keep_first))
+    }
+
+    async fn balance_sheets(
+        &self,
+        symbol: &str,
+        period: StatementPeriod,
+        limit: usize,
+    ) -> Result<ProviderResponse<Vec<BalanceSheet>>> {
+        let key = (symbol.to_string(), period);
+        if let Some(hit) = lookup(&self.balance, &key, limit, keep_first) {
+            return Ok(hit);
+        }
+        let response = self.inner.balance_sheets(symbol, period, limit).await?;
+        Ok(store(&self.balance, key, limit, response, limit, keep_first))
+    }
+
+    async fn cash_flow_statements(
+        &self,
+        symbol: &str,
+        period: StatementPeriod,
+        limit: usize,
+    ) -> Result<ProviderResponse<Vec<CashFlowStatement>>> {
+        let key = (symbol.to_string(), period);
+        if let Some(hit) = lookup(&self.cash_flow, &key, limit, keep_first) {
+            return Ok(hit);
+        }
+        let response = self.inner.cash_flow_statements(symbol, period, limit).await?;
+        Ok(store(&self.cash_flow, key, limit, response, limit, keep_first))
+    }
+
+    async fn financial_ratios(
+        &self,
+        symbol: &str,
+        period: StatementPeriod,
+        limit: usize,
+    ) -> Result<ProviderResponse<Vec<FinancialRatios>>> {
+        let key = (symbol.to_string(), period);
+        if let Some(hit) = lookup(&self.ratios, &key, limit, keep_first) {
+            return Ok(hit);
+        }
+        let response = self.inner.financial_ratios(symbol, period, limit).await?;
+        Ok(store(&self.ratios, key, limit, response, limit, keep_first))
+    }
+
+    async fn key_metrics(
+        &self,
+        symbol: &str,
+        period: StatementPeriod,
+        limit: usize,
+    ) -> Result<ProviderResponse<Vec<KeyMetrics>>> {
+        let key = (symbol.to_string(), period);
+        if let Some(hit) = lookup(&self.key_metrics, &key, limit, keep_first) {
+            return Ok(hit);
+        }
+        let response = self.inner.key_metrics(symbol, period, limit).await?;
+        Ok(store(&self.key_metrics, key, limit, response, limit, keep_first))
+    }
+
+    async fn financial_growth(
+        &self,
+        symbol: &str,
+        period: StatementPeriod,
+        limit: usize,
+    ) -> Result<ProviderResponse<Vec<FinancialGrowth>>> {
+        let key = (symbol.to_string(), period);
+        if let Some(hit) = lookup(&self.growth, &key, limit, keep_first) {
+            return Ok(hit);
+        }
+        let response = self.inner.financial_growth(symbol, period, limit).await?;
+        Ok(store(&self.growth, key, limit, response, limit, keep_first))
+    }
+
+    async fn treasury_yields_as_of(&self, as_of: NaiveDate) -> Result<ProviderResponse<TreasuryYields>> {
+        if let Some(hit) = self.treasury.lock().unwrap_or_else(|e| e.into_inner()).get(&as_of) {
+            return Ok(hit.clone());
+        }
+        let response = self.inner.treasury_yields_as_of(as_of).await?;
+        self.treasury
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(as_of, response.clone());
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use chrono::Duration;
+    use serde_json::Value;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Provider with 100 daily bars that counts how often it is called
+    #[derive(Default)]
+    struct CountingBars(AtomicUsize);
+
+    #[async_trait]
+    impl MarketDataProvider for CountingBars {
+        fn name(&self) -> &str {
+            "Counting"
+        }
+
+        async fn daily_bars(&self, _symbol: &str, limit: usize) -> Result<ProviderResponse<Vec<DailyBar>>> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+            let bars = (0..100)
+                .map(|i| DailyBar {
+                    date: start + Duration::days(i),
+                    open: i as f64,
+                    high: i as f64,
+                    low: i as f64,
+                    close: i as f64,
+                    adjusted_close: None,
+                    volume: 1_000,
+                })
+                .collect();
+            let data = keep_last(bars, limit);
+            Ok(ProviderResponse { data, source: "Counting".to_string(), url: String::new(), raw: Value::Null })
+        }
+
+        async fn quote(&self, _symbol: &str) -> Result<ProviderResponse<Quote>> {
+            Err(Error::ApiError("Counting".to_string(), "no quotes".to_string()))
+        }
+
+        async fn company_profile(&self, _symbol: &str) -> Result<ProviderResponse<CompanyProfile>> {
+            Err(Error::ApiError("Counting".to_string(), "no profiles".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bars_fetched_once_and_served_truncated() {
+        let inner = Arc::new(CountingBars::default());
+        let cached = CachedProvider::new(inner.clone(), 80);
+
+        assert_eq!(cached.preload_bars("TEST").await.unwrap(), 80);
+        let bars = cached.daily_bars("TEST", 10).await.unwrap().data;
+        assert_eq!(bars.len(), 10);
+        assert_eq!(bars[9].close, 99.0);
+        assert_eq!(inner.0.load(Ordering::SeqCst), 1);
+
+        // Asking for more than was cached goes back to the provider
+        assert_eq!(cached.daily_bars("TEST", 90).await.unwrap().data.len(), 90);
+        assert_eq!(inner.0.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,300 @@
+// Earnings calendar
+// Past and scheduled earnings reports per symbol, with EPS and revenue surprises
+
+use super::{FinnhubProvider, FmpProvider, ProviderResponse};
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// When on the report date the numbers are released
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportTime {
+    BeforeOpen,
+    AfterClose,
+}
+
+impl ReportTime {
+    /// Parse the vendor codes ("bmo", "amc")
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code.trim().to_lowercase().as_str() {
+            "bmo" => Some(ReportTime::BeforeOpen),
+            "amc" => Some(ReportTime::AfterClose),
+            _ => None,
+        }
+    }
+}
+
+/// One earnings report, scheduled or released
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EarningsReport {
+    /// Report (announcement) date
+    pub date: NaiveDate,
+    pub time: Option<ReportTime>,
+    /// End of the fiscal quarter being reported
+    pub fiscal_period_end: Option<NaiveDate>,
+    /// Reported EPS, `None` until released
+    pub eps_actual: Option<f64>,
+    /// Consensus EPS before the report
+    pub eps_estimate: Option<f64>,
+    pub revenue_actual: Option<f64>,
+    pub revenue_estimate: Option<f64>,
+}
+
+impl EarningsReport {
+    /// Whether the numbers were public at the open of `as_of`
+    ///
+    /// Reports dated `as_of` only count when released before the open.
+    pub fn released_by(&self, as_of: NaiveDate) -> bool {
+        self.date < as_of || (self.date == as_of && self.time == Some(ReportTime::BeforeOpen))
+    }
+
+    /// Reported minus estimated EPS
+    pub fn eps_surprise(&self) -> Option<f64> {
+        Some(self.eps_actual? - self.eps_estimate?)
+    }
+
+    /// EPS surprise relative to the absolute estimate, `None` for a zero estimate
+    pub fn eps_surprise_percent(&self) -> Option<f64> {
+        let estimate = self.eps_estimate.filter(|e| *e != 0.0)?;
+        Some(self.eps_surprise()? / estimate.abs() * 100.0)
+    }
+
+    /// Reported minus estimated revenue
+    pub fn revenue_surprise(&self) -> Option<f64> {
+        Some(self.revenue_actual? - self.revenue_estimate?)
+    }
+}
+
+/// Earnings reports of one symbol, oldest first
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EarningsCalendar {
+    pub symbol: String,
+    pub reports: Vec<EarningsReport>,
+}
+
+impl EarningsCalendar {
+    /// Sort reports oldest first and keep one report per date
+    pub fn new(symbol: &str, mut reports: Vec<EarningsReport>) -> Self {
+        reports.sort_by_key(|r| r.date);
+        reports.dedup_by_key(|r| r.date);
+        Self {
+            symbol: symbol.to_uppercase(),
+            reports,
+        }
+    }
+
+    /// Latest report released by `as_of`
+    pub fn last_report(&self, as_of: NaiveDate) -> Option<&EarningsReport> {
+        self.reports.iter().rev().find(|r| r.released_by(as_of))
+    }
+
+    /// Earliest report not yet released at the open of `as_of` (today's after-close report included)
+    ///
+    /// Historical calls assume the report date was already announced on `as_of`,
+    /// which holds for the few weeks before a report.
+    pub fn next_report(&self, as_of: NaiveDate) -> Option<&EarningsReport> {
+        self.reports.iter().find(|r| !r.released_by(as_of))
+    }
+
+    /// Calendar days from `as_of` to the next report
+    pub fn days_until_next(&self, as_of: NaiveDate) -> Option<i64> {
+        self.next_report(as_of).map(|r| (r.date - as_of).num_days())
+    }
+
+    /// Calendar days from the last report to `as_of`
+    pub fn days_since_last(&self, as_of: NaiveDate) -> Option<i64> {
+        self.last_report(as_of).map(|r| (as_of - r.date).num_days())
+    }
+
+    /// Report date closest to `as_of`, before or after
+    pub fn nearest_report_date(&self, as_of: NaiveDate) -> Option<NaiveDate> {
+        self.reports.iter().map(|r| r.date).min_by_key(|date| (*date - as_of).num_days().abs())
+    }
+
+    /// Released reports with both actual and estimated EPS, newest first
+    pub fn surprise_history(&self, as_of: NaiveDate, limit: usize) -> Vec<&EarningsReport> {
+        self.reports
+            .iter()
+            .rev()
+            .filter(|r| r.released_by(as_of) && r.eps_surprise().is_some())
+            .take(limit)
+            .collect()
+    }
+}
+
+/// Source of earnings calendars
+#[async_trait]
+pub trait EarningsCalendarProvider: Send + Sync {
+    /// Provider name used for API source tracking
+    fn name(&self) -> &str;
+
+    /// Past and scheduled reports for a symbol
+    async fn earnings_calendar(&self, symbol: &str) -> Result<ProviderResponse<EarningsCalendar>>;
+}
+
+/// Parse a saved FMP (`historical/earning_calendar`) or Finnhub (`calendar/earnings`) response
+pub fn parse_earnings_calendar(symbol: &str, json: &Value) -> EarningsCalendar {
+    if json.get("earningsCalendar").is_some() {
+        super::finnhub::parse_earnings_calendar(symbol, json)
+    } else {
+        super::fmp::parse_earnings_calendar(symbol, json)
+    }
+}
+
+/// Earnings calendars read from local JSON files (`dir/SYMBOL.json`)
+///
+/// Files hold a saved FMP or Finnhub calendar response, which makes them
+/// usable as test fixtures and as a frozen calendar for backtests.
+pub struct LocalEarningsCalendar {
+    dir: PathBuf,
+}
+
+impl LocalEarningsCalendar {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+#[async_trait]
+impl EarningsCalendarProvider for LocalEarningsCalendar {
+    fn name(&self) -> &str {
+        "Local earnings calendar"
+    }
+
+    async fn earnings_calendar(&self, symbol: &str) -> Result<ProviderResponse<EarningsCalendar>> {
+        let path = self.dir.join(format!("{}.json", symbol.to_uppercase()));
+        let text = tokio::fs::read_to_string(&path)
+            .await
+            .map_err(|e| Error::ApiError(self.name().to_string(), format!("Cannot read {}: {}", path.display(), e)))?;
+        let raw: Value = serde_json::from_str(&text)?;
+        Ok(ProviderResponse {
+            data: parse_earnings_calendar(symbol, &raw),
+            source: self.name().to_string(),
+            url: path.display().to_string(),
+            raw,
+        })
+    }
+}
+
+/// Keeps each symbol's calendar in memory so backfills fetch it once
+pub struct CachedEarningsCalendar {
+    inner: Arc<dyn EarningsCalendarProvider>,
+    calendars: Mutex<HashMap<String, ProviderResponse<EarningsCalendar>>>,
+}
+
+impl CachedEarningsCalendar {
+    pub fn new(inner: Arc<dyn EarningsCalendarProvider>) -> Self {
+        Self {
+            inner,
+            calendars: Mutex::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl EarningsCalendarProvider for CachedEarningsCalendar {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn earnings_calendar(&self, symbol: &str) -> Result<ProviderResponse<EarningsCalendar>> {
+        if let Some(hit) = self.calendars.lock().unwrap_or_else(|e| e.into_inner()).get(symbol) {
+            return Ok(hit.clone());
+        }
+        let response = self.inner.earnings_calendar(symbol).await?;
+        self.calendars
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(symbol.to_string(), response.clone());
+        Ok(response)
+    }
+}
+
+/// Calendar from EARNINGS_CALENDAR_DIR, else FMP, else Finnhub; `None` without a source
+pub fn earnings_calendar_from_env() -> Option<Arc<dyn EarningsCalendarProvider>> {
+    if let Ok(dir) = std::env::var("EARNINGS_CALENDAR_DIR") {
+        return Some(Arc::new(LocalEarningsCalendar::new(dir)));
+    }
+    if let Ok(provider) = FmpProvider::from_env() {
+        return Some(Arc::new(provider));
+    }
+    FinnhubProvider::from_env().ok().map(|provider| Arc::new(provider) as Arc<dyn EarningsCalendarProvider>)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn report(day: &str, time: Option<ReportTime>, actual: Option<f64>, estimate: Option<f64>) -> EarningsReport {
+        EarningsReport {
+            date: date(day),
+            time,
+            fiscal_period_end: None,
+            eps_actual: actual,
+            eps_estimate: estimate,
+            revenue_actual: None,
+            revenue_estimate: None,
+        }
+    }
+
+    #[test]
+    fn test_next_and_last_report() {
+        let calendar = EarningsCalendar::new(
+            "acme",
+            vec![
+                report("2024-05-02", Some(ReportTime::AfterClose), None, Some(1.5)),
+                report("2024-02-01", Some(ReportTime::AfterClose), Some(2.18), Some(2.10)),
+                report("2023-11-02", None, Some(1.46), Some(1.39)),
+            ],
+        );
+        assert_eq!(calendar.symbol, "ACME");
+        assert_eq!(calendar.reports[0].date, date("2023-11-02"));
+
+        let as_of = date("2024-04-29");
+        assert_eq!(calendar.days_until_next(as_of), Some(3));
+        assert_eq!(calendar.days_since_last(as_of), Some(88));
+        assert_eq!(calendar.nearest_report_date(as_of), Some(date("2024-05-02")));
+
+        // An after-close report is still ahead on its own date
+        assert_eq!(calendar.next_report(date("2024-02-01")).unwrap().date, date("2024-02-01"));
+        assert_eq!(calendar.last_report(date("2024-02-01")).unwrap().date, date("2023-11-02"));
+
+        let history = calendar.surprise_history(date("2024-05-03"), 8);
+        assert_eq!(history.len(), 2);
+        assert!((history[0].eps_surprise().unwrap() - 0.08).abs() < 1e-12);
+        assert!((history[1].eps_surprise_percent().unwrap() - 0.07 / 1.39 * 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_parse_saved_responses() {
+        let fmp = serde_json::json!([
+            {"date": "2024-05-02", "symbol": "AAPL", "eps": null, "epsEstimated": 1.5, "time": "amc",
+             "revenue": null, "revenueEstimated": 90.0, "fiscalDateEnding": "2024-03-30"},
+            {"date": "2024-02-01", "symbol": "AAPL", "eps": 2.18, "epsEstimated": 2.1, "time": "amc",
+             "revenue": 119.6, "revenueEstimated": 117.9, "fiscalDateEnding": "2023-12-30"}
+        ]);
+        let calendar = parse_earnings_calendar("AAPL", &fmp);
+        assert_eq!(calendar.reports.len(), 2);
+        assert_eq!(calendar.reports[0].fiscal_period_end, Some(date("2023-12-30")));
+        assert_eq!(calendar.reports[1].eps_actual, None);
+        assert_eq!(calendar.reports[1].time, Some(ReportTime::AfterClose));
+
+        let finnhub = serde_json::json!({"earningsCalendar": [
+            {"date": "2024-02-01", "epsActual": 2.18, "epsEstimate": 2.1, "hour": "bmo",
+             "revenueActual": 119.6, "revenueEstimate": 117.9, "symbol": "AAPL"}
+        ]});
+        let calendar = parse_earnings_calendar("AAPL", &finnhub);
+        assert_eq!(calendar.reports[0].time, Some(ReportTime::BeforeOpen));
+        assert!(calendar.reports[0].released_by(date("2024-02-01")));
+    }
+}
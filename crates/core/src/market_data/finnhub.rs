@@ -0,0 +1,244 @@
+// Finnhub market data provider
+
+use super::*;
+use chrono::Utc;
+
+const NAME: &str = "Finnhub";
+
+const BASE_URL: &str = "https://finnhub.io/api/v1";
+
+/// Market data from Finnhub (bars, quotes and profiles; no statements)
+#[derive(Clone)]
+pub struct FinnhubProvider {
+    client: Client,
+    api_key: String,
+}
+
+impl FinnhubProvider {
+    /// Create a new Finnhub provider
+    pub fn new(api_key: String) -> Self {
+        Self {
+            client: Client::new(),
+            api_key,
+        }
+    }
+
+    /// Create from the FINNHUB_API_KEY environment variable
+    pub fn from_env() -> Result<Self> {
+        let api_key = std::env::var("FINNHUB_API_KEY")
+            .map_err(|_| Error::MissingApiKey("Finnhub".to_string()))?;
+        Ok(Self::new(api_key))
+    }
+
+    async fn fetch(&self, path: &str, query: &str) -> Result<(String, Value)> {
+        let url = format!("{}/{}?{}&token={}", BASE_URL, path, query, self.api_key);
+        let json = get_json(&self.client, NAME, &url).await?;
+
+        if let Some(message) = json.get("error").and_then(|v| v.as_str()) {
+            return Err(Error::ApiError(NAME.to_string(), message.to_string()));
+        }
+
+        Ok((url, json))
+    }
+
+    fn respond<T>(&self, data: T, url: String, raw: Value) -> ProviderResponse<T> {
+        ProviderResponse {
+            data,
+            source: NAME.to_string(),
+            url,
+            raw,
+        }
+    }
+}
+
+/// Parse `/stock/candle` column arrays into bars (oldest first)
+pub(crate) fn parse_daily_bars(json: &Value, limit: usize) -> Vec<DailyBar> {
+    if json["s"].as_str() != Some("ok") {
+        return Vec::new();
+    }
+
+    let column = |key: &str| json[key].as_array().cloned().unwrap_or_default();
+    let (timestamps, opens, highs, lows, closes, volumes) =
+        (column("t"), column("o"), column("h"), column("l"), column("c"), column("v"));
+
+    let bars = timestamps
+        .iter()
+        .enumerate()
+        .filter_map(|(i, ts)| {
+            Some(DailyBar {
+                date: chrono::DateTime::from_timestamp(ts.as_i64()?, 0)?.date_naive(),
+                open: parse_f64(opens.get(i)?)?,
+                high: parse_f64(highs.get(i)?)?,
+                low: parse_f64(lows.get(i)?)?,
+                close: parse_f64(closes.get(i)?)?,
+                adjusted_close: None,
+                volume: parse_u64(volumes.get(i)?)?,
+            })
+        })
+        .collect();
+
+    finalize_bars(bars, limit)
+}
+
+pub(crate) fn parse_quote(json: &Value, symbol: &str) -> Option<Quote> {
+    // Finnhub returns zeros rather than an error for unknown symbols
+    let price = parse_f64(&json["c"]).filter(|p| *p > 0.0)?;
+    Some(Quote {
+        symbol: symbol.to_string(),
+        price,
+        open: parse_f64(&json["o"]),
+        high: parse_f64(&json["h"]),
+        low: parse_f64(&json["l"]),
+        previous_close: parse_f64(&json["pc"]),
+        change: parse_f64(&json["d"]),
+        change_percent: parse_f64(&json["dp"]),
+        volume: None,
+        timestamp: json["t"]
+            .as_i64()
+            .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+            .unwrap_or_else(Utc::now),
+    })
+}
+
+pub(crate) fn parse_profile(json: &Value, symbol: &str) -> CompanyProfile {
+    let text = |key: &str| json[key].as_str().filter(|s| !s.is_empty()).map(|s| s.to_string());
+    CompanyProfile {
+        symbol: json["ticker"].as_str().unwrap_or(symbol).to_string(),
+        name: text("name"),
+        exchange: text("exchange"),
+        // Finnhub only exposes a single industry classification
+        sector: None,
+        industry: text("finnhubIndustry"),
+        country: text("country"),
+        currency: text("currency"),
+        description: None,
+        cik: None,
+        // Reported in millions
+        market_cap: parse_f64(&json["marketCapitalization"]).map(|v| v * 1_000_000.0),
+        beta: None,
+        shares_outstanding: parse_f64(&json["shareOutstanding"]).map(|v| v * 1_000_000.0),
+        average_volume: None,
+    }
+}
+
+/// Parse `calendar/earnings` (`earningsCalendar` array, `hour` is "bmo"/"amc")
+pub(crate) fn parse_earnings_calendar(symbol: &str, json: &Value) -> EarningsCalendar {
+    let reports = json["earningsCalendar"]
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    Some(EarningsReport {
+                        date: parse_date(&item["date"])?,
+                        time: item["hour"].as_str().and_then(ReportTime::from_code),
+                        fiscal_period_end: None,
+                        eps_actual: parse_f64(&item["epsActual"]),
+                        eps_estimate: parse_f64(&item["epsEstimate"]),
+                        revenue_actual: parse_f64(&item["revenueActual"]),
+                        revenue_estimate: parse_f64(&item["revenueEstimate"]),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    EarningsCalendar::new(symbol, reports)
+}
+
+#[async_trait]
+impl MarketDataProvider for FinnhubProvider {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    async fn daily_bars(&self, symbol: &str, limit: usize) -> Result<ProviderResponse<Vec<DailyBar>>> {
+        // Roughly 7 calendar days per 5 sessions, plus slack for holidays
+        let to = Utc::now().timestamp();
+        let from = to - (limit as i64 * 7 / 5 + 10) * 86_400;
+        let (url, json) = self
+            .fetch("stock/candle", &format!("symbol={}&resolution=D&from={}&to={}", symbol, from, to))
+            .await?;
+        let bars = parse_daily_bars(&json, limit);
+
+        if bars.is_empty() {
+            return Err(Error::InvalidResponseFormat(
+                NAME.to_string(),
+                format!("No candles for {}", symbol),
+            ));
+        }
+
+        Ok(self.respond(bars, url, json))
+    }
+
+    async fn quote(&self, symbol: &str) -> Result<ProviderResponse<Quote>> {
+        let (url, json) = self.fetch("quote", &format!("symbol={}", symbol)).await?;
+        let quote = parse_quote(&json, symbol).ok_or_else(|| {
+            Error::InvalidResponseFormat(NAME.to_string(), format!("Quote for {} has no price", symbol))
+        })?;
+        Ok(self.respond(quote, url, json))
+    }
+
+    async fn company_profile(&self, symbol: &str) -> Result<ProviderResponse<CompanyProfile>> {
+        let (url, json) = self.fetch("stock/profile2", &format!("symbol={}", symbol)).await?;
+        if json.get("ticker").is_none() {
+            return Err(Error::InvalidResponseFormat(
+                NAME.to_string(),
+                format!("No profile for {}", symbol),
+            ));
+        }
+        let profile = parse_profile(&json, symbol);
+        Ok(self.respond(profile, url, json))
+    }
+}
+
+#[async_trait]
+impl EarningsCalendarProvider for FinnhubProvider {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    async fn earnings_calendar(&self, symbol: &str) -> Result<ProviderResponse<EarningsCalendar>> {
+        // The endpoint requires a date range: two years back and the scheduled year ahead
+        let today = Utc::now().date_naive();
+        let query = format!(
+            "symbol={}&from={}&to={}",
+            symbol,
+            today - chrono::Duration::days(730),
+            today + chrono::Duration::days(365)
+        );
+        let (url, json) = self.fetch("calendar/earnings", &query).await?;
+        let calendar = parse_earnings_calendar(symbol, &json);
+        Ok(self.respond(calendar, url, json))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_candles() {
+        let json = json!({
+            "s": "ok",
+            "t": [1704240000, 1704326400],
+            "o": [1.0, 2.0],
+            "h": [1.5, 2.5],
+            "l": [0.5, 1.5],
+            "c": [1.2, 2.2],
+            "v": [100, 200]
+        });
+        let bars = parse_daily_bars(&json, 10);
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[0].date, NaiveDate::from_ymd_opt(2024, 1, 3).unwrap());
+        assert_eq!(bars[1].close, 2.2);
+
+        assert!(parse_daily_bars(&json!({"s": "no_data"}), 10).is_empty());
+    }
+
+    #[test]
+    fn test_unknown_symbol_quote_is_none() {
+        let json = json!({"c": 0, "d": null, "dp": null, "h": 0, "l": 0, "o": 0, "pc": 0, "t": 0});
+        assert!(parse_quote(&json, "NOPE").is_none());
+    }
+}
@@ -0,0 +1,590 @@
+// Financial Modeling Prep market data provider
+
+use super::*;
+
+const NAME: &str = "FMP";
+
+const BASE_URL: &str = "https://financialmodelingprep.com/api/v3";
+const BASE_URL_V4: &str = "https://financialmodelingprep.com/api/v4";
+
+/// Days of treasury yields requested before the as-of date, to cover weekends and holidays
+const TREASURY_WINDOW_DAYS: i64 = 10;
+
+/// Market data from Financial Modeling Prep
+#[derive(Clone)]
+pub struct FmpProvider {
+    client: Client,
+    api_key: String,
+}
+
+impl FmpProvider {
+    /// Create a new FMP provider
+    pub fn new(api_key: String) -> Self {
+        Self {
+            client: Client::new(),
+            api_key,
+        }
+    }
+
+    /// Create from the FMP_API_KEY environment variable
+    pub fn from_env() -> Result<Self> {
+        let api_key = std::env::var("FMP_API_KEY")
+            .map_err(|_| Error::MissingApiKey("FMP".to_string()))?;
+        Ok(Self::new(api_key))
+    }
+
+    fn url(&self, base: &str, path: &str, query: &str) -> String {
+        if query.is_empty() {
+            format!("{}/{}?apikey={}", base, path, self.api_key)
+        } else {
+            format!("{}/{}?{}&apikey={}", base, path, query, self.api_key)
+        }
+    }
+
+    async fn fetch(&self, path: &str, query: &str) -> Result<(String, Value)> {
+        self.fetch_from(BASE_URL, path, query).await
+    }
+
+    async fn fetch_from(&self, base: &str, path: &str, query: &str) -> Result<(String, Value)> {
+        let url = self.url(base, path, query);
+        let json = get_json(&self.client, NAME, &url).await?;
+
+        if let Some(message) = json.get("Error Message").and_then(|v| v.as_str()) {
+            return Err(Error::ApiError(NAME.to_string(), message.to_string()));
+        }
+
+        Ok((url, json))
+    }
+
+    fn respond<T>(&self, data: T, url: String, raw: Value) -> ProviderResponse<T> {
+        ProviderResponse {
+            data,
+            source: NAME.to_string(),
+            url,
+            raw,
+        }
+    }
+}
+
+fn period_param(period: StatementPeriod) -> &'static str {
+    match period {
+        StatementPeriod::Annual => "annual",
+        StatementPeriod::Quarterly => "quarter",
+    }
+}
+
+fn first_object<'a>(json: &'a Value, provider: &str, what: &str) -> Result<&'a Value> {
+    json.as_array()
+        .and_then(|items| items.first())
+        .ok_or_else(|| Error::InvalidResponseFormat(provider.to_string(), format!("Expected non-empty {} array", what)))
+}
+
+/// Parse `historical-price-full` into bars (oldest first)
+pub(crate) fn parse_daily_bars(json: &Value, limit: usize) -> Vec<DailyBar> {
+    let bars = json["historical"]
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    Some(DailyBar {
+                        date: parse_date(&item["date"])?,
+                        open: parse_f64(&item["open"])?,
+                        high: parse_f64(&item["high"])?,
+                        low: parse_f64(&item["low"])?,
+                        close: parse_f64(&item["close"])?,
+                        adjusted_close: parse_f64(&item["adjClose"]),
+                        // Indices such as ^VIX carry no volume
+                        volume: parse_u64(&item["volume"]).unwrap_or(0),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    finalize_bars(bars, limit)
+}
+
+pub(crate) fn parse_quote(item: &Value, symbol: &str) -> Option<Quote> {
+    Some(Quote {
+        symbol: item["symbol"].as_str().unwrap_or(symbol).to_string(),
+        price: parse_f64(&item["price"])?,
+        open: parse_f64(&item["open"]),
+        high: parse_f64(&item["dayHigh"]),
+        low: parse_f64(&item["dayLow"]),
+        previous_close: parse_f64(&item["previousClose"]),
+        change: parse_f64(&item["change"]),
+        change_percent: parse_f64(&item["changesPercentage"]),
+        volume: parse_u64(&item["volume"]),
+        timestamp: item["timestamp"]
+            .as_i64()
+            .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+            .unwrap_or_else(chrono::Utc::now),
+    })
+}
+
+pub(crate) fn parse_profile(item: &Value, symbol: &str) -> CompanyProfile {
+    let text = |key: &str| item[key].as_str().filter(|s| !s.is_empty()).map(|s| s.to_string());
+    CompanyProfile {
+        symbol: item["symbol"].as_str().unwrap_or(symbol).to_string(),
+        name: text("companyName"),
+        exchange: text("exchangeShortName"),
+        sector: text("sector"),
+        industry: text("industry"),
+        country: text("country"),
+        currency: text("currency"),
+        description: text("description"),
+        cik: text("cik"),
+        market_cap: parse_f64(&item["mktCap"]),
+        beta: parse_f64(&item["beta"]),
+        shares_outstanding: match (parse_f64(&item["mktCap"]), parse_f64(&item["price"])) {
+            (Some(cap), Some(price)) if price > 0.0 => Some(cap / price),
+            _ => None,
+        },
+        average_volume: parse_f64(&item["volAvg"]),
+    }
+}
+
+fn fiscal_year(item: &Value) -> Option<i32> {
+    parse_f64(&item["calendarYear"]).map(|y| y as i32)
+}
+
+fn period_label(item: &Value) -> String {
+    item["period"].as_str().unwrap_or("FY").to_string()
+}
+
+fn currency(item: &Value) -> Option<String> {
+    item["reportedCurrency"].as_str().map(|s| s.to_string())
+}
+
+fn positive(value: Option<f64>) -> Option<f64> {
+    value.map(f64::abs)
+}
+
+pub(crate) fn parse_income_statements(json: &Value) -> Vec<IncomeStatement> {
+    json.as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    Some(IncomeStatement {
+                        date: parse_date(&item["date"])?,
+                        filing_date: parse_date(&item["fillingDate"]),
+                        period: period_label(item),
+                        fiscal_year: fiscal_year(item),
+                        reported_currency: currency(item),
+                        revenue: parse_f64(&item["revenue"]),
+                        cost_of_revenue: parse_f64(&item["costOfRevenue"]),
+                        gross_profit: parse_f64(&item["grossProfit"]),
+                        research_and_development: parse_f64(&item["researchAndDevelopmentExpenses"]),
+                        selling_general_admin: parse_f64(&item["sellingGeneralAndAdministrativeExpenses"]),
+                        depreciation_and_amortization: parse_f64(&item["depreciationAndAmortization"]),
+                        operating_income: parse_f64(&item["operatingIncome"]),
+                        interest_expense: parse_f64(&item["interestExpense"]),
+                        income_before_tax: parse_f64(&item["incomeBeforeTax"]),
+                        income_tax_expense: parse_f64(&item["incomeTaxExpense"]),
+                        net_income: parse_f64(&item["netIncome"]),
+                        ebitda: parse_f64(&item["ebitda"]),
+                        eps: parse_f64(&item["eps"]),
+                        eps_diluted: parse_f64(&item["epsdiluted"]),
+                        weighted_average_shares: parse_f64(&item["weightedAverageShsOut"]),
+                        weighted_average_shares_diluted: parse_f64(&item["weightedAverageShsOutDil"]),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub(crate) fn parse_balance_sheets(json: &Value) -> Vec<BalanceSheet> {
+    json.as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    Some(BalanceSheet {
+                        date: parse_date(&item["date"])?,
+                        filing_date: parse_date(&item["fillingDate"]),
+                        period: period_label(item),
+                        fiscal_year: fiscal_year(item),
+                        reported_currency: currency(item),
+                        cash_and_equivalents: parse_f64(&item["cashAndCashEquivalents"]),
+                        short_term_investments: parse_f64(&item["shortTermInvestments"]),
+                        net_receivables: parse_f64(&item["netReceivables"]),
+                        inventory: parse_f64(&item["inventory"]),
+                        total_current_assets: parse_f64(&item["totalCurrentAssets"]),
+                        property_plant_equipment_net: parse_f64(&item["propertyPlantEquipmentNet"]),
+                        goodwill: parse_f64(&item["goodwill"]),
+                        intangible_assets: parse_f64(&item["intangibleAssets"]),
+                        total_assets: parse_f64(&item["totalAssets"]),
+                        accounts_payable: parse_f64(&item["accountPayables"]),
+                        short_term_debt: parse_f64(&item["shortTermDebt"]),
+                        total_current_liabilities: parse_f64(&item["totalCurrentLiabilities"]),
+                        long_term_debt: parse_f64(&item["longTermDebt"]),
+                        total_debt: parse_f64(&item["totalDebt"]),
+                        total_liabilities: parse_f64(&item["totalLiabilities"]),
+                        retained_earnings: parse_f64(&item["retainedEarnings"]),
+                        total_equity: parse_f64(&item["totalStockholdersEquity"]),
+                        shares_outstanding: None,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub(crate) fn parse_cash_flow_statements(json: &Value) -> Vec<CashFlowStatement> {
+    json.as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    let operating_cash_flow = parse_f64(&item["operatingCashFlow"])
+                        .or_else(|| parse_f64(&item["netCashProvidedByOperatingActivities"]));
+                    let capital_expenditure = positive(parse_f64(&item["capitalExpenditure"]));
+                    Some(CashFlowStatement {
+                        date: parse_date(&item["date"])?,
+                        filing_date: parse_date(&item["fillingDate"]),
+                        period: period_label(item),
+                        fiscal_year: fiscal_year(item),
+                        reported_currency: currency(item),
+                        net_income: parse_f64(&item["netIncome"]),
+                        depreciation_and_amortization: parse_f64(&item["depreciationAndAmortization"]),
+                        stock_based_compensation: parse_f64(&item["stockBasedCompensation"]),
+                        operating_cash_flow,
+                        capital_expenditure,
+                        free_cash_flow: parse_f64(&item["freeCashFlow"]).or(match (operating_cash_flow, capital_expenditure) {
+                            (Some(ocf), Some(capex)) => Some(ocf - capex),
+                            _ => None,
+                        }),
+                        dividends_paid: positive(parse_f64(&item["dividendsPaid"])),
+                        common_stock_repurchased: positive(parse_f64(&item["commonStockRepurchased"])),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Parse the entries of a dated dataset (`ratios`, `key-metrics`, `financial-growth`), skipping undated ones
+fn parse_dated<T>(json: &Value, parse: impl Fn(&Value, NaiveDate, String) -> T) -> Vec<T> {
+    json.as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| Some(parse(item, parse_date(&item["date"])?, period_label(item))))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub(crate) fn parse_financial_ratios(json: &Value) -> Vec<FinancialRatios> {
+    parse_dated(json, |item, date, period| FinancialRatios {
+        date,
+        period,
+        return_on_equity: parse_f64(&item["returnOnEquity"]),
+        return_on_assets: parse_f64(&item["returnOnAssets"]),
+        net_profit_margin: parse_f64(&item["netProfitMargin"]),
+        gross_profit_margin: parse_f64(&item["grossProfitMargin"]),
+        operating_profit_margin: parse_f64(&item["operatingProfitMargin"]),
+        price_earnings: parse_f64(&item["priceEarningsRatio"]),
+        price_earnings_to_growth: parse_f64(&item["priceEarningsToGrowthRatio"]),
+        price_to_sales: parse_f64(&item["priceToSalesRatio"]),
+        price_to_book: parse_f64(&item["priceToBookRatio"]),
+        price_to_cash_flow: parse_f64(&item["priceCashFlowRatio"]),
+        price_to_free_cash_flow: parse_f64(&item["priceToFreeCashFlowsRatio"]),
+        debt_to_equity: parse_f64(&item["debtEquityRatio"]),
+        debt_ratio: parse_f64(&item["debtRatio"]),
+        current_ratio: parse_f64(&item["currentRatio"]),
+        quick_ratio: parse_f64(&item["quickRatio"]),
+        interest_coverage: parse_f64(&item["interestCoverage"]),
+        equity_multiplier: parse_f64(&item["companyEquityMultiplier"]),
+        asset_turnover: parse_f64(&item["assetTurnover"]),
+        inventory_turnover: parse_f64(&item["inventoryTurnover"]),
+        receivables_turnover: parse_f64(&item["receivablesTurnover"]),
+        payables_turnover: parse_f64(&item["payablesTurnover"]),
+        days_sales_outstanding: parse_f64(&item["daysOfSalesOutstanding"]),
+        days_inventory_outstanding: parse_f64(&item["daysOfInventoryOutstanding"]),
+        days_payables_outstanding: parse_f64(&item["daysOfPayablesOutstanding"]),
+        dividend_yield: parse_f64(&item["dividendYield"]),
+        payout_ratio: parse_f64(&item["payoutRatio"]),
+    })
+}
+
+pub(crate) fn parse_key_metrics(json: &Value) -> Vec<KeyMetrics> {
+    parse_dated(json, |item, date, period| KeyMetrics {
+        date,
+        period,
+        market_cap: parse_f64(&item["marketCap"]),
+        enterprise_value: parse_f64(&item["enterpriseValue"]),
+        ev_to_ebitda: parse_f64(&item["enterpriseValueOverEBITDA"]),
+        ev_to_sales: parse_f64(&item["evToSales"]),
+        roic: parse_f64(&item["roic"]),
+        dividend_yield: parse_f64(&item["dividendYield"]),
+        payout_ratio: parse_f64(&item["payoutRatio"]),
+    })
+}
+
+pub(crate) fn parse_financial_growth(json: &Value) -> Vec<FinancialGrowth> {
+    parse_dated(json, |item, date, period| FinancialGrowth {
+        date,
+        period,
+        revenue_growth: parse_f64(&item["revenueGrowth"]),
+        eps_growth: parse_f64(&item["epsgrowth"]),
+        net_income_growth: parse_f64(&item["netIncomeGrowth"]),
+        book_value_per_share_growth: parse_f64(&item["bookValueperShareGrowth"]),
+        operating_cash_flow_growth: parse_f64(&item["operatingCashFlowGrowth"]),
+    })
+}
+
+/// Latest yield curve on or before `as_of` from v4 `treasury` (FMP reports percent)
+pub(crate) fn parse_treasury_yields(json: &Value, as_of: NaiveDate) -> Option<TreasuryYields> {
+    json.as_array()?
+        .iter()
+        .filter_map(|item| {
+            Some(TreasuryYields {
+                date: parse_date(&item["date"]).filter(|date| *date <= as_of)?,
+                ten_year: parse_f64(&item["year10"]).map(|percent| percent / 100.0),
+            })
+        })
+        .max_by_key(|yields| yields.date)
+}
+
+/// Parse `historical/earning_calendar` (past reports with actuals, scheduled ones without)
+pub(crate) fn parse_earnings_calendar(symbol: &str, json: &Value) -> EarningsCalendar {
+    let reports = json
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    Some(EarningsReport {
+                        date: parse_date(&item["date"])?,
+                        time: item["time"].as_str().and_then(ReportTime::from_code),
+                        fiscal_period_end: parse_date(&item["fiscalDateEnding"]),
+                        eps_actual: parse_f64(&item["eps"]),
+                        eps_estimate: parse_f64(&item["epsEstimated"]),
+                        revenue_actual: parse_f64(&item["revenue"]),
+                        revenue_estimate: parse_f64(&item["revenueEstimated"]),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    EarningsCalendar::new(symbol, reports)
+}
+
+#[async_trait]
+impl MarketDataProvider for FmpProvider {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    async fn daily_bars(&self, symbol: &str, limit: usize) -> Result<ProviderResponse<Vec<DailyBar>>> {
+        let (url, json) = self
+            .fetch(&format!("historical-price-full/{}", symbol.replace('^', "%5E")), &format!("timeseries={}", limit))
+            .await?;
+        let bars = parse_daily_bars(&json, limit);
+
+        if bars.is_empty() {
+            return Err(Error::InvalidResponseFormat(
+                NAME.to_string(),
+                format!("No historical prices for {}", symbol),
+            ));
+        }
+
+        Ok(self.respond(bars, url, json))
+    }
+
+    async fn quote(&self, symbol: &str) -> Result<ProviderResponse<Quote>> {
+        let (url, json) = self.fetch(&format!("quote/{}", symbol), "").await?;
+        let quote = parse_quote(first_object(&json, NAME, "quote")?, symbol).ok_or_else(|| {
+            Error::InvalidResponseFormat(NAME.to_string(), format!("Quote for {} has no price", symbol))
+        })?;
+        Ok(self.respond(quote, url, json))
+    }
+
+    async fn company_profile(&self, symbol: &str) -> Result<ProviderResponse<CompanyProfile>> {
+        let (url, json) = self.fetch(&format!("profile/{}", symbol), "").await?;
+        let profile = parse_profile(first_object(&json, NAME, "profile")?, symbol);
+        Ok(self.respond(profile, url, json))
+    }
+
+    async fn income_statements(
+        &self,
+        symbol: &str,
+        period: StatementPeriod,
+        limit: usize,
+    ) -> Result<ProviderResponse<Vec<IncomeStatement>>> {
+        let query = format!("period={}&limit={}", period_param(period), limit);
+        let (url, json) = self.fetch(&format!("income-statement/{}", symbol), &query).await?;
+        let mut statements = parse_income_statements(&json);
+        statements.truncate(limit);
+        Ok(self.respond(statements, url, json))
+    }
+
+    async fn balance_sheets(
+        &self,
+        symbol: &str,
+        period: StatementPeriod,
+        limit: usize,
+    ) -> Result<ProviderResponse<Vec<BalanceSheet>>> {
+        let query = format!("period={}&limit={}", period_param(period), limit);
+        let (url, json) = self.fetch(&format!("balance-sheet-statement/{}", symbol), &query).await?;
+        let mut statements = parse_balance_sheets(&json);
+        statements.truncate(limit);
+        Ok(self.respond(statements, url, json))
+    }
+
+    async fn cash_flow_statements(
+        &self,
+        symbol: &str,
+        period: StatementPeriod,
+        limit: usize,
+    ) -> Result<ProviderResponse<Vec<CashFlowStatement>>> {
+        let query = format!("period={}&limit={}", period_param(period), limit);
+        let (url, json) = self.fetch(&format!("cash-flow-statement/{}", symbol), &query).await?;
+        let mut statements = parse_cash_flow_statements(&json);
+        statements.truncate(limit);
+        Ok(self.respond(statements, url, json))
+    }
+
+    async fn financial_ratios(
+        &self,
+        symbol: &str,
+        period: StatementPeriod,
+        limit: usize,
+    ) -> Result<ProviderResponse<Vec<FinancialRatios>>> {
+        let query = format!("period={}&limit={}", period_param(period), limit);
+        let (url, json) = self.fetch(&format!("ratios/{}", symbol), &query).await?;
+        let mut ratios = parse_financial_ratios(&json);
+        ratios.truncate(limit);
+        Ok(self.respond(ratios, url, json))
+    }
+
+    async fn key_metrics(
+        &self,
+        symbol: &str,
+        period: StatementPeriod,
+        limit: usize,
+    ) -> Result<ProviderResponse<Vec<KeyMetrics>>> {
+        let query = format!("period={}&limit={}", period_param(period), limit);
+        let (url, json) = self.fetch(&format!("key-metrics/{}", symbol), &query).await?;
+        let mut metrics = parse_key_metrics(&json);
+        metrics.truncate(limit);
+        Ok(self.respond(metrics, url, json))
+    }
+
+    async fn financial_growth(
+        &self,
+        symbol: &str,
+        period: StatementPeriod,
+        limit: usize,
+    ) -> Result<ProviderResponse<Vec<FinancialGrowth>>> {
+        let query = format!("period={}&limit={}", period_param(period), limit);
+        let (url, json) = self.fetch(&format!("financial-growth/{}", symbol), &query).await?;
+        let mut growth = parse_financial_growth(&json);
+        growth.truncate(limit);
+        Ok(self.respond(growth, url, json))
+    }
+
+    async fn treasury_yields_as_of(&self, as_of: NaiveDate) -> Result<ProviderResponse<TreasuryYields>> {
+        let query = format!("from={}&to={}", as_of - chrono::Duration::days(TREASURY_WINDOW_DAYS), as_of);
+        let (url, json) = self.fetch_from(BASE_URL_V4, "treasury", &query).await?;
+        let yields = parse_treasury_yields(&json, as_of).ok_or_else(|| {
+            Error::InvalidResponseFormat(NAME.to_string(), format!("No treasury yields on or before {}", as_of))
+        })?;
+        Ok(self.respond(yields, url, json))
+    }
+}
+
+#[async_trait]
+impl EarningsCalendarProvider for FmpProvider {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    async fn earnings_calendar(&self, symbol: &str) -> Result<ProviderResponse<EarningsCalendar>> {
+        let (url, json) = self.fetch(&format!("historical/earning_calendar/{}", symbol), "").await?;
+        let calendar = parse_earnings_calendar(symbol, &json);
+        Ok(self.respond(calendar, url, json))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_daily_bars_oldest_first() {
+        let json = json!({
+            "symbol": "AAPL",
+            "historical": [
+                {"date": "2024-01-03", "open": 2.0, "high": 3.0, "low": 1.0, "close": 2.5, "adjClose": 2.5, "volume": 100},
+                {"date": "2024-01-02", "open": 1.0, "high": 2.0, "low": 0.5, "close": 1.5, "adjClose": 1.5, "volume": 200.0}
+            ]
+        });
+        let bars = parse_daily_bars(&json, 10);
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[0].close, 1.5);
+        assert_eq!(bars[0].volume, 200);
+        assert_eq!(bars[1].close, 2.5);
+    }
+
+    #[test]
+    fn test_parse_index_bars_without_volume() {
+        let json = json!({
+            "symbol": "^VIX",
+            "historical": [
+                {"date": "2024-01-02", "open": 13.2, "high": 14.1, "low": 13.0, "close": 13.9}
+            ]
+        });
+        let bars = parse_daily_bars(&json, 10);
+        assert_eq!(bars.len(), 1);
+        assert_eq!(bars[0].volume, 0);
+    }
+
+    #[test]
+    fn test_parse_cash_flow_normalizes_signs() {
+        let json = json!([{
+            "date": "2023-09-30",
+            "period": "FY",
+            "calendarYear": "2023",
+            "operatingCashFlow": 110.0,
+            "capitalExpenditure": -10.0,
+            "dividendsPaid": -15.0
+        }]);
+        let statements = parse_cash_flow_statements(&json);
+        assert_eq!(statements[0].capital_expenditure, Some(10.0));
+        assert_eq!(statements[0].free_cash_flow, Some(100.0));
+        assert_eq!(statements[0].dividends_paid, Some(15.0));
+        assert_eq!(statements[0].fiscal_year, Some(2023));
+    }
+
+    #[test]
+    fn test_parse_vendor_ratios_and_treasury() {
+        let ratios = parse_financial_ratios(&json!([
+            {"date": "2023-12-31", "period": "FY", "returnOnEquity": 0.25, "priceEarningsRatio": "20.5"},
+            {"period": "FY", "returnOnEquity": 0.3}
+        ]));
+        assert_eq!(ratios.len(), 1);
+        assert_eq!(ratios[0].return_on_equity, Some(0.25));
+        assert_eq!(ratios[0].price_earnings, Some(20.5));
+        assert_eq!(ratios[0].debt_to_equity, None);
+
+        let growth = parse_financial_growth(&json!([{"date": "2024-03-30", "period": "Q2", "epsgrowth": -0.1}]));
+        assert_eq!(growth[0].period, "Q2");
+        assert_eq!(growth[0].eps_growth, Some(-0.1));
+
+        let curve = json!([
+            {"date": "2024-03-04", "year10": 4.2},
+            {"date": "2024-03-01", "year10": 4.1},
+            {"date": "2024-03-05", "year10": 4.3}
+        ]);
+        let as_of = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let yields = parse_treasury_yields(&curve, as_of).unwrap();
+        assert_eq!(yields.date, as_of);
+        assert!((yields.ten_year.unwrap() - 0.042).abs() < 1e-12);
+    }
+}
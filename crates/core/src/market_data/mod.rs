@@ -0,0 +1,511 @@
+// Market data providers shared by every study module
+// Each provider normalizes its vendor JSON into the types in `models`
+
+pub mod models;
+pub mod fmp;
+pub mod alpha_vantage;
+pub mod finnhub;
+pub mod point_in_time;
+pub mod cache;
+pub mod earnings_calendar;
+
+pub use models::*;
+pub use fmp::FmpProvider;
+pub use alpha_vantage::AlphaVantageProvider;
+pub use finnhub::FinnhubProvider;
+pub use cache::CachedProvider;
+pub use earnings_calendar::{
+    earnings_calendar_from_env, CachedEarningsCalendar, EarningsCalendar, EarningsCalendarProvider, EarningsReport,
+    LocalEarningsCalendar, ReportTime,
+};
+pub use point_in_time::{as_of_timestamp, bars_as_of, is_historical, statements_as_of, Filed};
+
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+use chrono::{NaiveDate, Utc};
+use point_in_time::{extra_periods, weekdays_between, HOLIDAY_SLACK};
+use reqwest::Client;
+use serde_json::Value;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tracing::warn;
+
+/// Typed data returned by a provider together with where it came from
+#[derive(Debug, Clone)]
+pub struct ProviderResponse<T> {
+    pub data: T,
+    /// Provider name (e.g. "FMP")
+    pub source: String,
+    /// Endpoint that was called
+    pub url: String,
+    /// Raw JSON payload, kept for API tracking
+    pub raw: Value,
+}
+
+impl<T> ProviderResponse<T> {
+    /// Transform the payload while keeping the tracking information
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> ProviderResponse<U> {
+        ProviderResponse {
+            data: f(self.data),
+            source: self.source,
+            url: self.url,
+            raw: self.raw,
+        }
+    }
+}
+
+/// Source of daily bars, quotes, profiles, financial statements and vendor ratios
+///
+/// Bars are returned oldest first; statements are returned newest first.
+/// The `*_as_of` methods only return data that was known on a given date.
+#[async_trait]
+pub trait MarketDataProvider: Send + Sync {
+    /// Provider name used for API source tracking
+    fn name(&self) -> &str;
+
+    /// Most recent `limit` daily bars for a symbol, oldest first
+    async fn daily_bars(&self, symbol: &str, limit: usize) -> Result<ProviderResponse<Vec<DailyBar>>>;
+
+    /// Latest quote for a symbol
+    async fn quote(&self, symbol: &str) -> Result<ProviderResponse<Quote>>;
+
+    /// Company profile for a symbol
+    async fn company_profile(&self, symbol: &str) -> Result<ProviderResponse<CompanyProfile>>;
+
+    /// Income statements, newest first
+    async fn income_statements(
+        &self,
+        _symbol: &str,
+        _period: StatementPeriod,
+        _limit: usize,
+    ) -> Result<ProviderResponse<Vec<IncomeStatement>>> {
+        Err(unsupported(self.name(), "income statements"))
+    }
+
+    /// Balance sheets, newest first
+    async fn balance_sheets(
+        &self,
+        _symbol: &str,
+        _period: StatementPeriod,
+        _limit: usize,
+    ) -> Result<ProviderResponse<Vec<BalanceSheet>>> {
+        Err(unsupported(self.name(), "balance sheets"))
+    }
+
+    /// Cash flow statements, newest first
+    async fn cash_flow_statements(
+        &self,
+        _symbol: &str,
+        _period: StatementPeriod,
+        _limit: usize,
+    ) -> Result<ProviderResponse<Vec<CashFlowStatement>>> {
+        Err(unsupported(self.name(), "cash flow statements"))
+    }
+
+    /// Vendor-computed financial ratios, newest first
+    async fn financial_ratios(
+        &self,
+        _symbol: &str,
+        _period: StatementPeriod,
+        _limit: usize,
+    ) -> Result<ProviderResponse<Vec<FinancialRatios>>> {
+        Err(unsupported(self.name(), "financial ratios"))
+    }
+
+    /// Vendor key metrics (market cap, enterprise value multiples, ROIC), newest first
+    async fn key_metrics(
+        &self,
+        _symbol: &str,
+        _period: StatementPeriod,
+        _limit: usize,
+    ) -> Result<ProviderResponse<Vec<KeyMetrics>>> {
+        Err(unsupported(self.name(), "key metrics"))
+    }
+
+    /// Vendor period-over-period growth rates, newest first
+    async fn financial_growth(
+        &self,
+        _symbol: &str,
+        _period: StatementPeriod,
+        _limit: usize,
+    ) -> Result<ProviderResponse<Vec<FinancialGrowth>>> {
+        Err(unsupported(self.name(), "financial growth"))
+    }
+
+    /// Treasury yields on the last day on or before `as_of`
+    async fn treasury_yields_as_of(&self, _as_of: NaiveDate) -> Result<ProviderResponse<TreasuryYields>> {
+        Err(unsupported(self.name(), "treasury yields"))
+    }
+
+    /// Most recent `limit` daily bars dated on or before `as_of`, oldest first
+    async fn daily_bars_as_of(
+        &self,
+        symbol: &str,
+        limit: usize,
+        as_of: NaiveDate,
+    ) -> Result<ProviderResponse<Vec<DailyBar>>> {
+        let extra = weekdays_between(as_of, Utc::now().date_naive()) + HOLIDAY_SLACK;
+        let response = self.daily_bars(symbol, limit + extra).await?;
+        Ok(response.map(|bars| bars_as_of(bars, as_of, limit)))
+    }
+
+    /// Income statements already filed on `as_of`, newest first
+    async fn income_statements_as_of(
+        &self,
+        symbol: &str,
+        period: StatementPeriod,
+        limit: usize,
+        as_of: NaiveDate,
+    ) -> Result<ProviderResponse<Vec<IncomeStatement>>> {
+        let response = self.income_statements(symbol, period, limit + extra_periods(period, as_of)).await?;
+        Ok(response.map(|statements| statements_as_of(statements, as_of, limit)))
+    }
+
+    /// Balance sheets already filed on `as_of`, newest first
+    async fn balance_sheets_as_of(
+        &self,
+        symbol: &str,
+        period: StatementPeriod,
+        limit: usize,
+        as_of: NaiveDate,
+    ) -> Result<ProviderResponse<Vec<BalanceSheet>>> {
+        let response = self.balance_sheets(symbol, period, limit + extra_periods(period, as_of)).await?;
+        Ok(response.map(|statements| statements_as_of(statements, as_of, limit)))
+    }
+
+    /// Cash flow statements already filed on `as_of`, newest first
+    async fn cash_flow_statements_as_of(
+        &self,
+        symbol: &str,
+        period: StatementPeriod,
+        limit: usize,
+        as_of: NaiveDate,
+    ) -> Result<ProviderResponse<Vec<CashFlowStatement>>> {
+        let response = self.cash_flow_statements(symbol, period, limit + extra_periods(period, as_of)).await?;
+        Ok(response.map(|statements| statements_as_of(statements, as_of, limit)))
+    }
+
+    /// Financial ratios public on `as_of` (period end plus the filing lag), newest first
+    async fn financial_ratios_as_of(
+        &self,
+        symbol: &str,
+        period: StatementPeriod,
+        limit: usize,
+        as_of: NaiveDate,
+    ) -> Result<ProviderResponse<Vec<FinancialRatios>>> {
+        let response = self.financial_ratios(symbol, period, limit + extra_periods(period, as_of)).await?;
+        Ok(response.map(|ratios| statements_as_of(ratios, as_of, limit)))
+    }
+
+    /// Key metrics public on `as_of` (period end plus the filing lag), newest first
+    async fn key_metrics_as_of(
+        &self,
+        symbol: &str,
+        period: StatementPeriod,
+        limit: usize,
+        as_of: NaiveDate,
+    ) -> Result<ProviderResponse<Vec<KeyMetrics>>> {
+        let response = self.key_metrics(symbol, period, limit + extra_periods(period, as_of)).await?;
+        Ok(response.map(|metrics| statements_as_of(metrics, as_of, limit)))
+    }
+
+    /// Growth rates public on `as_of` (period end plus the filing lag), newest first
+    async fn financial_growth_as_of(
+        &self,
+        symbol: &str,
+        period: StatementPeriod,
+        limit: usize,
+        as_of: NaiveDate,
+    ) -> Result<ProviderResponse<Vec<FinancialGrowth>>> {
+        let response = self.financial_growth(symbol, period, limit + extra_periods(period, as_of)).await?;
+        Ok(response.map(|growth| statements_as_of(growth, as_of, limit)))
+    }
+}
+
+type ProviderFuture<'a, T> = Pin<Box<dyn Future<Output = Result<ProviderResponse<T>>> + Send + 'a>>;
+
+/// Tries a list of providers in order and returns the first successful response
+///
+/// This is the FMP → Alpha Vantage → Finnhub fallback used by the studies.
+#[derive(Clone, Default)]
+pub struct ProviderChain {
+    providers: Vec<Arc<dyn MarketDataProvider>>,
+}
+
+impl ProviderChain {
+    /// Create a chain from an ordered list of providers
+    pub fn new(providers: Vec<Arc<dyn MarketDataProvider>>) -> Self {
+        Self { providers }
+    }
+
+    /// Build the default chain from whichever API keys are set in the environment
+    pub fn from_env() -> Self {
+        let mut chain = Self::default();
+        if let Ok(provider) = FmpProvider::from_env() {
+            chain.push(Arc::new(provider));
+        }
+        if let Ok(provider) = AlphaVantageProvider::from_env() {
+            chain.push(Arc::new(provider));
+        }
+        if let Ok(provider) = FinnhubProvider::from_env() {
+            chain.push(Arc::new(provider));
+        }
+        chain
+    }
+
+    /// Append a provider to the end of the chain
+    pub fn push(&mut self, provider: Arc<dyn MarketDataProvider>) {
+        self.providers.push(provider);
+    }
+
+    /// Number of providers in the chain
+    pub fn len(&self) -> usize {
+        self.providers.len()
+    }
+
+    /// Whether the chain has no providers
+    pub fn is_empty(&self) -> bool {
+        self.providers.is_empty()
+    }
+
+    async fn first_success<'a, T, F>(&'a self, what: &str, call: F) -> Result<ProviderResponse<T>>
+    where
+        F: Fn(&'a dyn MarketDataProvider) -> ProviderFuture<'a, T> + Send,
+        T: Send,
+    {
+        let mut last_error = None;
+        for provider in &self.providers {
+            match call(provider.as_ref()).await {
+                Ok(response) => return Ok(response),
+                Err(e) => {
+                    warn!("{} failed to provide {}: {}", provider.name(), what, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| Error::MissingApiKey("market data provider".to_string())))
+    }
+}
+
+#[async_trait]
+impl MarketDataProvider for ProviderChain {
+    fn name(&self) -> &str {
+        "Provider chain"
+    }
+
+    async fn daily_bars(&self, symbol: &str, limit: usize) -> Result<ProviderResponse<Vec<DailyBar>>> {
+        self.first_success("daily bars", |p| p.daily_bars(symbol, limit)).await
+    }
+
+    async fn quote(&self, symbol: &str) -> Result<ProviderResponse<Quote>> {
+        self.first_success("quote", |p| p.quote(symbol)).await
+    }
+
+    async fn company_profile(&self, symbol: &str) -> Result<ProviderResponse<CompanyProfile>> {
+        self.first_success("company profile", |p| p.company_profile(symbol)).await
+    }
+
+    async fn income_statements(
+        &self,
+        symbol: &str,
+        period: StatementPeriod,
+        limit: usize,
+    ) -> Result<ProviderResponse<Vec<IncomeStatement>>> {
+        self.first_success("income statements", |p| p.income_statements(symbol, period, limit)).await
+    }
+
+    async fn balance_sheets(
+        &self,
+        symbol: &str,
+        period: StatementPeriod,
+        limit: usize,
+    ) -> Result<ProviderResponse<Vec<BalanceSheet>>> {
+        self.first_success("balance sheets", |p| p.balance_sheets(symbol, period, limit)).await
+    }
+
+    async fn cash_flow_statements(
+        &self,
+        symbol: &str,
+        period: StatementPeriod,
+        limit: usize,
+    ) -> Result<ProviderResponse<Vec<CashFlowStatement>>> {
+        self.first_success("cash flow statements", |p| p.cash_flow_statements(symbol, period, limit)).await
+    }
+
+    async fn financial_ratios(
+        &self,
+        symbol: &str,
+        period: StatementPeriod,
+        limit: usize,
+    ) -> Result<ProviderResponse<Vec<FinancialRatios>>> {
+        self.first_success("financial ratios", |p| p.financial_ratios(symbol, period, limit)).await
+    }
+
+    async fn key_metrics(
+        &self,
+        symbol: &str,
+        period: StatementPeriod,
+        limit: usize,
+    ) -> Result<ProviderResponse<Vec<KeyMetrics>>> {
+        self.first_success("key metrics", |p| p.key_metrics(symbol, period, limit)).await
+    }
+
+    async fn financial_growth(
+        &self,
+        symbol: &str,
+        period: StatementPeriod,
+        limit: usize,
+    ) -> Result<ProviderResponse<Vec<FinancialGrowth>>> {
+        self.first_success("financial growth", |p| p.financial_growth(symbol, period, limit)).await
+    }
+
+    async fn treasury_yields_as_of(&self, as_of: NaiveDate) -> Result<ProviderResponse<TreasuryYields>> {
+        self.first_success("treasury yields", |p| p.treasury_yields_as_of(as_of)).await
+    }
+}
+
+fn unsupported(provider: &str, what: &str) -> Error {
+    Error::ApiError(provider.to_string(), format!("{} are not supported by this provider", what))
+}
+
+/// GET a URL and parse the JSON body, mapping HTTP failures to provider errors
+pub(crate) async fn get_json(client: &Client, provider: &str, url: &str) -> Result<Value> {
+    let response = client.get(url).send().await?;
+    let status = response.status();
+
+    if status.as_u16() == 429 {
+        return Err(Error::RateLimitExceeded(provider.to_string()));
+    }
+    if !status.is_success() {
+        let error_text = response.text().await.unwrap_or_default();
+        return Err(Error::ApiError(
+            provider.to_string(),
+            format!("API call failed with status {}: {}", status, error_text),
+        ));
+    }
+
+    Ok(response.json().await?)
+}
+
+/// Read a number that may be encoded as a JSON number or a numeric string
+pub(crate) fn parse_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().trim_end_matches('%').parse::<f64>().ok(),
+        _ => None,
+    }
+    .filter(|v| v.is_finite())
+}
+
+/// Read a volume that may be encoded as an integer, float or string
+pub(crate) fn parse_u64(value: &Value) -> Option<u64> {
+    value
+        .as_u64()
+        .or_else(|| parse_f64(value).filter(|v| *v >= 0.0).map(|v| v as u64))
+}
+
+/// Parse "YYYY-MM-DD", ignoring any trailing time component
+pub(crate) fn parse_date(value: &Value) -> Option<NaiveDate> {
+    let s = value.as_str()?;
+    NaiveDate::parse_from_str(s.get(..10)?, "%Y-%m-%d").ok()
+}
+
+/// Sort bars oldest first and keep the most recent `limit`
+pub(crate) fn finalize_bars(mut bars: Vec<DailyBar>, limit: usize) -> Vec<DailyBar> {
+    bars.sort_by_key(|b| b.date);
+    bars.dedup_by_key(|b| b.date);
+    if bars.len() > limit {
+        bars.drain(..bars.len() - limit);
+    }
+    bars
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn bar(date: &str, close: f64) -> DailyBar {
+        DailyBar {
+            date: NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap(),
+            open: close,
+            high: close,
+            low: close,
+            close,
+            adjusted_close: None,
+            volume: 1_000,
+        }
+    }
+
+    #[test]
+    fn test_parse_helpers() {
+        assert_eq!(parse_f64(&json!(1.5)), Some(1.5));
+        assert_eq!(parse_f64(&json!("2.25")), Some(2.25));
+        assert_eq!(parse_f64(&json!("-0.5%")), Some(-0.5));
+        assert_eq!(parse_f64(&json!("None")), None);
+        assert_eq!(parse_u64(&json!("1200")), Some(1200));
+        assert_eq!(
+            parse_date(&json!("2024-03-01 16:00:00")),
+            NaiveDate::from_ymd_opt(2024, 3, 1)
+        );
+    }
+
+    #[test]
+    fn test_finalize_bars_keeps_most_recent() {
+        let bars = vec![
+            bar("2024-01-04", 4.0),
+            bar("2024-01-02", 2.0),
+            bar("2024-01-03", 3.0),
+            bar("2024-01-01", 1.0),
+        ];
+        let bars = finalize_bars(bars, 2);
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[0].close, 3.0);
+        assert_eq!(bars[1].close, 4.0);
+    }
+
+    #[tokio::test]
+    async fn test_empty_chain_errors() {
+        let chain = ProviderChain::default();
+        assert!(chain.daily_bars("AAPL", 10).await.is_err());
+    }
+
+    struct StaticBars(Vec<DailyBar>);
+
+    #[async_trait]
+    impl MarketDataProvider for StaticBars {
+        fn name(&self) -> &str {
+            "Static"
+        }
+
+        async fn daily_bars(&self, _symbol: &str, limit: usize) -> Result<ProviderResponse<Vec<DailyBar>>> {
+            let data = finalize_bars(self.0.clone(), limit);
+            Ok(ProviderResponse { data, source: "Static".to_string(), url: String::new(), raw: Value::Null })
+        }
+
+        async fn quote(&self, _symbol: &str) -> Result<ProviderResponse<Quote>> {
+            Err(unsupported(self.name(), "quotes"))
+        }
+
+        async fn company_profile(&self, _symbol: &str) -> Result<ProviderResponse<CompanyProfile>> {
+            Err(unsupported(self.name(), "profiles"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_daily_bars_as_of_excludes_later_sessions() {
+        let provider = StaticBars(vec![
+            bar("2024-01-01", 1.0),
+            bar("2024-01-02", 2.0),
+            bar("2024-01-03", 3.0),
+            bar("2024-01-04", 4.0),
+        ]);
+        let as_of = NaiveDate::from_ymd_opt(2024, 1, 3).unwrap();
+
+        let bars = provider.daily_bars_as_of("TEST", 2, as_of).await.unwrap().data;
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[0].close, 2.0);
+        assert_eq!(bars[1].close, 3.0);
+    }
+}
@@ -0,0 +1,254 @@
+// Normalized market data types shared by every provider and study
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Daily OHLCV bar for a single trading session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailyBar {
+    /// Trading date
+    pub date: NaiveDate,
+    /// Opening price
+    pub open: f64,
+    /// High price
+    pub high: f64,
+    /// Low price
+    pub low: f64,
+    /// Closing price
+    pub close: f64,
+    /// Split/dividend adjusted close, when the provider reports it
+    pub adjusted_close: Option<f64>,
+    /// Trading volume
+    pub volume: u64,
+}
+
+impl DailyBar {
+    /// Simple return from `previous` close to this close
+    pub fn return_since(&self, previous: &DailyBar) -> f64 {
+        if previous.close == 0.0 {
+            return 0.0;
+        }
+        (self.close - previous.close) / previous.close
+    }
+}
+
+/// Latest quote for a symbol
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Quote {
+    pub symbol: String,
+    pub price: f64,
+    pub open: Option<f64>,
+    pub high: Option<f64>,
+    pub low: Option<f64>,
+    pub previous_close: Option<f64>,
+    pub change: Option<f64>,
+    /// Change in percent (1.5 means +1.5%)
+    pub change_percent: Option<f64>,
+    pub volume: Option<u64>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Company profile / overview
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CompanyProfile {
+    pub symbol: String,
+    pub name: Option<String>,
+    pub exchange: Option<String>,
+    pub sector: Option<String>,
+    pub industry: Option<String>,
+    pub country: Option<String>,
+    pub currency: Option<String>,
+    pub description: Option<String>,
+    pub cik: Option<String>,
+    pub market_cap: Option<f64>,
+    pub beta: Option<f64>,
+    pub shares_outstanding: Option<f64>,
+    pub average_volume: Option<f64>,
+}
+
+/// Reporting period of a financial statement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum StatementPeriod {
+    Annual,
+    Quarterly,
+}
+
+/// CBOE volatility indices, fetched as daily bars through any provider that carries indices
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum VolatilityIndex {
+    /// 30-day implied volatility of the S&P 500
+    Vix,
+    /// 9-day implied volatility
+    Vix9d,
+    /// 3-month implied volatility
+    Vix3m,
+}
+
+impl VolatilityIndex {
+    /// Index symbol in the caret notation used by FMP and Yahoo
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            VolatilityIndex::Vix => "^VIX",
+            VolatilityIndex::Vix9d => "^VIX9D",
+            VolatilityIndex::Vix3m => "^VIX3M",
+        }
+    }
+}
+
+/// Income statement for one reporting period
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IncomeStatement {
+    /// Fiscal period end date
+    pub date: NaiveDate,
+    /// Date the statement was filed, when known
+    pub filing_date: Option<NaiveDate>,
+    /// Provider period label ("FY", "Q1", ...)
+    pub period: String,
+    pub fiscal_year: Option<i32>,
+    pub reported_currency: Option<String>,
+    pub revenue: Option<f64>,
+    pub cost_of_revenue: Option<f64>,
+    pub gross_profit: Option<f64>,
+    pub research_and_development: Option<f64>,
+    pub selling_general_admin: Option<f64>,
+    pub depreciation_and_amortization: Option<f64>,
+    pub operating_income: Option<f64>,
+    pub interest_expense: Option<f64>,
+    pub income_before_tax: Option<f64>,
+    pub income_tax_expense: Option<f64>,
+    pub net_income: Option<f64>,
+    pub ebitda: Option<f64>,
+    pub eps: Option<f64>,
+    pub eps_diluted: Option<f64>,
+    pub weighted_average_shares: Option<f64>,
+    pub weighted_average_shares_diluted: Option<f64>,
+}
+
+/// Balance sheet at the end of one reporting period
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BalanceSheet {
+    /// Fiscal period end date
+    pub date: NaiveDate,
+    /// Date the statement was filed, when known
+    pub filing_date: Option<NaiveDate>,
+    /// Provider period label ("FY", "Q1", ...)
+    pub period: String,
+    pub fiscal_year: Option<i32>,
+    pub reported_currency: Option<String>,
+    pub cash_and_equivalents: Option<f64>,
+    pub short_term_investments: Option<f64>,
+    pub net_receivables: Option<f64>,
+    pub inventory: Option<f64>,
+    pub total_current_assets: Option<f64>,
+    pub property_plant_equipment_net: Option<f64>,
+    pub goodwill: Option<f64>,
+    pub intangible_assets: Option<f64>,
+    pub total_assets: Option<f64>,
+    pub accounts_payable: Option<f64>,
+    pub short_term_debt: Option<f64>,
+    pub total_current_liabilities: Option<f64>,
+    pub long_term_debt: Option<f64>,
+    pub total_debt: Option<f64>,
+    pub total_liabilities: Option<f64>,
+    pub retained_earnings: Option<f64>,
+    pub total_equity: Option<f64>,
+    pub shares_outstanding: Option<f64>,
+}
+
+/// Cash flow statement for one reporting period
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CashFlowStatement {
+    /// Fiscal period end date
+    pub date: NaiveDate,
+    /// Date the statement was filed, when known
+    pub filing_date: Option<NaiveDate>,
+    /// Provider period label ("FY", "Q1", ...)
+    pub period: String,
+    pub fiscal_year: Option<i32>,
+    pub reported_currency: Option<String>,
+    pub net_income: Option<f64>,
+    pub depreciation_and_amortization: Option<f64>,
+    pub stock_based_compensation: Option<f64>,
+    pub operating_cash_flow: Option<f64>,
+    /// Cash spent on capital expenditure, always reported as a positive number
+    pub capital_expenditure: Option<f64>,
+    pub free_cash_flow: Option<f64>,
+    /// Dividends paid, always reported as a positive number
+    pub dividends_paid: Option<f64>,
+    /// Share repurchases, always reported as a positive number
+    pub common_stock_repurchased: Option<f64>,
+}
+
+/// Vendor-computed ratios for one reporting period
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FinancialRatios {
+    /// Fiscal period end date
+    pub date: NaiveDate,
+    /// Provider period label ("FY", "Q1", ...)
+    pub period: String,
+    pub return_on_equity: Option<f64>,
+    pub return_on_assets: Option<f64>,
+    pub net_profit_margin: Option<f64>,
+    pub gross_profit_margin: Option<f64>,
+    pub operating_profit_margin: Option<f64>,
+    pub price_earnings: Option<f64>,
+    pub price_earnings_to_growth: Option<f64>,
+    pub price_to_sales: Option<f64>,
+    pub price_to_book: Option<f64>,
+    pub price_to_cash_flow: Option<f64>,
+    pub price_to_free_cash_flow: Option<f64>,
+    pub debt_to_equity: Option<f64>,
+    /// Total debt over total assets
+    pub debt_ratio: Option<f64>,
+    pub current_ratio: Option<f64>,
+    pub quick_ratio: Option<f64>,
+    pub interest_coverage: Option<f64>,
+    pub equity_multiplier: Option<f64>,
+    pub asset_turnover: Option<f64>,
+    pub inventory_turnover: Option<f64>,
+    pub receivables_turnover: Option<f64>,
+    pub payables_turnover: Option<f64>,
+    pub days_sales_outstanding: Option<f64>,
+    pub days_inventory_outstanding: Option<f64>,
+    pub days_payables_outstanding: Option<f64>,
+    pub dividend_yield: Option<f64>,
+    pub payout_ratio: Option<f64>,
+}
+
+/// Vendor per-period market and capital metrics
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KeyMetrics {
+    /// Fiscal period end date
+    pub date: NaiveDate,
+    /// Provider period label ("FY", "Q1", ...)
+    pub period: String,
+    /// Market cap at the period end
+    pub market_cap: Option<f64>,
+    pub enterprise_value: Option<f64>,
+    pub ev_to_ebitda: Option<f64>,
+    pub ev_to_sales: Option<f64>,
+    pub roic: Option<f64>,
+    pub dividend_yield: Option<f64>,
+    pub payout_ratio: Option<f64>,
+}
+
+/// Vendor growth rates against the previous period, as decimals (0.1 is 10%)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FinancialGrowth {
+    /// Fiscal period end date
+    pub date: NaiveDate,
+    /// Provider period label ("FY", "Q1", ...)
+    pub period: String,
+    pub revenue_growth: Option<f64>,
+    pub eps_growth: Option<f64>,
+    pub net_income_growth: Option<f64>,
+    pub book_value_per_share_growth: Option<f64>,
+    pub operating_cash_flow_growth: Option<f64>,
+}
+
+/// US Treasury yields on one day, as decimals (0.042 is 4.2%)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TreasuryYields {
+    pub date: NaiveDate,
+    pub ten_year: Option<f64>,
+}
@@ -0,0 +1,203 @@
+// Point-in-time helpers
+// Truncate provider data to what was actually known on a given date
+
+use super::models::*;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc, Weekday};
+
+/// Extra sessions fetched beyond the weekday count to absorb holidays
+pub(crate) const HOLIDAY_SLACK: usize = 10;
+
+/// Days after a fiscal year end before an annual report is assumed public
+pub const ANNUAL_FILING_LAG_DAYS: i64 = 90;
+
+/// Days after a fiscal quarter end before a quarterly report is assumed public
+pub const QUARTERLY_FILING_LAG_DAYS: i64 = 45;
+
+/// Statements that can be placed on a timeline of public availability
+pub trait Filed {
+    /// Fiscal period end date
+    fn period_end(&self) -> NaiveDate;
+    /// Date the statement was filed, when known
+    fn filing_date(&self) -> Option<NaiveDate>;
+    /// Provider period label ("FY", "Q1", ...)
+    fn period_label(&self) -> &str;
+
+    /// First date the statement could have been known to the market
+    ///
+    /// Uses the filing date when the provider reports one, otherwise the
+    /// period end plus the regulatory filing lag.
+    fn available_on(&self) -> NaiveDate {
+        self.filing_date().unwrap_or_else(|| {
+            let lag = if self.period_label() == "FY" {
+                ANNUAL_FILING_LAG_DAYS
+            } else {
+                QUARTERLY_FILING_LAG_DAYS
+            };
+            self.period_end() + Duration::days(lag)
+        })
+    }
+}
+
+macro_rules! impl_filed {
+    ($($statement:ty),*) => {
+        $(impl Filed for $statement {
+            fn period_end(&self) -> NaiveDate {
+                self.date
+            }
+
+            fn filing_date(&self) -> Option<NaiveDate> {
+                self.filing_date
+            }
+
+            fn period_label(&self) -> &str {
+                &self.period
+            }
+        })*
+    };
+}
+
+impl_filed!(IncomeStatement, BalanceSheet, CashFlowStatement);
+
+/// Vendor ratios carry no filing date, so they are placed by the filing lag alone
+macro_rules! impl_filed_by_lag {
+    ($($dataset:ty),*) => {
+        $(impl Filed for $dataset {
+            fn period_end(&self) -> NaiveDate {
+                self.date
+            }
+
+            fn filing_date(&self) -> Option<NaiveDate> {
+                None
+            }
+
+            fn period_label(&self) -> &str {
+                &self.period
+            }
+        })*
+    };
+}
+
+impl_filed_by_lag!(FinancialRatios, KeyMetrics, FinancialGrowth);
+
+/// Timestamp used for results computed as of a trading date (midnight UTC, like bar dates)
+pub fn as_of_timestamp(as_of: NaiveDate) -> DateTime<Utc> {
+    as_of.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc()
+}
+
+/// Whether `as_of` is before today, i.e. a historical (backfill) calculation
+pub fn is_historical(as_of: NaiveDate) -> bool {
+    as_of < Utc::now().date_naive()
+}
+
+/// Number of weekdays after `from` up to and including `to`
+pub fn weekdays_between(from: NaiveDate, to: NaiveDate) -> usize {
+    from.iter_days()
+        .skip(1)
+        .take_while(|day| *day <= to)
+        .filter(|day| !matches!(day.weekday(), Weekday::Sat | Weekday::Sun))
+        .count()
+}
+
+/// Statements needed beyond `limit` so that `limit` remain after dropping those filed after `as_of`
+pub(crate) fn extra_periods(period: StatementPeriod, as_of: NaiveDate) -> usize {
+    let days = (Utc::now().date_naive() - as_of).num_days().max(0) as usize;
+    match period {
+        StatementPeriod::Annual => days / 365 + 1,
+        StatementPeriod::Quarterly => days / 91 + 1,
+    }
+}
+
+/// Keep the most recent `limit` bars dated on or before `as_of` (input oldest first)
+pub fn bars_as_of(mut bars: Vec<DailyBar>, as_of: NaiveDate, limit: usize) -> Vec<DailyBar> {
+    bars.retain(|bar| bar.date <= as_of);
+    if bars.len() > limit {
+        bars.drain(..bars.len() - limit);
+    }
+    bars
+}
+
+/// Keep the most recent `limit` statements public on `as_of` (input newest first)
+pub fn statements_as_of<T: Filed>(mut statements: Vec<T>, as_of: NaiveDate, limit: usize) -> Vec<T> {
+    statements.retain(|statement| statement.available_on() <= as_of);
+    statements.truncate(limit);
+    statements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn income(period_end: &str, filing_date: Option<&str>, period: &str) -> IncomeStatement {
+        IncomeStatement {
+            date: date(period_end),
+            filing_date: filing_date.map(date),
+            period: period.to_string(),
+            fiscal_year: None,
+            reported_currency: None,
+            revenue: Some(1.0),
+            cost_of_revenue: None,
+            gross_profit: None,
+            research_and_development: None,
+            selling_general_admin: None,
+            depreciation_and_amortization: None,
+            operating_income: None,
+            interest_expense: None,
+            income_before_tax: None,
+            income_tax_expense: None,
+            net_income: None,
+            ebitda: None,
+            eps: None,
+            eps_diluted: None,
+            weighted_average_shares: None,
+            weighted_average_shares_diluted: None,
+        }
+    }
+
+    #[test]
+    fn test_weekdays_between() {
+        // Friday to the following Monday
+        assert_eq!(weekdays_between(date("2024-03-01"), date("2024-03-04")), 1);
+        assert_eq!(weekdays_between(date("2024-03-01"), date("2024-03-15")), 10);
+        assert_eq!(weekdays_between(date("2024-03-04"), date("2024-03-01")), 0);
+    }
+
+    #[test]
+    fn test_statements_respect_filing_dates() {
+        let statements = vec![
+            // Filed after the as-of date
+            income("2023-12-31", Some("2024-02-02"), "FY"),
+            // No filing date: assumed public 90 days after year end
+            income("2022-12-31", None, "FY"),
+            income("2021-12-31", Some("2022-01-28"), "FY"),
+        ];
+
+        let known = statements_as_of(statements, date("2024-01-15"), 5);
+        assert_eq!(known.len(), 2);
+        assert_eq!(known[0].date, date("2022-12-31"));
+
+        let early = income("2023-09-30", None, "Q3");
+        assert_eq!(early.available_on(), date("2023-11-14"));
+    }
+
+    #[test]
+    fn test_vendor_ratios_use_filing_lag() {
+        let ratios = |period_end: &str, period: &str| FinancialRatios {
+            date: date(period_end),
+            period: period.to_string(),
+            ..Default::default()
+        };
+        let known = statements_as_of(
+            vec![ratios("2023-12-31", "FY"), ratios("2023-09-30", "Q3"), ratios("2022-12-31", "FY")],
+            date("2024-02-15"),
+            5,
+        );
+
+        // The 2023 annual ratios are not public until 90 days after year end
+        assert_eq!(known.len(), 2);
+        assert_eq!(known[0].date, date("2023-09-30"));
+    }
+}
@@ -38,6 +38,7 @@ BEGIN
             'market_regime', 'market_regime_history', 'latest_market_regime',
             'fundamentals', 'fundamentals_history', 'latest_fundamentals',
             'sentiment', 'sentiment_history', 'latest_sentiment',
+            'estimate_snapshots',
             'regime'
         )
     ) LOOP
@@ -224,6 +225,7 @@ CREATE TABLE timing_history (
     
     -- Metadata
     flags JSONB DEFAULT '[]'::jsonb,
+    config_hash VARCHAR(16),
     created_at TIMESTAMPTZ DEFAULT NOW(),
     updated_at TIMESTAMPTZ DEFAULT NOW(),
     
@@ -236,6 +238,7 @@ CREATE INDEX idx_timing_history_date ON timing_history(analysis_date DESC);
 CREATE INDEX idx_timing_history_score ON timing_history(tts_score);
 CREATE INDEX idx_timing_history_signal ON timing_history(trading_signal);
 CREATE INDEX idx_timing_history_created ON timing_history(created_at DESC);
+CREATE INDEX idx_timing_history_config ON timing_history(config_hash);
 
 -- Create view for latest timing
 CREATE VIEW timing AS
@@ -307,12 +310,17 @@ CREATE TABLE market_regime_history (
     spy_20d_change DECIMAL(8,6),
     spy_50d_change DECIMAL(8,6),
     vix DECIMAL(6,2),
+    vix9d DECIMAL(6,2),
+    vix3m DECIMAL(6,2),
     market_breadth DECIMAL(5,4),
     sector_relative_performance DECIMAL(8,6),
     
     -- Market Volatility Analysis
     market_volatility DECIMAL(8,6),
     volatility_percentile DECIMAL(5,2),
+    vix_percentile DECIMAL(5,2),
+    vix_term_structure DECIMAL(6,4),
+    volatility_trend VARCHAR(20),
     
     -- Market Trend Analysis
     short_term_trend VARCHAR(20),
@@ -327,6 +335,10 @@ CREATE TABLE market_regime_history (
     unchanged_stocks INTEGER,
     new_highs INTEGER,
     new_lows INTEGER,
+    percent_above_sma50 DECIMAL(5,2),
+    percent_above_sma200 DECIMAL(5,2),
+    advance_decline_line BIGINT,
+    mcclellan_oscillator DECIMAL(8,2),
     
     -- Sector Analysis
     technology_performance DECIMAL(8,6),
@@ -334,6 +346,10 @@ CREATE TABLE market_regime_history (
     financial_performance DECIMAL(8,6),
     energy_performance DECIMAL(8,6),
     consumer_performance DECIMAL(8,6),
+    leading_sector VARCHAR(50),
+    lagging_sector VARCHAR(50),
+    sector_rotation VARCHAR(20),
+    sector_performance JSONB,
     
     -- Market Sentiment Indicators
     fear_greed_index INTEGER,
@@ -472,6 +488,24 @@ CREATE TABLE fundamentals_history (
     equity_multiplier DECIMAL(8,2),
     altman_z_score DECIMAL(8,2),
     
+    -- Quality scores
+    piotroski_f_score INTEGER,
+    beneish_m_score DECIMAL(8,2),
+    sloan_accrual_ratio DECIMAL(8,4),
+    
+    -- Peer-relative percentiles (0-100 within industry or sector)
+    peer_group_level VARCHAR(20),
+    peer_group VARCHAR(100),
+    peer_group_size INTEGER,
+    valuation_percentiles JSONB,
+    profitability_percentiles JSONB,
+    
+    -- DCF valuation (full projection and sensitivity grid in dcf_valuation)
+    dcf_intrinsic_value DECIMAL(14,2),
+    dcf_margin_of_safety DECIMAL(8,4),
+    dcf_wacc DECIMAL(6,4),
+    dcf_valuation JSONB,
+    
     -- Efficiency metrics
     asset_turnover DECIMAL(8,2),
     inventory_turnover DECIMAL(8,2),
@@ -617,12 +651,16 @@ CREATE TABLE sentiment_history (
     relative_strength_score DECIMAL(5,3) NOT NULL,
     short_interest_score DECIMAL(5,3) NOT NULL,
     options_flow_score DECIMAL(5,3) NOT NULL,
+    news_score DECIMAL(5,3) NOT NULL DEFAULT 0,
+    pead_score DECIMAL(5,3) NOT NULL DEFAULT 0,
     
     -- Component weights
-    earnings_weight DECIMAL(3,2) NOT NULL DEFAULT 0.40,
-    relative_strength_weight DECIMAL(3,2) NOT NULL DEFAULT 0.30,
-    short_interest_weight DECIMAL(3,2) NOT NULL DEFAULT 0.20,
+    earnings_weight DECIMAL(3,2) NOT NULL DEFAULT 0.30,
+    relative_strength_weight DECIMAL(3,2) NOT NULL DEFAULT 0.20,
+    short_interest_weight DECIMAL(3,2) NOT NULL DEFAULT 0.15,
     options_flow_weight DECIMAL(3,2) NOT NULL DEFAULT 0.10,
+    news_weight DECIMAL(3,2) NOT NULL DEFAULT 0.15,
+    pead_weight DECIMAL(3,2) NOT NULL DEFAULT 0.10,
     
     -- API endpoint information
     earnings_api_url TEXT,
@@ -654,7 +692,7 @@ CREATE TABLE sentiment_history (
     trend_direction DECIMAL(5,3) NOT NULL,
     data_freshness_score DECIMAL(3,2) NOT NULL,
     
-    -- Warning flags
+    -- Data quality flag codes (earnings_window, rate_limited, ...) and the components they left empty
     warning_flags TEXT[],
     missing_data_components TEXT[],
     
@@ -669,6 +707,7 @@ CREATE TABLE sentiment_history (
     -- Market context
     market_benchmark_return DECIMAL(8,4),
     sector_benchmark_return DECIMAL(8,4),
+    sector_benchmark VARCHAR(10),
     relative_to_market DECIMAL(8,4),
     relative_to_sector DECIMAL(8,4),
     
@@ -680,6 +719,22 @@ CREATE TABLE sentiment_history (
     previous_revenue_estimate BIGINT,
     revenue_change_percentage DECIMAL(8,4),
     analyst_count INTEGER,
+    estimate_dispersion DECIMAL(8,4),
+    
+    -- Consensus revision momentum (from estimate_snapshots)
+    eps_revision_7d DECIMAL(8,4),
+    eps_revision_30d DECIMAL(8,4),
+    eps_revision_90d DECIMAL(8,4),
+    revenue_revision_30d DECIMAL(8,4),
+    estimate_revisions_up INTEGER,
+    estimate_revisions_down INTEGER,
+    
+    -- Earnings surprise and post-earnings drift (from the earnings calendar)
+    standardized_unexpected_earnings DECIMAL(8,4),
+    eps_surprise_percent DECIMAL(8,4),
+    earnings_abnormal_return DECIMAL(8,4),
+    days_since_earnings INTEGER,
+    earnings_surprises JSONB,
     
     -- Price data
     current_price DECIMAL(10,2),
@@ -689,6 +744,22 @@ CREATE TABLE sentiment_history (
     return_30d DECIMAL(8,4),
     volume_ratio DECIMAL(8,4),
     
+    -- FINRA short volume
+    short_volume_ratio DECIMAL(8,4),
+    short_volume_zscore DECIMAL(8,4),
+    days_to_cover DECIMAL(10,2),
+    
+    -- Options flow
+    put_call_ratio DECIMAL(8,4),
+    options_premium_skew DECIMAL(6,4),
+    unusual_options_contracts INTEGER,
+    
+    -- News sentiment
+    news_sentiment_score DECIMAL(6,4),
+    news_article_count INTEGER,
+    news_articles JSONB,
+    news_llm_score DECIMAL(6,4),
+    
     -- Timestamps
     created_at TIMESTAMPTZ DEFAULT NOW(),
     updated_at TIMESTAMPTZ DEFAULT NOW(),
@@ -758,6 +829,30 @@ BEGIN
 END;
 $$ LANGUAGE plpgsql;
 
+-- Consensus estimate snapshots, one per symbol, day and targeted fiscal period
+CREATE TABLE estimate_snapshots (
+    id BIGSERIAL PRIMARY KEY,
+    symbol VARCHAR(10) NOT NULL,
+    snapshot_date DATE NOT NULL,
+    fiscal_period DATE,
+    
+    -- Consensus and analyst range
+    eps_estimate DECIMAL(12,4) NOT NULL,
+    eps_high DECIMAL(12,4),
+    eps_low DECIMAL(12,4),
+    revenue_estimate DECIMAL(20,2) NOT NULL,
+    revenue_high DECIMAL(20,2),
+    revenue_low DECIMAL(20,2),
+    recommendation DECIMAL(4,3) NOT NULL DEFAULT 0,
+    analyst_count INTEGER NOT NULL DEFAULT 0,
+    
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    
+    CONSTRAINT unique_estimate_snapshot UNIQUE NULLS NOT DISTINCT (symbol, snapshot_date, fiscal_period)
+);
+
+CREATE INDEX idx_estimate_snapshots_symbol_date ON estimate_snapshots(symbol, snapshot_date DESC);
+
 -- ============================================================================
 -- STEP 7: ADD COMMENTS
 -- ============================================================================
@@ -767,6 +862,7 @@ COMMENT ON TABLE timing_history IS 'Time-series storage of timing (TTS) analysis
 COMMENT ON TABLE market_regime_history IS 'Time-series storage of market regime analysis. Overall market conditions.';
 COMMENT ON TABLE fundamentals_history IS 'Time-series storage of fundamentals analysis. Financial health scores.';
 COMMENT ON TABLE sentiment_history IS 'Time-series storage of sentiment (QSS) analysis. Market sentiment scores.';
+COMMENT ON TABLE estimate_snapshots IS 'Daily consensus EPS/revenue estimates per symbol. Source of estimate revision momentum.';
 
 COMMENT ON VIEW invite_list IS 'Latest invite list analysis per symbol.';
 COMMENT ON VIEW timing IS 'Latest timing analysis per symbol.';
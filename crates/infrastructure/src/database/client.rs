@@ -1,7 +1,9 @@
 use super::models::DatabaseConfig;
 use buenotea_core::Result;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
 use reqwest::Client;
-use serde::{Serialize, de::DeserializeOwned};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::collections::BTreeSet;
 
 pub struct DatabaseClient {
     client: Client,
@@ -145,4 +147,32 @@ impl DatabaseClient {
             Err(buenotea_core::Error::DatabaseError(format!("Delete failed: {}", error_text)))
         }
     }
+
+    /// Dates between `from` and `to` (inclusive) that already have a row in a `*_history` table
+    ///
+    /// Pass `symbol` for per-symbol tables; `None` for market-wide tables such as market regime.
+    pub async fn analysis_dates(
+        &self,
+        table: &str,
+        symbol: Option<&str>,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<BTreeSet<NaiveDate>> {
+        #[derive(Deserialize)]
+        struct AnalysisDate {
+            analysis_date: DateTime<Utc>,
+        }
+
+        let mut filter = format!(
+            "select=analysis_date&analysis_date=gte.{}T00:00:00Z&analysis_date=lt.{}T00:00:00Z&order=analysis_date.asc",
+            from,
+            to + Duration::days(1)
+        );
+        if let Some(symbol) = symbol {
+            filter.push_str(&format!("&symbol=eq.{}", symbol));
+        }
+
+        let rows: Vec<AnalysisDate> = self.query(table, Some(&filter)).await?;
+        Ok(rows.into_iter().map(|row| row.analysis_date.date_naive()).collect())
+    }
 }
@@ -62,6 +62,36 @@ pub struct FundamentalsRecord {
     pub equity_multiplier: Option<f64>,
     pub altman_z_score: Option<f64>,
     
+    // Quality scores
+    #[serde(default)]
+    pub piotroski_f_score: Option<i32>,
+    #[serde(default)]
+    pub beneish_m_score: Option<f64>,
+    #[serde(default)]
+    pub sloan_accrual_ratio: Option<f64>,
+    
+    // Peer-relative percentiles
+    #[serde(default)]
+    pub peer_group_level: Option<String>,
+    #[serde(default)]
+    pub peer_group: Option<String>,
+    #[serde(default)]
+    pub peer_group_size: Option<i32>,
+    #[serde(default)]
+    pub valuation_percentiles: Option<serde_json::Value>,
+    #[serde(default)]
+    pub profitability_percentiles: Option<serde_json::Value>,
+    
+    // DCF valuation
+    #[serde(default)]
+    pub dcf_intrinsic_value: Option<f64>,
+    #[serde(default)]
+    pub dcf_margin_of_safety: Option<f64>,
+    #[serde(default)]
+    pub dcf_wacc: Option<f64>,
+    #[serde(default)]
+    pub dcf_valuation: Option<serde_json::Value>,
+    
     // Efficiency metrics
     pub asset_turnover: Option<f64>,
     pub inventory_turnover: Option<f64>,
@@ -180,6 +210,24 @@ pub struct CreateFundamentalsRecord {
     pub equity_multiplier: Option<f64>,
     pub altman_z_score: Option<f64>,
     
+    // Quality scores
+    pub piotroski_f_score: Option<i32>,
+    pub beneish_m_score: Option<f64>,
+    pub sloan_accrual_ratio: Option<f64>,
+    
+    // Peer-relative percentiles
+    pub peer_group_level: Option<String>,
+    pub peer_group: Option<String>,
+    pub peer_group_size: Option<i32>,
+    pub valuation_percentiles: Option<serde_json::Value>,
+    pub profitability_percentiles: Option<serde_json::Value>,
+    
+    // DCF valuation
+    pub dcf_intrinsic_value: Option<f64>,
+    pub dcf_margin_of_safety: Option<f64>,
+    pub dcf_wacc: Option<f64>,
+    pub dcf_valuation: Option<serde_json::Value>,
+    
     // Efficiency metrics
     pub asset_turnover: Option<f64>,
     pub inventory_turnover: Option<f64>,
@@ -294,6 +342,24 @@ pub struct FundamentalsInsert {
     pub equity_multiplier: Option<f64>,
     pub altman_z_score: Option<f64>,
     
+    // Quality scores
+    pub piotroski_f_score: Option<i32>,
+    pub beneish_m_score: Option<f64>,
+    pub sloan_accrual_ratio: Option<f64>,
+    
+    // Peer-relative percentiles
+    pub peer_group_level: Option<String>,
+    pub peer_group: Option<String>,
+    pub peer_group_size: Option<i32>,
+    pub valuation_percentiles: Option<serde_json::Value>,
+    pub profitability_percentiles: Option<serde_json::Value>,
+    
+    // DCF valuation
+    pub dcf_intrinsic_value: Option<f64>,
+    pub dcf_margin_of_safety: Option<f64>,
+    pub dcf_wacc: Option<f64>,
+    pub dcf_valuation: Option<serde_json::Value>,
+    
     // Efficiency metrics
     pub asset_turnover: Option<f64>,
     pub inventory_turnover: Option<f64>,
@@ -395,6 +461,18 @@ impl From<CreateFundamentalsRecord> for FundamentalsInsert {
             cash_to_debt: record.cash_to_debt,
             equity_multiplier: record.equity_multiplier,
             altman_z_score: record.altman_z_score,
+            piotroski_f_score: record.piotroski_f_score,
+            beneish_m_score: record.beneish_m_score,
+            sloan_accrual_ratio: record.sloan_accrual_ratio,
+            peer_group_level: record.peer_group_level,
+            peer_group: record.peer_group,
+            peer_group_size: record.peer_group_size,
+            valuation_percentiles: record.valuation_percentiles,
+            profitability_percentiles: record.profitability_percentiles,
+            dcf_intrinsic_value: record.dcf_intrinsic_value,
+            dcf_margin_of_safety: record.dcf_margin_of_safety,
+            dcf_wacc: record.dcf_wacc,
+            dcf_valuation: record.dcf_valuation,
             asset_turnover: record.asset_turnover,
             inventory_turnover: record.inventory_turnover,
             receivables_turnover: record.receivables_turnover,
@@ -20,11 +20,21 @@ pub struct MarketRegimeRecord {
     pub spy_20d_change: Option<f64>,
     pub spy_50d_change: Option<f64>,
     pub vix: Option<f64>,
+    #[serde(default)]
+    pub vix9d: Option<f64>,
+    #[serde(default)]
+    pub vix3m: Option<f64>,
     pub market_breadth: Option<f64>,
     
     // Market Volatility Analysis
     pub market_volatility: f64,
     pub volatility_percentile: f64,
+    #[serde(default)]
+    pub vix_percentile: Option<f64>,
+    #[serde(default)]
+    pub vix_term_structure: Option<f64>,
+    #[serde(default)]
+    pub volatility_trend: Option<String>,
     
     // Market Trend Analysis
     pub short_term_trend: String,
@@ -39,6 +49,14 @@ pub struct MarketRegimeRecord {
     pub unchanged_stocks: Option<i32>,
     pub new_highs: Option<i32>,
     pub new_lows: Option<i32>,
+    #[serde(default)]
+    pub percent_above_sma50: Option<f64>,
+    #[serde(default)]
+    pub percent_above_sma200: Option<f64>,
+    #[serde(default)]
+    pub advance_decline_line: Option<i64>,
+    #[serde(default)]
+    pub mcclellan_oscillator: Option<f64>,
     
     // Sector Analysis
     pub technology_performance: Option<f64>,
@@ -46,6 +64,14 @@ pub struct MarketRegimeRecord {
     pub financial_performance: Option<f64>,
     pub energy_performance: Option<f64>,
     pub consumer_performance: Option<f64>,
+    #[serde(default)]
+    pub leading_sector: Option<String>,
+    #[serde(default)]
+    pub lagging_sector: Option<String>,
+    #[serde(default)]
+    pub sector_rotation: Option<String>,
+    #[serde(default)]
+    pub sector_performance: Option<serde_json::Value>,
     
     // Market Sentiment Indicators
     pub fear_greed_index: Option<i32>,
@@ -86,11 +112,16 @@ pub struct CreateMarketRegimeRecord {
     pub spy_20d_change: Option<f64>,
     pub spy_50d_change: Option<f64>,
     pub vix: Option<f64>,
+    pub vix9d: Option<f64>,
+    pub vix3m: Option<f64>,
     pub market_breadth: Option<f64>,
     
     // Market Volatility Analysis
     pub market_volatility: f64,
     pub volatility_percentile: f64,
+    pub vix_percentile: Option<f64>,
+    pub vix_term_structure: Option<f64>,
+    pub volatility_trend: Option<String>,
     
     // Market Trend Analysis
     pub short_term_trend: String,
@@ -105,6 +136,10 @@ pub struct CreateMarketRegimeRecord {
     pub unchanged_stocks: Option<i32>,
     pub new_highs: Option<i32>,
     pub new_lows: Option<i32>,
+    pub percent_above_sma50: Option<f64>,
+    pub percent_above_sma200: Option<f64>,
+    pub advance_decline_line: Option<i64>,
+    pub mcclellan_oscillator: Option<f64>,
     
     // Sector Analysis
     pub technology_performance: Option<f64>,
@@ -112,6 +147,10 @@ pub struct CreateMarketRegimeRecord {
     pub financial_performance: Option<f64>,
     pub energy_performance: Option<f64>,
     pub consumer_performance: Option<f64>,
+    pub leading_sector: Option<String>,
+    pub lagging_sector: Option<String>,
+    pub sector_rotation: Option<String>,
+    pub sector_performance: Option<serde_json::Value>,
     
     // Market Sentiment Indicators
     pub fear_greed_index: Option<i32>,
@@ -146,9 +185,14 @@ pub struct MarketRegimeInsert {
     pub spy_20d_change: Option<f64>,
     pub spy_50d_change: Option<f64>,
     pub vix: Option<f64>,
+    pub vix9d: Option<f64>,
+    pub vix3m: Option<f64>,
     pub market_breadth: Option<f64>,
     pub market_volatility: f64,
     pub volatility_percentile: f64,
+    pub vix_percentile: Option<f64>,
+    pub vix_term_structure: Option<f64>,
+    pub volatility_trend: Option<String>,
     pub short_term_trend: String,
     pub medium_term_trend: String,
     pub long_term_trend: String,
@@ -159,11 +203,19 @@ pub struct MarketRegimeInsert {
     pub unchanged_stocks: Option<i32>,
     pub new_highs: Option<i32>,
     pub new_lows: Option<i32>,
+    pub percent_above_sma50: Option<f64>,
+    pub percent_above_sma200: Option<f64>,
+    pub advance_decline_line: Option<i64>,
+    pub mcclellan_oscillator: Option<f64>,
     pub technology_performance: Option<f64>,
     pub healthcare_performance: Option<f64>,
     pub financial_performance: Option<f64>,
     pub energy_performance: Option<f64>,
     pub consumer_performance: Option<f64>,
+    pub leading_sector: Option<String>,
+    pub lagging_sector: Option<String>,
+    pub sector_rotation: Option<String>,
+    pub sector_performance: Option<serde_json::Value>,
     pub fear_greed_index: Option<i32>,
     pub put_call_ratio: Option<f64>,
     pub margin_debt_trend: Option<String>,
@@ -190,9 +242,14 @@ impl From<CreateMarketRegimeRecord> for MarketRegimeInsert {
             spy_20d_change: record.spy_20d_change,
             spy_50d_change: record.spy_50d_change,
             vix: record.vix,
+            vix9d: record.vix9d,
+            vix3m: record.vix3m,
             market_breadth: record.market_breadth,
             market_volatility: record.market_volatility,
             volatility_percentile: record.volatility_percentile,
+            vix_percentile: record.vix_percentile,
+            vix_term_structure: record.vix_term_structure,
+            volatility_trend: record.volatility_trend,
             short_term_trend: record.short_term_trend,
             medium_term_trend: record.medium_term_trend,
             long_term_trend: record.long_term_trend,
@@ -203,11 +260,19 @@ impl From<CreateMarketRegimeRecord> for MarketRegimeInsert {
             unchanged_stocks: record.unchanged_stocks,
             new_highs: record.new_highs,
             new_lows: record.new_lows,
+            percent_above_sma50: record.percent_above_sma50,
+            percent_above_sma200: record.percent_above_sma200,
+            advance_decline_line: record.advance_decline_line,
+            mcclellan_oscillator: record.mcclellan_oscillator,
             technology_performance: record.technology_performance,
             healthcare_performance: record.healthcare_performance,
             financial_performance: record.financial_performance,
             energy_performance: record.energy_performance,
             consumer_performance: record.consumer_performance,
+            leading_sector: record.leading_sector,
+            lagging_sector: record.lagging_sector,
+            sector_rotation: record.sector_rotation,
+            sector_performance: record.sector_performance,
             fear_greed_index: record.fear_greed_index,
             put_call_ratio: record.put_call_ratio,
             margin_debt_trend: record.margin_debt_trend,
@@ -2,7 +2,7 @@
 // Stores sentiment analysis in the database
 // Note: These are database-specific types. Conversion from sentiment crate types happens in the sentiment crate.
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
 /// Sentiment database record (read from database)
@@ -22,12 +22,20 @@ pub struct SentimentRecord {
     pub relative_strength_score: f64,
     pub short_interest_score: f64,
     pub options_flow_score: f64,
+    #[serde(default)]
+    pub news_score: f64,
+    #[serde(default)]
+    pub pead_score: f64,
     
     // Component weights (for reference)
     pub earnings_weight: f64,
     pub relative_strength_weight: f64,
     pub short_interest_weight: f64,
     pub options_flow_weight: f64,
+    #[serde(default)]
+    pub news_weight: f64,
+    #[serde(default)]
+    pub pead_weight: f64,
     
     // API endpoint information
     pub earnings_api_url: Option<String>,
@@ -74,6 +82,8 @@ pub struct SentimentRecord {
     // Market context
     pub market_benchmark_return: Option<f64>,
     pub sector_benchmark_return: Option<f64>,
+    #[serde(default)]
+    pub sector_benchmark: Option<String>,
     pub relative_to_market: Option<f64>,
     pub relative_to_sector: Option<f64>,
     
@@ -85,6 +95,30 @@ pub struct SentimentRecord {
     pub previous_revenue_estimate: Option<i64>,
     pub revenue_change_percentage: Option<f64>,
     pub analyst_count: Option<i32>,
+    #[serde(default)]
+    pub estimate_dispersion: Option<f64>,
+    #[serde(default)]
+    pub eps_revision_7d: Option<f64>,
+    #[serde(default)]
+    pub eps_revision_30d: Option<f64>,
+    #[serde(default)]
+    pub eps_revision_90d: Option<f64>,
+    #[serde(default)]
+    pub revenue_revision_30d: Option<f64>,
+    #[serde(default)]
+    pub estimate_revisions_up: Option<i32>,
+    #[serde(default)]
+    pub estimate_revisions_down: Option<i32>,
+    #[serde(default)]
+    pub standardized_unexpected_earnings: Option<f64>,
+    #[serde(default)]
+    pub eps_surprise_percent: Option<f64>,
+    #[serde(default)]
+    pub earnings_abnormal_return: Option<f64>,
+    #[serde(default)]
+    pub days_since_earnings: Option<i32>,
+    #[serde(default)]
+    pub earnings_surprises: Option<serde_json::Value>,
     
     // Price data
     pub current_price: Option<f64>,
@@ -94,6 +128,32 @@ pub struct SentimentRecord {
     pub return_30d: Option<f64>,
     pub volume_ratio: Option<f64>,
     
+    // FINRA short volume
+    #[serde(default)]
+    pub short_volume_ratio: Option<f64>,
+    #[serde(default)]
+    pub short_volume_zscore: Option<f64>,
+    #[serde(default)]
+    pub days_to_cover: Option<f64>,
+    
+    // Options flow
+    #[serde(default)]
+    pub put_call_ratio: Option<f64>,
+    #[serde(default)]
+    pub options_premium_skew: Option<f64>,
+    #[serde(default)]
+    pub unusual_options_contracts: Option<i32>,
+    
+    // News sentiment
+    #[serde(default)]
+    pub news_sentiment_score: Option<f64>,
+    #[serde(default)]
+    pub news_article_count: Option<i32>,
+    #[serde(default)]
+    pub news_articles: Option<serde_json::Value>,
+    #[serde(default)]
+    pub news_llm_score: Option<f64>,
+    
     // Metadata
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -115,12 +175,16 @@ pub struct CreateSentimentRecord {
     pub relative_strength_score: f64,
     pub short_interest_score: f64,
     pub options_flow_score: f64,
+    pub news_score: f64,
+    pub pead_score: f64,
     
     // Component weights
     pub earnings_weight: f64,
     pub relative_strength_weight: f64,
     pub short_interest_weight: f64,
     pub options_flow_weight: f64,
+    pub news_weight: f64,
+    pub pead_weight: f64,
     
     // API endpoint information
     pub earnings_api_url: Option<String>,
@@ -164,6 +228,7 @@ pub struct CreateSentimentRecord {
     // Market context
     pub market_benchmark_return: Option<f64>,
     pub sector_benchmark_return: Option<f64>,
+    pub sector_benchmark: Option<String>,
     pub relative_to_market: Option<f64>,
     pub relative_to_sector: Option<f64>,
     
@@ -175,6 +240,18 @@ pub struct CreateSentimentRecord {
     pub previous_revenue_estimate: Option<i64>,
     pub revenue_change_percentage: Option<f64>,
     pub analyst_count: Option<i32>,
+    pub estimate_dispersion: Option<f64>,
+    pub eps_revision_7d: Option<f64>,
+    pub eps_revision_30d: Option<f64>,
+    pub eps_revision_90d: Option<f64>,
+    pub revenue_revision_30d: Option<f64>,
+    pub estimate_revisions_up: Option<i32>,
+    pub estimate_revisions_down: Option<i32>,
+    pub standardized_unexpected_earnings: Option<f64>,
+    pub eps_surprise_percent: Option<f64>,
+    pub earnings_abnormal_return: Option<f64>,
+    pub days_since_earnings: Option<i32>,
+    pub earnings_surprises: Option<serde_json::Value>,
     
     // Price data
     pub current_price: Option<f64>,
@@ -183,6 +260,22 @@ pub struct CreateSentimentRecord {
     pub return_15d: Option<f64>,
     pub return_30d: Option<f64>,
     pub volume_ratio: Option<f64>,
+    
+    // FINRA short volume
+    pub short_volume_ratio: Option<f64>,
+    pub short_volume_zscore: Option<f64>,
+    pub days_to_cover: Option<f64>,
+    
+    // Options flow
+    pub put_call_ratio: Option<f64>,
+    pub options_premium_skew: Option<f64>,
+    pub unusual_options_contracts: Option<i32>,
+    
+    // News sentiment
+    pub news_sentiment_score: Option<f64>,
+    pub news_article_count: Option<i32>,
+    pub news_articles: Option<serde_json::Value>,
+    pub news_llm_score: Option<f64>,
 }
 
 /// Sentiment record insert (for database inserts, without auto-generated fields)
@@ -201,12 +294,16 @@ pub struct SentimentInsert {
     pub relative_strength_score: f64,
     pub short_interest_score: f64,
     pub options_flow_score: f64,
+    pub news_score: f64,
+    pub pead_score: f64,
     
     // Component weights
     pub earnings_weight: f64,
     pub relative_strength_weight: f64,
     pub short_interest_weight: f64,
     pub options_flow_weight: f64,
+    pub news_weight: f64,
+    pub pead_weight: f64,
     
     // API endpoint information
     pub earnings_api_url: Option<String>,
@@ -250,6 +347,7 @@ pub struct SentimentInsert {
     // Market context
     pub market_benchmark_return: Option<f64>,
     pub sector_benchmark_return: Option<f64>,
+    pub sector_benchmark: Option<String>,
     pub relative_to_market: Option<f64>,
     pub relative_to_sector: Option<f64>,
     
@@ -261,6 +359,18 @@ pub struct SentimentInsert {
     pub previous_revenue_estimate: Option<i64>,
     pub revenue_change_percentage: Option<f64>,
     pub analyst_count: Option<i32>,
+    pub estimate_dispersion: Option<f64>,
+    pub eps_revision_7d: Option<f64>,
+    pub eps_revision_30d: Option<f64>,
+    pub eps_revision_90d: Option<f64>,
+    pub revenue_revision_30d: Option<f64>,
+    pub estimate_revisions_up: Option<i32>,
+    pub estimate_revisions_down: Option<i32>,
+    pub standardized_unexpected_earnings: Option<f64>,
+    pub eps_surprise_percent: Option<f64>,
+    pub earnings_abnormal_return: Option<f64>,
+    pub days_since_earnings: Option<i32>,
+    pub earnings_surprises: Option<serde_json::Value>,
     
     // Price data
     pub current_price: Option<f64>,
@@ -269,6 +379,22 @@ pub struct SentimentInsert {
     pub return_15d: Option<f64>,
     pub return_30d: Option<f64>,
     pub volume_ratio: Option<f64>,
+    
+    // FINRA short volume
+    pub short_volume_ratio: Option<f64>,
+    pub short_volume_zscore: Option<f64>,
+    pub days_to_cover: Option<f64>,
+    
+    // Options flow
+    pub put_call_ratio: Option<f64>,
+    pub options_premium_skew: Option<f64>,
+    pub unusual_options_contracts: Option<i32>,
+    
+    // News sentiment
+    pub news_sentiment_score: Option<f64>,
+    pub news_article_count: Option<i32>,
+    pub news_articles: Option<serde_json::Value>,
+    pub news_llm_score: Option<f64>,
 }
 
 impl From<CreateSentimentRecord> for SentimentInsert {
@@ -283,10 +409,14 @@ impl From<CreateSentimentRecord> for SentimentInsert {
             relative_strength_score: record.relative_strength_score,
             short_interest_score: record.short_interest_score,
             options_flow_score: record.options_flow_score,
+            news_score: record.news_score,
+            pead_score: record.pead_score,
             earnings_weight: record.earnings_weight,
             relative_strength_weight: record.relative_strength_weight,
             short_interest_weight: record.short_interest_weight,
             options_flow_weight: record.options_flow_weight,
+            news_weight: record.news_weight,
+            pead_weight: record.pead_weight,
             earnings_api_url: record.earnings_api_url,
             earnings_api_source: record.earnings_api_source,
             earnings_data_available: record.earnings_data_available,
@@ -316,6 +446,7 @@ impl From<CreateSentimentRecord> for SentimentInsert {
             rsi_source: record.rsi_source,
             market_benchmark_return: record.market_benchmark_return,
             sector_benchmark_return: record.sector_benchmark_return,
+            sector_benchmark: record.sector_benchmark,
             relative_to_market: record.relative_to_market,
             relative_to_sector: record.relative_to_sector,
             current_eps_estimate: record.current_eps_estimate,
@@ -325,12 +456,34 @@ impl From<CreateSentimentRecord> for SentimentInsert {
             previous_revenue_estimate: record.previous_revenue_estimate,
             revenue_change_percentage: record.revenue_change_percentage,
             analyst_count: record.analyst_count,
+            estimate_dispersion: record.estimate_dispersion,
+            eps_revision_7d: record.eps_revision_7d,
+            eps_revision_30d: record.eps_revision_30d,
+            eps_revision_90d: record.eps_revision_90d,
+            revenue_revision_30d: record.revenue_revision_30d,
+            estimate_revisions_up: record.estimate_revisions_up,
+            estimate_revisions_down: record.estimate_revisions_down,
+            standardized_unexpected_earnings: record.standardized_unexpected_earnings,
+            eps_surprise_percent: record.eps_surprise_percent,
+            earnings_abnormal_return: record.earnings_abnormal_return,
+            days_since_earnings: record.days_since_earnings,
+            earnings_surprises: record.earnings_surprises,
             current_price: record.current_price,
             price_15d_ago: record.price_15d_ago,
             price_30d_ago: record.price_30d_ago,
             return_15d: record.return_15d,
             return_30d: record.return_30d,
             volume_ratio: record.volume_ratio,
+            short_volume_ratio: record.short_volume_ratio,
+            short_volume_zscore: record.short_volume_zscore,
+            days_to_cover: record.days_to_cover,
+            put_call_ratio: record.put_call_ratio,
+            options_premium_skew: record.options_premium_skew,
+            unusual_options_contracts: record.unusual_options_contracts,
+            news_sentiment_score: record.news_sentiment_score,
+            news_article_count: record.news_article_count,
+            news_articles: record.news_articles,
+            news_llm_score: record.news_llm_score,
         }
     }
 }
@@ -382,3 +535,40 @@ impl Default for ApiUrls {
     }
 }
 
+
+/// Consensus estimate snapshot (read from estimate_snapshots)
+///
+/// One row per symbol, snapshot date and targeted fiscal period; revision
+/// momentum is computed by comparing snapshots over time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EstimateSnapshotRecord {
+    pub id: Option<i64>,
+    pub symbol: String,
+    pub snapshot_date: NaiveDate,
+    pub fiscal_period: Option<NaiveDate>,
+    pub eps_estimate: f64,
+    pub eps_high: Option<f64>,
+    pub eps_low: Option<f64>,
+    pub revenue_estimate: f64,
+    pub revenue_high: Option<f64>,
+    pub revenue_low: Option<f64>,
+    pub recommendation: f64,
+    pub analyst_count: i32,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Consensus estimate snapshot for database insertion (excludes auto-generated fields)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EstimateSnapshotInsert {
+    pub symbol: String,
+    pub snapshot_date: NaiveDate,
+    pub fiscal_period: Option<NaiveDate>,
+    pub eps_estimate: f64,
+    pub eps_high: Option<f64>,
+    pub eps_low: Option<f64>,
+    pub revenue_estimate: f64,
+    pub revenue_high: Option<f64>,
+    pub revenue_low: Option<f64>,
+    pub recommendation: f64,
+    pub analyst_count: i32,
+}
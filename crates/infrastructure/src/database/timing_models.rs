@@ -72,6 +72,9 @@ pub struct TimingRecord {
     
     // Metadata
     pub flags: Vec<String>,
+    /// Hash of the TTS scoring configuration that produced this record
+    #[serde(default)]
+    pub config_hash: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -163,6 +166,8 @@ pub struct CreateTimingRecord {
     
     // Metadata
     pub flags: Vec<String>,
+    /// Hash of the TTS scoring configuration that produced this record
+    pub config_hash: Option<String>,
 }
 
 /// Timing record insert (for database inserts, without auto-generated fields)
@@ -212,6 +217,7 @@ pub struct TimingInsert {
     pub chatgpt_explanation: Option<String>,
     pub trading_suggestion: Option<String>,
     pub flags: serde_json::Value, // Stored as JSONB
+    pub config_hash: Option<String>,
 }
 
 impl From<CreateTimingRecord> for TimingInsert {
@@ -261,6 +267,7 @@ impl From<CreateTimingRecord> for TimingInsert {
             chatgpt_explanation: record.chatgpt_explanation,
             trading_suggestion: record.trading_suggestion,
             flags: serde_json::json!(record.flags),
+            config_hash: record.config_hash,
         }
     }
 }
@@ -342,6 +349,7 @@ mod tests {
             chatgpt_explanation: Some("Technical indicators show bullish momentum with strong volume support.".to_string()),
             trading_suggestion: Some("Consider a long position with stop loss at $145.".to_string()),
             flags: vec!["High volume confirmation".to_string()],
+            config_hash: None,
         };
 
         assert_eq!(create_record.symbol, "AAPL");
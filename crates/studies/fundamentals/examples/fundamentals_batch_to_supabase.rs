@@ -1,7 +1,7 @@
 // Example: Run fundamentals analysis on a single stock and save to Supabase
 // This analyzes fundamental financial data and saves to fundamentals_history
 
-use buenotea_fundamentals::{FundamentalsCalculator, FundamentalsStorage, FundamentalsApiUrls, create_fundamentals_record_with_tracking};
+use buenotea_fundamentals::{FundamentalsCalculator, FundamentalsStorage, create_fundamentals_record_with_tracking};
 use buenotea_core::Result;
 use buenotea_infrastructure::DatabaseClient;
 use dotenv;
@@ -23,18 +23,17 @@ async fn main() -> Result<()> {
 
     // Step 1: Run fundamentals analysis for AAPL
     println!("📈 Analyzing AAPL fundamentals...");
-    let mut calculator = FundamentalsCalculator::new();
+    let calculator = FundamentalsCalculator::new();
     let symbol = "AAPL";
     
-    let result = calculator.calculate_fundamentals(symbol).await?;
+    let (result, api_urls) = calculator.calculate_fundamentals_with_tracking(symbol).await?;
     println!("✅ Fundamentals analysis completed!");
     println!("   Symbol: {}", result.symbol);
     println!("   Score: {:.2}", result.fundamentals_score);
     println!("   Signal: {}", result.trading_signal);
     println!("   Confidence: {:.1}%", result.confidence_score * 100.0);
 
-    // Step 2: Prepare API URLs tracking (in a real scenario, you'd get this from the calculator)
-    let api_urls = FundamentalsApiUrls::default(); // Using default for this example
+    // Step 2: API URLs and raw data used for each component come back with the result
 
     // Step 3: Create database record (no GPT analysis for now)
     let record_to_store = create_fundamentals_record_with_tracking(
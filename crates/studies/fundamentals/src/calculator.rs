@@ -2,132 +2,330 @@
 
 use buenotea_core::Result;
 use super::models::*;
-use chrono::Utc;
+use super::dcf::{dcf_valuation, historical_growth_rate, DcfAssumptions, DcfInputs};
+use super::edgar::{chain_with_edgar, EdgarPriority, EdgarProvider};
+use super::fmp::{freshness, FmpFundamentals};
+use super::peers::{peer_percentiles, peer_profitability_score, peer_valuation_score, PeerStock};
+use super::scoring::*;
+use super::quality::{
+    quality_scores, BENEISH_MANIPULATION_THRESHOLD, PIOTROSKI_STRONG_THRESHOLD, PIOTROSKI_WEAK_THRESHOLD,
+    SLOAN_HIGH_ACCRUAL_THRESHOLD,
+};
+use super::statements::FinancialStatements;
+use crate::FundamentalsApiUrls;
+use buenotea_core::market_data::{
+    as_of_timestamp, is_historical, MarketDataProvider, ProviderChain, StatementPeriod,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use std::sync::Arc;
 use std::time::Instant;
-use tracing::info;
-use reqwest::Client;
+use tracing::{info, warn};
+
+/// Annual and quarterly statements fetched per symbol (three years for the quality
+/// scores' opening balances, eight quarters)
+const ANNUAL_STATEMENTS: usize = 3;
+const QUARTERLY_STATEMENTS: usize = 8;
 
 /// Main fundamentals calculator that combines multiple data sources
 pub struct FundamentalsCalculator {
-    client: Client,
-    fmp_api_key: String,
-    alpha_vantage_api_key: String,
-    finnhub_api_key: String,
+    provider: Arc<dyn MarketDataProvider>,
 }
 
 impl FundamentalsCalculator {
-    /// Create a new fundamentals calculator
+    /// Create a new fundamentals calculator using the providers configured in the environment
+    ///
+    /// SEC EDGAR joins the chain for statements when SEC_EDGAR_DIR or SEC_USER_AGENT is set.
     pub fn new() -> Self {
-        Self {
-            client: Client::new(),
-            fmp_api_key: std::env::var("FMP_API_KEY").unwrap_or_default(),
-            alpha_vantage_api_key: std::env::var("ALPHA_VANTAGE_API_KEY").unwrap_or_default(),
-            finnhub_api_key: std::env::var("FINNHUB_API_KEY").unwrap_or_default(),
+        let providers: Arc<dyn MarketDataProvider> = Arc::new(ProviderChain::from_env());
+        match EdgarProvider::from_env() {
+            Ok(edgar) => Self::with_provider(Arc::new(chain_with_edgar(edgar, providers, EdgarPriority::from_env()))),
+            Err(_) => Self::with_provider(providers),
         }
     }
 
+    /// Create a fundamentals calculator that reads statements, vendor ratios and prices from a specific provider
+    pub fn with_provider(provider: Arc<dyn MarketDataProvider>) -> Self {
+        Self { provider }
+    }
+
     /// Calculate the complete fundamentals score for a given symbol
     pub async fn calculate_fundamentals(&self, symbol: &str) -> Result<FundamentalsResult> {
+        let (result, _) = self.calculate_fundamentals_with_tracking(symbol).await?;
+        Ok(result)
+    }
+
+    /// Calculate the fundamentals score together with the API URLs and data it used
+    pub async fn calculate_fundamentals_with_tracking(&self, symbol: &str) -> Result<(FundamentalsResult, FundamentalsApiUrls)> {
+        self.calculate(symbol, Utc::now().date_naive(), Utc::now()).await
+    }
+
+    /// Calculate the fundamentals score using only statements filed on or before `as_of`
+    pub async fn calculate_fundamentals_as_of(&self, symbol: &str, as_of: NaiveDate) -> Result<(FundamentalsResult, FundamentalsApiUrls)> {
+        self.calculate(symbol, as_of, as_of_timestamp(as_of)).await
+    }
+
+    /// Calculate every stock in `universe` and score valuation and profitability against peers
+    ///
+    /// Stocks that fail are logged and left out. Sector and industry come from
+    /// the company profile, or from `universe` when the profile is unavailable.
+    pub async fn calculate_universe_with_tracking(&self, universe: &[PeerStock]) -> Vec<(FundamentalsResult, FundamentalsApiUrls)> {
+        self.calculate_universe(universe, Utc::now().date_naive(), Utc::now()).await
+    }
+
+    /// Peer-relative universe calculation using only statements filed on or before `as_of`
+    pub async fn calculate_universe_as_of(&self, universe: &[PeerStock], as_of: NaiveDate) -> Vec<(FundamentalsResult, FundamentalsApiUrls)> {
+        self.calculate_universe(universe, as_of, as_of_timestamp(as_of)).await
+    }
+
+    async fn calculate_universe(
+        &self,
+        universe: &[PeerStock],
+        as_of: NaiveDate,
+        timestamp: DateTime<Utc>,
+    ) -> Vec<(FundamentalsResult, FundamentalsApiUrls)> {
+        let mut calculated = Vec::with_capacity(universe.len());
+        for stock in universe {
+            match self.calculate(&stock.symbol, as_of, timestamp).await {
+                Ok((mut result, api_urls)) => {
+                    if result.meta.sector.is_none() {
+                        result.meta.sector = stock.sector.clone();
+                    }
+                    if result.meta.industry.is_none() {
+                        result.meta.industry = stock.industry.clone();
+                    }
+                    calculated.push((result, api_urls));
+                }
+                Err(e) => warn!("Skipping {} in peer universe: {}", stock.symbol, e),
+            }
+        }
+
+        let (mut results, api_urls): (Vec<_>, Vec<_>) = calculated.into_iter().unzip();
+        self.apply_peer_scores(&mut results);
+        results.into_iter().zip(api_urls).collect()
+    }
+
+    /// Replace absolute valuation and profitability scores with peer-relative ones
+    fn apply_peer_scores(&self, results: &mut [FundamentalsResult]) {
+        let percentiles = peer_percentiles(results);
+        for (result, peers) in results.iter_mut().zip(percentiles) {
+            let Some(peers) = peers else {
+                result.flags.push("No sector peers for relative scoring".to_string());
+                continue;
+            };
+            if let Some(score) = with_dcf(peer_valuation_score(&result.metrics.valuation, &peers), result.dcf.as_ref()) {
+                result.components.valuation = score;
+            }
+            if let Some(score) = peer_profitability_score(&result.metrics.profitability, &peers) {
+                result.components.profitability = score;
+            }
+            result.fundamentals_score = result.components.calculate_fundamentals_score();
+            result.trading_signal = self.generate_trading_signal(result.fundamentals_score);
+            result.peer_percentiles = Some(peers);
+        }
+    }
+
+    async fn calculate(&self, symbol: &str, as_of: NaiveDate, timestamp: DateTime<Utc>) -> Result<(FundamentalsResult, FundamentalsApiUrls)> {
         let start_time = Instant::now();
-        info!("Starting fundamentals calculation for {}", symbol);
+        info!("Starting fundamentals calculation for {} as of {}", symbol, as_of);
+
+        // Vendor ratios cover growth and valuation; the statements cover everything else
+        let data = FmpFundamentals::fetch(self.provider.as_ref(), symbol, as_of).await;
+        let statements = self.fetch_statements(symbol, as_of).await;
+
+        let mut metrics = data.metrics();
+        let price = self.price(symbol, as_of).await;
+        let market_cap = price
+            .zip(statements.shares_outstanding())
+            .map(|(price, shares)| price * shares)
+            .or(data.meta().market_cap.map(|cap| cap as f64));
+        statements.apply_to(&mut metrics, market_cap);
+        let dcf = self.calculate_dcf(&data, &statements, &metrics, price, market_cap);
+        let quality = quality_scores(&statements);
 
         // Calculate individual component scores
-        let profitability_score = self.calculate_profitability_score(symbol).await?;
-        let growth_score = self.calculate_growth_score(symbol).await?;
-        let valuation_score = self.calculate_valuation_score(symbol).await?;
-        let financial_strength_score = self.calculate_financial_strength_score(symbol).await?;
-        let efficiency_score = self.calculate_efficiency_score(symbol).await?;
+        let scores = [
+            ("profitability", self.calculate_profitability_score(symbol, &metrics)),
+            ("growth", self.calculate_growth_score(symbol, &metrics)),
+            ("valuation", self.calculate_valuation_score(symbol, &metrics, dcf.as_ref())),
+            ("financial strength", self.calculate_financial_strength_score(symbol, &metrics)),
+            ("efficiency", self.calculate_efficiency_score(symbol, &metrics)),
+        ];
+        let available = scores.iter().filter(|(_, score)| score.is_some()).count();
+        if available == 0 {
+            return Err(buenotea_core::Error::ApiError(
+                self.provider.name().to_string(),
+                format!("No fundamentals data for {} as of {}", symbol, as_of),
+            ));
+        }
 
-        // Create components
+        // Create components, missing components count as neutral
         let components = FundamentalsComponents {
-            profitability: profitability_score,
-            growth: growth_score,
-            valuation: valuation_score,
-            financial_strength: financial_strength_score,
-            efficiency: efficiency_score,
+            profitability: scores[0].1.unwrap_or(0.0),
+            growth: scores[1].1.unwrap_or(0.0),
+            valuation: scores[2].1.unwrap_or(0.0),
+            financial_strength: scores[3].1.unwrap_or(0.0),
+            efficiency: scores[4].1.unwrap_or(0.0),
         };
 
         // Calculate final fundamentals score
         let fundamentals_score = components.calculate_fundamentals_score();
         let trading_signal = self.generate_trading_signal(fundamentals_score);
 
-        // Collect financial metrics
-        let metrics = self.collect_financial_metrics(symbol).await?;
-
         // Calculate confidence score
-        let confidence_score = self.calculate_confidence_score(&components);
+        let confidence_score = self.calculate_confidence_score(available);
 
         // Generate flags
-        let flags = self.generate_flags(&components, &metrics);
+        let mut flags = self.generate_flags(&components, &quality);
+        for (name, score) in &scores {
+            if score.is_none() {
+                flags.push(format!("No {} data available", name));
+            }
+        }
+        if is_historical(as_of) {
+            flags.push("Company profile skipped for historical date".to_string());
+        }
 
         // Collect metadata
-        let meta = self.collect_metadata(symbol, &metrics, start_time.elapsed().as_millis() as u64);
+        let meta = self.collect_metadata(&data, &statements, &metrics, as_of, start_time.elapsed().as_millis() as u64);
 
-        Ok(FundamentalsResult {
+        let result = FundamentalsResult {
             symbol: symbol.to_string(),
             fundamentals_score,
             trading_signal,
             components,
             metrics,
+            quality,
+            dcf,
+            peer_percentiles: None,
             flags,
             confidence_score,
-            timestamp: Utc::now(),
+            timestamp,
             meta,
-        })
+        };
+        Ok((result, self.api_urls(&data, &statements)))
     }
 
-    /// Calculate profitability score (-1 to +1)
-    async fn calculate_profitability_score(&self, symbol: &str) -> Result<f64> {
-        info!("Calculating profitability score for {}", symbol);
-        
-        // Try to get real data from APIs
-        match self.get_profitability_from_fmp(symbol).await {
-            Ok(score) => Ok(score),
-            Err(_) => {
-                // Fallback to realistic mock data
-                Ok(self.calculate_realistic_profitability_score(symbol))
+    /// Annual and quarterly statements filed on or before `as_of`
+    ///
+    /// A failed request leaves that statement list empty so the vendor ratios can still be used.
+    async fn fetch_statements(&self, symbol: &str, as_of: NaiveDate) -> FinancialStatements {
+        let mut statements = FinancialStatements::default();
+        for (period, limit) in [(StatementPeriod::Annual, ANNUAL_STATEMENTS), (StatementPeriod::Quarterly, QUARTERLY_STATEMENTS)] {
+            let quarterly = period == StatementPeriod::Quarterly;
+            match self.provider.income_statements_as_of(symbol, period, limit, as_of).await {
+                Ok(response) => {
+                    statements.source.get_or_insert(response.source);
+                    statements.url.get_or_insert(response.url);
+                    *if quarterly { &mut statements.quarterly_income } else { &mut statements.annual_income } = response.data;
+                }
+                Err(e) => warn!("Income statements unavailable for {}: {}", symbol, e),
+            }
+            match self.provider.balance_sheets_as_of(symbol, period, limit, as_of).await {
+                Ok(response) => {
+                    *if quarterly { &mut statements.quarterly_balance } else { &mut statements.annual_balance } = response.data
+                }
+                Err(e) => warn!("Balance sheets unavailable for {}: {}", symbol, e),
+            }
+            match self.provider.cash_flow_statements_as_of(symbol, period, limit, as_of).await {
+                Ok(response) => {
+                    *if quarterly { &mut statements.quarterly_cash_flow } else { &mut statements.annual_cash_flow } = response.data
+                }
+                Err(e) => warn!("Cash flow statements unavailable for {}: {}", symbol, e),
+            }
+        }
+        statements
+    }
+
+    /// Closing price on `as_of`
+    async fn price(&self, symbol: &str, as_of: NaiveDate) -> Option<f64> {
+        match self.provider.daily_bars_as_of(symbol, 1, as_of).await {
+            Ok(response) => response.data.last().map(|bar| bar.close),
+            Err(e) => {
+                warn!("Price unavailable for {}: {}", symbol, e);
+                None
             }
         }
     }
 
+    /// DCF valuation from trailing free cash flow, historical growth and the treasury yield
+    fn calculate_dcf(
+        &self,
+        data: &FmpFundamentals,
+        statements: &FinancialStatements,
+        metrics: &FinancialMetrics,
+        price: Option<f64>,
+        market_cap: Option<f64>,
+    ) -> Option<DcfValuation> {
+        let assumptions = DcfAssumptions::default();
+        let flows = statements.ttm()?;
+        let balance = statements.latest_balance();
+        let inputs = DcfInputs {
+            free_cash_flow: flows.free_cash_flow,
+            growth: historical_growth_rate(&metrics.growth, &assumptions),
+            risk_free_rate: data.risk_free_rate(),
+            beta: data.meta().beta,
+            interest_expense: flows.interest_expense,
+            tax_rate: flows.tax_rate(),
+            total_debt: balance.and_then(|b| b.total_debt),
+            cash: balance.and_then(|b| b.cash_and_equivalents),
+            shares_outstanding: statements.shares_outstanding(),
+            price,
+        };
+        dcf_valuation(&inputs, market_cap, &assumptions)
+    }
+
+    /// API tracking, crediting the statements provider for the components derived from statements
+    fn api_urls(&self, data: &FmpFundamentals, statements: &FinancialStatements) -> FundamentalsApiUrls {
+        let mut urls = data.api_urls();
+        if statements.ttm().is_none() || statements.latest_balance().is_none() {
+            return urls;
+        }
+        let raw = statements.raw_data();
+        urls.profitability_api_url = statements.url.clone();
+        urls.profitability_api_source = statements.source.clone();
+        urls.profitability_data_available = true;
+        urls.profitability_raw_data = raw.clone();
+        urls.financial_strength_api_url = statements.url.clone();
+        urls.financial_strength_api_source = statements.source.clone();
+        urls.financial_strength_data_available = true;
+        urls.financial_strength_raw_data = raw.clone();
+        urls.efficiency_api_url = statements.url.clone();
+        urls.efficiency_api_source = statements.source.clone();
+        urls.efficiency_data_available = true;
+        urls.efficiency_raw_data = raw;
+        urls
+    }
+
+    /// Calculate profitability score (-1 to +1)
+    fn calculate_profitability_score(&self, symbol: &str, metrics: &FinancialMetrics) -> Option<f64> {
+        info!("Calculating profitability score for {}", symbol);
+        profitability_score(&metrics.profitability)
+    }
+
     /// Calculate growth score (-1 to +1)
-    async fn calculate_growth_score(&self, symbol: &str) -> Result<f64> {
+    fn calculate_growth_score(&self, symbol: &str, metrics: &FinancialMetrics) -> Option<f64> {
         info!("Calculating growth score for {}", symbol);
-        
-        match self.get_growth_from_fmp(symbol).await {
-            Ok(score) => Ok(score),
-            Err(_) => Ok(self.calculate_realistic_growth_score(symbol))
-        }
+        growth_score(&metrics.growth)
     }
 
     /// Calculate valuation score (-1 to +1)
-    async fn calculate_valuation_score(&self, symbol: &str) -> Result<f64> {
+    ///
+    /// Multiples and the DCF margin of safety count equally when a DCF is available.
+    fn calculate_valuation_score(&self, symbol: &str, metrics: &FinancialMetrics, dcf: Option<&DcfValuation>) -> Option<f64> {
         info!("Calculating valuation score for {}", symbol);
-        
-        match self.get_valuation_from_fmp(symbol).await {
-            Ok(score) => Ok(score),
-            Err(_) => Ok(self.calculate_realistic_valuation_score(symbol))
-        }
+        with_dcf(valuation_score(&metrics.valuation), dcf)
     }
 
     /// Calculate financial strength score (-1 to +1)
-    async fn calculate_financial_strength_score(&self, symbol: &str) -> Result<f64> {
+    fn calculate_financial_strength_score(&self, symbol: &str, metrics: &FinancialMetrics) -> Option<f64> {
         info!("Calculating financial strength score for {}", symbol);
-        
-        match self.get_financial_strength_from_fmp(symbol).await {
-            Ok(score) => Ok(score),
-            Err(_) => Ok(self.calculate_realistic_financial_strength_score(symbol))
-        }
+        financial_strength_score(&metrics.financial_strength)
     }
 
     /// Calculate efficiency score (-1 to +1)
-    async fn calculate_efficiency_score(&self, symbol: &str) -> Result<f64> {
+    fn calculate_efficiency_score(&self, symbol: &str, metrics: &FinancialMetrics) -> Option<f64> {
         info!("Calculating efficiency score for {}", symbol);
-        
-        match self.get_efficiency_from_fmp(symbol).await {
-            Ok(score) => Ok(score),
-            Err(_) => Ok(self.calculate_realistic_efficiency_score(symbol))
-        }
+        efficiency_score(&metrics.efficiency)
     }
 
     /// Generate trading signal from fundamentals score
@@ -141,18 +339,13 @@ impl FundamentalsCalculator {
         }
     }
 
-    /// Calculate confidence score based on data availability
-    fn calculate_confidence_score(&self, components: &FundamentalsComponents) -> f64 {
-        let valid_components = components.valid_components_count();
-        if valid_components == 0 {
-            0.0
-        } else {
-            (valid_components as f64) / 5.0
-        }
+    /// Calculate confidence score based on how many components had data
+    fn calculate_confidence_score(&self, available_components: usize) -> f64 {
+        available_components as f64 / 5.0
     }
 
     /// Generate warning flags based on analysis
-    fn generate_flags(&self, components: &FundamentalsComponents, _metrics: &FinancialMetrics) -> Vec<String> {
+    fn generate_flags(&self, components: &FundamentalsComponents, quality: &QualityScores) -> Vec<String> {
         let mut flags = Vec::new();
         
         if components.valid_components_count() < 3 {
@@ -170,51 +363,43 @@ impl FundamentalsCalculator {
         if components.profitability < -0.3 {
             flags.push("Low profitability".to_string());
         }
+
+        match quality.piotroski_f_score {
+            Some(score) if score <= PIOTROSKI_WEAK_THRESHOLD => flags.push(format!("Weak Piotroski F-score ({}/9)", score)),
+            Some(score) if score >= PIOTROSKI_STRONG_THRESHOLD => flags.push(format!("Strong Piotroski F-score ({}/9)", score)),
+            _ => {}
+        }
+
+        if let Some(m_score) = quality.beneish_m_score.filter(|m| *m > BENEISH_MANIPULATION_THRESHOLD) {
+            flags.push(format!("Earnings manipulation risk (Beneish M-score {:.2})", m_score));
+        }
+
+        if let Some(accruals) = quality.sloan_accrual_ratio.filter(|r| *r > SLOAN_HIGH_ACCRUAL_THRESHOLD) {
+            flags.push(format!("High accruals (Sloan ratio {:.1}%)", accruals * 100.0));
+        }
         
         flags
     }
 
-    /// Collect financial metrics for the stock
-    async fn collect_financial_metrics(&self, symbol: &str) -> Result<FinancialMetrics> {
-        // For now, return realistic mock data
-        // In production, this would fetch real data from APIs
-        Ok(self.generate_realistic_mock_metrics(symbol))
-    }
-
     /// Collect metadata about the analysis
-    fn collect_metadata(&self, symbol: &str, metrics: &FinancialMetrics, computation_time: u64) -> FundamentalsMeta {
-        let mut meta = FundamentalsMeta::default();
-        
+    fn collect_metadata(
+        &self,
+        data: &FmpFundamentals,
+        statements: &FinancialStatements,
+        metrics: &FinancialMetrics,
+        as_of: NaiveDate,
+        computation_time: u64,
+    ) -> FundamentalsMeta {
+        let mut meta = data.meta();
+        // Quarterly statements are usually newer than the annual vendor ratios
+        if let Some(period_end) = statements.latest_balance().map(|balance| balance.date) {
+            meta.data_freshness = meta.data_freshness.max(freshness(as_of, period_end));
+        }
         meta.computation_time_ms = computation_time;
         meta.data_points_count = self.count_data_points(metrics);
-        meta.data_freshness = 0.85; // Mock freshness score
-        
-        // Set realistic metadata based on symbol
-        match symbol.to_uppercase().as_str() {
-            "AAPL" => {
-                meta.sector = Some("Technology".to_string());
-                meta.industry = Some("Consumer Electronics".to_string());
-                meta.market_cap_category = Some("Mega Cap".to_string());
-                meta.beta = Some(1.29);
-                meta.dividend_yield = Some(0.0044);
-                meta.payout_ratio = Some(0.15);
-                meta.shares_outstanding = Some(15_326_000_000);
-                meta.market_cap = Some(3_100_000_000_000);
-                meta.enterprise_value = Some(3_050_000_000_000);
-            }
-            _ => {
-                meta.sector = Some("Unknown".to_string());
-                meta.industry = Some("Unknown".to_string());
-                meta.market_cap_category = Some("Unknown".to_string());
-                meta.beta = Some(1.0);
-                meta.dividend_yield = Some(0.02);
-                meta.payout_ratio = Some(0.30);
-                meta.shares_outstanding = Some(1_000_000_000);
-                meta.market_cap = Some(100_000_000_000);
-                meta.enterprise_value = Some(95_000_000_000);
-            }
+        if meta.data_freshness < 0.5 {
+            warn!("Latest fundamentals are stale (freshness {:.2})", meta.data_freshness);
         }
-        
         meta
     }
 
@@ -272,204 +457,87 @@ impl FundamentalsCalculator {
         
         count
     }
+}
 
-    // API Integration Methods (placeholder implementations)
-    
-    async fn get_profitability_from_fmp(&self, _symbol: &str) -> Result<f64> {
-        if self.fmp_api_key.is_empty() {
-            info!("FMP API key not configured, skipping FMP profitability data");
-            return Err(buenotea_core::Error::ApiError("FMP".to_string(), "API key not configured".to_string()));
-        }
-        
-        // Placeholder for real FMP API call
-        Err(buenotea_core::Error::ApiError("FMP".to_string(), "Not implemented yet".to_string()))
-    }
-
-    async fn get_growth_from_fmp(&self, _symbol: &str) -> Result<f64> {
-        if self.fmp_api_key.is_empty() {
-            info!("FMP API key not configured, skipping FMP growth data");
-            return Err(buenotea_core::Error::ApiError("FMP".to_string(), "API key not configured".to_string()));
-        }
-        
-        Err(buenotea_core::Error::ApiError("FMP".to_string(), "Not implemented yet".to_string()))
+impl Default for FundamentalsCalculator {
+    fn default() -> Self {
+        Self::new()
     }
+}
 
-    async fn get_valuation_from_fmp(&self, _symbol: &str) -> Result<f64> {
-        if self.fmp_api_key.is_empty() {
-            info!("FMP API key not configured, skipping FMP valuation data");
-            return Err(buenotea_core::Error::ApiError("FMP".to_string(), "API key not configured".to_string()));
-        }
-        
-        Err(buenotea_core::Error::ApiError("FMP".to_string(), "Not implemented yet".to_string()))
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use buenotea_core::market_data::{CompanyProfile, DailyBar, FinancialRatios, ProviderResponse, Quote};
+    use serde_json::Value;
 
-    async fn get_financial_strength_from_fmp(&self, _symbol: &str) -> Result<f64> {
-        if self.fmp_api_key.is_empty() {
-            info!("FMP API key not configured, skipping FMP financial strength data");
-            return Err(buenotea_core::Error::ApiError("FMP".to_string(), "API key not configured".to_string()));
-        }
-        
-        Err(buenotea_core::Error::ApiError("FMP".to_string(), "Not implemented yet".to_string()))
-    }
+    /// Provider with one set of annual ratios per symbol, P/E 10 for "S0" up to 35 for "S5"
+    struct PeerRatios;
 
-    async fn get_efficiency_from_fmp(&self, _symbol: &str) -> Result<f64> {
-        if self.fmp_api_key.is_empty() {
-            info!("FMP API key not configured, skipping FMP efficiency data");
-            return Err(buenotea_core::Error::ApiError("FMP".to_string(), "API key not configured".to_string()));
+    #[async_trait]
+    impl MarketDataProvider for PeerRatios {
+        fn name(&self) -> &str {
+            "PeerRatios"
         }
-        
-        Err(buenotea_core::Error::ApiError("FMP".to_string(), "Not implemented yet".to_string()))
-    }
 
-    // Realistic Mock Data Generation Methods
-    
-    fn calculate_realistic_profitability_score(&self, symbol: &str) -> f64 {
-        info!("Using realistic profitability scoring for {}", symbol);
-        
-        match symbol.to_uppercase().as_str() {
-            "AAPL" => 0.8,   // Excellent profitability
-            "MSFT" => 0.7,   // Strong profitability
-            "GOOGL" => 0.6,  // Good profitability
-            "NVDA" => 0.9,   // Exceptional profitability
-            "TSLA" => 0.2,   // Weak profitability
-            _ => 0.0,        // Average profitability
+        async fn daily_bars(&self, _symbol: &str, _limit: usize) -> Result<ProviderResponse<Vec<DailyBar>>> {
+            Err(buenotea_core::Error::ApiError("PeerRatios".to_string(), "no bars".to_string()))
         }
-    }
-
-    fn calculate_realistic_growth_score(&self, symbol: &str) -> f64 {
-        info!("Using realistic growth scoring for {}", symbol);
-        
-        match symbol.to_uppercase().as_str() {
-            "AAPL" => 0.3,   // Moderate growth
-            "MSFT" => 0.4,   // Good growth
-            "GOOGL" => 0.2,  // Slow growth
-            "NVDA" => 0.8,   // High growth
-            "TSLA" => 0.1,   // Low growth
-            _ => 0.0,        // Average growth
-        }
-    }
 
-    fn calculate_realistic_valuation_score(&self, symbol: &str) -> f64 {
-        info!("Using realistic valuation scoring for {}", symbol);
-        
-        match symbol.to_uppercase().as_str() {
-            "AAPL" => -0.6,  // Overvalued
-            "MSFT" => -0.4,  // Somewhat overvalued
-            "GOOGL" => -0.3, // Slightly overvalued
-            "NVDA" => -0.7,  // Highly overvalued
-            "TSLA" => -0.8,  // Extremely overvalued
-            _ => 0.0,        // Fairly valued
+        async fn quote(&self, _symbol: &str) -> Result<ProviderResponse<Quote>> {
+            Err(buenotea_core::Error::ApiError("PeerRatios".to_string(), "no quotes".to_string()))
         }
-    }
 
-    fn calculate_realistic_financial_strength_score(&self, symbol: &str) -> f64 {
-        info!("Using realistic financial strength scoring for {}", symbol);
-        
-        match symbol.to_uppercase().as_str() {
-            "AAPL" => 0.9,   // Excellent financial strength
-            "MSFT" => 0.8,   // Strong financial strength
-            "GOOGL" => 0.7,  // Good financial strength
-            "NVDA" => 0.6,   // Moderate financial strength
-            "TSLA" => -0.2,  // Weak financial strength
-            _ => 0.0,        // Average financial strength
+        async fn company_profile(&self, _symbol: &str) -> Result<ProviderResponse<CompanyProfile>> {
+            Err(buenotea_core::Error::ApiError("PeerRatios".to_string(), "no profiles".to_string()))
         }
-    }
 
-    fn calculate_realistic_efficiency_score(&self, symbol: &str) -> f64 {
-        info!("Using realistic efficiency scoring for {}", symbol);
-        
-        match symbol.to_uppercase().as_str() {
-            "AAPL" => 0.8,   // Excellent efficiency
-            "MSFT" => 0.7,   // Good efficiency
-            "GOOGL" => 0.6,  // Good efficiency
-            "NVDA" => 0.5,   // Moderate efficiency
-            "TSLA" => 0.1,   // Low efficiency
-            _ => 0.0,        // Average efficiency
+        async fn financial_ratios(
+            &self,
+            symbol: &str,
+            _period: StatementPeriod,
+            _limit: usize,
+        ) -> Result<ProviderResponse<Vec<FinancialRatios>>> {
+            let rank: f64 = symbol[1..].parse().unwrap();
+            let ratios = FinancialRatios {
+                date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
+                period: "FY".to_string(),
+                price_earnings: Some(10.0 + 5.0 * rank),
+                return_on_equity: Some(0.10 + 0.02 * rank),
+                ..Default::default()
+            };
+            Ok(ProviderResponse { data: vec![ratios], source: "PeerRatios".to_string(), url: String::new(), raw: Value::Null })
         }
     }
 
-    fn generate_realistic_mock_metrics(&self, symbol: &str) -> FinancialMetrics {
-        match symbol.to_uppercase().as_str() {
-            "AAPL" => FinancialMetrics {
-                profitability: ProfitabilityMetrics {
-                    roe: Some(1.47), // 147% - Apple's exceptional ROE
-                    roa: Some(0.20), // 20% - Strong asset utilization
-                    roic: Some(0.28), // 28% - Excellent capital efficiency
-                    net_profit_margin: Some(0.25), // 25% - Very strong margins
-                    gross_profit_margin: Some(0.44), // 44% - Premium pricing power
-                    operating_profit_margin: Some(0.30), // 30% - Strong operations
-                    ebitda_margin: Some(0.32), // 32% - Strong EBITDA
-                },
-                growth: GrowthMetrics {
-                    revenue_growth_yoy: Some(0.08), // 8% - Steady growth
-                    revenue_growth_qoq: Some(0.02), // 2% - Quarterly growth
-                    eps_growth_yoy: Some(0.12), // 12% - Strong EPS growth
-                    eps_growth_qoq: Some(0.03), // 3% - Quarterly EPS growth
-                    net_income_growth_yoy: Some(0.15), // 15% - Strong profit growth
-                    book_value_growth_yoy: Some(0.10), // 10% - Book value growth
-                    operating_cash_flow_growth_yoy: Some(0.18), // 18% - Strong cash flow
-                },
-                valuation: ValuationMetrics {
-                    pe_ratio: Some(28.5), // 28.5 - Premium valuation
-                    peg_ratio: Some(2.4), // 2.4 - Growth-adjusted valuation
-                    ps_ratio: Some(7.2), // 7.2 - Revenue multiple
-                    pb_ratio: Some(42.1), // 42.1 - High book value multiple
-                    pcf_ratio: Some(22.8), // 22.8 - Cash flow multiple
-                    ev_ebitda: Some(21.3), // 21.3 - Enterprise value multiple
-                    ev_sales: Some(6.8), // 6.8 - Enterprise to sales
-                    pfcf_ratio: Some(25.2), // 25.2 - Free cash flow multiple
-                },
-                financial_strength: FinancialStrengthMetrics {
-                    debt_to_equity: Some(1.73), // 1.73 - Leveraged but manageable
-                    debt_to_assets: Some(0.31), // 31% - Moderate debt load
-                    current_ratio: Some(1.04), // 1.04 - Adequate liquidity
-                    quick_ratio: Some(0.95), // 0.95 - Quick liquidity
-                    interest_coverage: Some(18.5), // 18.5x - Excellent coverage
-                    cash_to_debt: Some(2.1), // 2.1x - Strong cash position
-                    equity_multiplier: Some(3.2), // 3.2x - Leverage ratio
-                    altman_z_score: Some(4.8), // 4.8 - Very safe zone
-                },
-                efficiency: EfficiencyMetrics {
-                    asset_turnover: Some(0.80), // 0.80 - Asset utilization
-                    inventory_turnover: Some(58.2), // 58.2 - Very efficient inventory
-                    receivables_turnover: Some(5.8), // 5.8 - Receivables management
-                    payables_turnover: Some(4.2), // 4.2 - Payables management
-                    working_capital_turnover: Some(12.5), // 12.5 - Working capital efficiency
-                    days_sales_outstanding: Some(62.9), // 62.9 days - DSO
-                    days_inventory_outstanding: Some(6.3), // 6.3 days - DIO
-                    days_payables_outstanding: Some(86.9), // 86.9 days - DPO
-                },
-            },
-            _ => FinancialMetrics {
-                profitability: ProfitabilityMetrics {
-                    roe: Some(0.15), roa: Some(0.08), roic: Some(0.12),
-                    net_profit_margin: Some(0.12), gross_profit_margin: Some(0.35),
-                    operating_profit_margin: Some(0.18), ebitda_margin: Some(0.20),
-                },
-                growth: GrowthMetrics {
-                    revenue_growth_yoy: Some(0.05), revenue_growth_qoq: Some(0.01),
-                    eps_growth_yoy: Some(0.08), eps_growth_qoq: Some(0.02),
-                    net_income_growth_yoy: Some(0.06), book_value_growth_yoy: Some(0.07),
-                    operating_cash_flow_growth_yoy: Some(0.09),
-                },
-                valuation: ValuationMetrics {
-                    pe_ratio: Some(18.5), peg_ratio: Some(2.3), ps_ratio: Some(2.8),
-                    pb_ratio: Some(3.2), pcf_ratio: Some(15.6), ev_ebitda: Some(14.2),
-                    ev_sales: Some(3.1), pfcf_ratio: Some(18.9),
-                },
-                financial_strength: FinancialStrengthMetrics {
-                    debt_to_equity: Some(0.45), debt_to_assets: Some(0.22),
-                    current_ratio: Some(2.1), quick_ratio: Some(1.8),
-                    interest_coverage: Some(8.5), cash_to_debt: Some(1.2),
-                    equity_multiplier: Some(2.1), altman_z_score: Some(3.2),
-                },
-                efficiency: EfficiencyMetrics {
-                    asset_turnover: Some(0.65), inventory_turnover: Some(6.2),
-                    receivables_turnover: Some(8.5), payables_turnover: Some(6.8),
-                    working_capital_turnover: Some(5.2), days_sales_outstanding: Some(42.9),
-                    days_inventory_outstanding: Some(58.8), days_payables_outstanding: Some(53.7),
-                },
-            },
-        }
+    #[tokio::test]
+    async fn test_universe_scores_relative_to_industry_peers() {
+        let universe: Vec<PeerStock> = (0..6)
+            .map(|i| PeerStock {
+                symbol: format!("S{}", i),
+                sector: Some("Technology".to_string()),
+                industry: Some("Software".to_string()),
+            })
+            .collect();
+        let calculator = FundamentalsCalculator::with_provider(Arc::new(PeerRatios));
+
+        let results = calculator
+            .calculate_universe_as_of(&universe, NaiveDate::from_ymd_opt(2024, 6, 28).unwrap())
+            .await;
+        assert_eq!(results.len(), 6);
+
+        let (cheapest, _) = &results[0];
+        let peers = cheapest.peer_percentiles.as_ref().expect("peer group");
+        assert_eq!(peers.level, PeerGroupLevel::Industry);
+        assert_eq!(peers.group, "Software");
+        assert_eq!(peers.group_size, 6);
+        assert_eq!(cheapest.meta.industry.as_deref(), Some("Software"));
+        assert!(peers.valuation.pe_ratio.unwrap() < 10.0);
+
+        // Cheapest P/E ranks best on valuation, highest ROE best on profitability
+        let (priciest, _) = &results[5];
+        assert!(cheapest.components.valuation > priciest.components.valuation);
+        assert!(priciest.components.profitability > cheapest.components.profitability);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,227 @@
+// Discounted cash flow valuation
+// Two-stage free cash flow projection discounted at WACC with a Gordon growth terminal value
+
+use super::models::{DcfSensitivity, DcfValuation, GrowthMetrics};
+
+/// Projection and cost of capital assumptions
+#[derive(Debug, Clone)]
+pub struct DcfAssumptions {
+    /// Years projected before the terminal value
+    pub projection_years: usize,
+    /// Years at the initial growth rate before it fades linearly to terminal growth
+    pub high_growth_years: usize,
+    pub terminal_growth_rate: f64,
+    pub equity_risk_premium: f64,
+    /// Used when no treasury yield is available
+    pub default_risk_free_rate: f64,
+    /// Used when no beta is available (historical dates)
+    pub default_beta: f64,
+    /// Bounds on the initial growth rate taken from history
+    pub min_growth_rate: f64,
+    pub max_growth_rate: f64,
+    /// Credit spread over the risk-free rate when interest expense gives no cost of debt
+    pub default_credit_spread: f64,
+}
+
+impl Default for DcfAssumptions {
+    fn default() -> Self {
+        Self {
+            projection_years: 10,
+            high_growth_years: 5,
+            terminal_growth_rate: 0.025,
+            equity_risk_premium: 0.055,
+            default_risk_free_rate: 0.042,
+            default_beta: 1.0,
+            min_growth_rate: -0.05,
+            max_growth_rate: 0.20,
+            default_credit_spread: 0.02,
+        }
+    }
+}
+
+/// Growth offsets (rows) and discount rate offsets (columns) of the sensitivity grid
+const GROWTH_STEPS: [f64; 5] = [-0.02, -0.01, 0.0, 0.01, 0.02];
+const DISCOUNT_STEPS: [f64; 5] = [-0.01, -0.005, 0.0, 0.005, 0.01];
+
+/// Smallest spread of the discount rate over terminal growth, keeping the terminal value finite
+const MIN_DISCOUNT_SPREAD: f64 = 0.01;
+
+/// Company inputs for one valuation
+#[derive(Debug, Clone, Default)]
+pub struct DcfInputs {
+    /// Trailing twelve-month free cash flow
+    pub free_cash_flow: Option<f64>,
+    pub growth: Option<f64>,
+    pub risk_free_rate: Option<f64>,
+    pub beta: Option<f64>,
+    /// Interest expense over the trailing twelve months
+    pub interest_expense: Option<f64>,
+    pub tax_rate: f64,
+    pub total_debt: Option<f64>,
+    pub cash: Option<f64>,
+    pub shares_outstanding: Option<f64>,
+    pub price: Option<f64>,
+}
+
+/// Initial free cash flow growth from historical growth, bounded by the assumptions
+///
+/// Averages the year-over-year growth of operating cash flow, revenue and net
+/// income that are available.
+pub fn historical_growth_rate(growth: &GrowthMetrics, assumptions: &DcfAssumptions) -> Option<f64> {
+    let rates: Vec<f64> = [growth.operating_cash_flow_growth_yoy, growth.revenue_growth_yoy, growth.net_income_growth_yoy]
+        .into_iter()
+        .flatten()
+        .collect();
+    if rates.is_empty() {
+        return None;
+    }
+    let average = rates.iter().sum::<f64>() / rates.len() as f64;
+    Some(average.clamp(assumptions.min_growth_rate, assumptions.max_growth_rate))
+}
+
+/// Growth in each projection year: flat, then fading linearly to terminal growth
+fn growth_path(initial: f64, assumptions: &DcfAssumptions) -> Vec<f64> {
+    let fade_years = assumptions.projection_years.saturating_sub(assumptions.high_growth_years);
+    (1..=assumptions.projection_years)
+        .map(|year| {
+            if year <= assumptions.high_growth_years {
+                initial
+            } else {
+                let progress = (year - assumptions.high_growth_years) as f64 / (fade_years + 1) as f64;
+                initial + (assumptions.terminal_growth_rate - initial) * progress
+            }
+        })
+        .collect()
+}
+
+/// Enterprise value and the present value of its terminal part
+fn enterprise_value(free_cash_flow: f64, growth: f64, discount: f64, assumptions: &DcfAssumptions) -> Option<(f64, f64)> {
+    if discount - assumptions.terminal_growth_rate < MIN_DISCOUNT_SPREAD {
+        return None;
+    }
+    let mut cash_flow = free_cash_flow;
+    let mut present_value = 0.0;
+    let mut discount_factor = 1.0;
+    for rate in growth_path(growth, assumptions) {
+        cash_flow *= 1.0 + rate;
+        discount_factor *= 1.0 + discount;
+        present_value += cash_flow / discount_factor;
+    }
+    let terminal = cash_flow * (1.0 + assumptions.terminal_growth_rate) / (discount - assumptions.terminal_growth_rate);
+    let terminal_present_value = terminal / discount_factor;
+    Some((present_value + terminal_present_value, terminal_present_value))
+}
+
+/// DCF valuation, `None` without positive free cash flow, a growth estimate or a share count
+pub fn dcf_valuation(inputs: &DcfInputs, market_cap: Option<f64>, assumptions: &DcfAssumptions) -> Option<DcfValuation> {
+    let free_cash_flow = inputs.free_cash_flow.filter(|fcf| *fcf > 0.0)?;
+    let growth = inputs.growth?;
+    let shares = inputs.shares_outstanding.filter(|shares| *shares > 0.0)?;
+    let debt = inputs.total_debt.unwrap_or(0.0).max(0.0);
+    let cash = inputs.cash.unwrap_or(0.0).max(0.0);
+
+    // Cost of capital: CAPM equity, interest expense over debt, weighted at market value
+    let risk_free = inputs.risk_free_rate.unwrap_or(assumptions.default_risk_free_rate);
+    let cost_of_equity = risk_free + inputs.beta.unwrap_or(assumptions.default_beta) * assumptions.equity_risk_premium;
+    let pre_tax_cost_of_debt = match (inputs.interest_expense, debt) {
+        (Some(interest), debt) if debt > 0.0 => (interest.abs() / debt).clamp(risk_free, risk_free + 0.10),
+        _ => risk_free + assumptions.default_credit_spread,
+    };
+    let cost_of_debt = pre_tax_cost_of_debt * (1.0 - inputs.tax_rate);
+    let wacc = match market_cap.filter(|cap| *cap > 0.0) {
+        Some(equity) => (equity * cost_of_equity + debt * cost_of_debt) / (equity + debt),
+        None => cost_of_equity,
+    }
+    .max(assumptions.terminal_growth_rate + MIN_DISCOUNT_SPREAD);
+
+    let per_share = |growth: f64, discount: f64| {
+        enterprise_value(free_cash_flow, growth, discount, assumptions).map(|(ev, _)| (ev - debt + cash) / shares)
+    };
+
+    let (enterprise_value, terminal_present_value) = enterprise_value(free_cash_flow, growth, wacc, assumptions)?;
+    let equity_value = enterprise_value - debt + cash;
+    let intrinsic_value_per_share = equity_value / shares;
+    let price = inputs.price.filter(|price| *price > 0.0);
+
+    let growth_rates: Vec<f64> = GROWTH_STEPS.iter().map(|step| growth + step).collect();
+    let discount_rates: Vec<f64> = DISCOUNT_STEPS.iter().map(|step| wacc + step).collect();
+    let values = growth_rates
+        .iter()
+        .map(|g| discount_rates.iter().map(|d| per_share(*g, *d)).collect())
+        .collect();
+
+    Some(DcfValuation {
+        intrinsic_value_per_share,
+        current_price: price,
+        margin_of_safety: price
+            .filter(|_| intrinsic_value_per_share > 0.0)
+            .map(|price| (intrinsic_value_per_share - price) / intrinsic_value_per_share),
+        base_free_cash_flow: free_cash_flow,
+        growth_rate: growth,
+        terminal_growth_rate: assumptions.terminal_growth_rate,
+        wacc,
+        cost_of_equity,
+        cost_of_debt,
+        enterprise_value,
+        equity_value,
+        terminal_value_share: terminal_present_value / enterprise_value,
+        sensitivity: DcfSensitivity { growth_rates, discount_rates, values },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inputs() -> DcfInputs {
+        DcfInputs {
+            free_cash_flow: Some(100.0),
+            growth: Some(0.05),
+            risk_free_rate: Some(0.04),
+            beta: Some(1.0),
+            interest_expense: Some(5.0),
+            tax_rate: 0.2,
+            total_debt: Some(100.0),
+            cash: Some(50.0),
+            shares_outstanding: Some(10.0),
+            price: Some(150.0),
+        }
+    }
+
+    #[test]
+    fn test_growth_path_fades_to_terminal() {
+        let assumptions = DcfAssumptions::default();
+        let path = growth_path(0.10, &assumptions);
+        assert_eq!(path.len(), 10);
+        assert_eq!(path[4], 0.10);
+        assert!(path[9] > assumptions.terminal_growth_rate && path[9] < path[5]);
+    }
+
+    #[test]
+    fn test_dcf_valuation_and_sensitivity() {
+        let assumptions = DcfAssumptions { projection_years: 1, high_growth_years: 1, ..DcfAssumptions::default() };
+        let valuation = dcf_valuation(&inputs(), Some(900.0), &assumptions).unwrap();
+
+        // Equity 9.5% and after-tax debt 4% weighted 900:100
+        assert!((valuation.cost_of_equity - 0.095).abs() < 1e-12);
+        assert!((valuation.cost_of_debt - 0.04).abs() < 1e-12);
+        assert!((valuation.wacc - 0.0895).abs() < 1e-12);
+
+        // One year of 5% growth, then a Gordon terminal value at 2.5%
+        let fcf = 105.0;
+        let expected_ev = fcf / 1.0895 + fcf * 1.025 / (0.0895 - 0.025) / 1.0895;
+        assert!((valuation.enterprise_value - expected_ev).abs() < 1e-6);
+        assert!((valuation.intrinsic_value_per_share - (expected_ev - 50.0) / 10.0).abs() < 1e-6);
+        assert!(valuation.margin_of_safety.unwrap() > 0.0);
+
+        // Higher growth is worth more, a higher discount rate less
+        let grid = &valuation.sensitivity.values;
+        assert_eq!(grid[2][2].unwrap(), valuation.intrinsic_value_per_share);
+        assert!(grid[4][2].unwrap() > grid[0][2].unwrap());
+        assert!(grid[2][0].unwrap() > grid[2][4].unwrap());
+
+        // No valuation from negative free cash flow
+        let burning = DcfInputs { free_cash_flow: Some(-10.0), ..inputs() };
+        assert!(dcf_valuation(&burning, Some(900.0), &assumptions).is_none());
+    }
+}
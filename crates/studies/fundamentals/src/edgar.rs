@@ -0,0 +1,749 @@
+// SEC EDGAR XBRL company facts
+// Maps us-gaap tags from the companyfacts JSON onto the shared statement structs,
+// read from the SEC API or from a local bulk download directory
+
+use async_trait::async_trait;
+use buenotea_core::market_data::{
+    BalanceSheet, CashFlowStatement, CompanyProfile, DailyBar, IncomeStatement, MarketDataProvider, ProviderChain,
+    ProviderResponse, Quote, StatementPeriod,
+};
+use buenotea_core::{Error, Result};
+use chrono::{Datelike, Duration, NaiveDate};
+use reqwest::Client;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::info;
+
+const PROVIDER_NAME: &str = "SEC EDGAR";
+const COMPANY_FACTS_URL: &str = "https://data.sec.gov/api/xbrl/companyfacts";
+const COMPANY_TICKERS_URL: &str = "https://www.sec.gov/files/company_tickers.json";
+
+/// Ticker file name, the same for the API and the bulk directory
+const COMPANY_TICKERS_FILE: &str = "company_tickers.json";
+
+/// Day spans of annual and quarterly duration facts (52/53-week years and 13-week quarters)
+const ANNUAL_DAYS: std::ops::RangeInclusive<i64> = 340..=390;
+const QUARTER_DAYS: std::ops::RangeInclusive<i64> = 80..=100;
+
+/// Tags tried in order for each field; the first tag reported for a period wins
+mod tags {
+    pub const REVENUE: &[&str] = &[
+        "Revenues",
+        "RevenueFromContractWithCustomerExcludingAssessedTax",
+        "RevenueFromContractWithCustomerIncludingAssessedTax",
+        "SalesRevenueNet",
+    ];
+    pub const COST_OF_REVENUE: &[&str] = &["CostOfRevenue", "CostOfGoodsAndServicesSold", "CostOfGoodsSold"];
+    pub const GROSS_PROFIT: &[&str] = &["GrossProfit"];
+    pub const RESEARCH_AND_DEVELOPMENT: &[&str] = &["ResearchAndDevelopmentExpense"];
+    pub const SELLING_GENERAL_ADMIN: &[&str] = &["SellingGeneralAndAdministrativeExpense"];
+    pub const DEPRECIATION: &[&str] = &[
+        "DepreciationDepletionAndAmortization",
+        "DepreciationAmortizationAndAccretionNet",
+        "DepreciationAndAmortization",
+    ];
+    pub const OPERATING_INCOME: &[&str] = &["OperatingIncomeLoss"];
+    pub const INTEREST_EXPENSE: &[&str] = &["InterestExpense", "InterestExpenseNonoperating"];
+    pub const INCOME_BEFORE_TAX: &[&str] = &[
+        "IncomeLossFromContinuingOperationsBeforeIncomeTaxesExtraordinaryItemsNoncontrollingInterest",
+        "IncomeLossFromContinuingOperationsBeforeIncomeTaxesMinorityInterestAndIncomeLossFromEquityMethodInvestments",
+    ];
+    pub const INCOME_TAX: &[&str] = &["IncomeTaxExpenseBenefit"];
+    pub const NET_INCOME: &[&str] = &["NetIncomeLoss", "ProfitLoss"];
+    pub const EPS: &[&str] = &["EarningsPerShareBasic"];
+    pub const EPS_DILUTED: &[&str] = &["EarningsPerShareDiluted"];
+    pub const SHARES: &[&str] = &["WeightedAverageNumberOfSharesOutstandingBasic"];
+    pub const SHARES_DILUTED: &[&str] = &["WeightedAverageNumberOfDilutedSharesOutstanding"];
+
+    pub const CASH: &[&str] = &[
+        "CashAndCashEquivalentsAtCarryingValue",
+        "CashCashEquivalentsRestrictedCashAndRestrictedCashEquivalents",
+    ];
+    pub const SHORT_TERM_INVESTMENTS: &[&str] = &["ShortTermInvestments", "MarketableSecuritiesCurrent"];
+    pub const RECEIVABLES: &[&str] = &["AccountsReceivableNetCurrent"];
+    pub const INVENTORY: &[&str] = &["InventoryNet"];
+    pub const CURRENT_ASSETS: &[&str] = &["AssetsCurrent"];
+    pub const PPE: &[&str] = &["PropertyPlantAndEquipmentNet"];
+    pub const GOODWILL: &[&str] = &["Goodwill"];
+    pub const INTANGIBLES: &[&str] = &["IntangibleAssetsNetExcludingGoodwill", "FiniteLivedIntangibleAssetsNet"];
+    pub const ASSETS: &[&str] = &["Assets"];
+    pub const ACCOUNTS_PAYABLE: &[&str] = &["AccountsPayableCurrent"];
+    pub const SHORT_TERM_DEBT: &[&str] = &["DebtCurrent", "LongTermDebtCurrent", "ShortTermBorrowings"];
+    pub const CURRENT_LIABILITIES: &[&str] = &["LiabilitiesCurrent"];
+    pub const LONG_TERM_DEBT: &[&str] = &["LongTermDebtNoncurrent", "LongTermDebt"];
+    pub const LIABILITIES: &[&str] = &["Liabilities"];
+    pub const LIABILITIES_AND_EQUITY: &[&str] = &["LiabilitiesAndStockholdersEquity"];
+    pub const RETAINED_EARNINGS: &[&str] = &["RetainedEarningsAccumulatedDeficit"];
+    pub const EQUITY: &[&str] = &[
+        "StockholdersEquity",
+        "StockholdersEquityIncludingPortionAttributableToNoncontrollingInterest",
+    ];
+    pub const SHARES_OUTSTANDING: &[&str] = &["CommonStockSharesOutstanding"];
+
+    pub const OPERATING_CASH_FLOW: &[&str] = &["NetCashProvidedByUsedInOperatingActivities"];
+    pub const CAPEX: &[&str] = &["PaymentsToAcquirePropertyPlantAndEquipment"];
+    pub const STOCK_COMPENSATION: &[&str] = &["ShareBasedCompensation"];
+    pub const DIVIDENDS: &[&str] = &["PaymentsOfDividends", "PaymentsOfDividendsCommonStock"];
+    pub const BUYBACKS: &[&str] = &["PaymentsForRepurchaseOfCommonStock"];
+}
+
+/// Symbol to SEC central index key mapping
+#[derive(Debug, Clone, Default)]
+pub struct CikMap {
+    ciks: HashMap<String, u64>,
+}
+
+impl CikMap {
+    /// Parse the SEC `company_tickers.json` table (`{"0": {"cik_str": 320193, "ticker": "AAPL", ...}}`)
+    pub fn from_company_tickers(json: &Value) -> Self {
+        let ciks = json
+            .as_object()
+            .into_iter()
+            .flat_map(|rows| rows.values())
+            .filter_map(|row| Some((row["ticker"].as_str()?.to_uppercase(), row["cik_str"].as_u64()?)))
+            .collect();
+        Self { ciks }
+    }
+
+    /// CIK for a ticker; share classes written with a dot or dash both match
+    pub fn get(&self, symbol: &str) -> Option<u64> {
+        let symbol = symbol.to_uppercase();
+        self.ciks
+            .get(&symbol)
+            .or_else(|| self.ciks.get(&symbol.replace('.', "-")))
+            .copied()
+    }
+
+    /// Add or override a mapping
+    pub fn insert(&mut self, symbol: &str, cik: u64) {
+        self.ciks.insert(symbol.to_uppercase(), cik);
+    }
+
+    pub fn len(&self) -> usize {
+        self.ciks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ciks.is_empty()
+    }
+}
+
+/// One reported value of a tag
+#[derive(Debug, Clone, PartialEq)]
+struct Fact {
+    start: Option<NaiveDate>,
+    end: NaiveDate,
+    value: f64,
+    filed: NaiveDate,
+    fiscal_period: String,
+}
+
+/// Values of one tag per period, keeping the first filing of each (later filings repeat them as comparatives)
+#[derive(Debug, Clone, Default)]
+struct TagSeries {
+    annual: BTreeMap<NaiveDate, Fact>,
+    quarterly: BTreeMap<NaiveDate, Fact>,
+    instant: BTreeMap<NaiveDate, Fact>,
+}
+
+fn keep_first_filed(map: &mut BTreeMap<NaiveDate, Fact>, fact: Fact) {
+    match map.get(&fact.end) {
+        Some(existing) if existing.filed <= fact.filed => {}
+        _ => {
+            map.insert(fact.end, fact);
+        }
+    }
+}
+
+impl TagSeries {
+    fn from_facts(facts: Vec<Fact>) -> Self {
+        let mut series = Self::default();
+        let mut durations: BTreeMap<(NaiveDate, NaiveDate), Fact> = BTreeMap::new();
+
+        for fact in facts {
+            match fact.start {
+                None => keep_first_filed(&mut series.instant, fact),
+                Some(start) => {
+                    let key = (start, fact.end);
+                    match durations.get(&key) {
+                        Some(existing) if existing.filed <= fact.filed => {}
+                        _ => {
+                            durations.insert(key, fact);
+                        }
+                    }
+                }
+            }
+        }
+
+        for fact in durations.values() {
+            let days = (fact.end - fact.start.unwrap_or(fact.end)).num_days();
+            if ANNUAL_DAYS.contains(&days) {
+                keep_first_filed(&mut series.annual, fact.clone());
+            } else if QUARTER_DAYS.contains(&days) {
+                keep_first_filed(&mut series.quarterly, fact.clone());
+            }
+        }
+
+        // Cash flows are reported year-to-date and fourth quarters only as the full year:
+        // a quarter is the difference of two cumulative values that share a start date
+        for ((start, end), later) in &durations {
+            if series.quarterly.contains_key(end) {
+                continue;
+            }
+            let earlier = durations
+                .range((*start, NaiveDate::MIN)..(*start, *end))
+                .map(|(_, fact)| fact)
+                .rev()
+                .find(|fact| QUARTER_DAYS.contains(&(*end - fact.end).num_days()));
+            if let Some(earlier) = earlier {
+                series.quarterly.insert(
+                    *end,
+                    Fact {
+                        start: Some(earlier.end + Duration::days(1)),
+                        end: *end,
+                        value: later.value - earlier.value,
+                        filed: later.filed.max(earlier.filed),
+                        fiscal_period: if ANNUAL_DAYS.contains(&(*end - *start).num_days()) {
+                            "Q4".to_string()
+                        } else {
+                            later.fiscal_period.clone()
+                        },
+                    },
+                );
+            }
+        }
+        series
+    }
+
+    fn periods(&self, kind: Kind) -> &BTreeMap<NaiveDate, Fact> {
+        match kind {
+            Kind::Annual => &self.annual,
+            Kind::Quarterly => &self.quarterly,
+            Kind::Instant => &self.instant,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Annual,
+    Quarterly,
+    Instant,
+}
+
+impl From<StatementPeriod> for Kind {
+    fn from(period: StatementPeriod) -> Self {
+        match period {
+            StatementPeriod::Annual => Kind::Annual,
+            StatementPeriod::Quarterly => Kind::Quarterly,
+        }
+    }
+}
+
+/// Parsed us-gaap facts of one company
+#[derive(Debug, Clone, Default)]
+pub struct CompanyFacts {
+    pub cik: Option<u64>,
+    pub entity_name: Option<String>,
+    series: HashMap<String, TagSeries>,
+}
+
+impl CompanyFacts {
+    /// Parse a companyfacts JSON document, keeping USD, share and per-share units
+    pub fn from_json(json: &Value) -> Self {
+        let mut series = HashMap::new();
+        if let Some(gaap) = json["facts"]["us-gaap"].as_object() {
+            for (tag, body) in gaap {
+                let Some(units) = body["units"].as_object() else { continue };
+                let facts: Vec<Fact> = ["USD", "shares", "USD/shares"]
+                    .iter()
+                    .filter_map(|unit| units.get(*unit).and_then(|v| v.as_array()))
+                    .flatten()
+                    .filter_map(parse_fact)
+                    .collect();
+                if !facts.is_empty() {
+                    series.insert(tag.clone(), TagSeries::from_facts(facts));
+                }
+            }
+        }
+        Self {
+            cik: json["cik"].as_u64(),
+            entity_name: json["entityName"].as_str().map(str::to_string),
+            series,
+        }
+    }
+
+    /// First fact among `tags` for the period ending on `end`
+    fn fact(&self, tags: &[&str], kind: Kind, end: NaiveDate) -> Option<&Fact> {
+        tags.iter().find_map(|tag| self.series.get(*tag)?.periods(kind).get(&end))
+    }
+
+    fn value(&self, tags: &[&str], kind: Kind, end: NaiveDate) -> Option<f64> {
+        self.fact(tags, kind, end).map(|fact| fact.value)
+    }
+
+    /// Period ends reported for any of the anchor tags, newest first
+    fn period_ends(&self, anchors: &[&[&str]], kind: Kind) -> Vec<NaiveDate> {
+        let mut ends: Vec<NaiveDate> = anchors
+            .iter()
+            .flat_map(|tags| tags.iter())
+            .filter_map(|tag| self.series.get(*tag))
+            .flat_map(|series| series.periods(kind).keys().copied())
+            .collect();
+        ends.sort_unstable_by(|a, b| b.cmp(a));
+        ends.dedup();
+        ends
+    }
+
+    /// Earliest filing that reported the anchor tags for a period, and its fiscal period label
+    fn filing(&self, anchors: &[&[&str]], kind: Kind, end: NaiveDate) -> (Option<NaiveDate>, String) {
+        let fact = anchors
+            .iter()
+            .filter_map(|tags| self.fact(tags, kind, end))
+            .min_by_key(|fact| fact.filed);
+        let label = match (kind, fact) {
+            (Kind::Annual, _) => "FY".to_string(),
+            (_, Some(fact)) => fact.fiscal_period.clone(),
+            (_, None) => String::new(),
+        };
+        (fact.map(|fact| fact.filed), label)
+    }
+
+    /// Income statements, newest first
+    pub fn income_statements(&self, period: StatementPeriod, limit: usize) -> Vec<IncomeStatement> {
+        use tags::*;
+        let kind = Kind::from(period);
+        let anchors: &[&[&str]] = &[NET_INCOME, REVENUE];
+        self.period_ends(anchors, kind)
+            .into_iter()
+            .take(limit)
+            .map(|end| {
+                let (filing_date, label) = self.filing(anchors, kind, end);
+                let value = |tags: &[&str]| self.value(tags, kind, end);
+                let revenue = value(REVENUE);
+                let cost_of_revenue = value(COST_OF_REVENUE);
+                IncomeStatement {
+                    date: end,
+                    filing_date,
+                    period: label,
+                    fiscal_year: Some(end.year()),
+                    reported_currency: Some("USD".to_string()),
+                    revenue,
+                    cost_of_revenue,
+                    gross_profit: value(GROSS_PROFIT).or(revenue.zip(cost_of_revenue).map(|(r, c)| r - c)),
+                    research_and_development: value(RESEARCH_AND_DEVELOPMENT),
+                    selling_general_admin: value(SELLING_GENERAL_ADMIN),
+                    depreciation_and_amortization: value(DEPRECIATION),
+                    operating_income: value(OPERATING_INCOME),
+                    interest_expense: value(INTEREST_EXPENSE),
+                    income_before_tax: value(INCOME_BEFORE_TAX),
+                    income_tax_expense: value(INCOME_TAX),
+                    net_income: value(NET_INCOME),
+                    ebitda: None,
+                    eps: value(EPS),
+                    eps_diluted: value(EPS_DILUTED),
+                    weighted_average_shares: value(SHARES),
+                    weighted_average_shares_diluted: value(SHARES_DILUTED),
+                }
+            })
+            .collect()
+    }
+
+    /// Balance sheets at the annual or quarterly period ends, newest first
+    pub fn balance_sheets(&self, period: StatementPeriod, limit: usize) -> Vec<BalanceSheet> {
+        use tags::*;
+        // Balance sheets are instants; the income statement periods say which instants are period ends
+        let kind = Kind::from(period);
+        let anchors: &[&[&str]] = &[NET_INCOME, REVENUE];
+        self.period_ends(anchors, kind)
+            .into_iter()
+            .filter(|end| self.value(ASSETS, Kind::Instant, *end).is_some())
+            .take(limit)
+            .map(|end| {
+                let (filing_date, label) = self.filing(&[ASSETS], Kind::Instant, end);
+                let value = |tags: &[&str]| self.value(tags, Kind::Instant, end);
+                let short_term_debt = value(SHORT_TERM_DEBT);
+                let long_term_debt = value(LONG_TERM_DEBT);
+                let total_equity = value(EQUITY);
+                BalanceSheet {
+                    date: end,
+                    filing_date,
+                    period: if kind == Kind::Annual { "FY".to_string() } else { label },
+                    fiscal_year: Some(end.year()),
+                    reported_currency: Some("USD".to_string()),
+                    cash_and_equivalents: value(CASH),
+                    short_term_investments: value(SHORT_TERM_INVESTMENTS),
+                    net_receivables: value(RECEIVABLES),
+                    inventory: value(INVENTORY),
+                    total_current_assets: value(CURRENT_ASSETS),
+                    property_plant_equipment_net: value(PPE),
+                    goodwill: value(GOODWILL),
+                    intangible_assets: value(INTANGIBLES),
+                    total_assets: value(ASSETS),
+                    accounts_payable: value(ACCOUNTS_PAYABLE),
+                    short_term_debt,
+                    total_current_liabilities: value(CURRENT_LIABILITIES),
+                    long_term_debt,
+                    total_debt: match (short_term_debt, long_term_debt) {
+                        (None, None) => None,
+                        (short, long) => Some(short.unwrap_or(0.0) + long.unwrap_or(0.0)),
+                    },
+                    total_liabilities: value(LIABILITIES)
+                        .or(value(LIABILITIES_AND_EQUITY).zip(total_equity).map(|(total, equity)| total - equity)),
+                    retained_earnings: value(RETAINED_EARNINGS),
+                    total_equity,
+                    shares_outstanding: value(SHARES_OUTSTANDING),
+                }
+            })
+            .collect()
+    }
+
+    /// Cash flow statements, newest first
+    pub fn cash_flow_statements(&self, period: StatementPeriod, limit: usize) -> Vec<CashFlowStatement> {
+        use tags::*;
+        let kind = Kind::from(period);
+        let anchors: &[&[&str]] = &[OPERATING_CASH_FLOW];
+        self.period_ends(anchors, kind)
+            .into_iter()
+            .take(limit)
+            .map(|end| {
+                let (filing_date, label) = self.filing(anchors, kind, end);
+                let value = |tags: &[&str]| self.value(tags, kind, end);
+                let operating_cash_flow = value(OPERATING_CASH_FLOW);
+                let capital_expenditure = value(CAPEX).map(f64::abs);
+                CashFlowStatement {
+                    date: end,
+                    filing_date,
+                    period: label,
+                    fiscal_year: Some(end.year()),
+                    reported_currency: Some("USD".to_string()),
+                    net_income: value(NET_INCOME),
+                    depreciation_and_amortization: value(DEPRECIATION),
+                    stock_based_compensation: value(STOCK_COMPENSATION),
+                    operating_cash_flow,
+                    capital_expenditure,
+                    free_cash_flow: operating_cash_flow.map(|ocf| ocf - capital_expenditure.unwrap_or(0.0)),
+                    dividends_paid: value(DIVIDENDS).map(f64::abs),
+                    common_stock_repurchased: value(BUYBACKS).map(f64::abs),
+                }
+            })
+            .collect()
+    }
+}
+
+fn parse_date(value: &Value) -> Option<NaiveDate> {
+    value.as_str().and_then(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok())
+}
+
+fn parse_fact(entry: &Value) -> Option<Fact> {
+    let form = entry["form"].as_str()?;
+    if !(form.starts_with("10-K") || form.starts_with("10-Q")) {
+        return None;
+    }
+    Some(Fact {
+        start: parse_date(&entry["start"]),
+        end: parse_date(&entry["end"])?,
+        value: entry["val"].as_f64().filter(|v| v.is_finite())?,
+        filed: parse_date(&entry["filed"])?,
+        fiscal_period: entry["fp"].as_str().unwrap_or_default().to_string(),
+    })
+}
+
+/// Where company facts are read from
+#[derive(Debug, Clone)]
+pub enum EdgarSource {
+    /// data.sec.gov; the SEC requires a User-Agent naming the requester and a contact address
+    Api { user_agent: String },
+    /// Directory with `company_tickers.json` and the extracted `companyfacts.zip` (`CIK##########.json`)
+    Directory(PathBuf),
+}
+
+/// Financial statements from SEC EDGAR company facts
+///
+/// Only statements are supported; bars, quotes and profiles return an error so
+/// a `ProviderChain` moves on to the next provider.
+pub struct EdgarProvider {
+    client: Client,
+    source: EdgarSource,
+    ciks: Mutex<Option<Arc<CikMap>>>,
+    /// Facts of the last company fetched, reused across its statement requests
+    last_facts: Mutex<Option<(u64, Arc<CompanyFacts>)>>,
+}
+
+impl EdgarProvider {
+    pub fn new(source: EdgarSource) -> Self {
+        Self {
+            client: Client::new(),
+            source,
+            ciks: Mutex::new(None),
+            last_facts: Mutex::new(None),
+        }
+    }
+
+    /// Create from SEC_EDGAR_DIR (bulk download directory) or SEC_USER_AGENT (API access)
+    pub fn from_env() -> Result<Self> {
+        if let Ok(dir) = std::env::var("SEC_EDGAR_DIR") {
+            return Ok(Self::new(EdgarSource::Directory(PathBuf::from(dir))));
+        }
+        let user_agent = std::env::var("SEC_USER_AGENT").map_err(|_| Error::MissingApiKey("SEC_USER_AGENT".to_string()))?;
+        Ok(Self::new(EdgarSource::Api { user_agent }))
+    }
+
+    /// Use a prepared symbol→CIK table instead of loading `company_tickers.json`
+    pub async fn with_cik_map(self, ciks: CikMap) -> Self {
+        *self.ciks.lock().await = Some(Arc::new(ciks));
+        self
+    }
+
+    /// CIK for a symbol, loading the ticker table on first use
+    pub async fn cik(&self, symbol: &str) -> Result<u64> {
+        let ciks = {
+            let mut ciks = self.ciks.lock().await;
+            if ciks.is_none() {
+                let json = self.read(COMPANY_TICKERS_URL, COMPANY_TICKERS_FILE).await?;
+                let map = CikMap::from_company_tickers(&json);
+                info!("Loaded {} SEC ticker mappings", map.len());
+                *ciks = Some(Arc::new(map));
+            }
+            ciks.clone().unwrap_or_default()
+        };
+        ciks.get(symbol)
+            .ok_or_else(|| Error::ApiError(PROVIDER_NAME.to_string(), format!("No CIK for {}", symbol)))
+    }
+
+    /// Company facts for a symbol and where they were read from
+    async fn company_facts(&self, symbol: &str) -> Result<(String, Arc<CompanyFacts>)> {
+        let cik = self.cik(symbol).await?;
+        let file = format!("CIK{:010}.json", cik);
+        let location = match &self.source {
+            EdgarSource::Api { .. } => format!("{}/{}", COMPANY_FACTS_URL, file),
+            EdgarSource::Directory(dir) => dir.join(&file).display().to_string(),
+        };
+
+        let mut last = self.last_facts.lock().await;
+        if let Some((cached, facts)) = last.as_ref() {
+            if *cached == cik {
+                return Ok((location, facts.clone()));
+            }
+        }
+        let json = self.read(&location, &file).await?;
+        let facts = Arc::new(CompanyFacts::from_json(&json));
+        *last = Some((cik, facts.clone()));
+        Ok((location, facts))
+    }
+
+    /// Read a JSON document from the API URL or the file of the same name in the bulk directory
+    async fn read(&self, url: &str, file: &str) -> Result<Value> {
+        match &self.source {
+            EdgarSource::Directory(dir) => {
+                let path = dir.join(file);
+                let text = tokio::fs::read_to_string(&path).await.map_err(|e| {
+                    Error::ApiError(PROVIDER_NAME.to_string(), format!("Cannot read {}: {}", path.display(), e))
+                })?;
+                Ok(serde_json::from_str(&text)?)
+            }
+            EdgarSource::Api { user_agent } => {
+                let response = self.client.get(url).header("User-Agent", user_agent).send().await?;
+                let status = response.status();
+                if status.as_u16() == 429 {
+                    return Err(Error::RateLimitExceeded(PROVIDER_NAME.to_string()));
+                }
+                if !status.is_success() {
+                    return Err(Error::ApiError(PROVIDER_NAME.to_string(), format!("HTTP {} for {}", status, url)));
+                }
+                Ok(response.json().await?)
+            }
+        }
+    }
+
+    async fn statements<T: serde::Serialize>(
+        &self,
+        symbol: &str,
+        parse: impl FnOnce(&CompanyFacts) -> Vec<T>,
+    ) -> Result<ProviderResponse<Vec<T>>> {
+        let (url, facts) = self.company_facts(symbol).await?;
+        let data = parse(&facts);
+        if data.is_empty() {
+            return Err(Error::ApiError(PROVIDER_NAME.to_string(), format!("No us-gaap statements for {}", symbol)));
+        }
+        Ok(ProviderResponse {
+            raw: serde_json::to_value(&data)?,
+            data,
+            source: PROVIDER_NAME.to_string(),
+            url,
+        })
+    }
+}
+
+/// Whether EDGAR statements are tried before or after the other providers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgarPriority {
+    Primary,
+    Fallback,
+}
+
+impl EdgarPriority {
+    /// SEC_EDGAR_PRIORITY=primary puts EDGAR first; anything else keeps it as a fallback
+    pub fn from_env() -> Self {
+        match std::env::var("SEC_EDGAR_PRIORITY").map(|v| v.to_lowercase()).as_deref() {
+            Ok("primary") => EdgarPriority::Primary,
+            _ => EdgarPriority::Fallback,
+        }
+    }
+}
+
+/// Chain EDGAR with other providers; prices and profiles always come from `providers`
+pub fn chain_with_edgar(edgar: EdgarProvider, providers: Arc<dyn MarketDataProvider>, priority: EdgarPriority) -> ProviderChain {
+    let edgar: Arc<dyn MarketDataProvider> = Arc::new(edgar);
+    match priority {
+        EdgarPriority::Primary => ProviderChain::new(vec![edgar, providers]),
+        EdgarPriority::Fallback => ProviderChain::new(vec![providers, edgar]),
+    }
+}
+
+fn unsupported(what: &str) -> Error {
+    Error::ApiError(PROVIDER_NAME.to_string(), format!("{} are not supported by this provider", what))
+}
+
+#[async_trait]
+impl MarketDataProvider for EdgarProvider {
+    fn name(&self) -> &str {
+        PROVIDER_NAME
+    }
+
+    async fn daily_bars(&self, _symbol: &str, _limit: usize) -> Result<ProviderResponse<Vec<DailyBar>>> {
+        Err(unsupported("daily bars"))
+    }
+
+    async fn quote(&self, _symbol: &str) -> Result<ProviderResponse<Quote>> {
+        Err(unsupported("quotes"))
+    }
+
+    async fn company_profile(&self, _symbol: &str) -> Result<ProviderResponse<CompanyProfile>> {
+        Err(unsupported("company profiles"))
+    }
+
+    async fn income_statements(
+        &self,
+        symbol: &str,
+        period: StatementPeriod,
+        limit: usize,
+    ) -> Result<ProviderResponse<Vec<IncomeStatement>>> {
+        self.statements(symbol, |facts| facts.income_statements(period, limit)).await
+    }
+
+    async fn balance_sheets(
+        &self,
+        symbol: &str,
+        period: StatementPeriod,
+        limit: usize,
+    ) -> Result<ProviderResponse<Vec<BalanceSheet>>> {
+        self.statements(symbol, |facts| facts.balance_sheets(period, limit)).await
+    }
+
+    async fn cash_flow_statements(
+        &self,
+        symbol: &str,
+        period: StatementPeriod,
+        limit: usize,
+    ) -> Result<ProviderResponse<Vec<CashFlowStatement>>> {
+        self.statements(symbol, |facts| facts.cash_flow_statements(period, limit)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn fact(start: Option<&str>, end: &str, val: f64, fp: &str, form: &str, filed: &str) -> Value {
+        let mut fact = json!({"end": end, "val": val, "fp": fp, "form": form, "filed": filed});
+        if let Some(start) = start {
+            fact["start"] = json!(start);
+        }
+        fact
+    }
+
+    fn company_facts() -> Value {
+        json!({
+            "cik": 320193,
+            "entityName": "Example Inc.",
+            "facts": {"us-gaap": {
+                "Revenues": {"units": {"USD": [
+                    fact(Some("2023-01-01"), "2023-03-31", 100.0, "Q1", "10-Q", "2023-05-01"),
+                    fact(Some("2023-01-01"), "2023-12-31", 450.0, "FY", "10-K", "2024-02-15"),
+                    // Comparative repeated in the next year's 10-K
+                    fact(Some("2023-01-01"), "2023-12-31", 450.0, "FY", "10-K", "2025-02-15"),
+                    fact(Some("2023-01-01"), "2023-09-30", 330.0, "Q3", "10-Q", "2023-11-01"),
+                ]}},
+                "NetCashProvidedByUsedInOperatingActivities": {"units": {"USD": [
+                    fact(Some("2023-01-01"), "2023-03-31", 20.0, "Q1", "10-Q", "2023-05-01"),
+                    fact(Some("2023-01-01"), "2023-06-30", 50.0, "Q2", "10-Q", "2023-08-01"),
+                ]}},
+                "Assets": {"units": {"USD": [
+                    fact(None, "2023-12-31", 1000.0, "FY", "10-K", "2024-02-15"),
+                ]}},
+                "StockholdersEquity": {"units": {"USD": [
+                    fact(None, "2023-12-31", 600.0, "FY", "10-K", "2024-02-15"),
+                ]}},
+                "LiabilitiesAndStockholdersEquity": {"units": {"USD": [
+                    fact(None, "2023-12-31", 1000.0, "FY", "10-K", "2024-02-15"),
+                ]}},
+                "EarningsPerShareDiluted": {"units": {"USD/shares": [
+                    fact(Some("2023-01-01"), "2023-12-31", 1.5, "FY", "10-K", "2024-02-15"),
+                ]}},
+                "Revenues8K": {"units": {"USD": [
+                    fact(Some("2023-01-01"), "2023-12-31", 1.0, "FY", "8-K", "2024-01-15"),
+                ]}},
+            }}
+        })
+    }
+
+    #[test]
+    fn test_cik_map_from_company_tickers() {
+        let tickers = json!({
+            "0": {"cik_str": 320193, "ticker": "AAPL", "title": "Apple Inc."},
+            "1": {"cik_str": 1067983, "ticker": "BRK-B", "title": "Berkshire Hathaway"},
+        });
+        let mut map = CikMap::from_company_tickers(&tickers);
+        assert_eq!(map.get("aapl"), Some(320193));
+        assert_eq!(map.get("BRK.B"), Some(1067983));
+        assert_eq!(map.get("MSFT"), None);
+        map.insert("msft", 789019);
+        assert_eq!(map.get("MSFT"), Some(789019));
+    }
+
+    #[test]
+    fn test_statements_from_company_facts() {
+        let facts = CompanyFacts::from_json(&company_facts());
+        assert_eq!(facts.entity_name.as_deref(), Some("Example Inc."));
+
+        let annual = facts.income_statements(StatementPeriod::Annual, 4);
+        assert_eq!(annual.len(), 1);
+        assert_eq!(annual[0].revenue, Some(450.0));
+        assert_eq!(annual[0].eps_diluted, Some(1.5));
+        // First filing wins over the comparative
+        assert_eq!(annual[0].filing_date, NaiveDate::from_ymd_opt(2024, 2, 15));
+
+        // Q1 is reported directly, Q4 is the full year less the nine-month year-to-date
+        let quarterly = facts.income_statements(StatementPeriod::Quarterly, 4);
+        assert_eq!(quarterly.len(), 2);
+        assert_eq!(quarterly[0].date, NaiveDate::from_ymd_opt(2023, 12, 31).unwrap());
+        assert_eq!(quarterly[0].revenue, Some(120.0));
+        assert_eq!(quarterly[0].period, "Q4");
+        assert_eq!(quarterly[1].revenue, Some(100.0));
+
+        // Year-to-date cash flows become quarters
+        let cash_flows = facts.cash_flow_statements(StatementPeriod::Quarterly, 4);
+        assert_eq!(cash_flows[0].operating_cash_flow, Some(30.0));
+        assert_eq!(cash_flows[1].operating_cash_flow, Some(20.0));
+
+        let balance = facts.balance_sheets(StatementPeriod::Annual, 4);
+        assert_eq!(balance.len(), 1);
+        assert_eq!(balance[0].total_liabilities, Some(400.0));
+        assert_eq!(balance[0].total_debt, None);
+    }
+}
@@ -0,0 +1,371 @@
+// Financial Modeling Prep fundamentals data
+// Reads vendor ratios, key metrics and growth through the market data provider and maps them onto the metric structs
+
+use super::models::*;
+use buenotea_core::market_data::{
+    is_historical, CompanyProfile, FinancialGrowth, FinancialRatios, KeyMetrics, MarketDataProvider, ProviderResponse,
+    StatementPeriod, TreasuryYields,
+};
+use buenotea_core::Result;
+use chrono::NaiveDate;
+use serde::Serialize;
+use serde_json::Value;
+use tracing::warn;
+
+/// One provider dataset: where it came from and its entries public on the as-of date, newest first
+#[derive(Debug, Clone)]
+pub struct Dataset<T> {
+    pub source: Option<String>,
+    pub url: Option<String>,
+    pub entries: Vec<T>,
+}
+
+impl<T> Default for Dataset<T> {
+    fn default() -> Self {
+        Self { source: None, url: None, entries: Vec::new() }
+    }
+}
+
+impl<T> Dataset<T> {
+    /// Keep a successful response; a failed one is logged and leaves the dataset empty
+    fn from_response(response: Result<ProviderResponse<Vec<T>>>, what: &str, symbol: &str) -> Self {
+        match response {
+            Ok(response) => Self {
+                source: Some(response.source),
+                url: Some(without_api_key(&response.url)),
+                entries: response.data,
+            },
+            Err(e) => {
+                warn!("{} unavailable for {}: {}", what, symbol, e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Most recent entry
+    pub fn latest(&self) -> Option<&T> {
+        self.entries.first()
+    }
+
+    /// Field of the most recent entry
+    pub fn field(&self, field: impl Fn(&T) -> Option<f64>) -> Option<f64> {
+        self.latest().and_then(field)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Vendor fundamentals for one symbol, truncated to what was known on `as_of`
+#[derive(Debug, Clone, Default)]
+pub struct FmpFundamentals {
+    pub as_of: Option<NaiveDate>,
+    pub ratios: Dataset<FinancialRatios>,
+    pub key_metrics: Dataset<KeyMetrics>,
+    pub growth: Dataset<FinancialGrowth>,
+    pub quarterly_growth: Dataset<FinancialGrowth>,
+    /// Company profile, only fetched for live calculations
+    pub profile: Dataset<CompanyProfile>,
+    /// Treasury yield curve on the as-of date
+    pub treasury: Dataset<TreasuryYields>,
+}
+
+impl FmpFundamentals {
+    /// Fetch the vendor datasets for `symbol`, keeping entries public on `as_of`
+    ///
+    /// Individual dataset failures are logged and leave that dataset empty.
+    pub async fn fetch(provider: &dyn MarketDataProvider, symbol: &str, as_of: NaiveDate) -> Self {
+        use StatementPeriod::{Annual, Quarterly};
+
+        let mut data = Self {
+            as_of: Some(as_of),
+            ratios: Dataset::from_response(provider.financial_ratios_as_of(symbol, Annual, 1, as_of).await, "Financial ratios", symbol),
+            key_metrics: Dataset::from_response(provider.key_metrics_as_of(symbol, Annual, 1, as_of).await, "Key metrics", symbol),
+            growth: Dataset::from_response(provider.financial_growth_as_of(symbol, Annual, 1, as_of).await, "Annual growth", symbol),
+            quarterly_growth: Dataset::from_response(
+                provider.financial_growth_as_of(symbol, Quarterly, 1, as_of).await,
+                "Quarterly growth",
+                symbol,
+            ),
+            treasury: Dataset::from_response(
+                provider.treasury_yields_as_of(as_of).await.map(|r| r.map(|yields| vec![yields])),
+                "Treasury yields",
+                symbol,
+            ),
+            ..Default::default()
+        };
+
+        // The profile describes the company today, so it is skipped for past dates
+        if !is_historical(as_of) {
+            data.profile = Dataset::from_response(
+                provider.company_profile(symbol).await.map(|r| r.map(|profile| vec![profile])),
+                "Company profile",
+                symbol,
+            );
+        }
+        data
+    }
+
+    pub fn profitability(&self) -> ProfitabilityMetrics {
+        ProfitabilityMetrics {
+            roe: self.ratios.field(|r| r.return_on_equity),
+            roa: self.ratios.field(|r| r.return_on_assets),
+            roic: self.key_metrics.field(|r| r.roic),
+            net_profit_margin: self.ratios.field(|r| r.net_profit_margin),
+            gross_profit_margin: self.ratios.field(|r| r.gross_profit_margin),
+            operating_profit_margin: self.ratios.field(|r| r.operating_profit_margin),
+            // Derived from the statements
+            ebitda_margin: None,
+        }
+    }
+
+    pub fn growth_metrics(&self) -> GrowthMetrics {
+        GrowthMetrics {
+            revenue_growth_yoy: self.growth.field(|r| r.revenue_growth),
+            revenue_growth_qoq: self.quarterly_growth.field(|r| r.revenue_growth),
+            eps_growth_yoy: self.growth.field(|r| r.eps_growth),
+            eps_growth_qoq: self.quarterly_growth.field(|r| r.eps_growth),
+            net_income_growth_yoy: self.growth.field(|r| r.net_income_growth),
+            book_value_growth_yoy: self.growth.field(|r| r.book_value_per_share_growth),
+            operating_cash_flow_growth_yoy: self.growth.field(|r| r.operating_cash_flow_growth),
+        }
+    }
+
+    pub fn valuation(&self) -> ValuationMetrics {
+        ValuationMetrics {
+            pe_ratio: self.ratios.field(|r| r.price_earnings),
+            peg_ratio: self.ratios.field(|r| r.price_earnings_to_growth),
+            ps_ratio: self.ratios.field(|r| r.price_to_sales),
+            pb_ratio: self.ratios.field(|r| r.price_to_book),
+            pcf_ratio: self.ratios.field(|r| r.price_to_cash_flow),
+            ev_ebitda: self.key_metrics.field(|r| r.ev_to_ebitda),
+            ev_sales: self.key_metrics.field(|r| r.ev_to_sales),
+            pfcf_ratio: self.ratios.field(|r| r.price_to_free_cash_flow),
+        }
+    }
+
+    pub fn financial_strength(&self) -> FinancialStrengthMetrics {
+        FinancialStrengthMetrics {
+            debt_to_equity: self.ratios.field(|r| r.debt_to_equity),
+            debt_to_assets: self.ratios.field(|r| r.debt_ratio),
+            current_ratio: self.ratios.field(|r| r.current_ratio),
+            quick_ratio: self.ratios.field(|r| r.quick_ratio),
+            interest_coverage: self.ratios.field(|r| r.interest_coverage),
+            // Derived from the statements
+            cash_to_debt: None,
+            equity_multiplier: self.ratios.field(|r| r.equity_multiplier),
+            // Derived from the statements
+            altman_z_score: None,
+        }
+    }
+
+    pub fn efficiency(&self) -> EfficiencyMetrics {
+        EfficiencyMetrics {
+            asset_turnover: self.ratios.field(|r| r.asset_turnover),
+            inventory_turnover: self.ratios.field(|r| r.inventory_turnover),
+            receivables_turnover: self.ratios.field(|r| r.receivables_turnover),
+            payables_turnover: self.ratios.field(|r| r.payables_turnover),
+            // Derived from the statements
+            working_capital_turnover: None,
+            days_sales_outstanding: self.ratios.field(|r| r.days_sales_outstanding),
+            days_inventory_outstanding: self.ratios.field(|r| r.days_inventory_outstanding),
+            days_payables_outstanding: self.ratios.field(|r| r.days_payables_outstanding),
+        }
+    }
+
+    /// All five metric groups
+    pub fn metrics(&self) -> FinancialMetrics {
+        FinancialMetrics {
+            profitability: self.profitability(),
+            growth: self.growth_metrics(),
+            valuation: self.valuation(),
+            financial_strength: self.financial_strength(),
+            efficiency: self.efficiency(),
+        }
+    }
+
+    /// Company metadata from the profile (live only) and the latest key metrics
+    pub fn meta(&self) -> FundamentalsMeta {
+        let profile = self.profile.latest();
+        let text = |field: fn(&CompanyProfile) -> &Option<String>| {
+            profile
+                .and_then(|p| field(p).clone())
+                .filter(|s| !s.is_empty())
+        };
+        let market_cap = self
+            .key_metrics
+            .field(|m| m.market_cap)
+            .or_else(|| self.profile.field(|p| p.market_cap));
+
+        FundamentalsMeta {
+            sector: text(|p| &p.sector),
+            industry: text(|p| &p.industry),
+            market_cap_category: market_cap.map(|cap| market_cap_category(cap).to_string()),
+            beta: self.profile.field(|p| p.beta),
+            dividend_yield: self.ratios.field(|r| r.dividend_yield).or_else(|| self.key_metrics.field(|m| m.dividend_yield)),
+            payout_ratio: self.ratios.field(|r| r.payout_ratio).or_else(|| self.key_metrics.field(|m| m.payout_ratio)),
+            shares_outstanding: self.profile.field(|p| p.shares_outstanding).map(|shares| shares as i64),
+            market_cap: market_cap.map(|cap| cap as i64),
+            enterprise_value: self.key_metrics.field(|m| m.enterprise_value).map(|ev| ev as i64),
+            data_freshness: self.freshness(),
+            ..FundamentalsMeta::default()
+        }
+    }
+
+    /// Ten-year treasury yield on the as-of date as a decimal
+    pub fn risk_free_rate(&self) -> Option<f64> {
+        self.treasury.field(|t| t.ten_year)
+    }
+
+    /// Freshness of the latest annual ratios
+    fn freshness(&self) -> f64 {
+        match (self.as_of, self.ratios.latest()) {
+            (Some(as_of), Some(ratios)) => freshness(as_of, ratios.date),
+            _ => 0.0,
+        }
+    }
+
+    /// API tracking for each component: primary URL, availability and the entries used
+    pub fn api_urls(&self) -> crate::FundamentalsApiUrls {
+        let ratios = latest_value(&self.ratios);
+        let key_metrics = latest_value(&self.key_metrics);
+        let raw = |parts: &[(&str, &Option<Value>)]| {
+            let object: serde_json::Map<String, Value> = parts
+                .iter()
+                .filter_map(|(name, entry)| entry.as_ref().map(|entry| (name.to_string(), entry.clone())))
+                .collect();
+            (!object.is_empty()).then_some(Value::Object(object))
+        };
+
+        crate::FundamentalsApiUrls {
+            profitability_api_url: self.ratios.url.clone(),
+            profitability_api_source: self.ratios.source.clone(),
+            profitability_data_available: !self.ratios.is_empty(),
+            profitability_raw_data: raw(&[("ratios", &ratios), ("key_metrics", &key_metrics)]),
+            growth_api_url: self.growth.url.clone(),
+            growth_api_source: self.growth.source.clone(),
+            growth_data_available: !self.growth.is_empty(),
+            growth_raw_data: raw(&[
+                ("annual", &latest_value(&self.growth)),
+                ("quarterly", &latest_value(&self.quarterly_growth)),
+            ]),
+            valuation_api_url: self.key_metrics.url.clone(),
+            valuation_api_source: self.key_metrics.source.clone(),
+            valuation_data_available: !self.ratios.is_empty() || !self.key_metrics.is_empty(),
+            valuation_raw_data: raw(&[("ratios", &ratios), ("key_metrics", &key_metrics)]),
+            financial_strength_api_url: self.ratios.url.clone(),
+            financial_strength_api_source: self.ratios.source.clone(),
+            financial_strength_data_available: !self.ratios.is_empty(),
+            financial_strength_raw_data: raw(&[("ratios", &ratios)]),
+            efficiency_api_url: self.ratios.url.clone(),
+            efficiency_api_source: self.ratios.source.clone(),
+            efficiency_data_available: !self.ratios.is_empty(),
+            efficiency_raw_data: raw(&[("ratios", &ratios), ("key_metrics", &key_metrics)]),
+        }
+    }
+}
+
+/// Provider URL with its `apikey` parameter dropped, so stored URLs carry no credentials
+fn without_api_key(url: &str) -> String {
+    match url.split_once('?') {
+        Some((path, query)) => {
+            let query: Vec<&str> = query.split('&').filter(|param| !param.starts_with("apikey=")).collect();
+            if query.is_empty() {
+                path.to_string()
+            } else {
+                format!("{}?{}", path, query.join("&"))
+            }
+        }
+        None => url.to_string(),
+    }
+}
+
+/// Latest entry of a dataset as JSON for the raw-data columns
+fn latest_value<T: Serialize>(dataset: &Dataset<T>) -> Option<Value> {
+    dataset.latest().and_then(|entry| serde_json::to_value(entry).ok())
+}
+
+/// 1.0 for a period that ended within four months of `as_of`, falling to 0.0 at eighteen months
+pub fn freshness(as_of: NaiveDate, period_end: NaiveDate) -> f64 {
+    let age = (as_of - period_end).num_days() as f64;
+    (1.0 - (age - 120.0) / 420.0).clamp(0.0, 1.0)
+}
+
+/// Conventional market cap buckets
+fn market_cap_category(market_cap: f64) -> &'static str {
+    match market_cap {
+        cap if cap >= 200e9 => "Mega Cap",
+        cap if cap >= 10e9 => "Large Cap",
+        cap if cap >= 2e9 => "Mid Cap",
+        cap if cap >= 300e6 => "Small Cap",
+        _ => "Micro Cap",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_metrics_from_fmp_fields() {
+        let dataset = |entry| Dataset {
+            source: Some("FMP".to_string()),
+            url: Some("https://example.com".to_string()),
+            entries: vec![entry],
+        };
+        let data = FmpFundamentals {
+            as_of: Some(day("2024-06-30")),
+            ratios: dataset(FinancialRatios {
+                date: day("2023-12-31"),
+                return_on_equity: Some(0.25),
+                price_earnings: Some(20.0),
+                debt_to_equity: Some(0.5),
+                ..Default::default()
+            }),
+            key_metrics: Dataset {
+                entries: vec![KeyMetrics {
+                    date: day("2023-12-31"),
+                    roic: Some(0.18),
+                    market_cap: Some(3.0e11),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            treasury: Dataset {
+                entries: vec![TreasuryYields { date: day("2024-06-28"), ten_year: Some(0.0436) }],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let metrics = data.metrics();
+        assert_eq!(metrics.profitability.roe, Some(0.25));
+        assert_eq!(metrics.profitability.roic, Some(0.18));
+        assert_eq!(metrics.valuation.pe_ratio, Some(20.0));
+        // Left to the statements
+        assert_eq!(metrics.profitability.ebitda_margin, None);
+        assert_eq!(metrics.financial_strength.cash_to_debt, None);
+        assert_eq!(metrics.financial_strength.altman_z_score, None);
+
+        let meta = data.meta();
+        assert_eq!(meta.market_cap_category.as_deref(), Some("Mega Cap"));
+        assert!(meta.data_freshness > 0.8);
+
+        let urls = data.api_urls();
+        assert!(urls.profitability_data_available);
+        assert!(!urls.growth_data_available);
+        assert_eq!(urls.profitability_api_source.as_deref(), Some("FMP"));
+        assert_eq!(urls.profitability_raw_data.unwrap()["ratios"]["return_on_equity"], 0.25);
+        assert_eq!(data.risk_free_rate(), Some(0.0436));
+
+        assert_eq!(
+            without_api_key("https://financialmodelingprep.com/api/v3/ratios/AAPL?period=annual&apikey=secret"),
+            "https://financialmodelingprep.com/api/v3/ratios/AAPL?period=annual"
+        );
+    }
+}
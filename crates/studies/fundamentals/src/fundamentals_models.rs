@@ -60,6 +60,36 @@ pub struct FundamentalsRecord {
     pub equity_multiplier: Option<f64>,
     pub altman_z_score: Option<f64>,
     
+    // Quality scores
+    #[serde(default)]
+    pub piotroski_f_score: Option<i32>,
+    #[serde(default)]
+    pub beneish_m_score: Option<f64>,
+    #[serde(default)]
+    pub sloan_accrual_ratio: Option<f64>,
+    
+    // Peer-relative percentiles
+    #[serde(default)]
+    pub peer_group_level: Option<String>,
+    #[serde(default)]
+    pub peer_group: Option<String>,
+    #[serde(default)]
+    pub peer_group_size: Option<i32>,
+    #[serde(default)]
+    pub valuation_percentiles: Option<serde_json::Value>,
+    #[serde(default)]
+    pub profitability_percentiles: Option<serde_json::Value>,
+    
+    // DCF valuation
+    #[serde(default)]
+    pub dcf_intrinsic_value: Option<f64>,
+    #[serde(default)]
+    pub dcf_margin_of_safety: Option<f64>,
+    #[serde(default)]
+    pub dcf_wacc: Option<f64>,
+    #[serde(default)]
+    pub dcf_valuation: Option<serde_json::Value>,
+    
     // Efficiency metrics
     pub asset_turnover: Option<f64>,
     pub inventory_turnover: Option<f64>,
@@ -178,6 +208,24 @@ pub struct FundamentalsInsert {
     pub equity_multiplier: Option<f64>,
     pub altman_z_score: Option<f64>,
     
+    // Quality scores
+    pub piotroski_f_score: Option<i32>,
+    pub beneish_m_score: Option<f64>,
+    pub sloan_accrual_ratio: Option<f64>,
+    
+    // Peer-relative percentiles
+    pub peer_group_level: Option<String>,
+    pub peer_group: Option<String>,
+    pub peer_group_size: Option<i32>,
+    pub valuation_percentiles: Option<serde_json::Value>,
+    pub profitability_percentiles: Option<serde_json::Value>,
+    
+    // DCF valuation
+    pub dcf_intrinsic_value: Option<f64>,
+    pub dcf_margin_of_safety: Option<f64>,
+    pub dcf_wacc: Option<f64>,
+    pub dcf_valuation: Option<serde_json::Value>,
+    
     // Efficiency metrics
     pub asset_turnover: Option<f64>,
     pub inventory_turnover: Option<f64>,
@@ -341,6 +389,18 @@ pub fn create_fundamentals_record_with_tracking(
         cash_to_debt: result.metrics.financial_strength.cash_to_debt,
         equity_multiplier: result.metrics.financial_strength.equity_multiplier,
         altman_z_score: result.metrics.financial_strength.altman_z_score,
+        piotroski_f_score: result.quality.piotroski_f_score,
+        beneish_m_score: result.quality.beneish_m_score,
+        sloan_accrual_ratio: result.quality.sloan_accrual_ratio,
+        peer_group_level: result.peer_percentiles.as_ref().map(|p| p.level.to_string()),
+        peer_group: result.peer_percentiles.as_ref().map(|p| p.group.clone()),
+        peer_group_size: result.peer_percentiles.as_ref().map(|p| p.group_size as i32),
+        valuation_percentiles: result.peer_percentiles.as_ref().and_then(|p| serde_json::to_value(&p.valuation).ok()),
+        profitability_percentiles: result.peer_percentiles.as_ref().and_then(|p| serde_json::to_value(&p.profitability).ok()),
+        dcf_intrinsic_value: result.dcf.as_ref().map(|d| d.intrinsic_value_per_share),
+        dcf_margin_of_safety: result.dcf.as_ref().and_then(|d| d.margin_of_safety),
+        dcf_wacc: result.dcf.as_ref().map(|d| d.wacc),
+        dcf_valuation: result.dcf.as_ref().and_then(|d| serde_json::to_value(d).ok()),
         asset_turnover: result.metrics.efficiency.asset_turnover,
         inventory_turnover: result.metrics.efficiency.inventory_turnover,
         receivables_turnover: result.metrics.efficiency.receivables_turnover,
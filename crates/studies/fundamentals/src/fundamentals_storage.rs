@@ -4,6 +4,8 @@
 use buenotea_core::Result;
 use buenotea_infrastructure::DatabaseClient;
 use buenotea_infrastructure::fundamentals_models::{CreateFundamentalsRecord, FundamentalsInsert, FundamentalsRecord};
+use chrono::NaiveDate;
+use std::collections::BTreeSet;
 use tracing::info;
 
 /// Fundamentals data storage operations
@@ -109,6 +111,12 @@ impl FundamentalsStorage {
         Ok(ids)
     }
 
+    /// Dates between `from` and `to` that already have a fundamentals record for `symbol`
+    pub async fn get_analysis_dates(&self, symbol: &str, from: NaiveDate, to: NaiveDate) -> Result<BTreeSet<NaiveDate>> {
+        info!("Getting stored fundamentals dates for {} from {} to {}", symbol, from, to);
+        self.db_client.analysis_dates("fundamentals_history", Some(symbol), from, to).await
+    }
+
     /// Get the latest fundamentals record for a specific symbol (from fundamentals view)
     pub async fn get_latest_fundamentals(&self, symbol: &str) -> Result<Option<FundamentalsRecord>> {
         info!("Getting latest fundamentals record for {}", symbol);
@@ -3,10 +3,18 @@
 
 pub mod calculator;
 pub mod models;
+pub mod fmp;
+pub mod scoring;
+pub mod statements;
+pub mod quality;
+pub mod peers;
+pub mod dcf;
+pub mod edgar;
 pub mod fundamentals_models;
 pub mod fundamentals_storage;
 
 pub use calculator::FundamentalsCalculator;
+pub use peers::PeerStock;
 pub use models::*;
 pub use fundamentals_models::{FundamentalsApiUrls, create_fundamentals_record_with_tracking};
 pub use fundamentals_storage::FundamentalsStorage;
@@ -78,6 +78,15 @@ pub struct FundamentalsResult {
     pub components: FundamentalsComponents,
     /// Financial metrics used in analysis
     pub metrics: FinancialMetrics,
+    /// Piotroski, Beneish and Sloan quality scores from annual statements
+    #[serde(default)]
+    pub quality: QualityScores,
+    /// Discounted cash flow valuation, when free cash flow and share count are available
+    #[serde(default)]
+    pub dcf: Option<DcfValuation>,
+    /// Valuation and profitability percentiles within the industry or sector, when scored against peers
+    #[serde(default)]
+    pub peer_percentiles: Option<PeerPercentiles>,
     /// Warning and context flags
     pub flags: Vec<String>,
     /// Confidence score (0.0 to 1.0)
@@ -208,6 +217,83 @@ pub struct EfficiencyMetrics {
     pub days_payables_outstanding: Option<f64>, // Days Payables Outstanding
 }
 
+/// Earnings quality scores computed from the last two or three annual reports
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QualityScores {
+    pub piotroski_f_score: Option<i32>,     // Piotroski F-score (0-9, higher is stronger)
+    pub beneish_m_score: Option<f64>,       // Beneish M-score (above -1.78 suggests manipulation)
+    pub sloan_accrual_ratio: Option<f64>,   // (Net income - operating cash flow) / average total assets
+}
+
+/// Discounted cash flow valuation of one stock
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DcfValuation {
+    /// Intrinsic equity value per share
+    pub intrinsic_value_per_share: f64,
+    /// Closing price on the analysis date
+    pub current_price: Option<f64>,
+    /// (intrinsic value - price) / intrinsic value; positive means trading below value
+    pub margin_of_safety: Option<f64>,
+    /// Trailing free cash flow the projection starts from
+    pub base_free_cash_flow: f64,
+    /// Annual free cash flow growth for the first projection years
+    pub growth_rate: f64,
+    /// Perpetual growth after the projection
+    pub terminal_growth_rate: f64,
+    /// Weighted average cost of capital used as the discount rate
+    pub wacc: f64,
+    pub cost_of_equity: f64,
+    /// After-tax cost of debt
+    pub cost_of_debt: f64,
+    pub enterprise_value: f64,
+    pub equity_value: f64,
+    /// Share of enterprise value coming from the terminal value
+    pub terminal_value_share: f64,
+    pub sensitivity: DcfSensitivity,
+}
+
+/// Intrinsic value per share over a grid of growth and discount rates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DcfSensitivity {
+    /// Row labels
+    pub growth_rates: Vec<f64>,
+    /// Column labels
+    pub discount_rates: Vec<f64>,
+    /// `values[row][column]`, `None` where the discount rate does not exceed terminal growth
+    pub values: Vec<Vec<Option<f64>>>,
+}
+
+/// Classification level a stock was compared at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PeerGroupLevel {
+    Industry,
+    Sector,
+}
+
+impl std::fmt::Display for PeerGroupLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PeerGroupLevel::Industry => write!(f, "Industry"),
+            PeerGroupLevel::Sector => write!(f, "Sector"),
+        }
+    }
+}
+
+/// Percentile (0-100) of each valuation and profitability metric within the peer group
+///
+/// A high valuation percentile means more expensive than peers; a high
+/// profitability percentile means more profitable.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerPercentiles {
+    pub level: PeerGroupLevel,
+    /// Industry or sector name
+    pub group: String,
+    /// Analyzed stocks in the group
+    pub group_size: usize,
+    pub valuation: ValuationMetrics,
+    pub profitability: ProfitabilityMetrics,
+}
+
 /// Additional metadata about the fundamentals calculation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FundamentalsMeta {
@@ -0,0 +1,269 @@
+// Peer-relative percentiles
+// Ranks valuation and profitability metrics within each stock's industry or sector
+// across a universe of analyzed stocks
+
+use super::models::*;
+use super::scoring;
+use std::collections::HashMap;
+
+/// Fewest stocks with a metric for an industry or sector to count as a peer group
+pub const MIN_PEER_GROUP_SIZE: usize = 5;
+
+/// Stock in the peer universe with its classification (e.g. from the invite list)
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeerStock {
+    pub symbol: String,
+    pub sector: Option<String>,
+    pub industry: Option<String>,
+}
+
+/// Percentage of peers below `value`, counting ties as half (0-100)
+pub fn percentile_rank(value: f64, peers: &[f64]) -> f64 {
+    let below = peers.iter().filter(|p| **p < value).count() as f64;
+    let equal = peers.iter().filter(|p| **p == value).count() as f64;
+    (below + 0.5 * equal) / peers.len() as f64 * 100.0
+}
+
+fn valuation_values(m: &ValuationMetrics) -> [Option<f64>; 8] {
+    [m.pe_ratio, m.peg_ratio, m.ps_ratio, m.pb_ratio, m.pcf_ratio, m.ev_ebitda, m.ev_sales, m.pfcf_ratio]
+}
+
+fn valuation_from(v: [Option<f64>; 8]) -> ValuationMetrics {
+    ValuationMetrics {
+        pe_ratio: v[0],
+        peg_ratio: v[1],
+        ps_ratio: v[2],
+        pb_ratio: v[3],
+        pcf_ratio: v[4],
+        ev_ebitda: v[5],
+        ev_sales: v[6],
+        pfcf_ratio: v[7],
+    }
+}
+
+fn profitability_values(m: &ProfitabilityMetrics) -> [Option<f64>; 7] {
+    [
+        m.roe,
+        m.roa,
+        m.roic,
+        m.net_profit_margin,
+        m.gross_profit_margin,
+        m.operating_profit_margin,
+        m.ebitda_margin,
+    ]
+}
+
+fn profitability_from(v: [Option<f64>; 7]) -> ProfitabilityMetrics {
+    ProfitabilityMetrics {
+        roe: v[0],
+        roa: v[1],
+        roic: v[2],
+        net_profit_margin: v[3],
+        gross_profit_margin: v[4],
+        operating_profit_margin: v[5],
+        ebitda_margin: v[6],
+    }
+}
+
+/// Industry when it has enough analyzed stocks, otherwise the sector
+fn peer_group(meta: &FundamentalsMeta, industries: &HashMap<&str, usize>, sectors: &HashMap<&str, usize>) -> Option<(PeerGroupLevel, String, usize)> {
+    let industry = meta.industry.as_deref().map(|name| (name, industries[name]));
+    let sector = meta.sector.as_deref().map(|name| (name, sectors[name]));
+    match (industry, sector) {
+        (Some((name, size)), _) if size >= MIN_PEER_GROUP_SIZE => Some((PeerGroupLevel::Industry, name.to_string(), size)),
+        (_, Some((name, size))) if size >= MIN_PEER_GROUP_SIZE => Some((PeerGroupLevel::Sector, name.to_string(), size)),
+        _ => None,
+    }
+}
+
+/// Percentile of each metric among the members of `group` that report it
+///
+/// Valuation multiples at or below zero (losses, negative book value) are left
+/// out of the ranking; they already score -1.
+fn percentiles<const N: usize>(members: &[[Option<f64>; N]], own: [Option<f64>; N], positive_only: bool) -> [Option<f64>; N] {
+    std::array::from_fn(|i| {
+        let usable = |v: &f64| !positive_only || *v > 0.0;
+        let value = own[i].filter(usable)?;
+        let peers: Vec<f64> = members.iter().filter_map(|m| m[i]).filter(usable).collect();
+        (peers.len() >= MIN_PEER_GROUP_SIZE).then(|| percentile_rank(value, &peers))
+    })
+}
+
+/// Peer percentiles for every result with an industry or sector group in the universe
+///
+/// Returned in the same order as `results`; `None` when a stock has no
+/// classification or its groups are smaller than `MIN_PEER_GROUP_SIZE`.
+pub fn peer_percentiles(results: &[FundamentalsResult]) -> Vec<Option<PeerPercentiles>> {
+    let mut industries: HashMap<&str, usize> = HashMap::new();
+    let mut sectors: HashMap<&str, usize> = HashMap::new();
+    for result in results {
+        if let Some(industry) = result.meta.industry.as_deref() {
+            *industries.entry(industry).or_default() += 1;
+        }
+        if let Some(sector) = result.meta.sector.as_deref() {
+            *sectors.entry(sector).or_default() += 1;
+        }
+    }
+
+    results
+        .iter()
+        .map(|result| {
+            let (level, group, group_size) = peer_group(&result.meta, &industries, &sectors)?;
+            let members: Vec<&FundamentalsResult> = results
+                .iter()
+                .filter(|peer| match level {
+                    PeerGroupLevel::Industry => peer.meta.industry.as_deref() == Some(group.as_str()),
+                    PeerGroupLevel::Sector => peer.meta.sector.as_deref() == Some(group.as_str()),
+                })
+                .collect();
+            let valuation: Vec<_> = members.iter().map(|peer| valuation_values(&peer.metrics.valuation)).collect();
+            let profitability: Vec<_> = members.iter().map(|peer| profitability_values(&peer.metrics.profitability)).collect();
+
+            Some(PeerPercentiles {
+                level,
+                group,
+                group_size,
+                valuation: valuation_from(percentiles(&valuation, valuation_values(&result.metrics.valuation), true)),
+                profitability: profitability_from(percentiles(
+                    &profitability,
+                    profitability_values(&result.metrics.profitability),
+                    false,
+                )),
+            })
+        })
+        .collect()
+}
+
+/// Valuation score from peer percentiles, cheaper than peers scoring higher
+///
+/// Metrics without a percentile fall back to the absolute bands.
+pub fn peer_valuation_score(metrics: &ValuationMetrics, peers: &PeerPercentiles) -> Option<f64> {
+    let absolute = scoring::valuation_scores(metrics);
+    let raw = valuation_values(metrics);
+    let percentiles = valuation_values(&peers.valuation);
+    let scores: Vec<Option<f64>> = (0..raw.len())
+        .map(|i| match (raw[i], percentiles[i]) {
+            (Some(value), _) if value <= 0.0 => Some(-1.0),
+            (Some(_), Some(percentile)) => Some(1.0 - percentile / 50.0),
+            _ => absolute[i],
+        })
+        .collect();
+    scoring::average(&scores)
+}
+
+/// Profitability score from peer percentiles, higher returns and margins scoring higher
+///
+/// Metrics without a percentile fall back to the absolute bands.
+pub fn peer_profitability_score(metrics: &ProfitabilityMetrics, peers: &PeerPercentiles) -> Option<f64> {
+    let absolute = scoring::profitability_scores(metrics);
+    let percentiles = profitability_values(&peers.profitability);
+    let scores: Vec<Option<f64>> = (0..percentiles.len())
+        .map(|i| percentiles[i].map(|percentile| percentile / 50.0 - 1.0).or(absolute[i]))
+        .collect();
+    scoring::average(&scores)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn result(symbol: &str, sector: &str, industry: &str, pe_ratio: f64, roe: f64) -> FundamentalsResult {
+        let mut valuation = [None; 8];
+        valuation[0] = Some(pe_ratio);
+        let mut profitability = [None; 7];
+        profitability[0] = Some(roe);
+
+        FundamentalsResult {
+            symbol: symbol.to_string(),
+            fundamentals_score: 0.0,
+            trading_signal: TradingSignal::Hold,
+            components: FundamentalsComponents {
+                profitability: 0.0,
+                growth: 0.0,
+                valuation: 0.0,
+                financial_strength: 0.0,
+                efficiency: 0.0,
+            },
+            metrics: FinancialMetrics {
+                profitability: profitability_from(profitability),
+                growth: GrowthMetrics {
+                    revenue_growth_yoy: None,
+                    revenue_growth_qoq: None,
+                    eps_growth_yoy: None,
+                    eps_growth_qoq: None,
+                    net_income_growth_yoy: None,
+                    book_value_growth_yoy: None,
+                    operating_cash_flow_growth_yoy: None,
+                },
+                valuation: valuation_from(valuation),
+                financial_strength: FinancialStrengthMetrics {
+                    debt_to_equity: None,
+                    debt_to_assets: None,
+                    current_ratio: None,
+                    quick_ratio: None,
+                    interest_coverage: None,
+                    cash_to_debt: None,
+                    equity_multiplier: None,
+                    altman_z_score: None,
+                },
+                efficiency: EfficiencyMetrics {
+                    asset_turnover: None,
+                    inventory_turnover: None,
+                    receivables_turnover: None,
+                    payables_turnover: None,
+                    working_capital_turnover: None,
+                    days_sales_outstanding: None,
+                    days_inventory_outstanding: None,
+                    days_payables_outstanding: None,
+                },
+            },
+            quality: QualityScores::default(),
+            dcf: None,
+            peer_percentiles: None,
+            flags: Vec::new(),
+            confidence_score: 1.0,
+            timestamp: Utc::now(),
+            meta: FundamentalsMeta {
+                sector: Some(sector.to_string()),
+                industry: Some(industry.to_string()),
+                ..FundamentalsMeta::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_percentile_rank_counts_ties_as_half() {
+        let peers = [10.0, 20.0, 30.0, 40.0];
+        assert_eq!(percentile_rank(10.0, &peers), 12.5);
+        assert_eq!(percentile_rank(40.0, &peers), 87.5);
+        assert_eq!(percentile_rank(25.0, &peers), 50.0);
+    }
+
+    #[test]
+    fn test_peers_by_industry_then_sector() {
+        let mut universe: Vec<FundamentalsResult> = (0..5)
+            .map(|i| result(&format!("SW{}", i), "Technology", "Software", 20.0 + 10.0 * i as f64, 0.10 + 0.05 * i as f64))
+            .collect();
+        universe.push(result("SEMI", "Technology", "Semiconductors", 28.0, 0.30));
+        universe.push(result("UTIL", "Utilities", "Utilities - Regulated", 28.0, 0.10));
+
+        let percentiles = peer_percentiles(&universe);
+
+        // Five software names form an industry group; the cheapest, least profitable ranks lowest
+        let cheapest = percentiles[0].as_ref().unwrap();
+        assert_eq!(cheapest.level, PeerGroupLevel::Industry);
+        assert_eq!(cheapest.valuation.pe_ratio, Some(10.0));
+        assert_eq!(peer_valuation_score(&universe[0].metrics.valuation, cheapest), Some(0.8));
+        assert!((peer_profitability_score(&universe[0].metrics.profitability, cheapest).unwrap() + 0.8).abs() < 1e-12);
+
+        // A lone semiconductor name is ranked within its six-stock sector
+        let semi = percentiles[5].as_ref().unwrap();
+        assert_eq!(semi.level, PeerGroupLevel::Sector);
+        assert_eq!(semi.group, "Technology");
+        assert_eq!(semi.group_size, 6);
+
+        // The same P/E has no peers in a one-stock sector
+        assert!(percentiles[6].is_none());
+    }
+}
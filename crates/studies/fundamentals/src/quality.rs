@@ -0,0 +1,280 @@
+// Earnings quality scores
+// Piotroski F-score, Beneish M-score and Sloan accrual ratio from annual statements
+
+use super::models::QualityScores;
+use super::statements::{ratio, FinancialStatements, FiscalYear};
+
+/// Beneish M-score above which earnings manipulation is likely (eight-variable model)
+pub const BENEISH_MANIPULATION_THRESHOLD: f64 = -1.78;
+
+/// Piotroski F-score at or below which a company is considered financially weak
+pub const PIOTROSKI_WEAK_THRESHOLD: i32 = 2;
+
+/// Piotroski F-score at or above which a company is considered financially strong
+pub const PIOTROSKI_STRONG_THRESHOLD: i32 = 8;
+
+/// Sloan accrual ratio above which earnings are mostly accruals
+pub const SLOAN_HIGH_ACCRUAL_THRESHOLD: f64 = 0.10;
+
+/// Per-year inputs shared by the three scores
+impl FiscalYear<'_> {
+    fn revenue(&self) -> Option<f64> {
+        self.income.revenue
+    }
+
+    fn net_income(&self) -> Option<f64> {
+        self.income.net_income.or(self.cash_flow.and_then(|cf| cf.net_income))
+    }
+
+    fn operating_cash_flow(&self) -> Option<f64> {
+        self.cash_flow.and_then(|cf| cf.operating_cash_flow)
+    }
+
+    fn total_assets(&self) -> Option<f64> {
+        self.balance.total_assets.filter(|ta| *ta > 0.0)
+    }
+
+    /// Total assets at the start of the year, or at year end when the opening balance is missing
+    fn opening_assets(&self, use_opening: bool) -> Option<f64> {
+        if use_opening {
+            self.opening_balance.and_then(|b| b.total_assets).filter(|ta| *ta > 0.0)
+        } else {
+            self.total_assets()
+        }
+    }
+
+    fn gross_margin(&self) -> Option<f64> {
+        let gross_profit = self.income.gross_profit.or(match (self.income.revenue, self.income.cost_of_revenue) {
+            (Some(r), Some(c)) => Some(r - c),
+            _ => None,
+        });
+        ratio(gross_profit, self.revenue())
+    }
+
+    fn long_term_debt(&self) -> Option<f64> {
+        self.balance.long_term_debt.or(self.balance.total_debt)
+    }
+
+    fn current_ratio(&self) -> Option<f64> {
+        ratio(self.balance.total_current_assets, self.balance.total_current_liabilities)
+    }
+
+    fn shares(&self) -> Option<f64> {
+        self.balance
+            .shares_outstanding
+            .or(self.income.weighted_average_shares_diluted)
+            .or(self.income.weighted_average_shares)
+    }
+
+    fn depreciation(&self) -> Option<f64> {
+        self.income
+            .depreciation_and_amortization
+            .or(self.cash_flow.and_then(|cf| cf.depreciation_and_amortization))
+    }
+}
+
+/// All three quality scores; each is `None` when its inputs are incomplete
+pub fn quality_scores(statements: &FinancialStatements) -> QualityScores {
+    QualityScores {
+        piotroski_f_score: piotroski_f_score(statements),
+        beneish_m_score: beneish_m_score(statements),
+        sloan_accrual_ratio: sloan_accrual_ratio(statements),
+    }
+}
+
+/// Piotroski F-score: one point for each of nine profitability, leverage and efficiency tests
+///
+/// Returns and turnover use opening total assets when both years have an
+/// opening balance sheet, otherwise year-end assets for both years.
+pub fn piotroski_f_score(statements: &FinancialStatements) -> Option<i32> {
+    let (current, prior) = statements.consecutive_years()?;
+    let use_opening = current.opening_balance.is_some() && prior.opening_balance.is_some();
+    let roa = |year: &FiscalYear| ratio(year.net_income(), year.opening_assets(use_opening));
+    let turnover = |year: &FiscalYear| ratio(year.revenue(), year.opening_assets(use_opening));
+    let leverage = |year: &FiscalYear| ratio(year.long_term_debt(), year.total_assets());
+
+    let roa_current = roa(&current)?;
+    let cfo = current.operating_cash_flow()?;
+
+    let tests = [
+        roa_current > 0.0,
+        cfo > 0.0,
+        roa_current > roa(&prior)?,
+        cfo > current.net_income()?,
+        leverage(&current)? <= leverage(&prior)?,
+        current.current_ratio()? > prior.current_ratio()?,
+        current.shares()? <= prior.shares()?,
+        current.gross_margin()? > prior.gross_margin()?,
+        turnover(&current)? > turnover(&prior)?,
+    ];
+    Some(tests.iter().filter(|passed| **passed).count() as i32)
+}
+
+/// Beneish eight-variable M-score comparing the latest fiscal year with the one before
+pub fn beneish_m_score(statements: &FinancialStatements) -> Option<f64> {
+    let (current, prior) = statements.consecutive_years()?;
+    let index = |now: Option<f64>, before: Option<f64>| ratio(now, before);
+
+    let receivables_to_sales = |year: &FiscalYear| ratio(year.balance.net_receivables, year.revenue());
+    let soft_assets = |year: &FiscalYear| {
+        let hard = year.balance.total_current_assets? + year.balance.property_plant_equipment_net?;
+        Some(1.0 - hard / year.total_assets()?)
+    };
+    let depreciation_rate = |year: &FiscalYear| {
+        let depreciation = year.depreciation()?;
+        ratio(Some(depreciation), Some(depreciation + year.balance.property_plant_equipment_net?))
+    };
+    let sga_to_sales = |year: &FiscalYear| ratio(year.income.selling_general_admin, year.revenue());
+    let leverage = |year: &FiscalYear| {
+        ratio(Some(year.balance.total_current_liabilities? + year.long_term_debt()?), year.total_assets())
+    };
+
+    let dsri = index(receivables_to_sales(&current), receivables_to_sales(&prior))?;
+    let gmi = index(prior.gross_margin(), current.gross_margin())?;
+    let aqi = index(soft_assets(&current), soft_assets(&prior))?;
+    let sgi = index(current.revenue(), prior.revenue())?;
+    let depi = index(depreciation_rate(&prior), depreciation_rate(&current))?;
+    let sgai = index(sga_to_sales(&current), sga_to_sales(&prior))?;
+    let tata = (current.net_income()? - current.operating_cash_flow()?) / current.total_assets()?;
+    let lvgi = index(leverage(&current), leverage(&prior))?;
+
+    Some(
+        -4.84 + 0.920 * dsri + 0.528 * gmi + 0.404 * aqi + 0.892 * sgi + 0.115 * depi - 0.172 * sgai + 4.679 * tata
+            - 0.327 * lvgi,
+    )
+}
+
+/// Sloan accrual ratio: the share of average assets backing earnings that did not turn into cash
+pub fn sloan_accrual_ratio(statements: &FinancialStatements) -> Option<f64> {
+    let year = statements.fiscal_year(0)?;
+    let accruals = year.net_income()? - year.operating_cash_flow()?;
+    let average_assets = match year.opening_balance.and_then(|b| b.total_assets) {
+        Some(opening) => (year.total_assets()? + opening) / 2.0,
+        None => year.total_assets()?,
+    };
+    ratio(Some(accruals), Some(average_assets))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use buenotea_core::market_data::{BalanceSheet, CashFlowStatement, IncomeStatement};
+    use chrono::NaiveDate;
+
+    fn year_end(year: i32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, 12, 31).unwrap()
+    }
+
+    /// One fiscal year; margins and balance sheet items are fixed shares of revenue and assets
+    fn annual(year: i32, revenue: f64, net_income: f64, cfo: f64, assets: f64) -> (IncomeStatement, BalanceSheet, CashFlowStatement) {
+        let income = IncomeStatement {
+            date: year_end(year),
+            filing_date: None,
+            period: "FY".to_string(),
+            fiscal_year: Some(year),
+            reported_currency: None,
+            revenue: Some(revenue),
+            cost_of_revenue: Some(revenue * 0.6),
+            gross_profit: None,
+            research_and_development: None,
+            selling_general_admin: Some(revenue * 0.1),
+            depreciation_and_amortization: Some(10.0),
+            operating_income: Some(revenue * 0.2),
+            interest_expense: None,
+            income_before_tax: None,
+            income_tax_expense: None,
+            net_income: Some(net_income),
+            ebitda: None,
+            eps: None,
+            eps_diluted: None,
+            weighted_average_shares: None,
+            weighted_average_shares_diluted: Some(100.0),
+        };
+        let balance = BalanceSheet {
+            date: year_end(year),
+            filing_date: None,
+            period: "FY".to_string(),
+            fiscal_year: Some(year),
+            reported_currency: None,
+            cash_and_equivalents: None,
+            short_term_investments: None,
+            net_receivables: Some(revenue * 0.1),
+            inventory: None,
+            total_current_assets: Some(assets * 0.4),
+            property_plant_equipment_net: Some(assets * 0.4),
+            goodwill: None,
+            intangible_assets: None,
+            total_assets: Some(assets),
+            accounts_payable: None,
+            short_term_debt: None,
+            total_current_liabilities: Some(assets * 0.2),
+            long_term_debt: Some(assets * 0.2),
+            total_debt: None,
+            total_liabilities: None,
+            retained_earnings: None,
+            total_equity: None,
+            shares_outstanding: None,
+        };
+        let cash_flow = CashFlowStatement {
+            date: year_end(year),
+            filing_date: None,
+            period: "FY".to_string(),
+            fiscal_year: Some(year),
+            reported_currency: None,
+            net_income: Some(net_income),
+            depreciation_and_amortization: Some(10.0),
+            stock_based_compensation: None,
+            operating_cash_flow: Some(cfo),
+            capital_expenditure: None,
+            free_cash_flow: None,
+            dividends_paid: None,
+            common_stock_repurchased: None,
+        };
+        (income, balance, cash_flow)
+    }
+
+    fn statements(years: Vec<(IncomeStatement, BalanceSheet, CashFlowStatement)>) -> FinancialStatements {
+        let mut statements = FinancialStatements::default();
+        for (income, balance, cash_flow) in years {
+            statements.annual_income.push(income);
+            statements.annual_balance.push(balance);
+            statements.annual_cash_flow.push(cash_flow);
+        }
+        statements
+    }
+
+    #[test]
+    fn test_quality_scores_for_steady_company() {
+        // Identical years: only the level tests (ROA, CFO, CFO > NI) and the non-increase tests pass
+        let steady = statements(vec![
+            annual(2023, 1000.0, 100.0, 150.0, 1000.0),
+            annual(2022, 1000.0, 100.0, 150.0, 1000.0),
+            annual(2021, 1000.0, 100.0, 150.0, 1000.0),
+        ]);
+        let scores = quality_scores(&steady);
+        assert_eq!(scores.piotroski_f_score, Some(5));
+        // Every index is 1.0 and accruals are -5% of assets
+        let expected = -4.84 + 0.920 + 0.528 + 0.404 + 0.892 + 0.115 - 0.172 + 4.679 * -0.05 - 0.327;
+        assert!((scores.beneish_m_score.unwrap() - expected).abs() < 1e-9);
+        assert_eq!(scores.sloan_accrual_ratio, Some(-0.05));
+    }
+
+    #[test]
+    fn test_quality_scores_flag_accrual_driven_growth() {
+        // Sales jump 50% while cash flow falls far short of earnings
+        let aggressive = statements(vec![
+            annual(2023, 1500.0, 200.0, 20.0, 1100.0),
+            annual(2022, 1000.0, 100.0, 150.0, 1000.0),
+        ]);
+        let scores = quality_scores(&aggressive);
+        assert!(scores.beneish_m_score.unwrap() > BENEISH_MANIPULATION_THRESHOLD);
+        assert!(scores.sloan_accrual_ratio.unwrap() > SLOAN_HIGH_ACCRUAL_THRESHOLD);
+        assert!(scores.piotroski_f_score.is_some());
+
+        // A single year only supports the accrual ratio
+        let single = statements(vec![annual(2023, 1000.0, 100.0, 150.0, 1000.0)]);
+        assert_eq!(piotroski_f_score(&single), None);
+        assert_eq!(beneish_m_score(&single), None);
+        assert_eq!(sloan_accrual_ratio(&single), Some(-0.05));
+    }
+}
@@ -0,0 +1,149 @@
+// Component scoring
+// Maps each group of financial metrics onto a score between -1 and +1
+
+use super::models::*;
+
+/// Linear score of `value` between a poor level (-1) and a strong level (+1)
+///
+/// `poor` may be above `strong` for metrics where lower is better.
+fn scale(value: f64, poor: f64, strong: f64) -> f64 {
+    ((value - poor) / (strong - poor) * 2.0 - 1.0).clamp(-1.0, 1.0)
+}
+
+/// Score a valuation multiple; zero or negative multiples mean losses and score -1
+fn multiple(value: f64, expensive: f64, cheap: f64) -> f64 {
+    if value <= 0.0 {
+        -1.0
+    } else {
+        scale(value, expensive, cheap)
+    }
+}
+
+/// Average of the sub-scores that have data, `None` when none do
+pub(crate) fn average(scores: &[Option<f64>]) -> Option<f64> {
+    let available: Vec<f64> = scores.iter().flatten().copied().collect();
+    (!available.is_empty()).then(|| available.iter().sum::<f64>() / available.len() as f64)
+}
+
+pub fn profitability_score(m: &ProfitabilityMetrics) -> Option<f64> {
+    average(&profitability_scores(m))
+}
+
+/// Absolute score of each profitability metric, in field order
+pub(crate) fn profitability_scores(m: &ProfitabilityMetrics) -> [Option<f64>; 7] {
+    [
+        m.roe.map(|v| scale(v, 0.0, 0.25)),
+        m.roa.map(|v| scale(v, 0.0, 0.12)),
+        m.roic.map(|v| scale(v, 0.0, 0.20)),
+        m.net_profit_margin.map(|v| scale(v, 0.0, 0.20)),
+        m.gross_profit_margin.map(|v| scale(v, 0.15, 0.60)),
+        m.operating_profit_margin.map(|v| scale(v, 0.0, 0.25)),
+        m.ebitda_margin.map(|v| scale(v, 0.05, 0.35)),
+    ]
+}
+
+pub fn growth_score(m: &GrowthMetrics) -> Option<f64> {
+    average(&[
+        m.revenue_growth_yoy.map(|v| scale(v, -0.05, 0.20)),
+        m.revenue_growth_qoq.map(|v| scale(v, -0.03, 0.05)),
+        m.eps_growth_yoy.map(|v| scale(v, -0.10, 0.25)),
+        m.eps_growth_qoq.map(|v| scale(v, -0.05, 0.08)),
+        m.net_income_growth_yoy.map(|v| scale(v, -0.10, 0.25)),
+        m.book_value_growth_yoy.map(|v| scale(v, -0.05, 0.15)),
+        m.operating_cash_flow_growth_yoy.map(|v| scale(v, -0.10, 0.20)),
+    ])
+}
+
+/// Cheaper multiples score higher
+pub fn valuation_score(m: &ValuationMetrics) -> Option<f64> {
+    average(&valuation_scores(m))
+}
+
+/// Absolute score of each valuation multiple, in field order
+pub(crate) fn valuation_scores(m: &ValuationMetrics) -> [Option<f64>; 8] {
+    [
+        m.pe_ratio.map(|v| multiple(v, 35.0, 10.0)),
+        m.peg_ratio.map(|v| multiple(v, 3.0, 0.8)),
+        m.ps_ratio.map(|v| multiple(v, 10.0, 1.0)),
+        m.pb_ratio.map(|v| multiple(v, 8.0, 1.0)),
+        m.pcf_ratio.map(|v| multiple(v, 30.0, 8.0)),
+        m.ev_ebitda.map(|v| multiple(v, 25.0, 8.0)),
+        m.ev_sales.map(|v| multiple(v, 10.0, 1.0)),
+        m.pfcf_ratio.map(|v| multiple(v, 40.0, 10.0)),
+    ]
+}
+
+/// DCF margin of safety: 50% below intrinsic value scores +1, 50% above scores -1
+pub fn dcf_score(dcf: &DcfValuation) -> Option<f64> {
+    dcf.margin_of_safety.map(|margin| scale(margin, -0.5, 0.5))
+}
+
+/// Valuation score blending the multiples score with the DCF score when both exist
+pub fn with_dcf(multiples: Option<f64>, dcf: Option<&DcfValuation>) -> Option<f64> {
+    average(&[multiples, dcf.and_then(dcf_score)])
+}
+
+pub fn financial_strength_score(m: &FinancialStrengthMetrics) -> Option<f64> {
+    average(&[
+        m.debt_to_equity.map(|v| if v < 0.0 { -1.0 } else { scale(v, 2.0, 0.2) }),
+        m.debt_to_assets.map(|v| scale(v, 0.6, 0.1)),
+        m.current_ratio.map(|v| scale(v, 0.8, 2.0)),
+        m.quick_ratio.map(|v| scale(v, 0.5, 1.5)),
+        m.interest_coverage.map(|v| scale(v, 1.5, 10.0)),
+        m.cash_to_debt.map(|v| scale(v, 0.1, 1.0)),
+        m.equity_multiplier.map(|v| if v < 0.0 { -1.0 } else { scale(v, 5.0, 1.5) }),
+        m.altman_z_score.map(|v| scale(v, 1.8, 3.0)),
+    ])
+}
+
+/// Payables turnover and DPO are reported but not scored: slow payment can mean
+/// bargaining power or cash stress
+pub fn efficiency_score(m: &EfficiencyMetrics) -> Option<f64> {
+    average(&[
+        m.asset_turnover.map(|v| scale(v, 0.2, 1.2)),
+        m.inventory_turnover.map(|v| scale(v, 2.0, 12.0)),
+        m.receivables_turnover.map(|v| scale(v, 3.0, 12.0)),
+        m.working_capital_turnover.map(|v| scale(v, 1.0, 8.0)),
+        m.days_sales_outstanding.map(|v| scale(v, 90.0, 30.0)),
+        m.days_inventory_outstanding.map(|v| scale(v, 120.0, 20.0)),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scale_and_multiples() {
+        assert_eq!(scale(0.125, 0.0, 0.25), 0.0);
+        assert_eq!(scale(1.0, 0.0, 0.25), 1.0);
+        // Lower is better
+        assert_eq!(scale(10.0, 35.0, 10.0), 1.0);
+        assert_eq!(multiple(-5.0, 35.0, 10.0), -1.0);
+    }
+
+    #[test]
+    fn test_scores_ignore_missing_metrics() {
+        let metrics = ProfitabilityMetrics {
+            roe: Some(0.25),
+            roa: Some(0.0),
+            roic: None,
+            net_profit_margin: None,
+            gross_profit_margin: None,
+            operating_profit_margin: None,
+            ebitda_margin: None,
+        };
+        assert_eq!(profitability_score(&metrics), Some(0.0));
+
+        let empty = GrowthMetrics {
+            revenue_growth_yoy: None,
+            revenue_growth_qoq: None,
+            eps_growth_yoy: None,
+            eps_growth_qoq: None,
+            net_income_growth_yoy: None,
+            book_value_growth_yoy: None,
+            operating_cash_flow_growth_yoy: None,
+        };
+        assert_eq!(growth_score(&empty), None);
+    }
+}
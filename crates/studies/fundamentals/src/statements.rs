@@ -0,0 +1,492 @@
+// Statement-derived ratios
+// Computes profitability, strength and efficiency ratios and the Altman Z-score from
+// income statements, balance sheets and cash flow statements instead of vendor ratios
+
+use super::models::*;
+use buenotea_core::market_data::{BalanceSheet, CashFlowStatement, IncomeStatement};
+use chrono::NaiveDate;
+
+/// Quarters summed for trailing twelve months
+const TTM_QUARTERS: usize = 4;
+
+/// Day span between the first and fourth quarter end that counts as consecutive quarters
+const TTM_SPAN_DAYS: std::ops::RangeInclusive<i64> = 250..=300;
+
+/// Tax rate assumed when the effective rate cannot be computed
+const DEFAULT_TAX_RATE: f64 = 0.21;
+
+const DAYS_PER_YEAR: f64 = 365.0;
+
+/// Annual and quarterly statements for one company, each newest first
+#[derive(Debug, Clone, Default)]
+pub struct FinancialStatements {
+    pub annual_income: Vec<IncomeStatement>,
+    pub quarterly_income: Vec<IncomeStatement>,
+    pub annual_balance: Vec<BalanceSheet>,
+    pub quarterly_balance: Vec<BalanceSheet>,
+    pub annual_cash_flow: Vec<CashFlowStatement>,
+    pub quarterly_cash_flow: Vec<CashFlowStatement>,
+    /// Provider that returned the statements, for API tracking
+    pub source: Option<String>,
+    /// Income statement endpoint that was called
+    pub url: Option<String>,
+}
+
+/// One fiscal year's annual statements and the balance sheet that opened the year
+#[derive(Debug, Clone, Copy)]
+pub struct FiscalYear<'a> {
+    pub income: &'a IncomeStatement,
+    pub balance: &'a BalanceSheet,
+    pub cash_flow: Option<&'a CashFlowStatement>,
+    pub opening_balance: Option<&'a BalanceSheet>,
+}
+
+/// Days between consecutive fiscal year ends that count as one year apart
+const FISCAL_YEAR_DAYS: std::ops::RangeInclusive<i64> = 330..=400;
+
+/// Income and cash flow items over twelve months, from an annual report or four quarters
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PeriodFlows {
+    /// End of the last period included
+    pub period_end: Option<NaiveDate>,
+    /// Whether the flows were summed from quarters
+    pub trailing: bool,
+    pub revenue: Option<f64>,
+    pub cost_of_revenue: Option<f64>,
+    pub gross_profit: Option<f64>,
+    pub operating_income: Option<f64>,
+    pub interest_expense: Option<f64>,
+    pub income_before_tax: Option<f64>,
+    pub income_tax_expense: Option<f64>,
+    pub net_income: Option<f64>,
+    pub ebitda: Option<f64>,
+    pub depreciation_and_amortization: Option<f64>,
+    pub operating_cash_flow: Option<f64>,
+    pub free_cash_flow: Option<f64>,
+}
+
+/// Sum a field over all periods, `None` if any period lacks it
+fn sum<T>(periods: &[T], field: impl Fn(&T) -> Option<f64>) -> Option<f64> {
+    periods.iter().map(field).sum()
+}
+
+/// `numerator / denominator`, `None` unless the denominator is positive
+pub(crate) fn ratio(numerator: Option<f64>, denominator: Option<f64>) -> Option<f64> {
+    match (numerator, denominator) {
+        (Some(n), Some(d)) if d > 0.0 => Some(n / d),
+        _ => None,
+    }
+}
+
+/// Average of an opening and closing balance, or the closing balance alone
+fn average(closing: Option<f64>, opening: Option<f64>) -> Option<f64> {
+    match (closing, opening) {
+        (Some(c), Some(o)) => Some((c + o) / 2.0),
+        (closing, _) => closing,
+    }
+}
+
+/// Replace `target` when a statement-derived value is available
+fn prefer(target: &mut Option<f64>, derived: Option<f64>) {
+    if derived.is_some() {
+        *target = derived;
+    }
+}
+
+impl PeriodFlows {
+    fn from_income(income: &[IncomeStatement], cash_flow: &[CashFlowStatement], trailing: bool) -> Self {
+        let cash_flow_matches = cash_flow.len() == income.len()
+            && cash_flow.iter().zip(income).all(|(cf, is)| cf.date == is.date);
+        let cash = |field: fn(&CashFlowStatement) -> Option<f64>| {
+            if cash_flow_matches { sum(cash_flow, field) } else { None }
+        };
+        let revenue = sum(income, |s| s.revenue);
+        let cost_of_revenue = sum(income, |s| s.cost_of_revenue);
+        let operating_income = sum(income, |s| s.operating_income);
+        let depreciation = sum(income, |s| s.depreciation_and_amortization);
+
+        Self {
+            period_end: income.first().map(|s| s.date),
+            trailing,
+            revenue,
+            cost_of_revenue,
+            gross_profit: sum(income, |s| s.gross_profit).or(match (revenue, cost_of_revenue) {
+                (Some(r), Some(c)) => Some(r - c),
+                _ => None,
+            }),
+            operating_income,
+            interest_expense: sum(income, |s| s.interest_expense.map(f64::abs)),
+            income_before_tax: sum(income, |s| s.income_before_tax),
+            income_tax_expense: sum(income, |s| s.income_tax_expense),
+            net_income: sum(income, |s| s.net_income),
+            ebitda: sum(income, |s| s.ebitda).or(match (operating_income, depreciation) {
+                (Some(oi), Some(da)) => Some(oi + da),
+                _ => None,
+            }),
+            depreciation_and_amortization: depreciation,
+            operating_cash_flow: cash(|s| s.operating_cash_flow),
+            free_cash_flow: cash(|s| s.free_cash_flow),
+        }
+    }
+
+    /// Effective tax rate, falling back to the statutory rate
+    pub fn tax_rate(&self) -> f64 {
+        ratio(self.income_tax_expense, self.income_before_tax)
+            .filter(|rate| (0.0..=0.5).contains(rate))
+            .unwrap_or(DEFAULT_TAX_RATE)
+    }
+}
+
+impl FinancialStatements {
+    /// Trailing twelve months from the last four consecutive quarters, or the latest annual report
+    pub fn ttm(&self) -> Option<PeriodFlows> {
+        let quarters = self.quarterly_income.get(..TTM_QUARTERS);
+        let consecutive = quarters.is_some_and(|q| TTM_SPAN_DAYS.contains(&(q[0].date - q[TTM_QUARTERS - 1].date).num_days()));
+        let annual = self.annual_income.first();
+
+        match quarters {
+            // Use quarters unless a newer annual report is available
+            Some(quarters) if consecutive && annual.is_none_or(|a| a.date <= quarters[0].date) => {
+                let cash_flow = self.quarterly_cash_flow.get(..TTM_QUARTERS).unwrap_or_default();
+                Some(PeriodFlows::from_income(quarters, cash_flow, true))
+            }
+            _ => annual.map(|a| {
+                PeriodFlows::from_income(std::slice::from_ref(a), self.annual_cash_flow.get(..1).unwrap_or_default(), false)
+            }),
+        }
+    }
+
+    /// Most recent balance sheet, quarterly or annual
+    pub fn latest_balance(&self) -> Option<&BalanceSheet> {
+        match (self.quarterly_balance.first(), self.annual_balance.first()) {
+            (Some(q), Some(a)) => Some(if a.date > q.date { a } else { q }),
+            (q, a) => q.or(a),
+        }
+    }
+
+    /// Balance sheet about a year before the latest one, for average balances
+    pub fn year_ago_balance(&self) -> Option<&BalanceSheet> {
+        let latest = self.latest_balance()?.date;
+        self.quarterly_balance
+            .iter()
+            .chain(&self.annual_balance)
+            .find(|b| FISCAL_YEAR_DAYS.contains(&(latest - b.date).num_days()))
+    }
+
+    /// Annual statements `years_back` fiscal years before the latest, matched on period end
+    pub fn fiscal_year(&self, years_back: usize) -> Option<FiscalYear<'_>> {
+        let income = self.annual_income.get(years_back)?;
+        let position = self.annual_balance.iter().position(|b| b.date == income.date)?;
+        Some(FiscalYear {
+            income,
+            balance: &self.annual_balance[position],
+            cash_flow: self.annual_cash_flow.iter().find(|cf| cf.date == income.date),
+            opening_balance: self
+                .annual_balance
+                .get(position + 1)
+                .filter(|b| FISCAL_YEAR_DAYS.contains(&(income.date - b.date).num_days())),
+        })
+    }
+
+    /// The latest fiscal year and the one before it, when they are one year apart
+    pub fn consecutive_years(&self) -> Option<(FiscalYear<'_>, FiscalYear<'_>)> {
+        let current = self.fiscal_year(0)?;
+        let prior = self.fiscal_year(1)?;
+        FISCAL_YEAR_DAYS
+            .contains(&(current.income.date - prior.income.date).num_days())
+            .then_some((current, prior))
+    }
+
+    /// Shares outstanding from the latest balance sheet or diluted share count
+    pub fn shares_outstanding(&self) -> Option<f64> {
+        self.latest_balance()
+            .and_then(|b| b.shares_outstanding)
+            .or_else(|| self.quarterly_income.first().and_then(|s| s.weighted_average_shares_diluted))
+            .or_else(|| self.annual_income.first().and_then(|s| s.weighted_average_shares_diluted))
+            .filter(|shares| *shares > 0.0)
+    }
+
+    /// Whether any statements were found
+    pub fn is_empty(&self) -> bool {
+        self.annual_income.is_empty() && self.quarterly_income.is_empty() && self.latest_balance().is_none()
+    }
+
+    /// Trailing flows and the balance sheets used, for API tracking
+    pub fn raw_data(&self) -> Option<serde_json::Value> {
+        let flows = self.ttm()?;
+        Some(serde_json::json!({
+            "period_end": flows.period_end,
+            "trailing_twelve_months": flows.trailing,
+            "revenue": flows.revenue,
+            "operating_income": flows.operating_income,
+            "net_income": flows.net_income,
+            "ebitda": flows.ebitda,
+            "balance_sheet": self.latest_balance(),
+            "year_ago_balance_sheet": self.year_ago_balance(),
+        }))
+    }
+
+    /// Overwrite vendor metrics with ratios derived from the statements where possible
+    ///
+    /// Flows are trailing twelve months; turnover and return ratios use the
+    /// average of the latest and year-ago balances when both exist.
+    pub fn apply_to(&self, metrics: &mut FinancialMetrics, market_cap: Option<f64>) {
+        let (Some(flows), Some(balance)) = (self.ttm(), self.latest_balance()) else {
+            return;
+        };
+        let prior = self.year_ago_balance();
+        let avg = |field: fn(&BalanceSheet) -> Option<f64>| average(field(balance), prior.and_then(field));
+
+        let revenue = flows.revenue;
+        let equity = balance.total_equity.filter(|e| *e > 0.0);
+        let cash = match (balance.cash_and_equivalents, balance.short_term_investments) {
+            (Some(c), Some(s)) => Some(c + s),
+            (c, _) => c,
+        };
+        let invested_capital = match (balance.total_debt, equity) {
+            (Some(debt), Some(equity)) => Some(debt + equity - balance.cash_and_equivalents.unwrap_or(0.0)),
+            _ => None,
+        };
+        let nopat = flows.operating_income.map(|oi| oi * (1.0 - flows.tax_rate()));
+
+        let p = &mut metrics.profitability;
+        prefer(&mut p.roe, ratio(flows.net_income, avg(|b| b.total_equity.filter(|e| *e > 0.0))));
+        prefer(&mut p.roa, ratio(flows.net_income, avg(|b| b.total_assets)));
+        prefer(&mut p.roic, ratio(nopat, invested_capital));
+        prefer(&mut p.net_profit_margin, ratio(flows.net_income, revenue));
+        prefer(&mut p.gross_profit_margin, ratio(flows.gross_profit, revenue));
+        prefer(&mut p.operating_profit_margin, ratio(flows.operating_income, revenue));
+        prefer(&mut p.ebitda_margin, ratio(flows.ebitda, revenue));
+
+        let s = &mut metrics.financial_strength;
+        prefer(&mut s.debt_to_equity, ratio(balance.total_debt, equity));
+        prefer(&mut s.debt_to_assets, ratio(balance.total_debt, balance.total_assets));
+        prefer(&mut s.current_ratio, ratio(balance.total_current_assets, balance.total_current_liabilities));
+        prefer(
+            &mut s.quick_ratio,
+            ratio(
+                balance.total_current_assets.map(|ca| ca - balance.inventory.unwrap_or(0.0)),
+                balance.total_current_liabilities,
+            ),
+        );
+        prefer(&mut s.interest_coverage, ratio(flows.operating_income, flows.interest_expense));
+        prefer(&mut s.cash_to_debt, ratio(cash, balance.total_debt));
+        prefer(&mut s.equity_multiplier, ratio(balance.total_assets, equity));
+        prefer(&mut s.altman_z_score, altman_z_score(&flows, balance, market_cap));
+
+        let inventory_turnover = ratio(flows.cost_of_revenue, avg(|b| b.inventory));
+        let receivables_turnover = ratio(revenue, avg(|b| b.net_receivables));
+        let payables_turnover = ratio(flows.cost_of_revenue, avg(|b| b.accounts_payable));
+        let working_capital = match (balance.total_current_assets, balance.total_current_liabilities) {
+            (Some(ca), Some(cl)) => Some(ca - cl),
+            _ => None,
+        };
+        let days = |turnover: Option<f64>| turnover.filter(|t| *t > 0.0).map(|t| DAYS_PER_YEAR / t);
+
+        let e = &mut metrics.efficiency;
+        prefer(&mut e.asset_turnover, ratio(revenue, avg(|b| b.total_assets)));
+        prefer(&mut e.inventory_turnover, inventory_turnover);
+        prefer(&mut e.receivables_turnover, receivables_turnover);
+        prefer(&mut e.payables_turnover, payables_turnover);
+        prefer(&mut e.working_capital_turnover, ratio(revenue, working_capital));
+        prefer(&mut e.days_sales_outstanding, days(receivables_turnover));
+        prefer(&mut e.days_inventory_outstanding, days(inventory_turnover));
+        prefer(&mut e.days_payables_outstanding, days(payables_turnover));
+    }
+}
+
+/// Altman Z-score
+///
+/// Uses the original public-company weights with market value of equity when
+/// a market cap is known, otherwise the Z' variant with book equity.
+pub fn altman_z_score(flows: &PeriodFlows, balance: &BalanceSheet, market_cap: Option<f64>) -> Option<f64> {
+    let total_assets = balance.total_assets.filter(|ta| *ta > 0.0)?;
+    let total_liabilities = balance.total_liabilities.filter(|tl| *tl > 0.0)?;
+    let working_capital = balance.total_current_assets? - balance.total_current_liabilities?;
+    let retained_earnings = balance.retained_earnings?;
+    let ebit = flows.operating_income?;
+    let sales = flows.revenue?;
+
+    let (weights, equity) = match market_cap {
+        Some(market_cap) => ([1.2, 1.4, 3.3, 0.6, 1.0], market_cap),
+        None => ([0.717, 0.847, 3.107, 0.420, 0.998], balance.total_equity?),
+    };
+
+    Some(
+        weights[0] * working_capital / total_assets
+            + weights[1] * retained_earnings / total_assets
+            + weights[2] * ebit / total_assets
+            + weights[3] * equity / total_liabilities
+            + weights[4] * sales / total_assets,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn quarter(date: &str, revenue: f64, net_income: f64) -> IncomeStatement {
+        IncomeStatement {
+            date: day(date),
+            filing_date: None,
+            period: "Q".to_string(),
+            fiscal_year: None,
+            reported_currency: None,
+            revenue: Some(revenue),
+            cost_of_revenue: Some(revenue * 0.6),
+            gross_profit: None,
+            research_and_development: None,
+            selling_general_admin: None,
+            depreciation_and_amortization: Some(5.0),
+            operating_income: Some(revenue * 0.2),
+            interest_expense: Some(-2.0),
+            income_before_tax: Some(revenue * 0.18),
+            income_tax_expense: Some(revenue * 0.18 * 0.25),
+            net_income: Some(net_income),
+            ebitda: None,
+            eps: None,
+            eps_diluted: None,
+            weighted_average_shares: None,
+            weighted_average_shares_diluted: Some(100.0),
+        }
+    }
+
+    fn balance(date: &str, scale: f64) -> BalanceSheet {
+        BalanceSheet {
+            date: day(date),
+            filing_date: None,
+            period: "Q".to_string(),
+            fiscal_year: None,
+            reported_currency: None,
+            cash_and_equivalents: Some(50.0 * scale),
+            short_term_investments: None,
+            net_receivables: Some(40.0 * scale),
+            inventory: Some(30.0 * scale),
+            total_current_assets: Some(150.0 * scale),
+            property_plant_equipment_net: None,
+            goodwill: None,
+            intangible_assets: None,
+            total_assets: Some(500.0 * scale),
+            accounts_payable: Some(20.0 * scale),
+            short_term_debt: None,
+            total_current_liabilities: Some(100.0 * scale),
+            long_term_debt: None,
+            total_debt: Some(100.0 * scale),
+            total_liabilities: Some(250.0 * scale),
+            retained_earnings: Some(120.0 * scale),
+            total_equity: Some(250.0 * scale),
+            shares_outstanding: None,
+        }
+    }
+
+    fn statements() -> FinancialStatements {
+        FinancialStatements {
+            quarterly_income: vec![
+                quarter("2024-03-31", 100.0, 12.0),
+                quarter("2023-12-31", 100.0, 12.0),
+                quarter("2023-09-30", 100.0, 12.0),
+                quarter("2023-06-30", 100.0, 14.0),
+            ],
+            quarterly_balance: vec![balance("2024-03-31", 1.0), balance("2023-03-31", 1.0)],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_ttm_sums_four_consecutive_quarters() {
+        let flows = statements().ttm().unwrap();
+        assert!(flows.trailing);
+        assert_eq!(flows.revenue, Some(400.0));
+        assert_eq!(flows.net_income, Some(50.0));
+        assert_eq!(flows.gross_profit, Some(160.0));
+        assert_eq!(flows.ebitda, Some(100.0));
+        assert_eq!(flows.interest_expense, Some(8.0));
+        assert!((flows.tax_rate() - 0.25).abs() < 1e-12);
+
+        // A gap in the quarters falls back to the annual report (none here)
+        let mut gappy = statements();
+        gappy.quarterly_income.remove(1);
+        gappy.quarterly_income.push(quarter("2023-03-31", 100.0, 12.0));
+        assert_eq!(gappy.ttm(), None);
+    }
+
+    #[test]
+    fn test_derived_ratios_override_vendor_values() {
+        let mut metrics = FinancialMetrics {
+            profitability: ProfitabilityMetrics {
+                roe: Some(9.9),
+                roa: None,
+                roic: None,
+                net_profit_margin: None,
+                gross_profit_margin: None,
+                operating_profit_margin: None,
+                ebitda_margin: None,
+            },
+            growth: GrowthMetrics {
+                revenue_growth_yoy: Some(0.1),
+                revenue_growth_qoq: None,
+                eps_growth_yoy: None,
+                eps_growth_qoq: None,
+                net_income_growth_yoy: None,
+                book_value_growth_yoy: None,
+                operating_cash_flow_growth_yoy: None,
+            },
+            valuation: ValuationMetrics {
+                pe_ratio: None,
+                peg_ratio: None,
+                ps_ratio: None,
+                pb_ratio: None,
+                pcf_ratio: None,
+                ev_ebitda: None,
+                ev_sales: None,
+                pfcf_ratio: None,
+            },
+            financial_strength: FinancialStrengthMetrics {
+                debt_to_equity: None,
+                debt_to_assets: None,
+                current_ratio: None,
+                quick_ratio: None,
+                interest_coverage: None,
+                cash_to_debt: None,
+                equity_multiplier: None,
+                altman_z_score: None,
+            },
+            efficiency: EfficiencyMetrics {
+                asset_turnover: None,
+                inventory_turnover: None,
+                receivables_turnover: None,
+                payables_turnover: None,
+                working_capital_turnover: None,
+                days_sales_outstanding: None,
+                days_inventory_outstanding: None,
+                days_payables_outstanding: None,
+            },
+        };
+
+        statements().apply_to(&mut metrics, Some(1_000.0));
+
+        assert_eq!(metrics.profitability.roe, Some(0.2));
+        assert_eq!(metrics.profitability.net_profit_margin, Some(0.125));
+        assert_eq!(metrics.profitability.ebitda_margin, Some(0.25));
+        // NOPAT 80 × 0.75 = 60 over invested capital 100 + 250 - 50
+        assert_eq!(metrics.profitability.roic, Some(0.2));
+        assert_eq!(metrics.financial_strength.current_ratio, Some(1.5));
+        assert_eq!(metrics.financial_strength.quick_ratio, Some(1.2));
+        assert_eq!(metrics.financial_strength.interest_coverage, Some(10.0));
+        assert_eq!(metrics.financial_strength.cash_to_debt, Some(0.5));
+        assert_eq!(metrics.efficiency.receivables_turnover, Some(10.0));
+        assert_eq!(metrics.efficiency.days_sales_outstanding, Some(36.5));
+        assert_eq!(metrics.efficiency.inventory_turnover, Some(8.0));
+        assert_eq!(metrics.efficiency.working_capital_turnover, Some(8.0));
+        // Growth is left to the vendor
+        assert_eq!(metrics.growth.revenue_growth_yoy, Some(0.1));
+
+        // 1.2×0.1 + 1.4×0.24 + 3.3×0.16 + 0.6×4 + 1.0×0.8
+        let z = metrics.financial_strength.altman_z_score.unwrap();
+        assert!((z - 4.184).abs() < 1e-9);
+    }
+}
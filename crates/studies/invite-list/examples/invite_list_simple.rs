@@ -56,6 +56,7 @@ async fn main() -> Result<()> {
         // Fetch detailed data for the stock
         match fetcher.fetch_complete_stock_data(symbol).await {
             Ok((company_data, financial_data, price_data)) => {
+                let earnings = fetcher.fetch_earnings_calendar(symbol).await.ok();
                 println!("  ✅ Data fetched successfully");
                 
                 // Analyze safety
@@ -63,7 +64,8 @@ async fn main() -> Result<()> {
                     &mock_stock, 
                     &company_data, 
                     &financial_data, 
-                    &price_data
+                    &price_data,
+                    earnings.as_ref()
                 );
 
                 // Display results
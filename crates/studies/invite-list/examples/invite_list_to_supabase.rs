@@ -61,12 +61,15 @@ async fn main() -> Result<()> {
         // Fetch detailed data for the stock
         match fetcher.fetch_complete_stock_data(&stock.symbol).await {
             Ok((company_data, financial_data, price_data)) => {
+                let earnings = fetcher.fetch_earnings_calendar(&stock.symbol).await.ok();
+
                 // Analyze safety
                 let safety_analysis = calculator.analyze_stock_safety(
                     stock, 
                     &company_data, 
                     &financial_data, 
-                    &price_data
+                    &price_data,
+                    earnings.as_ref()
                 );
 
                 // Create database record
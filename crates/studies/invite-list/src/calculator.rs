@@ -1,7 +1,13 @@
 use crate::models::{SafetyAnalysis, SP500Stock, InviteListRecord, ApiConfig};
-use chrono::Utc;
+use buenotea_core::market_data::point_in_time::ANNUAL_FILING_LAG_DAYS;
+use buenotea_core::market_data::{as_of_timestamp, DailyBar, EarningsCalendar};
+use chrono::{Duration, NaiveDate, Utc};
 use serde_json::Value;
 
+/// Longest gap in calendar days since the last report that still counts as recent earnings
+/// (one quarter plus a late filer's slack)
+pub const RECENT_EARNINGS_DAYS: i64 = 120;
+
 /// Calculates safety analysis for S&P 500 stocks
 pub struct InviteListCalculator {
     api_config: ApiConfig,
@@ -13,19 +19,24 @@ impl InviteListCalculator {
     }
 
     /// Analyzes a stock for trading safety
+    ///
+    /// Recency of earnings is judged as of the last price bar, so truncated
+    /// histories give point-in-time answers.
     pub fn analyze_stock_safety(
         &self,
         _stock: &SP500Stock,
         company_data: &Value,
         financial_data: &Value,
-        price_data: &Value,
+        price_data: &[DailyBar],
+        earnings: Option<&EarningsCalendar>,
     ) -> SafetyAnalysis {
         let warning_flags = Vec::new();
-        let missing_data_components = Vec::new();
+        let mut missing_data_components = Vec::new();
         let mut safety_checks = Vec::new();
+        let as_of = price_data.last().map(|bar| bar.date).unwrap_or_else(|| Utc::now().date_naive());
 
-        // Check 1: Recent earnings data
-        let has_recent_earnings = self.check_recent_earnings(company_data, financial_data);
+        // Check 1: Recent earnings report
+        let has_recent_earnings = self.check_recent_earnings(earnings, as_of, &mut missing_data_components);
         safety_checks.push(("recent_earnings", has_recent_earnings, 0.20));
 
         // Check 2: Positive revenue
@@ -80,25 +91,21 @@ impl InviteListCalculator {
         }
     }
 
-    /// Checks if the company has recent earnings data
-    fn check_recent_earnings(&self, company_data: &Value, financial_data: &Value) -> bool {
-        // Check if we have recent financial data
-        if let Some(ratios) = financial_data.as_array() {
-            if !ratios.is_empty() {
-                return true;
-            }
-        }
-
-        // Check company profile for recent data
-        if let Some(profile) = company_data.as_array() {
-            if let Some(first_profile) = profile.first() {
-                if first_profile["sector"].is_string() && first_profile["industry"].is_string() {
-                    return true;
-                }
-            }
-        }
-
-        false
+    /// Checks if the company reported earnings within `RECENT_EARNINGS_DAYS` of `as_of`
+    fn check_recent_earnings(
+        &self,
+        earnings: Option<&EarningsCalendar>,
+        as_of: NaiveDate,
+        missing_data_components: &mut Vec<String>,
+    ) -> bool {
+        let Some(calendar) = earnings else {
+            missing_data_components.push("earnings_calendar".to_string());
+            return false;
+        };
+
+        calendar
+            .days_since_last(as_of)
+            .is_some_and(|days| days <= RECENT_EARNINGS_DAYS)
     }
 
     /// Checks if the company has positive revenue
@@ -132,61 +139,54 @@ impl InviteListCalculator {
     }
 
     /// Checks if the stock has stable price (not too volatile)
-    fn check_stable_price(&self, price_data: &Value) -> bool {
-        if let Some(historical) = price_data["historical"].as_array() {
-            if historical.len() < 30 {
-                return false; // Need at least 30 days of data
-            }
-
-            let prices: Vec<f64> = historical
-                .iter()
-                .take(30) // Last 30 days
-                .filter_map(|day| day["close"].as_f64())
-                .collect();
-
-            if prices.len() < 30 {
-                return false;
-            }
-
-            // Calculate volatility (standard deviation)
-            let mean = prices.iter().sum::<f64>() / prices.len() as f64;
-            let variance = prices.iter()
-                .map(|price| (price - mean).powi(2))
-                .sum::<f64>() / prices.len() as f64;
-            let std_dev = variance.sqrt();
-
-            // Consider stable if volatility is less than 5% of mean price
-            let volatility_threshold = mean * 0.05;
-            std_dev < volatility_threshold
-        } else {
-            false
+    fn check_stable_price(&self, price_data: &[DailyBar]) -> bool {
+        if price_data.len() < 30 {
+            return false; // Need at least 30 days of data
         }
+
+        let prices: Vec<f64> = price_data
+            .iter()
+            .rev()
+            .take(30) // Last 30 days
+            .map(|day| day.close)
+            .collect();
+
+        // Calculate volatility (standard deviation)
+        let mean = prices.iter().sum::<f64>() / prices.len() as f64;
+        let variance = prices.iter()
+            .map(|price| (price - mean).powi(2))
+            .sum::<f64>() / prices.len() as f64;
+        let std_dev = variance.sqrt();
+
+        // Consider stable if volatility is less than 5% of mean price
+        let volatility_threshold = mean * 0.05;
+        std_dev < volatility_threshold
     }
 
     /// Checks if the stock has sufficient trading volume
-    fn check_sufficient_volume(&self, price_data: &Value) -> bool {
-        if let Some(historical) = price_data["historical"].as_array() {
-            if historical.len() < 10 {
-                return false;
-            }
-
-            let volumes: Vec<i64> = historical
-                .iter()
-                .take(10) // Last 10 days
-                .filter_map(|day| day["volume"].as_i64())
-                .collect();
+    fn check_sufficient_volume(&self, price_data: &[DailyBar]) -> bool {
+        if price_data.len() < 10 {
+            return false;
+        }
 
-            if volumes.is_empty() {
-                return false;
-            }
+        // Consider sufficient if average volume over the last 10 days is at least 100,000 shares
+        self.average_recent_volume(price_data, 10) >= 100_000
+    }
 
-            let avg_volume = volumes.iter().sum::<i64>() / volumes.len() as i64;
-            
-            // Consider sufficient if average volume is at least 100,000 shares
-            avg_volume >= 100_000
-        } else {
-            false
+    /// Average volume over the most recent `days` sessions
+    fn average_recent_volume(&self, price_data: &[DailyBar], days: usize) -> u64 {
+        let volumes: Vec<u64> = price_data
+            .iter()
+            .rev()
+            .take(days)
+            .map(|day| day.volume)
+            .collect();
+
+        if volumes.is_empty() {
+            return 0;
         }
+
+        volumes.iter().sum::<u64>() / volumes.len() as u64
     }
 
     /// Checks if the stock has analyst coverage
@@ -234,66 +234,43 @@ impl InviteListCalculator {
     }
 
     /// Determines volatility rating based on price data
-    fn determine_volatility_rating(&self, price_data: &Value) -> String {
-        if let Some(historical) = price_data["historical"].as_array() {
-            if historical.len() < 30 {
-                return "High".to_string();
-            }
-
-            let prices: Vec<f64> = historical
-                .iter()
-                .take(30)
-                .filter_map(|day| day["close"].as_f64())
-                .collect();
-
-            if prices.len() < 30 {
-                return "High".to_string();
-            }
-
-            let mean = prices.iter().sum::<f64>() / prices.len() as f64;
-            let variance = prices.iter()
-                .map(|price| (price - mean).powi(2))
-                .sum::<f64>() / prices.len() as f64;
-            let std_dev = variance.sqrt();
-
-            let volatility_percentage = (std_dev / mean) * 100.0;
+    fn determine_volatility_rating(&self, price_data: &[DailyBar]) -> String {
+        if price_data.len() < 30 {
+            return "High".to_string();
+        }
 
-            match volatility_percentage {
-                p if p < 2.0 => "Low".to_string(),
-                p if p < 5.0 => "Medium".to_string(),
-                _ => "High".to_string(),
-            }
-        } else {
-            "High".to_string()
+        let prices: Vec<f64> = price_data
+            .iter()
+            .rev()
+            .take(30)
+            .map(|day| day.close)
+            .collect();
+
+        let mean = prices.iter().sum::<f64>() / prices.len() as f64;
+        let variance = prices.iter()
+            .map(|price| (price - mean).powi(2))
+            .sum::<f64>() / prices.len() as f64;
+        let std_dev = variance.sqrt();
+
+        let volatility_percentage = (std_dev / mean) * 100.0;
+
+        match volatility_percentage {
+            p if p < 2.0 => "Low".to_string(),
+            p if p < 5.0 => "Medium".to_string(),
+            _ => "High".to_string(),
         }
     }
 
     /// Determines liquidity rating based on volume data
-    fn determine_liquidity_rating(&self, price_data: &Value) -> String {
-        if let Some(historical) = price_data["historical"].as_array() {
-            if historical.len() < 10 {
-                return "Low".to_string();
-            }
-
-            let volumes: Vec<i64> = historical
-                .iter()
-                .take(10)
-                .filter_map(|day| day["volume"].as_i64())
-                .collect();
-
-            if volumes.is_empty() {
-                return "Low".to_string();
-            }
-
-            let avg_volume = volumes.iter().sum::<i64>() / volumes.len() as i64;
+    fn determine_liquidity_rating(&self, price_data: &[DailyBar]) -> String {
+        if price_data.len() < 10 {
+            return "Low".to_string();
+        }
 
-            match avg_volume {
-                v if v >= 1_000_000 => "High".to_string(),
-                v if v >= 100_000 => "Medium".to_string(),
-                _ => "Low".to_string(),
-            }
-        } else {
-            "Low".to_string()
+        match self.average_recent_volume(price_data, 10) {
+            v if v >= 1_000_000 => "High".to_string(),
+            v if v >= 100_000 => "Medium".to_string(),
+            _ => "Low".to_string(),
         }
     }
 
@@ -327,7 +304,7 @@ impl InviteListCalculator {
         safety_analysis: &SafetyAnalysis,
         company_data: &Value,
         financial_data: &Value,
-        price_data: &Value,
+        price_data: &[DailyBar],
     ) -> InviteListRecord {
         InviteListRecord {
             id: None,
@@ -357,9 +334,49 @@ impl InviteListCalculator {
             missing_data_components: safety_analysis.missing_data_components.clone(),
             raw_company_data: Some(company_data.clone()),
             raw_financial_data: Some(financial_data.clone()),
-            raw_price_data: Some(price_data.clone()),
+            raw_price_data: serde_json::to_value(price_data).ok(),
             created_at: None,
             updated_at: None,
         }
     }
+
+    /// Creates an InviteListRecord for a past trading day (used by backfills)
+    ///
+    /// `price_data` must already be truncated to `as_of`; the last close becomes the current price.
+    pub fn create_invite_list_record_as_of(
+        &self,
+        stock: &SP500Stock,
+        safety_analysis: &SafetyAnalysis,
+        company_data: &Value,
+        financial_data: &Value,
+        price_data: &[DailyBar],
+        as_of: NaiveDate,
+    ) -> InviteListRecord {
+        let mut record = self.create_invite_list_record(stock, safety_analysis, company_data, financial_data, price_data);
+        record.analysis_date = as_of_timestamp(as_of);
+        record.current_price = price_data.last().map(|bar| bar.close).or(record.current_price);
+        record
+            .warning_flags
+            .push("Company profile and index membership are current, not as of the analysis date".to_string());
+        record
+    }
+
+    /// Keeps only the annual ratio entries that were public on `as_of`
+    pub fn financial_data_as_of(&self, financial_data: &Value, as_of: NaiveDate) -> Value {
+        let Some(ratios) = financial_data.as_array() else {
+            return financial_data.clone();
+        };
+
+        let known = ratios
+            .iter()
+            .filter(|ratio| {
+                ratio["date"]
+                    .as_str()
+                    .and_then(|date| NaiveDate::parse_from_str(date.get(..10)?, "%Y-%m-%d").ok())
+                    .is_some_and(|period_end| period_end + Duration::days(ANNUAL_FILING_LAG_DAYS) <= as_of)
+            })
+            .cloned()
+            .collect();
+        Value::Array(known)
+    }
 }
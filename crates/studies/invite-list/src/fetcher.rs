@@ -1,23 +1,49 @@
 use buenotea_core::{Error, Result};
+use buenotea_core::market_data::{
+    earnings_calendar_from_env, DailyBar, EarningsCalendar, EarningsCalendarProvider, FmpProvider, MarketDataProvider,
+};
 use crate::models::{SP500Stock, ApiConfig};
+use chrono::NaiveDate;
 use reqwest;
 use serde_json::Value;
 use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Trading sessions of price history fetched per stock (about one year)
+const PRICE_HISTORY_SESSIONS: usize = 252;
 
 /// Fetches S&P 500 stock list from Financial Modeling Prep API
 pub struct SP500Fetcher {
     api_config: ApiConfig,
     client: reqwest::Client,
+    provider: Arc<dyn MarketDataProvider>,
+    earnings: Arc<dyn EarningsCalendarProvider>,
 }
 
 impl SP500Fetcher {
     pub fn new(api_config: ApiConfig) -> Self {
+        let provider = Arc::new(FmpProvider::new(api_config.fmp_api_key.clone()));
+        Self::with_provider(api_config, provider)
+    }
+
+    /// Creates a fetcher that reads price history from a specific market data provider
+    pub fn with_provider(api_config: ApiConfig, provider: Arc<dyn MarketDataProvider>) -> Self {
+        let earnings = earnings_calendar_from_env()
+            .unwrap_or_else(|| Arc::new(FmpProvider::new(api_config.fmp_api_key.clone())));
         Self {
             api_config,
             client: reqwest::Client::new(),
+            provider,
+            earnings,
         }
     }
 
+    /// Reads report dates from a specific earnings calendar
+    pub fn with_earnings_calendar(mut self, earnings: Arc<dyn EarningsCalendarProvider>) -> Self {
+        self.earnings = earnings;
+        self
+    }
+
     /// Fetches the complete S&P 500 stock list
     pub async fn fetch_sp500_list(&self) -> Result<Vec<SP500Stock>> {
         let url = format!(
@@ -106,33 +132,22 @@ impl SP500Fetcher {
         Ok(json_data)
     }
 
-    /// Fetches price data for a specific stock
-    pub async fn fetch_price_data(&self, symbol: &str) -> Result<Value> {
-        let url = format!(
-            "https://financialmodelingprep.com/api/v3/historical-price-full/{}?apikey={}",
-            symbol, self.api_config.fmp_api_key
-        );
-
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| Error::ApiError("FMP".to_string(), format!("Failed to fetch price data for {}: {}", symbol, e)))?;
-
-        if !response.status().is_success() {
-            return Err(Error::ApiError("FMP".to_string(), format!(
-                "API request failed for {} with status: {}",
-                symbol, response.status()
-            )));
-        }
+    /// Fetches roughly one year of daily bars for a specific stock (oldest first)
+    pub async fn fetch_price_data(&self, symbol: &str) -> Result<Vec<DailyBar>> {
+        let response = self.provider.daily_bars(symbol, PRICE_HISTORY_SESSIONS).await?;
+        Ok(response.data)
+    }
 
-        let json_data: Value = response
-            .json()
-            .await
-            .map_err(|e| Error::ApiError("FMP".to_string(), format!("Failed to parse JSON response for {}: {}", symbol, e)))?;
+    /// Fetches roughly one year of daily bars ending on or before `as_of` (oldest first)
+    pub async fn fetch_price_data_as_of(&self, symbol: &str, as_of: NaiveDate) -> Result<Vec<DailyBar>> {
+        let response = self.provider.daily_bars_as_of(symbol, PRICE_HISTORY_SESSIONS, as_of).await?;
+        Ok(response.data)
+    }
 
-        Ok(json_data)
+    /// Fetches past and scheduled earnings reports for a specific stock
+    pub async fn fetch_earnings_calendar(&self, symbol: &str) -> Result<EarningsCalendar> {
+        let response = self.earnings.earnings_calendar(symbol).await?;
+        Ok(response.data)
     }
 
     /// Parses the S&P 500 API response into SP500Stock structs
@@ -174,7 +189,7 @@ impl SP500Fetcher {
     }
 
     /// Fetches all data for a single stock (company, financial, price)
-    pub async fn fetch_complete_stock_data(&self, symbol: &str) -> Result<(Value, Value, Value)> {
+    pub async fn fetch_complete_stock_data(&self, symbol: &str) -> Result<(Value, Value, Vec<DailyBar>)> {
         let (company_data, financial_data, price_data) = tokio::try_join!(
             self.fetch_company_data(symbol),
             self.fetch_financial_data(symbol),
@@ -185,9 +200,9 @@ impl SP500Fetcher {
     }
 
     /// Fetches data for multiple stocks in parallel (with rate limiting)
-    pub async fn fetch_multiple_stocks_data(&self, symbols: &[String]) -> Result<HashMap<String, (Value, Value, Value)>> {
+    pub async fn fetch_multiple_stocks_data(&self, symbols: &[String]) -> Result<HashMap<String, (Value, Value, Vec<DailyBar>)>> {
         let mut results = HashMap::new();
-        let _tasks: Vec<tokio::task::JoinHandle<Option<(String, (Value, Value, Value))>>> = Vec::new();
+        let _tasks: Vec<tokio::task::JoinHandle<Option<(String, (Value, Value, Vec<DailyBar>))>>> = Vec::new();
 
         // Process stocks in batches to respect rate limits
         const BATCH_SIZE: usize = 5;
@@ -229,6 +244,8 @@ impl Clone for SP500Fetcher {
         Self {
             api_config: self.api_config.clone(),
             client: reqwest::Client::new(),
+            provider: self.provider.clone(),
+            earnings: self.earnings.clone(),
         }
     }
 }
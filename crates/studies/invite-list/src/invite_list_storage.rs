@@ -2,6 +2,8 @@
 // Uses time-series approach to track safety changes over time
 
 use buenotea_core::Result;
+use chrono::NaiveDate;
+use std::collections::BTreeSet;
 use crate::models::{InviteListRecord, InviteListInsert};
 use buenotea_infrastructure::DatabaseClient;
 use tracing::info;
@@ -168,6 +170,12 @@ impl InviteListStorage {
         Ok(ids)
     }
 
+    /// Dates between `from` and `to` that already have a invite list record for a symbol
+    pub async fn get_analysis_dates(&self, symbol: &str, from: NaiveDate, to: NaiveDate) -> Result<BTreeSet<NaiveDate>> {
+        info!("Getting stored invite list dates for {} from {} to {}", symbol, from, to);
+        self.db_client.analysis_dates("invite_list_history", Some(symbol), from, to).await
+    }
+
     /// Get all safe stocks from the database
     pub async fn get_safe_stocks(&self) -> Result<Vec<InviteListRecord>> {
         info!("Fetching safe stocks from database");
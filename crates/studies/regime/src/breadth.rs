@@ -0,0 +1,199 @@
+// Market breadth helpers
+// Advance/decline, moving-average participation, new highs/lows and the McClellan oscillator
+// computed from the daily bars of every stock in the breadth universe
+
+use super::models::MarketBreadthAnalysis;
+use buenotea_core::market_data::DailyBar;
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+/// Sessions of history fetched per constituent (52 weeks, enough for the 200-day SMA)
+pub const BREADTH_HISTORY_SESSIONS: usize = 252;
+
+/// Moving averages used for participation
+const SHORT_SMA: usize = 50;
+const LONG_SMA: usize = 200;
+
+/// Sessions a stock needs before its high or low counts as a 52-week extreme
+const MIN_EXTREME_SESSIONS: usize = 240;
+
+/// McClellan exponential moving averages of net advances
+const MCCLELLAN_FAST: usize = 19;
+const MCCLELLAN_SLOW: usize = 39;
+
+/// Daily advancing, declining and unchanged counts
+#[derive(Debug, Default, Clone, Copy)]
+struct DailyBreadth {
+    advancing: i32,
+    declining: i32,
+    unchanged: i32,
+}
+
+impl DailyBreadth {
+    fn net(&self) -> i32 {
+        self.advancing - self.declining
+    }
+
+    /// Net advances per thousand issues, so the oscillator does not depend on universe size
+    fn ratio_adjusted(&self) -> Option<f64> {
+        let issues = self.advancing + self.declining;
+        (issues > 0).then(|| self.net() as f64 / issues as f64 * 1000.0)
+    }
+}
+
+/// Breadth of a universe of stocks on the latest session found in their bars
+///
+/// Each series is one stock's daily bars, oldest first. Stocks without a bar on
+/// the latest session are ignored for that day's counts. Returns `None` when no
+/// stock has at least two bars.
+pub fn analyze_breadth(universe: &[Vec<DailyBar>]) -> Option<MarketBreadthAnalysis> {
+    let latest = universe.iter().filter(|bars| bars.len() >= 2).filter_map(|bars| bars.last()).map(|bar| bar.date).max()?;
+
+    let daily = daily_breadth(universe);
+    let today = daily.get(&latest).copied().unwrap_or_default();
+    let current: Vec<&Vec<DailyBar>> = universe
+        .iter()
+        .filter(|bars| bars.len() >= 2 && bars.last().is_some_and(|bar| bar.date == latest))
+        .collect();
+
+    let (new_highs, new_lows) = new_extremes(&current);
+    let advance_decline_line = daily.values().map(|day| day.net() as i64).sum::<i64>();
+    let ratio_adjusted: Vec<f64> = daily.values().filter_map(DailyBreadth::ratio_adjusted).collect();
+
+    Some(MarketBreadthAnalysis {
+        advancing_stocks: Some(today.advancing),
+        declining_stocks: Some(today.declining),
+        unchanged_stocks: Some(today.unchanged),
+        new_highs: Some(new_highs),
+        new_lows: Some(new_lows),
+        breadth_ratio: (today.declining > 0).then(|| today.advancing as f64 / today.declining as f64),
+        percent_above_sma50: percent_above_sma(&current, SHORT_SMA),
+        percent_above_sma200: percent_above_sma(&current, LONG_SMA),
+        advance_decline_line: Some(advance_decline_line),
+        mcclellan_oscillator: mcclellan_oscillator(&ratio_adjusted),
+    })
+}
+
+/// Advancing/declining counts for every session in the universe
+fn daily_breadth(universe: &[Vec<DailyBar>]) -> BTreeMap<NaiveDate, DailyBreadth> {
+    let mut daily: BTreeMap<NaiveDate, DailyBreadth> = BTreeMap::new();
+    for bars in universe {
+        for pair in bars.windows(2) {
+            let day = daily.entry(pair[1].date).or_default();
+            if pair[1].close > pair[0].close {
+                day.advancing += 1;
+            } else if pair[1].close < pair[0].close {
+                day.declining += 1;
+            } else {
+                day.unchanged += 1;
+            }
+        }
+    }
+    daily
+}
+
+/// Percentage of stocks closing above their `window`-day simple moving average
+fn percent_above_sma(current: &[&Vec<DailyBar>], window: usize) -> Option<f64> {
+    let eligible: Vec<bool> = current
+        .iter()
+        .filter(|bars| bars.len() >= window)
+        .map(|bars| {
+            let recent = &bars[bars.len() - window..];
+            let sma = recent.iter().map(|bar| bar.close).sum::<f64>() / window as f64;
+            recent[window - 1].close > sma
+        })
+        .collect();
+    if eligible.is_empty() {
+        return None;
+    }
+    let above = eligible.iter().filter(|above| **above).count();
+    Some(above as f64 / eligible.len() as f64 * 100.0)
+}
+
+/// Stocks whose latest high (low) exceeds every earlier high (low) in the window
+fn new_extremes(current: &[&Vec<DailyBar>]) -> (i32, i32) {
+    let mut highs = 0;
+    let mut lows = 0;
+    for bars in current.iter().filter(|bars| bars.len() >= MIN_EXTREME_SESSIONS) {
+        let (earlier, last) = bars.split_at(bars.len() - 1);
+        let last = &last[0];
+        if earlier.iter().all(|bar| last.high > bar.high) {
+            highs += 1;
+        }
+        if earlier.iter().all(|bar| last.low < bar.low) {
+            lows += 1;
+        }
+    }
+    (highs, lows)
+}
+
+/// 19-day minus 39-day EMA of ratio-adjusted net advances (oldest first)
+fn mcclellan_oscillator(net_advances: &[f64]) -> Option<f64> {
+    if net_advances.len() < MCCLELLAN_SLOW {
+        return None;
+    }
+    Some(ema(net_advances, MCCLELLAN_FAST) - ema(net_advances, MCCLELLAN_SLOW))
+}
+
+/// Exponential moving average seeded with the first value
+fn ema(values: &[f64], period: usize) -> f64 {
+    let alpha = 2.0 / (period as f64 + 1.0);
+    values.iter().skip(1).fold(values[0], |ema, value| ema + alpha * (value - ema))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn series(closes: impl IntoIterator<Item = f64>) -> Vec<DailyBar> {
+        let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        closes
+            .into_iter()
+            .enumerate()
+            .map(|(i, close)| DailyBar {
+                date: start + Duration::days(i as i64),
+                open: close,
+                high: close,
+                low: close,
+                close,
+                adjusted_close: None,
+                volume: 1_000,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_breadth_of_rising_and_falling_stocks() {
+        let rising = series((0..252).map(|i| 100.0 + i as f64));
+        let falling = series((0..252).map(|i| 300.0 - i as f64));
+        let flat = series(vec![50.0; 252]);
+        let breadth = analyze_breadth(&[rising.clone(), rising, falling, flat]).unwrap();
+
+        assert_eq!(breadth.advancing_stocks, Some(2));
+        assert_eq!(breadth.declining_stocks, Some(1));
+        assert_eq!(breadth.unchanged_stocks, Some(1));
+        assert_eq!(breadth.breadth_ratio, Some(2.0));
+        assert_eq!(breadth.new_highs, Some(2));
+        assert_eq!(breadth.new_lows, Some(1));
+        assert_eq!(breadth.percent_above_sma50, Some(50.0));
+        assert_eq!(breadth.percent_above_sma200, Some(50.0));
+        assert_eq!(breadth.advance_decline_line, Some(251));
+        // Constant net advances: both averages converge on the same value
+        assert!(breadth.mcclellan_oscillator.unwrap().abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_mcclellan_turns_with_breadth() {
+        // Broad advance that reverses into a broad decline
+        let closes = |up: usize| (0..100).map(move |i| if i < up { i as f64 } else { (2 * up - i) as f64 });
+        let universe: Vec<Vec<DailyBar>> = (0..10).map(|_| series(closes(80).map(|c| c + 100.0))).collect();
+        let breadth = analyze_breadth(&universe).unwrap();
+        assert!(breadth.mcclellan_oscillator.unwrap() < 0.0);
+        // Too little history for 52-week extremes or the 200-day average
+        assert_eq!(breadth.new_lows, Some(0));
+        assert_eq!(breadth.percent_above_sma200, None);
+
+        assert!(analyze_breadth(&[series(vec![1.0])]).is_none());
+    }
+}
@@ -3,48 +3,84 @@
 
 use buenotea_core::Result;
 use super::models::*;
-use chrono::{DateTime, Utc};
+use super::breadth::{analyze_breadth, BREADTH_HISTORY_SESSIONS};
+use super::sectors::{classify_rotation, leaders_and_laggers, sector_performance, SECTOR_ETFS, THREE_MONTH_SESSIONS};
+use super::volatility::{term_structure, vix_percentile, vix_trend, VIX_HISTORY_SESSIONS};
+use buenotea_core::market_data::{as_of_timestamp, DailyBar, MarketDataProvider, ProviderChain, VolatilityIndex};
+use chrono::{DateTime, NaiveDate, Utc};
 use std::time::Instant;
-use tracing::info;
-use reqwest::Client;
-use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use tracing::{info, warn};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 /// Main Market Regime Calculator that analyzes overall market conditions
 pub struct MarketRegimeCalculator {
-    client: Client,
+    provider: Arc<dyn MarketDataProvider>,
     raw_api_responses: HashMap<String, serde_json::Value>,
     api_endpoints_used: Vec<String>,
     primary_api_source: String,
     fallback_api_source: Option<String>,
+    /// VIX closes (oldest first) collected for the current calculation
+    vix_history: Vec<f64>,
+    /// SPY closes (oldest first) collected for the current calculation
+    spy_history: Vec<f64>,
+    /// Symbols whose daily bars make up market breadth (usually the S&P 500 constituents)
+    breadth_universe: Vec<String>,
 }
 
 impl MarketRegimeCalculator {
-    /// Create a new Market Regime Calculator
+    /// Create a new Market Regime Calculator using the providers configured in the environment
     pub fn new() -> Self {
+        Self::with_provider(Arc::new(ProviderChain::from_env()))
+    }
+
+    /// Create a Market Regime Calculator backed by a specific market data provider
+    pub fn with_provider(provider: Arc<dyn MarketDataProvider>) -> Self {
         Self {
-            client: Client::new(),
+            provider,
             raw_api_responses: HashMap::new(),
             api_endpoints_used: Vec::new(),
             primary_api_source: String::new(),
             fallback_api_source: None,
+            vix_history: Vec::new(),
+            spy_history: Vec::new(),
+            breadth_universe: Vec::new(),
         }
     }
 
+    /// Compute market breadth from the daily bars of these symbols
+    ///
+    /// Callers normally pass the invite list (`InviteListStorage::get_all_stock_symbols`).
+    /// Without a universe the breadth fields are left empty.
+    pub fn with_breadth_universe(mut self, symbols: Vec<String>) -> Self {
+        self.breadth_universe = symbols;
+        self
+    }
+
     /// Calculate overall market regime analysis
     pub async fn calculate_market_regime(&mut self) -> Result<MarketRegimeResult> {
+        self.calculate(Utc::now().date_naive(), Utc::now()).await
+    }
+
+    /// Calculate market regime analysis using only data available on `as_of`
+    pub async fn calculate_market_regime_as_of(&mut self, as_of: NaiveDate) -> Result<MarketRegimeResult> {
+        self.calculate(as_of, as_of_timestamp(as_of)).await
+    }
+
+    async fn calculate(&mut self, as_of: NaiveDate, timestamp: DateTime<Utc>) -> Result<MarketRegimeResult> {
         let start_time = Instant::now();
-        info!("Starting market regime analysis...");
+        info!("Starting market regime analysis as of {}...", as_of);
 
         // Reset tracking for new calculation
         self.raw_api_responses.clear();
         self.api_endpoints_used.clear();
         self.primary_api_source.clear();
         self.fallback_api_source = None;
+        self.vix_history.clear();
+        self.spy_history.clear();
 
         // Step 1: Collect market context data (SPY, VIX, etc.)
-        let market_context = self.collect_market_context().await?;
+        let mut market_context = self.collect_market_context(as_of).await?;
 
         // Step 2: Analyze market volatility
         let volatility_analysis = self.analyze_market_volatility(&market_context).await?;
@@ -53,10 +89,11 @@ impl MarketRegimeCalculator {
         let trend_analysis = self.analyze_market_trends(&market_context).await?;
 
         // Step 4: Analyze market breadth
-        let breadth_analysis = self.analyze_market_breadth().await?;
+        let breadth_analysis = self.analyze_market_breadth(as_of).await?;
+        market_context.market_breadth = breadth_analysis.advancing_fraction();
 
         // Step 5: Analyze sector performance
-        let sector_analysis = self.analyze_sector_performance().await?;
+        let sector_analysis = self.analyze_sector_performance(as_of).await?;
 
         // Step 6: Analyze market sentiment
         let sentiment_indicators = self.analyze_market_sentiment().await?;
@@ -100,7 +137,7 @@ impl MarketRegimeCalculator {
             sector_analysis,
             sentiment_indicators,
             risk_assessment,
-            timestamp: Utc::now(),
+            timestamp,
             metadata,
         };
 
@@ -109,17 +146,20 @@ impl MarketRegimeCalculator {
     }
 
     /// Collect market context data (SPY, VIX, etc.)
-    async fn collect_market_context(&mut self) -> Result<MarketContext> {
+    async fn collect_market_context(&mut self, as_of: NaiveDate) -> Result<MarketContext> {
         let mut market_context = MarketContext {
             spy_price: None,
             spy_20d_change: None,
             spy_50d_change: None,
             vix: None,
+            vix9d: None,
+            vix3m: None,
             market_breadth: None,
         };
 
         // Fetch SPY data for market context
-        if let Ok(spy_data) = self.fetch_price_data_fmp("SPY").await {
+        if let Ok(spy_data) = self.fetch_daily_bars("SPY", 250, as_of).await {
+            self.spy_history = spy_data.iter().map(|bar| bar.close).collect();
             if let (Some(current), Some(price_20d_ago), Some(price_50d_ago)) = (
                 spy_data.last().map(|p| p.close),
                 spy_data.get(spy_data.len().saturating_sub(20)).map(|p| p.close),
@@ -131,13 +171,26 @@ impl MarketRegimeCalculator {
             }
         }
 
-        // Fetch VIX data (simplified - using a mock value for now)
-        // In a real implementation, you would fetch VIX data from a financial API
-        market_context.vix = Some(20.0); // Mock VIX value
-        
-        // Calculate market breadth (simplified)
-        // In a real implementation, you would analyze advancing vs declining stocks
-        market_context.market_breadth = Some(0.65); // Mock market breadth (65% advancing)
+        // Fetch a year of VIX history plus the 9-day and 3-month indices for the term structure
+        match self.fetch_daily_bars(VolatilityIndex::Vix.symbol(), VIX_HISTORY_SESSIONS, as_of).await {
+            Ok(vix_data) => {
+                self.vix_history = vix_data.iter().map(|bar| bar.close).collect();
+                market_context.vix = self.vix_history.last().copied();
+            }
+            Err(e) => warn!("VIX data unavailable: {}", e),
+        }
+        for index in [VolatilityIndex::Vix9d, VolatilityIndex::Vix3m] {
+            match self.fetch_daily_bars(index.symbol(), 5, as_of).await {
+                Ok(bars) => {
+                    let close = bars.last().map(|bar| bar.close);
+                    match index {
+                        VolatilityIndex::Vix9d => market_context.vix9d = close,
+                        _ => market_context.vix3m = close,
+                    }
+                }
+                Err(e) => warn!("{} data unavailable: {}", index.symbol(), e),
+            }
+        }
 
         Ok(market_context)
     }
@@ -151,8 +204,11 @@ impl MarketRegimeCalculator {
             2.0 // Default volatility
         };
 
-        // Calculate volatility percentile (simplified)
-        let volatility_percentile = if market_volatility > 3.0 {
+        // Percentile of the current VIX over the past year, falling back to SPY movement
+        let vix_percentile = market_context.vix.and_then(|vix| vix_percentile(&self.vix_history, vix));
+        let volatility_percentile = if let Some(percentile) = vix_percentile {
+            percentile
+        } else if market_volatility > 3.0 {
             90.0 // High volatility
         } else if market_volatility > 2.0 {
             70.0 // Medium-high volatility
@@ -162,19 +218,22 @@ impl MarketRegimeCalculator {
             30.0 // Low volatility
         };
 
-        // Determine volatility trend (simplified)
-        let volatility_trend = if market_volatility > 2.5 {
+        // Volatility trend from the VIX series, falling back to SPY movement
+        let volatility_trend = vix_trend(&self.vix_history).unwrap_or(if market_volatility > 2.5 {
             VolatilityTrend::Increasing
         } else if market_volatility < 1.0 {
             VolatilityTrend::Decreasing
         } else {
             VolatilityTrend::Stable
-        };
+        });
 
         Ok(VolatilityAnalysis {
             market_volatility,
             volatility_percentile,
             volatility_trend,
+            vix_percentile,
+            vix_term_structure: term_structure(market_context.vix, market_context.vix3m),
+            vix_short_term_structure: term_structure(market_context.vix9d, market_context.vix),
         })
     }
 
@@ -235,32 +294,67 @@ impl MarketRegimeCalculator {
         })
     }
 
-    /// Analyze market breadth
-    async fn analyze_market_breadth(&self) -> Result<MarketBreadthAnalysis> {
-        // Mock market breadth analysis
-        // In a real implementation, you would fetch data from market breadth APIs
-        Ok(MarketBreadthAnalysis {
-            advancing_stocks: Some(2500),
-            declining_stocks: Some(1500),
-            unchanged_stocks: Some(500),
-            new_highs: Some(150),
-            new_lows: Some(75),
-            breadth_ratio: Some(2500.0 / 1500.0),
-        })
+    /// Analyze market breadth from the daily bars of the breadth universe
+    async fn analyze_market_breadth(&mut self, as_of: NaiveDate) -> Result<MarketBreadthAnalysis> {
+        if self.breadth_universe.is_empty() {
+            warn!("No breadth universe configured, market breadth unavailable");
+            return Ok(MarketBreadthAnalysis::unavailable());
+        }
+
+        // Constituent bars are not kept in raw_api_responses: 500 series would swamp the record
+        let mut universe = Vec::with_capacity(self.breadth_universe.len());
+        let mut failed = 0;
+        for symbol in &self.breadth_universe {
+            match self.provider.daily_bars_as_of(symbol, BREADTH_HISTORY_SESSIONS, as_of).await {
+                Ok(response) => universe.push(response.data),
+                Err(e) => {
+                    failed += 1;
+                    warn!("Breadth bars unavailable for {}: {}", symbol, e);
+                }
+            }
+        }
+        info!(
+            "Market breadth from {} of {} constituents ({} failed)",
+            universe.len(),
+            self.breadth_universe.len(),
+            failed
+        );
+        self.api_endpoints_used
+            .push(format!("daily bars for {} breadth constituents", universe.len()));
+
+        Ok(analyze_breadth(&universe).unwrap_or_else(MarketBreadthAnalysis::unavailable))
     }
 
-    /// Analyze sector performance
-    async fn analyze_sector_performance(&self) -> Result<SectorAnalysis> {
-        // Mock sector analysis
-        // In a real implementation, you would fetch sector ETF data
+    /// Analyze sector performance from the SPDR sector ETFs
+    async fn analyze_sector_performance(&mut self, as_of: NaiveDate) -> Result<SectorAnalysis> {
+        let mut sectors = Vec::with_capacity(SECTOR_ETFS.len());
+        for (symbol, sector, _) in SECTOR_ETFS {
+            match self.fetch_daily_bars(symbol, THREE_MONTH_SESSIONS + 1, as_of).await {
+                Ok(bars) => {
+                    let closes: Vec<f64> = bars.iter().map(|bar| bar.close).collect();
+                    sectors.push(sector_performance(symbol, sector, &closes, &self.spy_history));
+                }
+                Err(e) => warn!("Sector ETF {} unavailable: {}", symbol, e),
+            }
+        }
+
+        let relative_strength = |symbol: &str| {
+            sectors.iter().find(|s| s.symbol == symbol).and_then(|s| s.relative_strength)
+        };
+        let (leading_sectors, lagging_sectors) = leaders_and_laggers(&sectors);
+
         Ok(SectorAnalysis {
-            technology_performance: Some(0.05),
-            healthcare_performance: Some(0.02),
-            financial_performance: Some(-0.01),
-            energy_performance: Some(0.08),
-            consumer_performance: Some(0.03),
-            leading_sector: Some("Energy".to_string()),
-            lagging_sector: Some("Financial".to_string()),
+            technology_performance: relative_strength("XLK"),
+            healthcare_performance: relative_strength("XLV"),
+            financial_performance: relative_strength("XLF"),
+            energy_performance: relative_strength("XLE"),
+            consumer_performance: relative_strength("XLY"),
+            leading_sector: leading_sectors.first().cloned(),
+            lagging_sector: lagging_sectors.first().cloned(),
+            rotation: classify_rotation(&sectors),
+            leading_sectors,
+            lagging_sectors,
+            sectors,
         })
     }
 
@@ -289,6 +383,11 @@ impl MarketRegimeCalculator {
             risk_score += vix * 2.0; // Add VIX component
         }
 
+        // Inverted VIX term structure signals near-term stress
+        if volatility_analysis.vix_term_structure.is_some_and(|ratio| ratio > 1.0) {
+            risk_score += 10.0;
+        }
+
         // Determine risk level
         let risk_level = if risk_score > 80.0 {
             RiskLevel::VeryHigh
@@ -337,7 +436,7 @@ impl MarketRegimeCalculator {
         // Bull market conditions
         if trend_analysis.short_term == TrendDirection::StrongBullish
             && trend_analysis.medium_term == TrendDirection::Bullish
-            && breadth_analysis.breadth_ratio.unwrap_or(1.0) > 1.5
+            && breadth_analysis.confirms_advance()
         {
             return Ok(MarketRegime::Bull);
         }
@@ -345,7 +444,7 @@ impl MarketRegimeCalculator {
         // Bear market conditions
         if trend_analysis.short_term == TrendDirection::StrongBearish
             && trend_analysis.medium_term == TrendDirection::Bearish
-            && breadth_analysis.breadth_ratio.unwrap_or(1.0) < 0.7
+            && breadth_analysis.confirms_decline()
         {
             return Ok(MarketRegime::Bear);
         }
@@ -382,9 +481,9 @@ impl MarketRegimeCalculator {
         else if trend_analysis.consistency < 50.0 { confidence -= 0.1; }
 
         // Breadth confirmation = higher confidence
-        if let Some(ratio) = breadth_analysis.breadth_ratio {
-            if ratio > 1.2 || ratio < 0.8 { confidence += 0.1; } // Clear breadth signal
-        }
+        let clear_ratio = breadth_analysis.breadth_ratio.is_some_and(|ratio| !(0.8..=1.2).contains(&ratio));
+        let clear_momentum = breadth_analysis.mcclellan_oscillator.is_some_and(|osc| osc.abs() > 50.0);
+        if clear_ratio || clear_momentum { confidence += 0.1; } // Clear breadth signal
 
         // Volatility clarity = higher confidence
         if volatility_analysis.market_volatility > 3.0 || volatility_analysis.market_volatility < 1.0 {
@@ -394,72 +493,18 @@ impl MarketRegimeCalculator {
         confidence.max(0.0).min(1.0)
     }
 
-    /// Fetch price data from FMP (Financial Modeling Prep)
-    async fn fetch_price_data_fmp(&mut self, symbol: &str) -> Result<Vec<PriceData>> {
-        let api_key = std::env::var("FMP_API_KEY")
-            .map_err(|_| buenotea_core::Error::MissingApiKey("FMP".to_string()))?;
-        
-        let url = format!(
-            "https://financialmodelingprep.com/api/v3/historical-price-full/{}?apikey={}&limit=250",
-            symbol, api_key
-        );
-        
-        self.api_endpoints_used.push(url.clone());
-        
-        let response = self.client.get(&url).send().await?;
-        let json: Value = response.json().await?;
-        
-        // Store raw response for debugging
-        self.raw_api_responses.insert("fmp_price_data".to_string(), json.clone());
-        
-        let mut price_data = Vec::new();
-        
-        if let Some(historical_data) = json.get("historical") {
-            if let Some(array) = historical_data.as_array() {
-                for item in array.iter().take(250) { // Limit to 250 days
-                    if let (Some(date_str), Some(open), Some(high), Some(low), Some(close), Some(volume)) = (
-                        item.get("date").and_then(|v| v.as_str()),
-                        item.get("open").and_then(|v| v.as_f64()),
-                        item.get("high").and_then(|v| v.as_f64()),
-                        item.get("low").and_then(|v| v.as_f64()),
-                        item.get("close").and_then(|v| v.as_f64()),
-                        item.get("volume").and_then(|v| v.as_u64()),
-                    ) {
-                        if let Ok(date) = chrono::DateTime::parse_from_rfc3339(&format!("{}T00:00:00Z", date_str)) {
-                            price_data.push(PriceData {
-                                date: date.with_timezone(&Utc),
-                                open,
-                                high,
-                                low,
-                                close,
-                                volume,
-                            });
-                        }
-                    }
-                }
-            }
+    /// Fetch daily bars on or before `as_of` through the market data provider, recording API usage
+    async fn fetch_daily_bars(&mut self, symbol: &str, limit: usize, as_of: NaiveDate) -> Result<Vec<DailyBar>> {
+        let response = self.provider.daily_bars_as_of(symbol, limit, as_of).await?;
+
+        if self.primary_api_source.is_empty() {
+            self.primary_api_source = response.source.clone();
         }
-        
-        price_data.reverse(); // FMP returns newest first, we want oldest first
-        Ok(price_data)
-    }
-}
+        self.api_endpoints_used.push(response.url);
+        self.raw_api_responses.insert(format!("{}_price_data", symbol), response.raw);
 
-/// Price data point for market analysis
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PriceData {
-    /// Trading date
-    pub date: DateTime<Utc>,
-    /// Opening price
-    pub open: f64,
-    /// High price
-    pub high: f64,
-    /// Low price
-    pub low: f64,
-    /// Closing price
-    pub close: f64,
-    /// Trading volume
-    pub volume: u64,
+        Ok(response.data)
+    }
 }
 
 impl Default for MarketRegimeCalculator {
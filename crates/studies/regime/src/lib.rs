@@ -3,6 +3,9 @@
 
 pub mod models;
 pub mod calculator;
+pub mod volatility;
+pub mod breadth;
+pub mod sectors;
 pub mod market_regime_models;
 pub mod market_regime_storage;
 
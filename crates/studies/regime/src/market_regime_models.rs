@@ -19,11 +19,21 @@ pub struct MarketRegimeRecord {
     pub spy_20d_change: Option<f64>,
     pub spy_50d_change: Option<f64>,
     pub vix: Option<f64>,
+    #[serde(default)]
+    pub vix9d: Option<f64>,
+    #[serde(default)]
+    pub vix3m: Option<f64>,
     pub market_breadth: Option<f64>,
     
     // Market Volatility Analysis
     pub market_volatility: f64,
     pub volatility_percentile: f64,
+    #[serde(default)]
+    pub vix_percentile: Option<f64>,
+    #[serde(default)]
+    pub vix_term_structure: Option<f64>,
+    #[serde(default)]
+    pub volatility_trend: Option<String>,
     
     // Market Trend Analysis
     pub short_term_trend: String,
@@ -38,6 +48,14 @@ pub struct MarketRegimeRecord {
     pub unchanged_stocks: Option<i32>,
     pub new_highs: Option<i32>,
     pub new_lows: Option<i32>,
+    #[serde(default)]
+    pub percent_above_sma50: Option<f64>,
+    #[serde(default)]
+    pub percent_above_sma200: Option<f64>,
+    #[serde(default)]
+    pub advance_decline_line: Option<i64>,
+    #[serde(default)]
+    pub mcclellan_oscillator: Option<f64>,
     
     // Sector Analysis
     pub technology_performance: Option<f64>,
@@ -45,6 +63,14 @@ pub struct MarketRegimeRecord {
     pub financial_performance: Option<f64>,
     pub energy_performance: Option<f64>,
     pub consumer_performance: Option<f64>,
+    #[serde(default)]
+    pub leading_sector: Option<String>,
+    #[serde(default)]
+    pub lagging_sector: Option<String>,
+    #[serde(default)]
+    pub sector_rotation: Option<String>,
+    #[serde(default)]
+    pub sector_performance: Option<serde_json::Value>,
     
     // Market Sentiment Indicators
     pub fear_greed_index: Option<i32>,
@@ -89,11 +115,16 @@ impl MarketRegimeRecord {
             spy_20d_change: result.market_context.spy_20d_change,
             spy_50d_change: result.market_context.spy_50d_change,
             vix: result.market_context.vix,
+            vix9d: result.market_context.vix9d,
+            vix3m: result.market_context.vix3m,
             market_breadth: result.market_context.market_breadth,
             
             // Market Volatility Analysis
             market_volatility: result.volatility_analysis.market_volatility,
             volatility_percentile: result.volatility_analysis.volatility_percentile,
+            vix_percentile: result.volatility_analysis.vix_percentile,
+            vix_term_structure: result.volatility_analysis.vix_term_structure,
+            volatility_trend: Some(format!("{:?}", result.volatility_analysis.volatility_trend)),
             
             // Market Trend Analysis
             short_term_trend: format!("{:?}", result.trend_analysis.short_term),
@@ -108,6 +139,10 @@ impl MarketRegimeRecord {
             unchanged_stocks: result.breadth_analysis.unchanged_stocks,
             new_highs: result.breadth_analysis.new_highs,
             new_lows: result.breadth_analysis.new_lows,
+            percent_above_sma50: result.breadth_analysis.percent_above_sma50,
+            percent_above_sma200: result.breadth_analysis.percent_above_sma200,
+            advance_decline_line: result.breadth_analysis.advance_decline_line,
+            mcclellan_oscillator: result.breadth_analysis.mcclellan_oscillator,
             
             // Sector Analysis
             technology_performance: result.sector_analysis.technology_performance,
@@ -115,6 +150,10 @@ impl MarketRegimeRecord {
             financial_performance: result.sector_analysis.financial_performance,
             energy_performance: result.sector_analysis.energy_performance,
             consumer_performance: result.sector_analysis.consumer_performance,
+            leading_sector: result.sector_analysis.leading_sector.clone(),
+            lagging_sector: result.sector_analysis.lagging_sector.clone(),
+            sector_rotation: result.sector_analysis.rotation.as_ref().map(|r| format!("{:?}", r)),
+            sector_performance: serde_json::to_value(&result.sector_analysis.sectors).ok(),
             
             // Market Sentiment Indicators
             fear_greed_index: result.sentiment_indicators.fear_greed_index,
@@ -161,11 +200,16 @@ pub fn create_market_regime_record_with_tracking(
         spy_20d_change: result.market_context.spy_20d_change,
         spy_50d_change: result.market_context.spy_50d_change,
         vix: result.market_context.vix,
+        vix9d: result.market_context.vix9d,
+        vix3m: result.market_context.vix3m,
         market_breadth: result.market_context.market_breadth,
         
         // Market Volatility Analysis
         market_volatility: result.volatility_analysis.market_volatility,
         volatility_percentile: result.volatility_analysis.volatility_percentile,
+        vix_percentile: result.volatility_analysis.vix_percentile,
+        vix_term_structure: result.volatility_analysis.vix_term_structure,
+        volatility_trend: Some(format!("{:?}", result.volatility_analysis.volatility_trend)),
         
         // Market Trend Analysis
         short_term_trend: format!("{:?}", result.trend_analysis.short_term),
@@ -180,6 +224,10 @@ pub fn create_market_regime_record_with_tracking(
         unchanged_stocks: result.breadth_analysis.unchanged_stocks,
         new_highs: result.breadth_analysis.new_highs,
         new_lows: result.breadth_analysis.new_lows,
+        percent_above_sma50: result.breadth_analysis.percent_above_sma50,
+        percent_above_sma200: result.breadth_analysis.percent_above_sma200,
+        advance_decline_line: result.breadth_analysis.advance_decline_line,
+        mcclellan_oscillator: result.breadth_analysis.mcclellan_oscillator,
         
         // Sector Analysis
         technology_performance: result.sector_analysis.technology_performance,
@@ -187,6 +235,10 @@ pub fn create_market_regime_record_with_tracking(
         financial_performance: result.sector_analysis.financial_performance,
         energy_performance: result.sector_analysis.energy_performance,
         consumer_performance: result.sector_analysis.consumer_performance,
+        leading_sector: result.sector_analysis.leading_sector.clone(),
+        lagging_sector: result.sector_analysis.lagging_sector.clone(),
+        sector_rotation: result.sector_analysis.rotation.as_ref().map(|r| format!("{:?}", r)),
+        sector_performance: serde_json::to_value(&result.sector_analysis.sectors).ok(),
         
         // Market Sentiment Indicators
         fear_greed_index: result.sentiment_indicators.fear_greed_index,
@@ -241,6 +293,10 @@ mod tests {
         assert_eq!(record.regime_confidence, 0.85);
         assert_eq!(record.spy_price, Some(450.0));
         assert_eq!(record.vix, Some(18.5));
+        assert_eq!(record.vix3m, Some(20.0));
+        assert_eq!(record.volatility_trend.as_deref(), Some("Stable"));
+        assert_eq!(record.sector_rotation.as_deref(), Some("RiskOn"));
+        assert_eq!(record.sector_performance.unwrap()[0]["symbol"], "XLE");
     }
 
     fn create_mock_market_regime_result() -> MarketRegimeResult {
@@ -252,11 +308,17 @@ mod tests {
                 spy_20d_change: Some(0.05),
                 spy_50d_change: Some(0.08),
                 vix: Some(18.5),
+                vix9d: Some(17.0),
+                vix3m: Some(20.0),
                 market_breadth: Some(0.65),
             },
             volatility_analysis: VolatilityAnalysis {
                 market_volatility: 2.5,
                 volatility_percentile: 70.0,
+                volatility_trend: VolatilityTrend::Stable,
+                vix_percentile: Some(55.0),
+                vix_term_structure: Some(0.925),
+                vix_short_term_structure: Some(17.0 / 18.5),
             },
             trend_analysis: MarketTrendAnalysis {
                 short_term: TrendDirection::Bullish,
@@ -272,13 +334,30 @@ mod tests {
                 new_highs: Some(150),
                 new_lows: Some(75),
                 breadth_ratio: Some(1.67),
+                percent_above_sma50: Some(62.0),
+                percent_above_sma200: Some(58.5),
+                advance_decline_line: Some(1_250),
+                mcclellan_oscillator: Some(35.2),
             },
             sector_analysis: SectorAnalysis {
                 technology_performance: Some(0.05),
                 healthcare_performance: Some(0.02),
                 financial_performance: Some(-0.01),
-            energy_performance: Some(0.08),
-            consumer_performance: Some(0.03),
+                energy_performance: Some(0.08),
+                consumer_performance: Some(0.03),
+                leading_sector: Some("Energy".to_string()),
+                lagging_sector: Some("Financial".to_string()),
+                sectors: vec![SectorPerformance {
+                    symbol: "XLE".to_string(),
+                    sector: "Energy".to_string(),
+                    return_1w: Some(0.01),
+                    return_1m: Some(0.04),
+                    return_3m: Some(0.12),
+                    relative_strength: Some(0.08),
+                }],
+                leading_sectors: vec!["Energy".to_string()],
+                lagging_sectors: vec!["Financial".to_string()],
+                rotation: Some(SectorRotation::RiskOn),
             },
             sentiment_indicators: SentimentIndicators {
                 fear_greed_index: Some(65),
@@ -290,6 +369,8 @@ mod tests {
                 risk_level: RiskLevel::Medium,
                 risk_score: 45.0,
                 max_drawdown_risk: 15.0,
+                correlation_risk: 40.0,
+                liquidity_risk: 30.0,
             },
             timestamp: Utc::now(),
             metadata: AnalysisMetadata {
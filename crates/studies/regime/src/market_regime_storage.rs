@@ -2,6 +2,8 @@
 // Uses time-series approach to track market regime changes over time
 
 use buenotea_core::Result;
+use chrono::NaiveDate;
+use std::collections::BTreeSet;
 use buenotea_infrastructure::DatabaseClient;
 use buenotea_infrastructure::market_regime_models::{CreateMarketRegimeRecord, MarketRegimeInsert, MarketRegimeRecord};
 use tracing::info;
@@ -70,6 +72,57 @@ impl MarketRegimeStorage {
         Ok(id)
     }
 
+    /// Store multiple market regime records in batch (time-series history)
+    pub async fn store_multiple_records(&self, records: &[CreateMarketRegimeRecord]) -> Result<Vec<i64>> {
+        info!("Storing {} market regime records in batch", records.len());
+
+        let url = format!("{}/rest/v1/market_regime_history", self.db_client.config().supabase_url);
+
+        let insert_records: Vec<MarketRegimeInsert> = records
+            .iter()
+            .map(|record| record.clone().into())
+            .collect();
+
+        let response = self.db_client
+            .http_client()
+            .post(&url)
+            .header("apikey", &self.db_client.config().supabase_api_key)
+            .header("Authorization", format!("Bearer {}", self.db_client.config().supabase_api_key))
+            .header("Content-Type", "application/json")
+            .header("Prefer", "return=representation")
+            .json(&insert_records)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(buenotea_core::Error::DatabaseError(format!(
+                "Failed to store market regime records: {} - {}",
+                status,
+                error_text
+            )));
+        }
+
+        // Parse the response to get the IDs
+        let response_data: serde_json::Value = response.json().await?;
+        let ids: Vec<i64> = response_data
+            .as_array()
+            .ok_or_else(|| buenotea_core::Error::DatabaseError("Expected array response".to_string()))?
+            .iter()
+            .filter_map(|record| record["id"].as_i64())
+            .collect();
+
+        info!("Successfully stored {} market regime records", ids.len());
+        Ok(ids)
+    }
+
+    /// Dates between `from` and `to` that already have a market regime record
+    pub async fn get_analysis_dates(&self, from: NaiveDate, to: NaiveDate) -> Result<BTreeSet<NaiveDate>> {
+        info!("Getting stored market regime dates from {} to {}", from, to);
+        self.db_client.analysis_dates("market_regime_history", None, from, to).await
+    }
+
     /// Get the latest market regime record (from market_regime view)
     pub async fn get_latest_market_regime(&self) -> Result<Option<MarketRegimeRecord>> {
         info!("Getting latest market regime record");
@@ -112,7 +112,13 @@ pub struct MarketContext {
     pub spy_50d_change: Option<f64>,
     /// VIX (Volatility Index)
     pub vix: Option<f64>,
-    /// Market breadth (advancing vs declining stocks)
+    /// 9-day VIX
+    #[serde(default)]
+    pub vix9d: Option<f64>,
+    /// 3-month VIX
+    #[serde(default)]
+    pub vix3m: Option<f64>,
+    /// Market breadth (fraction of the breadth universe advancing)
     pub market_breadth: Option<f64>,
 }
 
@@ -125,6 +131,15 @@ pub struct VolatilityAnalysis {
     pub volatility_percentile: f64,
     /// Volatility trend
     pub volatility_trend: VolatilityTrend,
+    /// Percentile of the current VIX over the past year (0-100)
+    #[serde(default)]
+    pub vix_percentile: Option<f64>,
+    /// VIX / VIX3M (above 1.0 the term structure is inverted)
+    #[serde(default)]
+    pub vix_term_structure: Option<f64>,
+    /// VIX9D / VIX (above 1.0 near-term stress exceeds the 30-day outlook)
+    #[serde(default)]
+    pub vix_short_term_structure: Option<f64>,
 }
 
 /// Volatility trend
@@ -187,6 +202,57 @@ pub struct MarketBreadthAnalysis {
     pub new_lows: Option<i32>,
     /// Breadth ratio (advancing / declining)
     pub breadth_ratio: Option<f64>,
+    /// Percentage of stocks above their 50-day SMA
+    #[serde(default)]
+    pub percent_above_sma50: Option<f64>,
+    /// Percentage of stocks above their 200-day SMA
+    #[serde(default)]
+    pub percent_above_sma200: Option<f64>,
+    /// Cumulative net advances over the lookback window
+    #[serde(default)]
+    pub advance_decline_line: Option<i64>,
+    /// McClellan oscillator (19-day minus 39-day EMA of ratio-adjusted net advances)
+    #[serde(default)]
+    pub mcclellan_oscillator: Option<f64>,
+}
+
+impl MarketBreadthAnalysis {
+    /// Breadth with no constituent data
+    pub fn unavailable() -> Self {
+        Self {
+            advancing_stocks: None,
+            declining_stocks: None,
+            unchanged_stocks: None,
+            new_highs: None,
+            new_lows: None,
+            breadth_ratio: None,
+            percent_above_sma50: None,
+            percent_above_sma200: None,
+            advance_decline_line: None,
+            mcclellan_oscillator: None,
+        }
+    }
+
+    /// Fraction of stocks advancing on the latest session (0-1)
+    pub fn advancing_fraction(&self) -> Option<f64> {
+        let advancing = self.advancing_stocks?;
+        let total = advancing + self.declining_stocks? + self.unchanged_stocks.unwrap_or(0);
+        (total > 0).then(|| advancing as f64 / total as f64)
+    }
+
+    /// Breadth confirms an advance: most stocks rising, or broad participation with positive momentum
+    pub fn confirms_advance(&self) -> bool {
+        self.breadth_ratio.is_some_and(|ratio| ratio > 1.5)
+            || (self.percent_above_sma200.is_some_and(|pct| pct > 60.0)
+                && self.mcclellan_oscillator.is_some_and(|osc| osc > 0.0))
+    }
+
+    /// Breadth confirms a decline: most stocks falling, or narrow participation with negative momentum
+    pub fn confirms_decline(&self) -> bool {
+        self.breadth_ratio.is_some_and(|ratio| ratio < 0.7)
+            || (self.percent_above_sma200.is_some_and(|pct| pct < 40.0)
+                && self.mcclellan_oscillator.is_some_and(|osc| osc < 0.0))
+    }
 }
 
 /// Sector analysis
@@ -206,6 +272,46 @@ pub struct SectorAnalysis {
     pub leading_sector: Option<String>,
     /// Lagging sector
     pub lagging_sector: Option<String>,
+    /// Returns and relative strength of every sector ETF
+    #[serde(default)]
+    pub sectors: Vec<SectorPerformance>,
+    /// Strongest sectors by relative strength, best first
+    #[serde(default)]
+    pub leading_sectors: Vec<String>,
+    /// Weakest sectors by relative strength, worst first
+    #[serde(default)]
+    pub lagging_sectors: Vec<String>,
+    /// Whether cyclical or defensive sectors are leading
+    #[serde(default)]
+    pub rotation: Option<SectorRotation>,
+}
+
+/// Performance of one sector ETF
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SectorPerformance {
+    /// ETF symbol (e.g. XLK)
+    pub symbol: String,
+    /// Sector name
+    pub sector: String,
+    /// 1-week return
+    pub return_1w: Option<f64>,
+    /// 1-month return
+    pub return_1m: Option<f64>,
+    /// 3-month return
+    pub return_3m: Option<f64>,
+    /// 3-month relative strength vs SPY
+    pub relative_strength: Option<f64>,
+}
+
+/// Sector rotation classification
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum SectorRotation {
+    /// Cyclical sectors outperforming defensives
+    RiskOn,
+    /// Defensive sectors outperforming cyclicals
+    Defensive,
+    /// No clear leadership
+    Mixed,
 }
 
 /// Market sentiment indicators
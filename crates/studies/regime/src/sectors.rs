@@ -0,0 +1,147 @@
+// Sector ETF helpers
+// Returns, relative strength vs SPY, leader/lagger ranking and rotation for the SPDR sector funds
+
+use super::models::{SectorPerformance, SectorRotation};
+
+/// Whether a sector tends to lead in risk-on or defensive markets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SectorStyle {
+    Cyclical,
+    Defensive,
+}
+
+/// The eleven SPDR sector ETFs: symbol, sector name and style
+pub const SECTOR_ETFS: [(&str, &str, SectorStyle); 11] = [
+    ("XLK", "Technology", SectorStyle::Cyclical),
+    ("XLF", "Financials", SectorStyle::Cyclical),
+    ("XLV", "Health Care", SectorStyle::Defensive),
+    ("XLE", "Energy", SectorStyle::Cyclical),
+    ("XLY", "Consumer Discretionary", SectorStyle::Cyclical),
+    ("XLP", "Consumer Staples", SectorStyle::Defensive),
+    ("XLI", "Industrials", SectorStyle::Cyclical),
+    ("XLB", "Materials", SectorStyle::Cyclical),
+    ("XLU", "Utilities", SectorStyle::Defensive),
+    ("XLRE", "Real Estate", SectorStyle::Defensive),
+    ("XLC", "Communication Services", SectorStyle::Cyclical),
+];
+
+/// Return horizons in trading sessions
+pub const ONE_WEEK_SESSIONS: usize = 5;
+pub const ONE_MONTH_SESSIONS: usize = 21;
+pub const THREE_MONTH_SESSIONS: usize = 63;
+
+/// Sectors reported as leaders and laggers
+const RANKED_SECTORS: usize = 3;
+
+/// Spread between cyclical and defensive relative strength that counts as rotation
+const ROTATION_THRESHOLD: f64 = 0.02;
+
+/// Return over the last `sessions` sessions (closes oldest first)
+pub fn period_return(closes: &[f64], sessions: usize) -> Option<f64> {
+    if closes.len() <= sessions {
+        return None;
+    }
+    let start = closes[closes.len() - 1 - sessions];
+    let end = closes[closes.len() - 1];
+    (start > 0.0).then(|| end / start - 1.0)
+}
+
+/// Performance of one sector ETF against SPY
+///
+/// Relative strength is the three-month price ratio versus SPY minus one, so
+/// 0.05 means the sector beat the market by 5% over the period.
+pub fn sector_performance(symbol: &str, sector: &str, closes: &[f64], spy_closes: &[f64]) -> SectorPerformance {
+    let return_3m = period_return(closes, THREE_MONTH_SESSIONS);
+    let relative_strength = match (return_3m, period_return(spy_closes, THREE_MONTH_SESSIONS)) {
+        (Some(sector), Some(spy)) if spy > -1.0 => Some((1.0 + sector) / (1.0 + spy) - 1.0),
+        _ => None,
+    };
+
+    SectorPerformance {
+        symbol: symbol.to_string(),
+        sector: sector.to_string(),
+        return_1w: period_return(closes, ONE_WEEK_SESSIONS),
+        return_1m: period_return(closes, ONE_MONTH_SESSIONS),
+        return_3m,
+        relative_strength,
+    }
+}
+
+/// Sectors with relative strength, strongest first
+pub fn rank_sectors(sectors: &[SectorPerformance]) -> Vec<&SectorPerformance> {
+    let mut ranked: Vec<&SectorPerformance> = sectors.iter().filter(|s| s.relative_strength.is_some()).collect();
+    ranked.sort_by(|a, b| b.relative_strength.partial_cmp(&a.relative_strength).unwrap_or(std::cmp::Ordering::Equal));
+    ranked
+}
+
+/// Top and bottom sectors by relative strength
+pub fn leaders_and_laggers(sectors: &[SectorPerformance]) -> (Vec<String>, Vec<String>) {
+    let ranked = rank_sectors(sectors);
+    let count = RANKED_SECTORS.min(ranked.len() / 2);
+    let leaders = ranked.iter().take(count).map(|s| s.sector.clone()).collect();
+    let laggers = ranked.iter().rev().take(count).map(|s| s.sector.clone()).collect();
+    (leaders, laggers)
+}
+
+/// Classify rotation from the average relative strength of cyclical versus defensive sectors
+pub fn classify_rotation(sectors: &[SectorPerformance]) -> Option<SectorRotation> {
+    let average = |style: SectorStyle| {
+        let values: Vec<f64> = sectors
+            .iter()
+            .filter(|s| style_of(&s.symbol) == Some(style))
+            .filter_map(|s| s.relative_strength)
+            .collect();
+        (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+    };
+
+    let spread = average(SectorStyle::Cyclical)? - average(SectorStyle::Defensive)?;
+    Some(if spread > ROTATION_THRESHOLD {
+        SectorRotation::RiskOn
+    } else if spread < -ROTATION_THRESHOLD {
+        SectorRotation::Defensive
+    } else {
+        SectorRotation::Mixed
+    })
+}
+
+fn style_of(symbol: &str) -> Option<SectorStyle> {
+    SECTOR_ETFS.iter().find(|(etf, _, _)| *etf == symbol).map(|(_, _, style)| *style)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Closes growing by a constant daily rate
+    fn closes(daily: f64) -> Vec<f64> {
+        (0..=THREE_MONTH_SESSIONS).map(|i| 100.0 * (1.0 + daily).powi(i as i32)).collect()
+    }
+
+    #[test]
+    fn test_period_returns_and_relative_strength() {
+        let spy = closes(0.0);
+        let sector = sector_performance("XLK", "Technology", &closes(0.001), &spy);
+        assert!((sector.return_1w.unwrap() - (1.001f64.powi(5) - 1.0)).abs() < 1e-12);
+        assert!(sector.relative_strength.unwrap() > 0.06);
+        assert_eq!(period_return(&spy[..10], ONE_MONTH_SESSIONS), None);
+    }
+
+    #[test]
+    fn test_ranking_and_rotation() {
+        let spy = closes(0.0);
+        let sectors: Vec<SectorPerformance> = SECTOR_ETFS
+            .iter()
+            .map(|(symbol, sector, style)| {
+                let daily = if *style == SectorStyle::Defensive { 0.002 } else { -0.001 };
+                sector_performance(symbol, sector, &closes(daily), &spy)
+            })
+            .collect();
+
+        let (leaders, laggers) = leaders_and_laggers(&sectors);
+        assert_eq!(leaders.len(), 3);
+        assert!(leaders.contains(&"Utilities".to_string()));
+        assert!(!laggers.contains(&"Utilities".to_string()));
+        assert_eq!(classify_rotation(&sectors), Some(SectorRotation::Defensive));
+        assert_eq!(classify_rotation(&[]), None);
+    }
+}
@@ -0,0 +1,86 @@
+// VIX analysis helpers
+// Percentile, trend and term structure computed from the actual VIX series
+
+use super::models::VolatilityTrend;
+
+/// Sessions of VIX history used for the percentile (about one year)
+pub const VIX_HISTORY_SESSIONS: usize = 252;
+
+/// Minimum sessions before a percentile is reported
+const MIN_PERCENTILE_SESSIONS: usize = 60;
+
+/// Short and long averages compared for the VIX trend
+const TREND_SHORT_WINDOW: usize = 5;
+const TREND_LONG_WINDOW: usize = 20;
+
+/// Change of the short average versus the long average that counts as a trend
+const TREND_THRESHOLD: f64 = 0.10;
+
+/// Percentile rank (0-100) of `value` within `history`
+///
+/// Returns `None` when there is not enough history to be meaningful.
+pub fn vix_percentile(history: &[f64], value: f64) -> Option<f64> {
+    if history.len() < MIN_PERCENTILE_SESSIONS {
+        return None;
+    }
+    let below = history.iter().filter(|v| **v < value).count() as f64;
+    let equal = history.iter().filter(|v| **v == value).count() as f64;
+    Some((below + 0.5 * equal) / history.len() as f64 * 100.0)
+}
+
+/// Direction of the VIX from its 5-day versus 20-day average (closes oldest first)
+pub fn vix_trend(closes: &[f64]) -> Option<VolatilityTrend> {
+    if closes.len() < TREND_LONG_WINDOW {
+        return None;
+    }
+    let average = |window: usize| closes[closes.len() - window..].iter().sum::<f64>() / window as f64;
+    let long = average(TREND_LONG_WINDOW);
+    if long <= 0.0 {
+        return None;
+    }
+
+    let change = average(TREND_SHORT_WINDOW) / long - 1.0;
+    Some(if change > TREND_THRESHOLD {
+        VolatilityTrend::Increasing
+    } else if change < -TREND_THRESHOLD {
+        VolatilityTrend::Decreasing
+    } else {
+        VolatilityTrend::Stable
+    })
+}
+
+/// Ratio of a nearer-dated to a further-dated volatility index
+///
+/// Above 1.0 the curve is inverted (backwardation), which usually signals stress.
+pub fn term_structure(near: Option<f64>, far: Option<f64>) -> Option<f64> {
+    match (near, far) {
+        (Some(near), Some(far)) if far > 0.0 => Some(near / far),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vix_percentile() {
+        let history: Vec<f64> = (1..=100).map(|v| v as f64).collect();
+        assert_eq!(vix_percentile(&history, 0.5), Some(0.0));
+        assert_eq!(vix_percentile(&history, 150.0), Some(100.0));
+        assert_eq!(vix_percentile(&history, 50.0), Some(49.5));
+        assert_eq!(vix_percentile(&history[..10], 5.0), None);
+    }
+
+    #[test]
+    fn test_vix_trend_and_term_structure() {
+        let mut closes = vec![15.0; 20];
+        assert_eq!(vix_trend(&closes), Some(VolatilityTrend::Stable));
+        closes.extend([25.0; 5]);
+        assert_eq!(vix_trend(&closes), Some(VolatilityTrend::Increasing));
+        assert_eq!(vix_trend(&closes[..10]), None);
+
+        assert_eq!(term_structure(Some(30.0), Some(25.0)), Some(1.2));
+        assert_eq!(term_structure(Some(30.0), None), None);
+    }
+}
@@ -2,7 +2,7 @@
 // This shows how to calculate buy/sell signals for stocks
 
 use buenotea_sentiment::{QSSCalculator, QSSResult};
-use buenotea_sentiment::models::TradingSignal;
+use buenotea_sentiment::models::{DataQualityFlag, TradingSignal};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -191,7 +191,7 @@ async fn programmatic_example() -> Result<(), Box<dyn std::error::Error>> {
     }
     
     // Check for warning flags
-    if result.flags.contains(&"earnings_window".to_string()) {
+    if result.flags.contains(&DataQualityFlag::EarningsWindow) {
         println!("⚠️  Earnings announcement approaching - be cautious");
     }
     
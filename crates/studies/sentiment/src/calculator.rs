@@ -3,11 +3,33 @@
 
 use buenotea_core::Result;
 use super::models::*;
-use chrono::Utc;
+use super::data_quality::{
+    confidence_penalty, estimate_dispersion, in_earnings_window, is_stale, DataQualityFlags, MAX_ESTIMATE_DISPERSION,
+    MIN_ANALYST_COUNT, MIN_VALID_COMPONENTS,
+};
+use super::earnings_surprise::{earnings_surprise, pead_score, SURPRISE_QUARTERS};
+use super::estimate_revisions::{
+    earnings_revisions_score, estimate_history_from_env, estimate_revisions, parse_fmp_estimate_snapshot,
+    recommendation_score, EstimateHistory, SNAPSHOT_LOOKBACK_DAYS,
+};
+use super::sector_benchmark::{SectorClassification, MARKET_BENCHMARK};
+use super::llm_news::{llm_news_score, LlmNewsScorer};
+use super::news_sentiment::{news_sentiment, parse_finnhub_news, NEWS_LOOKBACK_DAYS};
+use super::options_flow::{aggregate_flow, is_flow_conflict, options_flow_score, options_provider_from_env, OptionsChainProvider};
+use super::short_volume::{short_interest_score, short_volume_metrics, FinraShortVolume, ZSCORE_SESSIONS};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
 use std::time::Instant;
 use tracing::{info, warn};
 use reqwest::Client;
 use serde_json::Value;
+use buenotea_core::market_data::point_in_time::{weekdays_between, ANNUAL_FILING_LAG_DAYS};
+use buenotea_core::market_data::{
+    as_of_timestamp, earnings_calendar_from_env, is_historical, DailyBar, EarningsCalendar, EarningsCalendarProvider,
+    MarketDataProvider,
+    ProviderChain, ProviderResponse, StatementPeriod,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
 
 // Helper structs for detailed data collection
 #[derive(Debug, Default)]
@@ -24,6 +46,7 @@ struct PriceData {
     return_15d: Option<f64>,
     return_30d: Option<f64>,
     volume_ratio: Option<f64>,
+    latest_date: Option<NaiveDate>,
 }
 
 #[derive(Debug, Default)]
@@ -35,40 +58,155 @@ struct EarningsData {
     previous_revenue: Option<i64>,
     revenue_change_percentage: Option<f64>,
     analyst_count: Option<i32>,
+    estimate_dispersion: Option<f64>,
 }
 
+/// FMP analyst endpoints, fetched once per run and shared by the snapshot and detailed data
+#[derive(Debug, Default)]
+struct FmpConsensus {
+    /// `analyst-estimates`, live runs only (the endpoint has no history)
+    estimates: Option<Value>,
+    /// `analyst-stock-recommendations`, filtered by publication date where used
+    recommendations: Option<Value>,
+}
+
+/// Calendar days searched for the chain preceding `as_of` (covers long weekends)
+const PREVIOUS_CHAIN_LOOKBACK_DAYS: i64 = 4;
+
 /// Main QSS calculator that combines multiple data sources
 pub struct QSSCalculator {
     client: Client,
+    provider: Arc<dyn MarketDataProvider>,
+    classifications: HashMap<String, SectorClassification>,
+    short_volume: Arc<FinraShortVolume>,
+    options: Box<dyn OptionsChainProvider>,
+    news_scorer: Option<LlmNewsScorer>,
+    earnings_calendar: Option<Arc<dyn EarningsCalendarProvider>>,
+    estimate_history: Option<Arc<dyn EstimateHistory>>,
 }
 
 impl QSSCalculator {
-    /// Create a new QSS calculator
+    /// Create a new QSS calculator using the providers configured in the environment
     pub fn new() -> Self {
+        Self::with_provider(Arc::new(ProviderChain::from_env()))
+    }
+
+    /// Create a QSS calculator backed by a specific market data provider
+    pub fn with_provider(provider: Arc<dyn MarketDataProvider>) -> Self {
         Self {
             client: Client::new(),
+            provider,
+            classifications: HashMap::new(),
+            short_volume: Arc::new(FinraShortVolume::from_env()),
+            options: options_provider_from_env(),
+            news_scorer: LlmNewsScorer::from_env().ok(),
+            earnings_calendar: earnings_calendar_from_env(),
+            estimate_history: estimate_history_from_env(),
         }
     }
 
+    /// Store and read consensus estimate snapshots in a specific store instead of the database
+    pub fn with_estimate_history(mut self, estimate_history: Arc<dyn EstimateHistory>) -> Self {
+        self.estimate_history = Some(estimate_history);
+        self
+    }
+
+    /// Read report dates from a specific earnings calendar instead of the environment
+    pub fn with_earnings_calendar(mut self, earnings_calendar: Arc<dyn EarningsCalendarProvider>) -> Self {
+        self.earnings_calendar = Some(earnings_calendar);
+        self
+    }
+
+    /// Score headlines with a specific language model client instead of the environment
+    pub fn with_news_scorer(mut self, news_scorer: LlmNewsScorer) -> Self {
+        self.news_scorer = Some(news_scorer);
+        self
+    }
+
+    /// Read options chains from a specific provider instead of the environment
+    pub fn with_options_provider(mut self, options: Box<dyn OptionsChainProvider>) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Read FINRA short volume from a specific (possibly shared) reader instead of the environment
+    pub fn with_short_volume(mut self, short_volume: Arc<FinraShortVolume>) -> Self {
+        self.short_volume = short_volume;
+        self
+    }
+
+    /// Use known sector and industry classifications (e.g. from the invite list)
+    ///
+    /// Symbols missing from the map are classified from the provider's company profile.
+    pub fn with_classifications(mut self, classifications: HashMap<String, SectorClassification>) -> Self {
+        self.classifications = classifications;
+        self
+    }
+
     /// Calculate the complete QSS score for a given symbol
     pub async fn calculate_qss(&self, symbol: &str) -> Result<QSSResult> {
+        self.calculate(symbol, Utc::now().date_naive(), Utc::now()).await
+    }
+
+    /// Calculate the QSS score using only data available on `as_of`
+    ///
+    /// Sources that only expose current values (consensus estimates, quotes)
+    /// are skipped for past dates and reported as `ReportedEpsOnly`.
+    pub async fn calculate_qss_as_of(&self, symbol: &str, as_of: NaiveDate) -> Result<QSSResult> {
+        self.calculate(symbol, as_of, as_of_timestamp(as_of)).await
+    }
+
+    async fn calculate(&self, symbol: &str, as_of: NaiveDate, timestamp: DateTime<Utc>) -> Result<QSSResult> {
         let start_time = Instant::now();
-        info!("Starting QSS calculation for {}", symbol);
+        info!("Starting QSS calculation for {} as of {}", symbol, as_of);
 
         // Simplified calculation - using mock data for now
         // In a real implementation, this would fetch data from APIs
         
-        // Step 1: Calculate earnings revisions (40% weight)
-        let earnings_revisions = self.calculate_earnings_revisions(symbol).await?;
+        let mut flags = DataQualityFlags::default();
+        let consensus = self.fetch_fmp_consensus(symbol, as_of, &mut flags).await;
+
+        // Step 1: Calculate earnings revisions (30% weight)
+        let (earnings_revisions, revisions) = self.calculate_earnings_revisions(symbol, as_of, &consensus, &mut flags).await?;
         
-        // Step 2: Calculate relative strength (30% weight)
-        let relative_strength = self.calculate_relative_strength(symbol).await?;
+        // Step 2: Calculate relative strength (20% weight)
+        let relative_strength = self.calculate_relative_strength(symbol, as_of, &mut flags).await?;
         
-        // Step 3: Calculate short interest (20% weight)
-        let short_interest = self.calculate_short_interest(symbol).await?;
+        // Step 3: Calculate short interest (15% weight)
+        let (short_interest, short_volume) = self.calculate_short_interest(symbol, as_of, &mut flags).await?;
         
         // Step 4: Calculate options flow (10% weight)
-        let options_flow = self.calculate_options_flow(symbol).await?;
+        let (options_flow, flow) = self.calculate_options_flow(symbol, as_of, &mut flags).await?;
+
+        // Step 5: Calculate news sentiment (15% weight)
+        let mut news = self.collect_news_sentiment(symbol, as_of, timestamp, &mut flags).await;
+        let news_llm_sentiment = match (&self.news_scorer, news.as_mut()) {
+            // The model has read later news, so past dates keep the lexicon score
+            (Some(scorer), Some(news)) if !is_historical(as_of) => match scorer.assess(symbol, &mut news.articles).await {
+                Ok(()) => llm_news_score(&news.articles),
+                Err(e) => {
+                    warn!("❌ Headline assessment rejected for {}: {}, using lexicon score", symbol, e);
+                    flags.note_error(&e);
+                    flags.raise(DataQualityFlag::NewsModelRejected);
+                    None
+                }
+            },
+            _ => None,
+        };
+        let news_score = news_llm_sentiment.or_else(|| news.as_ref().and_then(|n| n.score));
+        if news_score.is_none() {
+            flags.raise(DataQualityFlag::NoNewsData);
+        }
+
+        // Step 6: Calculate post-earnings-announcement drift (10% weight)
+        let calendar = self.collect_earnings_calendar(symbol, &mut flags).await;
+        let earnings_surprise = match &calendar {
+            Some(calendar) => self.collect_earnings_surprise(symbol, calendar, as_of, &mut flags).await,
+            None => None,
+        };
+        if earnings_surprise.as_ref().is_none_or(|surprise| surprise.sue.is_none()) {
+            flags.raise(DataQualityFlag::NoSurpriseData);
+        }
 
         // Calculate final QSS score
         let components = QSSComponents {
@@ -76,19 +214,20 @@ impl QSSCalculator {
             relative_strength,
             short_interest,
             options_flow,
+            news: news_score.unwrap_or(0.0),
+            pead: earnings_surprise.as_ref().map(pead_score).unwrap_or(0.0),
         };
 
         let qss_score = components.calculate_qss();
         let trading_signal = self.generate_trading_signal(qss_score);
-        let confidence_score = self.calculate_confidence_score(&components);
 
         let computation_time = start_time.elapsed().as_millis() as u64;
 
         // Collect additional detailed data
-        let (rsi_data, price_data, earnings_data) = self.collect_detailed_data(symbol).await?;
+        let (rsi_data, price_data, earnings_data) = self.collect_detailed_data(symbol, as_of, &consensus, &mut flags).await?;
         
         // Collect market benchmark data
-        let (market_benchmark, sector_benchmark) = self.collect_market_benchmark_data(symbol).await?;
+        let (market_benchmark, sector_benchmark, sector_etf) = self.collect_market_benchmark_data(symbol, as_of).await?;
         
         // Calculate relative performance
         let relative_to_market = if let (Some(stock_return), Some(market_return)) = (price_data.return_15d, market_benchmark) {
@@ -103,6 +242,9 @@ impl QSSCalculator {
             None
         };
         
+        // Live consensus range, else the latest stored snapshot's (point-in-time runs)
+        let estimate_dispersion = earnings_data.estimate_dispersion.or(revisions.as_ref().and_then(|r| r.dispersion));
+
         // Create metadata with detailed data
         let meta = QSSMeta {
             computation_time_ms: computation_time,
@@ -123,17 +265,53 @@ impl QSSCalculator {
             previous_revenue_estimate: earnings_data.previous_revenue,
             revenue_change_percentage: earnings_data.revenue_change_percentage,
             analyst_count: earnings_data.analyst_count,
+            estimate_dispersion,
+            estimate_revisions: revisions,
             market_benchmark_return: market_benchmark,
             sector_benchmark_return: sector_benchmark,
+            sector_benchmark: Some(sector_etf.to_string()),
             relative_to_market,
             relative_to_sector,
             volume_ratio: price_data.volume_ratio,
+            short_volume_ratio: short_volume.as_ref().map(|m| m.short_volume_ratio),
+            short_volume_zscore: short_volume.as_ref().and_then(|m| m.short_volume_zscore),
+            days_to_cover: short_volume.as_ref().and_then(|m| m.days_to_cover),
+            put_call_ratio: flow.as_ref().filter(|f| f.call_volume > 0).map(|f| f.put_call_ratio()),
+            options_premium_skew: flow.as_ref().map(|f| f.premium_skew()),
+            unusual_options_contracts: flow.as_ref().map(|f| f.unusual_contracts),
+            news_sentiment: news.as_ref().and_then(|n| n.score),
+            news_article_count: news.as_ref().map(|n| n.article_count),
+            news_articles: news.map(|n| n.articles).unwrap_or_default(),
+            news_llm_sentiment,
+            earnings_surprise,
         };
 
-        let flags = vec![
-            "no_short_data".to_string(),
-            "no_options_data".to_string(),
-        ];
+        if short_volume.is_none() {
+            flags.raise(DataQualityFlag::NoShortData);
+        }
+        if flow.is_none() {
+            flags.raise(DataQualityFlag::NoOptionsData);
+        } else if is_flow_conflict(components.options_flow, components.relative_strength) {
+            flags.raise(DataQualityFlag::FlowConflict);
+        }
+        if price_data.latest_date.is_some_and(|latest| is_stale(latest, as_of)) {
+            flags.raise(DataQualityFlag::StaleData);
+        }
+        if earnings_data.analyst_count.is_some_and(|count| count < MIN_ANALYST_COUNT) {
+            flags.raise(DataQualityFlag::NoEstimates);
+        }
+        if estimate_dispersion.is_some_and(|dispersion| dispersion > MAX_ESTIMATE_DISPERSION) {
+            flags.raise(DataQualityFlag::LowConfidence);
+        }
+        if calendar.and_then(|c| c.nearest_report_date(as_of)).is_some_and(|date| in_earnings_window(date, as_of)) {
+            flags.raise(DataQualityFlag::EarningsWindow);
+        }
+        if components.valid_components_count() < MIN_VALID_COMPONENTS {
+            flags.raise(DataQualityFlag::InsufficientData);
+        }
+
+        let flags = flags.into_vec();
+        let confidence_score = self.calculate_confidence_score(&components, &flags);
 
         Ok(QSSResult {
             symbol: symbol.to_string(),
@@ -142,12 +320,108 @@ impl QSSCalculator {
             components,
             flags,
             confidence_score,
-            timestamp: Utc::now(),
+            timestamp,
             meta,
         })
     }
 
-    async fn calculate_earnings_revisions(&self, symbol: &str) -> Result<f64> {
+    /// Score earnings revisions from stored consensus snapshots, else from a single estimates call
+    async fn calculate_earnings_revisions(
+        &self,
+        symbol: &str,
+        as_of: NaiveDate,
+        consensus: &FmpConsensus,
+        flags: &mut DataQualityFlags,
+    ) -> Result<(f64, Option<EstimateRevisions>)> {
+        if let Some(revisions) = self.collect_estimate_revisions(symbol, as_of, consensus).await {
+            let score = earnings_revisions_score(&revisions);
+            info!("✅ Estimate revisions for {} from {} snapshots: {:.4}", symbol, revisions.snapshots, score);
+            return Ok((score, Some(revisions)));
+        }
+
+        let score = self.calculate_single_call_revision(symbol, as_of, flags).await?;
+        Ok((score, None))
+    }
+
+    /// Record today's consensus snapshot and measure revisions from the stored history
+    ///
+    /// Past dates only read snapshots stored on or before `as_of`.
+    async fn collect_estimate_revisions(
+        &self,
+        symbol: &str,
+        as_of: NaiveDate,
+        consensus: &FmpConsensus,
+    ) -> Option<EstimateRevisions> {
+        let history = self.estimate_history.as_ref()?;
+
+        if let Some(snapshot) = estimate_snapshot(consensus, as_of) {
+            if let Err(e) = history.record(symbol, &snapshot).await {
+                warn!("❌ Failed to store estimate snapshot for {}: {}", symbol, e);
+            }
+        }
+
+        let from = as_of - Duration::days(SNAPSHOT_LOOKBACK_DAYS);
+        match history.snapshots(symbol, from, as_of).await {
+            Ok(snapshots) => estimate_revisions(&snapshots, as_of),
+            Err(e) => {
+                warn!("❌ Failed to read estimate snapshots for {}: {}", symbol, e);
+                None
+            }
+        }
+    }
+
+    /// FMP analyst estimates (live only) and recommendations, empty without an FMP key
+    async fn fetch_fmp_consensus(&self, symbol: &str, as_of: NaiveDate, flags: &mut DataQualityFlags) -> FmpConsensus {
+        let Ok(fmp_key) = std::env::var("FMP_API_KEY") else {
+            return FmpConsensus::default();
+        };
+
+        let estimates = if is_historical(as_of) {
+            None
+        } else {
+            match self.client
+                .get(format!("https://financialmodelingprep.com/api/v3/analyst-estimates/{}?apikey={}", symbol, fmp_key))
+                .send()
+                .await
+                .and_then(|r| r.error_for_status())
+            {
+                Ok(response) => response.json().await.ok(),
+                Err(e) => {
+                    warn!("❌ Consensus estimates unavailable for {}: {}", symbol, e);
+                    flags.note_http_error(&e);
+                    None
+                }
+            }
+        };
+
+        let recommendations = match self.client
+            .get(format!("https://financialmodelingprep.com/api/v3/analyst-stock-recommendations/{}?apikey={}", symbol, fmp_key))
+            .send()
+            .await
+        {
+            Ok(response) => response.json().await.ok(),
+            Err(_) => None,
+        };
+
+        FmpConsensus { estimates, recommendations }
+    }
+
+    /// Revision between the two latest values of a single estimates call (no snapshot history yet)
+    async fn calculate_single_call_revision(&self, symbol: &str, as_of: NaiveDate, flags: &mut DataQualityFlags) -> Result<f64> {
+        // Consensus estimates are only available as of today; use reported EPS for past dates
+        if is_historical(as_of) {
+            flags.raise(DataQualityFlag::ReportedEpsOnly);
+            return match self.get_reported_eps_revision(symbol, as_of).await {
+                Ok(score) => Ok(score),
+                Err(e) => {
+                    warn!("⚠️  No reported EPS for {} as of {}: {}, using 0.0", symbol, as_of, e);
+                    flags.note_error(&e);
+                    flags.raise(DataQualityFlag::NoEstimates);
+                    Ok(0.0)
+                }
+            };
+        }
+
         info!("Fetching earnings estimates for {}", symbol);
         
         // Try Alpha Vantage first
@@ -159,6 +433,7 @@ impl QSSCalculator {
                 }
                 Err(e) => {
                     warn!("❌ Alpha Vantage failed for {}: {}", symbol, e);
+                    flags.note_error(&e);
                 }
             }
         } else {
@@ -174,6 +449,7 @@ impl QSSCalculator {
                 }
                 Err(e) => {
                     warn!("❌ FMP failed for {}: {}", symbol, e);
+                    flags.note_error(&e);
                 }
             }
         } else {
@@ -181,9 +457,28 @@ impl QSSCalculator {
         }
 
         warn!("⚠️  No earnings data available for {}, using 0.0", symbol);
+        flags.raise(DataQualityFlag::NoEstimates);
         Ok(0.0)
     }
 
+    /// EPS change between the two latest annual reports filed on or before `as_of`
+    async fn get_reported_eps_revision(&self, symbol: &str, as_of: NaiveDate) -> Result<f64> {
+        let response = self.provider.income_statements_as_of(symbol, StatementPeriod::Annual, 2, as_of).await?;
+        let eps: Vec<f64> = response.data.iter().filter_map(|s| s.eps_diluted.or(s.eps)).collect();
+
+        match eps[..] {
+            [current, previous] if previous != 0.0 => {
+                let revision = (current - previous) / previous.abs();
+                info!("✅ Reported EPS revision for {} as of {}: {:.4}", symbol, as_of, revision);
+                Ok(revision.clamp(-1.0, 1.0))
+            }
+            _ => Err(buenotea_core::Error::InvalidResponseFormat(
+                response.source,
+                format!("Fewer than two annual EPS values filed by {}", as_of),
+            )),
+        }
+    }
+
     async fn get_alpha_vantage_earnings(&self, symbol: &str, api_key: &str) -> Result<f64> {
         let url = format!(
             "https://www.alphavantage.co/query?function=EARNINGS_ESTIMATES&symbol={}&apikey={}",
@@ -309,113 +604,89 @@ impl QSSCalculator {
         ))
     }
 
-    async fn calculate_relative_strength(&self, symbol: &str) -> Result<f64> {
+    async fn calculate_relative_strength(&self, symbol: &str, as_of: NaiveDate, flags: &mut DataQualityFlags) -> Result<f64> {
         info!("Calculating relative strength for {}", symbol);
         
-        // Try FMP for historical price data
-        if let Ok(fmp_key) = std::env::var("FMP_API_KEY") {
-            match self.get_fmp_relative_strength(symbol, &fmp_key).await {
-                Ok(score) => {
-                    info!("✅ Got relative strength from FMP for {}: {}", symbol, score);
-                    return Ok(score);
-                }
-                Err(e) => {
-                    warn!("❌ FMP relative strength failed for {}: {}", symbol, e);
-                }
+        match self.get_price_relative_strength(symbol, as_of).await {
+            Ok(score) => {
+                info!("✅ Got relative strength for {}: {}", symbol, score);
+                return Ok(score);
+            }
+            Err(e) => {
+                warn!("❌ Price-based relative strength failed for {}: {}", symbol, e);
+                flags.note_error(&e);
             }
-        } else {
-            warn!("⚠️  No FMP API key found");
         }
 
         warn!("⚠️  No relative strength data available for {}, using 0.0", symbol);
+        flags.raise(DataQualityFlag::NoPriceData);
         Ok(0.0)
     }
 
-    async fn get_fmp_relative_strength(&self, symbol: &str, api_key: &str) -> Result<f64> {
-        // Try multiple approaches for relative strength
-        let endpoints = vec![
-            format!("https://financialmodelingprep.com/api/v3/historical-price-full/{}?apikey={}", symbol, api_key),
-            format!("https://financialmodelingprep.com/api/v3/quote/{}?apikey={}", symbol, api_key),
-            format!("https://financialmodelingprep.com/api/v3/technical-indicator/{}?period=14&type=rsi&apikey={}", symbol, api_key),
-        ];
+    async fn get_price_relative_strength(&self, symbol: &str, as_of: NaiveDate) -> Result<f64> {
+        // Daily bars give RSI plus momentum
+        if let Ok(response) = self.fetch_bars_newest_first(symbol, 30, as_of).await {
+            info!("📊 Found {} historical price records from {}", response.data.len(), response.source);
+            let closes: Vec<f64> = response.data.iter().map(|b| b.close).collect();
 
-        for (i, url) in endpoints.iter().enumerate() {
-            info!("🔍 Calling FMP price API {}: {}", i+1, url);
-            
-            match self.client.get(url).send().await {
-                Ok(response) => {
-                    let json: Value = response.json().await?;
-                    info!("📊 FMP price response keys: {:?}", json.as_object().map(|o| o.keys().collect::<Vec<_>>()));
-                    
-                    // Try historical data for RSI calculation
-                    if let Some(historical_data) = json.get("historical") {
-                        if let Some(prices_array) = historical_data.as_array() {
-                            info!("📊 Found {} historical price records", prices_array.len());
-                            
-                            if prices_array.len() >= 15 {
-                                let mut closes: Vec<f64> = Vec::new();
-                                
-                                // Extract closing prices (most recent first)
-                                for price_data in prices_array.iter().take(30) { // Get more data for better RSI
-                                    if let Some(close) = price_data.get("close").and_then(|v| v.as_f64()) {
-                                        closes.push(close);
-                                    }
-                                }
-                                
-                                if closes.len() >= 14 {
-                                    let rsi = self.calculate_rsi(&closes);
-                                    info!("📈 Calculated RSI: {}", rsi);
-                                    
-                                    // Also calculate price momentum
-                                    let price_change_14d = (closes[0] - closes[13]) / closes[13] * 100.0;
-                                    let price_change_30d = if closes.len() > 29 { (closes[0] - closes[29]) / closes[29] * 100.0 } else { price_change_14d };
-                                    
-                                    info!("📊 14-day price change: {:.2}%, 30-day: {:.2}%", price_change_14d, price_change_30d);
-                                    
-                                    // Combine RSI and price momentum for sentiment
-                                    let rsi_sentiment = if rsi > 70.0 {
-                                        -((rsi - 70.0) / 30.0).min(1.0)
-                                    } else if rsi < 30.0 {
-                                        (30.0 - rsi) / 30.0
-                                    } else {
-                                        0.0
-                                    };
-                                    
-                                    let momentum_sentiment = (price_change_14d / 10.0).max(-1.0).min(1.0); // Normalize to [-1,1]
-                                    
-                                    // Weighted combination: 70% RSI, 30% momentum
-                                    let combined_sentiment = 0.7 * rsi_sentiment + 0.3 * momentum_sentiment;
-                                    
-                                    info!("✅ Combined relative strength: {} (RSI: {}, Momentum: {})", combined_sentiment, rsi_sentiment, momentum_sentiment);
-                                    return Ok(combined_sentiment);
-                                }
-                            }
-                        }
-                    }
-                    
-                    // Try direct quote data for current price and change
-                    if let Some(quote_array) = json.as_array() {
-                        if let Some(quote) = quote_array.first() {
-                            if let Some(price_change_percent) = quote.get("changesPercentage").and_then(|v| v.as_f64()) {
-                                info!("📊 Found direct price change: {}%", price_change_percent);
-                                let sentiment = (price_change_percent / 10.0).max(-1.0).min(1.0);
-                                return Ok(sentiment);
-                            }
-                        }
-                    }
-                }
-                Err(e) => {
-                    warn!("❌ FMP price API call {} failed: {}", i+1, e);
-                }
+            if closes.len() >= 15 {
+                let rsi = self.calculate_rsi(&closes);
+                info!("📈 Calculated RSI: {}", rsi);
+                
+                // Also calculate price momentum
+                let price_change_14d = (closes[0] - closes[13]) / closes[13] * 100.0;
+                let price_change_30d = if closes.len() > 29 { (closes[0] - closes[29]) / closes[29] * 100.0 } else { price_change_14d };
+                
+                info!("📊 14-day price change: {:.2}%, 30-day: {:.2}%", price_change_14d, price_change_30d);
+                
+                // Combine RSI and price momentum for sentiment
+                let rsi_sentiment = if rsi > 70.0 {
+                    -((rsi - 70.0) / 30.0).min(1.0)
+                } else if rsi < 30.0 {
+                    (30.0 - rsi) / 30.0
+                } else {
+                    0.0
+                };
+                
+                let momentum_sentiment = (price_change_14d / 10.0).max(-1.0).min(1.0); // Normalize to [-1,1]
+                
+                // Weighted combination: 70% RSI, 30% momentum
+                let combined_sentiment = 0.7 * rsi_sentiment + 0.3 * momentum_sentiment;
+                
+                info!("✅ Combined relative strength: {} (RSI: {}, Momentum: {})", combined_sentiment, rsi_sentiment, momentum_sentiment);
+                return Ok(combined_sentiment);
             }
         }
 
+        // Fall back to the daily change on the latest quote (only meaningful today)
+        if is_historical(as_of) {
+            return Err(buenotea_core::Error::InvalidResponseFormat(
+                self.provider.name().to_string(),
+                format!("Not enough price history for {} as of {}", symbol, as_of),
+            ));
+        }
+        let quote = self.provider.quote(symbol).await?;
+        if let Some(price_change_percent) = quote.data.change_percent {
+            info!("📊 Found direct price change: {}%", price_change_percent);
+            let sentiment = (price_change_percent / 10.0).max(-1.0).min(1.0);
+            return Ok(sentiment);
+        }
+
         Err(buenotea_core::Error::InvalidResponseFormat(
-            "FMP".to_string(),
-            "No price data found in any FMP endpoint".to_string(),
+            quote.source,
+            "No price data found for relative strength".to_string(),
         ))
     }
 
+    /// Fetch the most recent daily bars on or before `as_of`, newest first
+    async fn fetch_bars_newest_first(&self, symbol: &str, sessions: usize, as_of: NaiveDate) -> Result<ProviderResponse<Vec<DailyBar>>> {
+        let response = self.provider.daily_bars_as_of(symbol, sessions, as_of).await?;
+        Ok(response.map(|mut bars| {
+            bars.reverse();
+            bars
+        }))
+    }
+
     fn calculate_rsi(&self, prices: &[f64]) -> f64 {
         if prices.len() < 14 {
             return 50.0; // Default neutral RSI
@@ -448,135 +719,133 @@ impl QSSCalculator {
         100.0 - (100.0 / (1.0 + rs))
     }
 
-    async fn calculate_short_interest(&self, symbol: &str) -> Result<f64> {
-        info!("Trying to get short interest data for {}", symbol);
-        
-        // Try Finnhub for basic sentiment data
-        if let Ok(finnhub_key) = std::env::var("FINNHUB_API_KEY") {
-            match self.get_finnhub_sentiment(symbol, &finnhub_key).await {
-                Ok(score) => {
-                    info!("✅ Got sentiment data from Finnhub for {}: {}", symbol, score);
-                    return Ok(score);
-                }
-                Err(e) => {
-                    warn!("❌ Finnhub sentiment failed for {}: {}", symbol, e);
-                }
+    /// Score short selling pressure from FINRA daily short volume
+    async fn calculate_short_interest(
+        &self,
+        symbol: &str,
+        as_of: NaiveDate,
+        flags: &mut DataQualityFlags,
+    ) -> Result<(f64, Option<ShortVolumeMetrics>)> {
+        info!("Loading FINRA short volume for {} from {}", symbol, self.short_volume.location(as_of));
+
+        let history = match self.short_volume.history(symbol, ZSCORE_SESSIONS + 1, as_of).await {
+            Ok(history) => history,
+            Err(e) => {
+                warn!("❌ FINRA short volume failed for {}: {}", symbol, e);
+                flags.note_error(&e);
+                Vec::new()
             }
-        } else {
-            warn!("⚠️  No Finnhub API key found");
-        }
+        };
 
-        warn!("⚠️  No short interest data available for {}, using 0.0", symbol);
-        Ok(0.0)
-    }
+        // Consolidated volume for days to cover; FINRA totals only cover off-exchange trades
+        let average_volume = match self.fetch_bars_newest_first(symbol, ZSCORE_SESSIONS, as_of).await {
+            Ok(response) if !response.data.is_empty() => {
+                Some(response.data.iter().map(|b| b.volume as f64).sum::<f64>() / response.data.len() as f64)
+            }
+            _ => None,
+        };
 
-    async fn calculate_options_flow(&self, symbol: &str) -> Result<f64> {
-        info!("Trying to get options flow data for {}", symbol);
-        
-        // Try to get analyst recommendations as a proxy for options sentiment
-        if let Ok(fmp_key) = std::env::var("FMP_API_KEY") {
-            match self.get_fmp_analyst_recommendations(symbol, &fmp_key).await {
-                Ok(score) => {
-                    info!("✅ Got analyst recommendations from FMP for {}: {}", symbol, score);
-                    return Ok(score);
-                }
-                Err(e) => {
-                    warn!("❌ FMP analyst recommendations failed for {}: {}", symbol, e);
-                }
+        match short_volume_metrics(&history, average_volume) {
+            Some(metrics) => {
+                let score = short_interest_score(&metrics);
+                info!(
+                    "✅ Short volume for {}: ratio {:.2}, z-score {:?}, days to cover {:?} → {:.2}",
+                    symbol, metrics.short_volume_ratio, metrics.short_volume_zscore, metrics.days_to_cover, score
+                );
+                Ok((score, Some(metrics)))
+            }
+            None => {
+                warn!("⚠️  No short volume data available for {}, using 0.0", symbol);
+                Ok((0.0, None))
             }
         }
-
-        warn!("⚠️  No options flow data available for {}, using 0.0", symbol);
-        Ok(0.0)
     }
 
-    async fn get_finnhub_sentiment(&self, symbol: &str, api_key: &str) -> Result<f64> {
-        // Try Finnhub news sentiment
-        let url = format!(
-            "https://finnhub.io/api/v1/company-news?symbol={}&from={}&to={}&token={}",
-            symbol,
-            chrono::Utc::now().date_naive() - chrono::Duration::days(7),
-            chrono::Utc::now().date_naive(),
-            api_key
-        );
-
-        info!("🔍 Calling Finnhub news API: {}", url);
-        let response = self.client.get(&url).send().await?;
-        let json: Value = response.json().await?;
+    /// Score options positioning from the chain on `as_of` and the previous session's chain
+    async fn calculate_options_flow(
+        &self,
+        symbol: &str,
+        as_of: NaiveDate,
+        flags: &mut DataQualityFlags,
+    ) -> Result<(f64, Option<OptionsFlowData>)> {
+        info!("Fetching {} options chain for {}", self.options.name(), symbol);
+
+        let chain = match self.options.options_chain(symbol, as_of).await {
+            Ok(chain) => chain,
+            Err(e) => {
+                warn!("⚠️  No options chain for {}: {}, using 0.0", symbol, e);
+                flags.note_error(&e);
+                return Ok((0.0, None));
+            }
+        };
 
-        if let Some(news_array) = json.as_array() {
-            info!("📰 Found {} news articles", news_array.len());
-            
-            if !news_array.is_empty() {
-                // Simple sentiment analysis based on news sentiment scores
-                let mut total_sentiment = 0.0;
-                let mut valid_articles = 0;
-                
-                for article in news_array.iter().take(10) { // Limit to recent articles
-                    if let Some(sentiment) = article.get("sentiment").and_then(|v| v.as_f64()) {
-                        total_sentiment += sentiment;
-                        valid_articles += 1;
-                    }
-                }
-                
-                if valid_articles > 0 {
-                    let avg_sentiment = total_sentiment / valid_articles as f64;
-                    // Convert Finnhub sentiment (-1 to 1) to our scale
-                    info!("📊 Average news sentiment: {} from {} articles", avg_sentiment, valid_articles);
-                    return Ok(avg_sentiment);
-                }
+        // Open interest settles overnight, so compare with the latest earlier chain
+        let mut previous = None;
+        for days_back in 1..=PREVIOUS_CHAIN_LOOKBACK_DAYS {
+            if let Ok(chain) = self.options.options_chain(symbol, as_of - Duration::days(days_back)).await {
+                previous = Some(chain);
+                break;
             }
         }
 
-        Err(buenotea_core::Error::InvalidResponseFormat(
-            "Finnhub".to_string(),
-            "No news sentiment data found".to_string(),
-        ))
+        match aggregate_flow(&chain, previous.as_ref()) {
+            Some(flow) => {
+                let score = options_flow_score(&flow);
+                info!(
+                    "✅ Options flow for {}: put/call {:.2}, premium skew {:.2}, {} unusual contracts → {:.2}",
+                    symbol,
+                    flow.put_call_ratio(),
+                    flow.premium_skew(),
+                    flow.unusual_contracts,
+                    score
+                );
+                Ok((score, Some(flow)))
+            }
+            None => {
+                warn!("⚠️  Options chain for {} has no volume, using 0.0", symbol);
+                Ok((0.0, None))
+            }
+        }
     }
 
-    async fn get_fmp_analyst_recommendations(&self, symbol: &str, api_key: &str) -> Result<f64> {
+    /// Score company news from the week before `as_of` with the local lexicon
+    async fn collect_news_sentiment(
+        &self,
+        symbol: &str,
+        as_of: NaiveDate,
+        timestamp: DateTime<Utc>,
+        flags: &mut DataQualityFlags,
+    ) -> Option<NewsSentiment> {
+        let Ok(finnhub_key) = std::env::var("FINNHUB_API_KEY") else {
+            warn!("⚠️  No Finnhub API key found, skipping news sentiment");
+            return None;
+        };
+
         let url = format!(
-            "https://financialmodelingprep.com/api/v3/analyst-stock-recommendations/{}?apikey={}",
-            symbol, api_key
+            "https://finnhub.io/api/v1/company-news?symbol={}&from={}&to={}&token={}",
+            symbol,
+            as_of - Duration::days(NEWS_LOOKBACK_DAYS),
+            as_of,
+            finnhub_key
         );
-
-        info!("🔍 Calling FMP analyst recommendations API: {}", url);
-        let response = self.client.get(&url).send().await?;
-        let json: Value = response.json().await?;
-
-        if let Some(recommendations_array) = json.as_array() {
-            info!("📊 Found {} analyst recommendations", recommendations_array.len());
-            
-            if !recommendations_array.is_empty() {
-                let mut total_score = 0.0;
-                let mut count = 0;
-                
-                for rec in recommendations_array.iter().take(5) { // Recent recommendations
-                    if let Some(rating) = rec.get("rating").and_then(|v| v.as_str()) {
-                        let score = match rating.to_uppercase().as_str() {
-                            "STRONG_BUY" | "BUY" => 1.0,
-                            "HOLD" => 0.0,
-                            "SELL" | "STRONG_SELL" => -1.0,
-                            _ => 0.0,
-                        };
-                        total_score += score;
-                        count += 1;
-                        info!("📊 Recommendation: {} -> score: {}", rating, score);
-                    }
-                }
-                
-                if count > 0 {
-                    let avg_score = total_score / count as f64;
-                    info!("📊 Average analyst score: {} from {} recommendations", avg_score, count);
-                    return Ok(avg_score);
+        let json: Value = match self.client.get(&url).send().await.and_then(|r| r.error_for_status()) {
+            Ok(response) => match response.json().await {
+                Ok(json) => json,
+                Err(e) => {
+                    warn!("❌ Finnhub news response for {} could not be parsed: {}", symbol, e);
+                    return None;
                 }
+            },
+            Err(e) => {
+                warn!("❌ Finnhub news request failed for {}: {}", symbol, e);
+                flags.note_http_error(&e);
+                return None;
             }
-        }
+        };
 
-        Err(buenotea_core::Error::InvalidResponseFormat(
-            "FMP".to_string(),
-            "No analyst recommendations found".to_string(),
-        ))
+        let news = news_sentiment(&parse_finnhub_news(&json), timestamp);
+        info!("📰 Scored {} news articles for {}: {:?}", news.article_count, symbol, news.score);
+        Some(news)
     }
 
     fn generate_trading_signal(&self, qss_score: f64) -> TradingSignal {
@@ -589,92 +858,130 @@ impl QSSCalculator {
         }
     }
 
-    fn calculate_confidence_score(&self, components: &QSSComponents) -> f64 {
-        // Simple confidence based on number of valid components
+    /// Confidence from the number of valid components, less the penalty of the raised flags
+    fn calculate_confidence_score(&self, components: &QSSComponents, flags: &[DataQualityFlag]) -> f64 {
         let valid_count = components.valid_components_count();
-        match valid_count {
-            4 => 1.0,  // All components available
-            3 => 0.8,  // Most components available
-            2 => 0.6,  // Half components available
-            1 => 0.4,  // Few components available
+        let base: f64 = match valid_count {
+            6 => 1.0,  // All components available
+            5 => 0.9,  // Nearly all components available
+            4 => 0.8,  // Most components available
+            3 => 0.6,  // Half components available
+            2 => 0.4,  // Few components available
             _ => 0.2,  // Very few components available
+        };
+        (base - confidence_penalty(flags)).clamp(0.0, 1.0)
+    }
+
+    /// Past and scheduled earnings reports, `None` without a calendar or when it fails
+    async fn collect_earnings_calendar(&self, symbol: &str, flags: &mut DataQualityFlags) -> Option<EarningsCalendar> {
+        let calendar = self.earnings_calendar.as_ref()?;
+        match calendar.earnings_calendar(symbol).await {
+            Ok(response) => Some(response.data),
+            Err(e) => {
+                warn!("❌ {} earnings calendar failed for {}: {}", calendar.name(), symbol, e);
+                flags.note_error(&e);
+                None
+            }
+        }
+    }
+
+    /// Surprise history of the last eight quarters with each report's reaction against the market
+    async fn collect_earnings_surprise(
+        &self,
+        symbol: &str,
+        calendar: &EarningsCalendar,
+        as_of: NaiveDate,
+        flags: &mut DataQualityFlags,
+    ) -> Option<EarningsSurpriseData> {
+        let oldest = calendar.surprise_history(as_of, SURPRISE_QUARTERS).last()?.date;
+        // Plus the close before the oldest report
+        let sessions = weekdays_between(oldest, as_of) + 2;
+
+        let bars = self.fetch_reaction_bars(symbol, sessions, as_of, flags).await;
+        let benchmark = self.fetch_reaction_bars(MARKET_BENCHMARK, sessions, as_of, flags).await;
+
+        let surprise = earnings_surprise(calendar, &bars, &benchmark, as_of)?;
+        info!("✅ Earnings surprise for {}: SUE {:?} over {} quarters", symbol, surprise.sue, surprise.quarters.len());
+        Some(surprise)
+    }
+
+    /// Daily bars for measuring report reactions, oldest first; empty when the provider fails
+    async fn fetch_reaction_bars(
+        &self,
+        symbol: &str,
+        sessions: usize,
+        as_of: NaiveDate,
+        flags: &mut DataQualityFlags,
+    ) -> Vec<DailyBar> {
+        match self.provider.daily_bars_as_of(symbol, sessions, as_of).await {
+            Ok(response) => response.data,
+            Err(e) => {
+                warn!("❌ No bars for {} earnings reactions: {}", symbol, e);
+                flags.note_error(&e);
+                Vec::new()
+            }
         }
     }
 
-    async fn collect_detailed_data(&self, symbol: &str) -> Result<(RSIData, PriceData, EarningsData)> {
+    async fn collect_detailed_data(
+        &self,
+        symbol: &str,
+        as_of: NaiveDate,
+        consensus: &FmpConsensus,
+        flags: &mut DataQualityFlags,
+    ) -> Result<(RSIData, PriceData, EarningsData)> {
         info!("🔍 Collecting detailed data for {}", symbol);
+        let historical = is_historical(as_of);
         
         let mut rsi_data = RSIData::default();
         let mut price_data = PriceData::default();
         let mut earnings_data = EarningsData::default();
 
-        // Collect RSI and price data from FMP
-        if let Ok(fmp_key) = std::env::var("FMP_API_KEY") {
-            if let Ok(response) = self.client
-                .get(&format!("https://financialmodelingprep.com/api/v3/historical-price-full/{}?apikey={}", symbol, fmp_key))
-                .send()
-                .await
-            {
-                if let Ok(json) = response.json::<Value>().await {
-                    if let Some(historical_data) = json.get("historical") {
-                        if let Some(prices_array) = historical_data.as_array() {
-                            if prices_array.len() >= 30 {
-                                let mut closes: Vec<f64> = Vec::new();
-                                for price_entry in prices_array.iter().take(30) {
-                                    if let Some(close) = price_entry.get("close").and_then(|v| v.as_f64()) {
-                                        closes.push(close);
-                                    }
-                                }
-                                
-                                if closes.len() >= 14 {
-                                    rsi_data.rsi = Some(self.calculate_rsi(&closes));
-                                    rsi_data.source = Some("FMP".to_string());
-                                    
-                                    // Extract price and volume data
-                                    price_data.current_price = closes.first().copied();
-                                    price_data.price_15d_ago = closes.get(14).copied();
-                                    price_data.price_30d_ago = closes.get(29).copied();
-                                    
-                                    if let (Some(current), Some(price_15d)) = (price_data.current_price, price_data.price_15d_ago) {
-                                        price_data.return_15d = Some((current - price_15d) / price_15d);
-                                    }
-                                    
-                                    if let (Some(current), Some(price_30d)) = (price_data.current_price, price_data.price_30d_ago) {
-                                        price_data.return_30d = Some((current - price_30d) / price_30d);
-                                    }
-                                    
-                                    // Calculate volume ratio (current volume vs 30-day average)
-                                    let mut volumes: Vec<i64> = Vec::new();
-                                    for price_entry in prices_array.iter().take(30) {
-                                        if let Some(volume) = price_entry.get("volume").and_then(|v| v.as_i64()) {
-                                            volumes.push(volume);
-                                        }
-                                    }
-                                    
-                                    if volumes.len() >= 30 {
-                                        let current_volume = volumes[0];
-                                        let avg_volume_30d = volumes.iter().sum::<i64>() as f64 / volumes.len() as f64;
-                                        price_data.volume_ratio = Some(current_volume as f64 / avg_volume_30d);
-                                        
-                                        info!("✅ Collected volume data: current volume={}, 30d avg={:.0}, ratio={:.2}", 
-                                              current_volume, avg_volume_30d, price_data.volume_ratio.unwrap_or(0.0));
-                                    }
-                                    
-                                    info!("✅ Collected price data: current=${:.2}, 15d return={:.2}%", 
-                                          price_data.current_price.unwrap_or(0.0),
-                                          price_data.return_15d.unwrap_or(0.0) * 100.0);
-                                }
-                            }
-                        }
-                    }
+        // Collect RSI and price data
+        if let Ok(response) = self.fetch_bars_newest_first(symbol, 30, as_of).await.inspect_err(|e| flags.note_error(e)) {
+            let bars = &response.data;
+            price_data.latest_date = bars.first().map(|b| b.date);
+            if bars.len() >= 30 {
+                let closes: Vec<f64> = bars.iter().map(|b| b.close).collect();
+                
+                rsi_data.rsi = Some(self.calculate_rsi(&closes));
+                rsi_data.source = Some(response.source.clone());
+                
+                // Extract price and volume data
+                price_data.current_price = closes.first().copied();
+                price_data.price_15d_ago = closes.get(14).copied();
+                price_data.price_30d_ago = closes.get(29).copied();
+                
+                if let (Some(current), Some(price_15d)) = (price_data.current_price, price_data.price_15d_ago) {
+                    price_data.return_15d = Some((current - price_15d) / price_15d);
+                }
+                
+                if let (Some(current), Some(price_30d)) = (price_data.current_price, price_data.price_30d_ago) {
+                    price_data.return_30d = Some((current - price_30d) / price_30d);
+                }
+                
+                // Calculate volume ratio (current volume vs 30-day average)
+                let current_volume = bars[0].volume;
+                let avg_volume_30d = bars.iter().map(|b| b.volume as f64).sum::<f64>() / bars.len() as f64;
+                if avg_volume_30d > 0.0 {
+                    price_data.volume_ratio = Some(current_volume as f64 / avg_volume_30d);
+                    
+                    info!("✅ Collected volume data: current volume={}, 30d avg={:.0}, ratio={:.2}", 
+                          current_volume, avg_volume_30d, price_data.volume_ratio.unwrap_or(0.0));
                 }
+                
+                info!("✅ Collected price data: current=${:.2}, 15d return={:.2}%", 
+                      price_data.current_price.unwrap_or(0.0),
+                      price_data.return_15d.unwrap_or(0.0) * 100.0);
             }
         }
 
         // Collect earnings and revenue data from Alpha Vantage
         if let Ok(alpha_key) = std::env::var("ALPHA_VANTAGE_API_KEY") {
-            // Try earnings estimates first
-            if let Ok(response) = self.client
+            // Try earnings estimates first (current consensus only)
+            if historical {
+                info!("Skipping current earnings estimates for {} as of {}", symbol, as_of);
+            } else if let Ok(response) = self.client
                 .get(&format!("https://www.alphavantage.co/query?function=EARNINGS_ESTIMATES&symbol={}&apikey={}", symbol, alpha_key))
                 .send()
                 .await
@@ -719,6 +1026,12 @@ impl QSSCalculator {
                     if let Ok(json) = response.json::<Value>().await {
                         if let Some(annual_earnings) = json.get("annualEarnings") {
                             if let Some(earnings_array) = annual_earnings.as_array() {
+                                // Only fiscal years whose annual report was public by `as_of`
+                                let earnings_array: Vec<&Value> = earnings_array
+                                    .iter()
+                                    .filter(|e| json_date(&e["fiscalDateEnding"])
+                                        .is_some_and(|end| end + Duration::days(ANNUAL_FILING_LAG_DAYS) <= as_of))
+                                    .collect();
                                 if earnings_array.len() >= 2 {
                                     let current = earnings_array[0].get("reportedEPS").and_then(|v| v.as_str()).unwrap_or("0");
                                     let previous = earnings_array[1].get("reportedEPS").and_then(|v| v.as_str()).unwrap_or("0");
@@ -740,40 +1053,25 @@ impl QSSCalculator {
                     }
                 }
             }
+        }
 
-            // Try income statement for revenue data
-            if let Ok(response) = self.client
-                .get(&format!("https://www.alphavantage.co/query?function=INCOME_STATEMENT&symbol={}&apikey={}", symbol, alpha_key))
-                .send()
-                .await
-            {
-                if let Ok(json) = response.json::<Value>().await {
-                    if let Some(annual_reports) = json.get("annualReports") {
-                        if let Some(reports_array) = annual_reports.as_array() {
-                            if reports_array.len() >= 2 {
-                                let current = reports_array[0].get("totalRevenue").and_then(|v| v.as_str()).unwrap_or("0");
-                                let previous = reports_array[1].get("totalRevenue").and_then(|v| v.as_str()).unwrap_or("0");
-                                
-                                let current_revenue: i64 = current.parse().unwrap_or(0);
-                                let previous_revenue: i64 = previous.parse().unwrap_or(0);
-                                
-                                if current_revenue != 0 && previous_revenue != 0 {
-                                    earnings_data.current_revenue = Some(current_revenue);
-                                    earnings_data.previous_revenue = Some(previous_revenue);
-                                    earnings_data.revenue_change_percentage = Some((current_revenue as f64 - previous_revenue as f64) / previous_revenue as f64 * 100.0);
-                                    
-                                    info!("✅ Collected revenue data: current=${}, change={:.2}%", 
-                                          current_revenue, earnings_data.revenue_change_percentage.unwrap_or(0.0));
-                                }
-                            }
-                        }
-                    }
+        // Revenue from the two most recent annual income statements
+        if let Ok(response) = self.provider.income_statements_as_of(symbol, StatementPeriod::Annual, 2, as_of).await {
+            let revenues: Vec<i64> = response.data.iter().filter_map(|s| s.revenue).map(|r| r as i64).collect();
+            if let [current, previous] = revenues[..] {
+                if current != 0 && previous != 0 {
+                    earnings_data.current_revenue = Some(current);
+                    earnings_data.previous_revenue = Some(previous);
+                    earnings_data.revenue_change_percentage = Some((current as f64 - previous as f64) / previous as f64 * 100.0);
+                    
+                    info!("✅ Collected {} revenue data: current=${}, change={:.2}%", 
+                          response.source, current, earnings_data.revenue_change_percentage.unwrap_or(0.0));
                 }
             }
         }
 
-        // Try Finnhub for revenue estimates
-        if let Ok(finnhub_key) = std::env::var("FINNHUB_API_KEY") {
+        // Try Finnhub for revenue estimates (current consensus only)
+        if let (Ok(finnhub_key), false) = (std::env::var("FINNHUB_API_KEY"), historical) {
             if let Ok(response) = self.client
                 .get(&format!("https://finnhub.io/api/v1/company-revenue-estimates?symbol={}&token={}", symbol, finnhub_key))
                 .send()
@@ -787,7 +1085,7 @@ impl QSSCalculator {
                                 let previous = estimates_array[1].get("revenue").and_then(|v| v.as_i64()).unwrap_or(0);
                                 
                                 if current != 0 && previous != 0 {
-                                    // Only update if we don't already have revenue data from Alpha Vantage
+                                    // Only update if we don't already have revenue from income statements
                                     if earnings_data.current_revenue.is_none() {
                                         earnings_data.current_revenue = Some(current);
                                         earnings_data.previous_revenue = Some(previous);
@@ -804,73 +1102,35 @@ impl QSSCalculator {
             }
         }
 
-        // Collect analyst recommendations and earnings estimates from FMP
-        if let Ok(fmp_key) = std::env::var("FMP_API_KEY") {
-            // Get analyst recommendations count
-            if let Ok(response) = self.client
-                .get(&format!("https://financialmodelingprep.com/api/v3/analyst-stock-recommendations/{}?apikey={}", symbol, fmp_key))
-                .send()
-                .await
-            {
-                if let Ok(json) = response.json::<Value>().await {
-                    if let Some(recommendations_array) = json.as_array() {
-                        earnings_data.analyst_count = Some(recommendations_array.len() as i32);
-                        info!("✅ Found {} analyst recommendations", recommendations_array.len());
-                    }
-                }
-            }
+        // Analyst recommendation count and current consensus from the shared FMP responses
+        if let Some(recommendations_array) = consensus.recommendations.as_ref().and_then(|json| json.as_array()) {
+            let count = recommendations_published_by(recommendations_array, as_of).count();
+            earnings_data.analyst_count = Some(count as i32);
+            info!("✅ Found {} analyst recommendations", count);
+        }
 
-            // Try FMP earnings estimates if we don't have EPS data yet
-            if earnings_data.current_eps.is_none() {
-                if let Ok(response) = self.client
-                    .get(&format!("https://financialmodelingprep.com/api/v3/analyst-estimates/{}?apikey={}", symbol, fmp_key))
-                    .send()
-                    .await
-                {
-                    if let Ok(json) = response.json::<Value>().await {
-                        if let Some(estimates_array) = json.as_array() {
-                            if estimates_array.len() >= 2 {
-                                let current = estimates_array[0].get("estimatedEps").and_then(|v| v.as_f64()).unwrap_or(0.0);
-                                let previous = estimates_array[1].get("estimatedEps").and_then(|v| v.as_f64()).unwrap_or(0.0);
-                                
-                                if current != 0.0 && previous != 0.0 {
-                                    earnings_data.current_eps = Some(current);
-                                    earnings_data.previous_eps = Some(previous);
-                                    earnings_data.eps_change_percentage = Some((current - previous) / previous.abs() * 100.0);
-                                    
-                                    info!("✅ Collected FMP earnings estimates: current EPS=${:.2}, change={:.2}%", 
-                                          current, earnings_data.eps_change_percentage.unwrap_or(0.0));
-                                }
-                            }
-                        }
-                    }
-                }
+        // FMP estimates (current consensus only): dispersion, and EPS if we don't have it yet
+        if let Some(estimates_array) = consensus.estimates.as_ref().and_then(|json| json.as_array()) {
+            if let Some(next) = estimates_array.first() {
+                let eps = |key: &str| next.get(key).and_then(|v| v.as_f64());
+                earnings_data.estimate_dispersion = match (eps("estimatedEpsHigh"), eps("estimatedEpsLow")) {
+                    (Some(high), Some(low)) => eps("estimatedEpsAvg")
+                        .or_else(|| eps("estimatedEps"))
+                        .and_then(|average| estimate_dispersion(high, low, average)),
+                    _ => None,
+                };
             }
-
-            // Try FMP income statement for revenue if we don't have it yet
-            if earnings_data.current_revenue.is_none() {
-                if let Ok(response) = self.client
-                    .get(&format!("https://financialmodelingprep.com/api/v3/income-statement/{}?apikey={}", symbol, fmp_key))
-                    .send()
-                    .await
-                {
-                    if let Ok(json) = response.json::<Value>().await {
-                        if let Some(income_statements) = json.as_array() {
-                            if income_statements.len() >= 2 {
-                                let current = income_statements[0].get("revenue").and_then(|v| v.as_i64()).unwrap_or(0);
-                                let previous = income_statements[1].get("revenue").and_then(|v| v.as_i64()).unwrap_or(0);
-                                
-                                if current != 0 && previous != 0 {
-                                    earnings_data.current_revenue = Some(current);
-                                    earnings_data.previous_revenue = Some(previous);
-                                    earnings_data.revenue_change_percentage = Some((current as f64 - previous as f64) / previous as f64 * 100.0);
-                                    
-                                    info!("✅ Collected FMP revenue data: current=${}, change={:.2}%", 
-                                          current, earnings_data.revenue_change_percentage.unwrap_or(0.0));
-                                }
-                            }
-                        }
-                    }
+            if earnings_data.current_eps.is_none() && estimates_array.len() >= 2 {
+                let current = estimates_array[0].get("estimatedEps").and_then(|v| v.as_f64()).unwrap_or(0.0);
+                let previous = estimates_array[1].get("estimatedEps").and_then(|v| v.as_f64()).unwrap_or(0.0);
+                
+                if current != 0.0 && previous != 0.0 {
+                    earnings_data.current_eps = Some(current);
+                    earnings_data.previous_eps = Some(previous);
+                    earnings_data.eps_change_percentage = Some((current - previous) / previous.abs() * 100.0);
+                    
+                    info!("✅ Collected FMP earnings estimates: current EPS=${:.2}, change={:.2}%", 
+                          current, earnings_data.eps_change_percentage.unwrap_or(0.0));
                 }
             }
         }
@@ -878,91 +1138,105 @@ impl QSSCalculator {
         Ok((rsi_data, price_data, earnings_data))
     }
 
-    async fn collect_market_benchmark_data(&self, symbol: &str) -> Result<(Option<f64>, Option<f64>)> {
+    async fn collect_market_benchmark_data(
+        &self,
+        symbol: &str,
+        as_of: NaiveDate,
+    ) -> Result<(Option<f64>, Option<f64>, &'static str)> {
         info!("🔍 Collecting market benchmark data for {}", symbol);
         
         let mut market_benchmark = None;
         let mut sector_benchmark = None;
 
-        // Get S&P 500 data for market benchmark using FMP
-        if let Ok(fmp_key) = std::env::var("FMP_API_KEY") {
-            // Get S&P 500 historical data
-            if let Ok(response) = self.client
-                .get(&format!("https://financialmodelingprep.com/api/v3/historical-price-full/SPY?apikey={}", fmp_key))
-                .send()
-                .await
-            {
-                if let Ok(json) = response.json::<Value>().await {
-                    if let Some(historical_data) = json.get("historical") {
-                        if let Some(prices_array) = historical_data.as_array() {
-                            if prices_array.len() >= 30 {
-                                let mut closes: Vec<f64> = Vec::new();
-                                for price_entry in prices_array.iter().take(30) {
-                                    if let Some(close) = price_entry.get("close").and_then(|v| v.as_f64()) {
-                                        closes.push(close);
-                                    }
-                                }
-                                
-                                if closes.len() >= 15 {
-                                    // Calculate 15-day return for S&P 500
-                                    let market_return_15d = (closes[0] - closes[14]) / closes[14];
-                                    market_benchmark = Some(market_return_15d * 100.0); // Convert to percentage
-                                    
-                                    info!("✅ Calculated S&P 500 benchmark return: {:.2}%", market_benchmark.unwrap_or(0.0));
-                                }
-                            }
-                        }
-                    }
-                }
+        // Get S&P 500 data for market benchmark
+        if let Ok(response) = self.fetch_bars_newest_first(MARKET_BENCHMARK, 30, as_of).await {
+            let closes: Vec<f64> = response.data.iter().map(|b| b.close).collect();
+            if closes.len() >= 15 {
+                // Calculate 15-day return for S&P 500
+                let market_return_15d = (closes[0] - closes[14]) / closes[14];
+                market_benchmark = Some(market_return_15d * 100.0); // Convert to percentage
+                
+                info!("✅ Calculated S&P 500 benchmark return: {:.2}%", market_benchmark.unwrap_or(0.0));
             }
+        }
 
-            // Try to get sector-specific ETF data based on the stock symbol
-            let sector_etf = self.get_sector_etf(symbol);
-            if let Some(etf) = sector_etf {
-                if let Ok(response) = self.client
-                    .get(&format!("https://financialmodelingprep.com/api/v3/historical-price-full/{}?apikey={}", etf, fmp_key))
-                    .send()
-                    .await
-                {
-                    if let Ok(json) = response.json::<Value>().await {
-                        if let Some(historical_data) = json.get("historical") {
-                            if let Some(prices_array) = historical_data.as_array() {
-                                if prices_array.len() >= 15 {
-                                    let mut closes: Vec<f64> = Vec::new();
-                                    for price_entry in prices_array.iter().take(15) {
-                                        if let Some(close) = price_entry.get("close").and_then(|v| v.as_f64()) {
-                                            closes.push(close);
-                                        }
-                                    }
-                                    
-                                    if closes.len() >= 15 {
-                                        // Calculate 15-day return for sector ETF
-                                        let sector_return_15d = (closes[0] - closes[14]) / closes[14];
-                                        sector_benchmark = Some(sector_return_15d * 100.0); // Convert to percentage
-                                        
-                                        info!("✅ Calculated {} sector benchmark return: {:.2}%", etf, sector_benchmark.unwrap_or(0.0));
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+        // Sector or industry ETF chosen from the company classification
+        let etf = self.classification(symbol).await.benchmark_etf();
+        if etf == MARKET_BENCHMARK {
+            sector_benchmark = market_benchmark;
+        } else if let Ok(response) = self.fetch_bars_newest_first(etf, 15, as_of).await {
+            let closes: Vec<f64> = response.data.iter().map(|b| b.close).collect();
+            if closes.len() >= 15 {
+                // Calculate 15-day return for sector ETF
+                let sector_return_15d = (closes[0] - closes[14]) / closes[14];
+                sector_benchmark = Some(sector_return_15d * 100.0); // Convert to percentage
+                
+                info!("✅ Calculated {} sector benchmark return: {:.2}%", etf, sector_benchmark.unwrap_or(0.0));
             }
         }
 
-        Ok((market_benchmark, sector_benchmark))
+        Ok((market_benchmark, sector_benchmark, etf))
     }
 
-    fn get_sector_etf(&self, symbol: &str) -> Option<&'static str> {
-        // Map major stocks to their sector ETFs
-        match symbol {
-            "AAPL" | "MSFT" | "GOOGL" | "NVDA" | "TSLA" | "META" | "AMZN" => Some("XLK"), // Technology
-            "JPM" | "BAC" | "WFC" | "GS" => Some("XLF"), // Financials
-            "JNJ" | "PFE" | "UNH" => Some("XLV"), // Healthcare
-            "XOM" | "CVX" | "COP" => Some("XLE"), // Energy
-            "KO" | "PEP" | "WMT" => Some("XLP"), // Consumer Staples
-            "HD" | "MCD" | "NKE" => Some("XLY"), // Consumer Discretionary
-            _ => Some("SPY"), // Default to S&P 500 if sector unknown
+    /// Sector and industry of `symbol`, from the known classifications or the company profile
+    async fn classification(&self, symbol: &str) -> SectorClassification {
+        if let Some(known) = self.classifications.get(symbol) {
+            return known.clone();
+        }
+        match self.provider.company_profile(symbol).await {
+            Ok(response) => SectorClassification::new(response.data.sector, response.data.industry),
+            Err(e) => {
+                warn!("⚠️  No company profile for {}: {}, benchmarking against {}", symbol, e, MARKET_BENCHMARK);
+                SectorClassification::default()
+            }
         }
     }
-}
\ No newline at end of file
+}
+
+/// Parse a `YYYY-MM-DD` (or longer timestamp) JSON string into a date
+pub(crate) fn json_date(value: &Value) -> Option<NaiveDate> {
+    value.as_str().and_then(|s| NaiveDate::parse_from_str(s.get(..10)?, "%Y-%m-%d").ok())
+}
+
+/// Analyst recommendations published on or before `as_of` (entries without a date are kept)
+fn recommendations_published_by(recommendations: &[Value], as_of: NaiveDate) -> impl Iterator<Item = &Value> {
+    recommendations
+        .iter()
+        .filter(move |rec| json_date(&rec["date"]).is_none_or(|date| date <= as_of))
+}
+
+/// Consensus snapshot for the next fiscal period from the shared FMP responses, `None` without estimates
+fn estimate_snapshot(consensus: &FmpConsensus, as_of: NaiveDate) -> Option<EstimateData> {
+    let estimates = consensus.estimates.as_ref()?;
+    // The rating only annotates the snapshot, so missing recommendations leave it neutral
+    let recommendation = consensus
+        .recommendations
+        .as_ref()
+        .and_then(|json| json.as_array())
+        .and_then(|entries| recommendations_published_by(entries, as_of).next())
+        .and_then(recommendation_score)
+        .unwrap_or(0.0);
+    parse_fmp_estimate_snapshot(estimates, as_of, recommendation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_components_without_flags_give_full_confidence() {
+        let components = QSSComponents {
+            earnings_revisions: 0.4,
+            relative_strength: 0.3,
+            short_interest: -0.2,
+            options_flow: 0.1,
+            news: 0.5,
+            pead: 0.2,
+        };
+        let calculator = QSSCalculator::new();
+
+        assert_eq!(components.valid_components_count(), 6);
+        assert_eq!(calculator.calculate_confidence_score(&components, &[]), 1.0);
+        assert_eq!(calculator.calculate_confidence_score(&QSSComponents { pead: 0.0, ..components }, &[]), 0.9);
+    }
+}
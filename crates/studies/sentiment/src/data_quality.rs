@@ -0,0 +1,139 @@
+// Data quality
+// Collects typed flags from what actually happened while gathering data and turns them into a confidence penalty
+
+use super::models::DataQualityFlag;
+use buenotea_core::Error;
+use chrono::NaiveDate;
+
+/// Calendar days either side of a report date that count as the earnings window
+pub const EARNINGS_WINDOW_DAYS: i64 = 2;
+
+/// Calendar days the latest bar may trail `as_of` (covers long weekends)
+pub const STALE_PRICE_DAYS: i64 = 4;
+
+/// Fewest analysts behind a usable consensus
+pub const MIN_ANALYST_COUNT: i32 = 3;
+
+/// Estimate range over the absolute consensus above which estimates are too dispersed
+pub const MAX_ESTIMATE_DISPERSION: f64 = 0.5;
+
+/// Fewest non-zero components behind a usable score
+pub const MIN_VALID_COMPONENTS: usize = 2;
+
+/// Flags raised during one calculation, in the order they were first raised
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DataQualityFlags(Vec<DataQualityFlag>);
+
+impl DataQualityFlags {
+    /// Raise a flag; raising it again has no effect
+    pub fn raise(&mut self, flag: DataQualityFlag) {
+        if !self.0.contains(&flag) {
+            self.0.push(flag);
+        }
+    }
+
+    /// Raise the flag matching a swallowed collection error, if any
+    pub fn note_error(&mut self, error: &Error) {
+        if let Some(flag) = flag_for_error(error) {
+            self.raise(flag);
+        }
+    }
+
+    /// Raise the flag matching a failed HTTP request, if any
+    pub fn note_http_error(&mut self, error: &reqwest::Error) {
+        if let Some(flag) = flag_for_http_error(error) {
+            self.raise(flag);
+        }
+    }
+
+    pub fn contains(&self, flag: DataQualityFlag) -> bool {
+        self.0.contains(&flag)
+    }
+
+    pub fn into_vec(self) -> Vec<DataQualityFlag> {
+        self.0
+    }
+}
+
+/// Flag for a collection error: rate limits and transport failures
+///
+/// Missing or malformed data is flagged by the caller instead, as only it
+/// knows which component went without.
+pub fn flag_for_error(error: &Error) -> Option<DataQualityFlag> {
+    match error {
+        Error::RateLimitExceeded(_) => Some(DataQualityFlag::RateLimited),
+        Error::HttpRequest(e) => flag_for_http_error(e),
+        _ => None,
+    }
+}
+
+/// Flag for a failed HTTP request: HTTP 429 is a rate limit, undecodable bodies are not flagged
+pub fn flag_for_http_error(error: &reqwest::Error) -> Option<DataQualityFlag> {
+    if error.status().is_some_and(|status| status.as_u16() == 429) {
+        Some(DataQualityFlag::RateLimited)
+    } else if error.is_decode() {
+        None
+    } else {
+        Some(DataQualityFlag::NetworkIssues)
+    }
+}
+
+/// Whether the latest bar on `latest` is too old to describe `as_of`
+pub fn is_stale(latest: NaiveDate, as_of: NaiveDate) -> bool {
+    (as_of - latest).num_days() > STALE_PRICE_DAYS
+}
+
+/// Whether an earnings report on `report_date` falls within two days of `as_of`
+pub fn in_earnings_window(report_date: NaiveDate, as_of: NaiveDate) -> bool {
+    (report_date - as_of).num_days().abs() <= EARNINGS_WINDOW_DAYS
+}
+
+/// Estimate range relative to the consensus, `None` when the consensus is zero or the range inverted
+pub fn estimate_dispersion(high: f64, low: f64, average: f64) -> Option<f64> {
+    (average != 0.0 && high >= low).then(|| (high - low) / average.abs())
+}
+
+/// Confidence lost to the raised flags, summed over their severities
+pub fn confidence_penalty(flags: &[DataQualityFlag]) -> f64 {
+    flags.iter().map(|flag| flag.severity().confidence_penalty()).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    #[test]
+    fn test_flags_from_errors_and_dedup() {
+        let mut flags = DataQualityFlags::default();
+        flags.note_error(&Error::RateLimitExceeded("Alpha Vantage".to_string()));
+        flags.note_error(&Error::RateLimitExceeded("FINRA".to_string()));
+        flags.note_error(&Error::ApiError("FMP".to_string(), "Not found".to_string()));
+        flags.raise(DataQualityFlag::NoShortData);
+
+        assert!(flags.contains(DataQualityFlag::RateLimited));
+        assert_eq!(flags.into_vec(), vec![DataQualityFlag::RateLimited, DataQualityFlag::NoShortData]);
+        assert_eq!(
+            confidence_penalty(&[DataQualityFlag::RateLimited, DataQualityFlag::NoShortData, DataQualityFlag::EarningsWindow]),
+            0.15 + 0.05
+        );
+    }
+
+    #[test]
+    fn test_staleness_window_and_dispersion() {
+        // Friday's close still describes the following Tuesday
+        assert!(!is_stale(date(1), date(5)));
+        assert!(is_stale(date(1), date(6)));
+
+        assert!(in_earnings_window(date(12), date(10)));
+        assert!(in_earnings_window(date(8), date(10)));
+        assert!(!in_earnings_window(date(13), date(10)));
+
+        assert_eq!(estimate_dispersion(2.5, 1.5, -2.0), Some(0.5));
+        assert_eq!(estimate_dispersion(1.0, 2.0, 1.5), None);
+        assert_eq!(estimate_dispersion(1.0, 0.5, 0.0), None);
+    }
+}
@@ -0,0 +1,198 @@
+// Earnings surprise
+// Standardized unexpected earnings and post-report abnormal returns for the post-earnings-announcement drift component
+
+use super::estimate_revisions::weighted_mean;
+use super::models::{EarningsSurpriseData, QuarterSurprise};
+use buenotea_core::market_data::{DailyBar, EarningsCalendar, EarningsReport, ReportTime};
+use chrono::NaiveDate;
+
+/// Reported quarters used for the SUE
+pub const SURPRISE_QUARTERS: usize = 8;
+
+/// Fewest reported quarters behind a SUE (the latest plus three prior)
+pub const MIN_SUE_QUARTERS: usize = 4;
+
+/// Sessions after a report over which the price reaction is measured
+pub const REACTION_SESSIONS: usize = 3;
+
+/// Calendar days after a report over which drift is expected, fading linearly to zero
+pub const DRIFT_DAYS: i64 = 90;
+
+/// SUE and abnormal return that score a full ±1
+const SUE_SCALE: f64 = 2.0;
+const REACTION_SCALE: f64 = 0.05;
+
+/// Surprise history of the last eight quarters released by `as_of`
+///
+/// `bars` and `benchmark` are daily bars ending on or before `as_of` (oldest
+/// first); reports whose reaction window is not complete get no abnormal return.
+/// `None` when no report with both actual and estimated EPS was released.
+pub fn earnings_surprise(
+    calendar: &EarningsCalendar,
+    bars: &[DailyBar],
+    benchmark: &[DailyBar],
+    as_of: NaiveDate,
+) -> Option<EarningsSurpriseData> {
+    let reports = calendar.surprise_history(as_of, SURPRISE_QUARTERS);
+    let latest = reports.first()?;
+
+    let quarters: Vec<QuarterSurprise> = reports
+        .iter()
+        .filter_map(|report| {
+            Some(QuarterSurprise {
+                report_date: report.date,
+                eps_actual: report.eps_actual?,
+                eps_estimate: report.eps_estimate?,
+                abnormal_return_3d: abnormal_return(report, bars, benchmark),
+            })
+        })
+        .collect();
+    let surprises: Vec<f64> = quarters.iter().map(QuarterSurprise::eps_surprise).collect();
+
+    Some(EarningsSurpriseData {
+        sue: standardized_unexpected_earnings(&surprises),
+        days_since_report: (as_of - latest.date).num_days(),
+        quarters,
+    })
+}
+
+/// Latest surprise over the sample standard deviation of the prior ones (`surprises` newest first)
+///
+/// `None` with fewer than `MIN_SUE_QUARTERS` surprises or identical prior surprises.
+pub fn standardized_unexpected_earnings(surprises: &[f64]) -> Option<f64> {
+    if surprises.len() < MIN_SUE_QUARTERS {
+        return None;
+    }
+    let (latest, prior) = surprises.split_first()?;
+    let mean = prior.iter().sum::<f64>() / prior.len() as f64;
+    let variance = prior.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (prior.len() - 1) as f64;
+    let std_dev = variance.sqrt();
+    (std_dev > f64::EPSILON).then(|| latest / std_dev)
+}
+
+/// Stock minus benchmark return over the first `REACTION_SESSIONS` sessions the report was public
+///
+/// Measured from the close before the first such session: the report date for
+/// before-open releases, the next session otherwise (after-close or unknown).
+pub fn abnormal_return(report: &EarningsReport, bars: &[DailyBar], benchmark: &[DailyBar]) -> Option<f64> {
+    let first = bars.iter().position(|bar| match report.time {
+        Some(ReportTime::BeforeOpen) => bar.date >= report.date,
+        _ => bar.date > report.date,
+    })?;
+    let (before, last) = (bars.get(first.checked_sub(1)?)?, bars.get(first + REACTION_SESSIONS - 1)?);
+
+    let close_on = |date: NaiveDate| {
+        benchmark
+            .binary_search_by_key(&date, |bar| bar.date)
+            .ok()
+            .map(|i| benchmark[i].close)
+    };
+    let (benchmark_before, benchmark_last) = (close_on(before.date)?, close_on(last.date)?);
+
+    Some((last.close / before.close - 1.0) - (benchmark_last / benchmark_before - 1.0))
+}
+
+/// Score the pead component between -1 and +1
+///
+/// The SUE (±2 is a full score) and the latest report's abnormal return (±5%)
+/// are weighted 60/40 and fade linearly to zero `DRIFT_DAYS` after the report.
+pub fn pead_score(surprise: &EarningsSurpriseData) -> f64 {
+    let scaled = |value: Option<f64>, scale: f64| value.map(|v| (v / scale).clamp(-1.0, 1.0));
+    let reaction = surprise.latest().and_then(|q| q.abnormal_return_3d);
+    let signal = weighted_mean(&[(scaled(surprise.sue, SUE_SCALE), 0.6), (scaled(reaction, REACTION_SCALE), 0.4)]);
+    let decay = (1.0 - surprise.days_since_report as f64 / DRIFT_DAYS as f64).max(0.0);
+    signal.unwrap_or(0.0) * decay
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn bars(start: &str, closes: &[f64]) -> Vec<DailyBar> {
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, close)| DailyBar {
+                date: date(start) + Duration::days(i as i64),
+                open: *close,
+                high: *close,
+                low: *close,
+                close: *close,
+                adjusted_close: None,
+                volume: 1_000_000,
+            })
+            .collect()
+    }
+
+    fn report(day: &str, time: Option<ReportTime>, actual: f64, estimate: f64) -> EarningsReport {
+        EarningsReport {
+            date: date(day),
+            time,
+            fiscal_period_end: None,
+            eps_actual: Some(actual),
+            eps_estimate: Some(estimate),
+            revenue_actual: None,
+            revenue_estimate: None,
+        }
+    }
+
+    #[test]
+    fn test_sue_and_reaction_window() {
+        // Prior surprises 0.02, 0.04, 0.06 have a sample std of 0.02
+        assert!((standardized_unexpected_earnings(&[0.10, 0.02, 0.04, 0.06]).unwrap() - 5.0).abs() < 1e-9);
+        assert_eq!(standardized_unexpected_earnings(&[0.10, 0.02, 0.04]), None);
+        assert_eq!(standardized_unexpected_earnings(&[0.10, 0.02, 0.02, 0.02]), None);
+
+        let stock = bars("2024-05-01", &[100.0, 100.0, 110.0, 111.0, 112.0, 90.0]);
+        let market = bars("2024-05-01", &[400.0, 400.0, 404.0, 404.0, 408.0, 408.0]);
+
+        // After the close on 05-02: measured from the 05-02 close to the 05-05 close
+        let after_close = report("2024-05-02", Some(ReportTime::AfterClose), 1.2, 1.0);
+        assert!((abnormal_return(&after_close, &stock, &market).unwrap() - (0.12 - 0.02)).abs() < 1e-12);
+
+        // Before the open on 05-02: measured from the 05-01 close to the 05-04 close
+        let before_open = report("2024-05-02", Some(ReportTime::BeforeOpen), 1.2, 1.0);
+        assert!((abnormal_return(&before_open, &stock, &market).unwrap() - (0.11 - 0.01)).abs() < 1e-12);
+
+        // The window is not complete yet
+        let recent = report("2024-05-04", None, 1.2, 1.0);
+        assert_eq!(abnormal_return(&recent, &stock, &market), None);
+    }
+
+    #[test]
+    fn test_pead_from_calendar() {
+        let calendar = EarningsCalendar::new(
+            "ACME",
+            vec![
+                report("2023-05-02", None, 1.02, 1.00),
+                report("2023-08-02", None, 1.04, 1.00),
+                report("2023-11-02", None, 1.06, 1.00),
+                report("2024-02-01", None, 1.10, 1.00),
+                report("2024-05-02", Some(ReportTime::AfterClose), 1.00, 1.00),
+            ],
+        );
+        let stock = bars("2024-01-30", &[50.0, 50.0, 50.0, 52.0, 53.0, 54.0]);
+        let market = bars("2024-01-30", &[400.0; 6]);
+        let as_of = date("2024-03-02");
+
+        // The after-close May report is not known yet on March 2nd
+        let surprise = earnings_surprise(&calendar, &stock, &market, as_of).unwrap();
+        assert_eq!(surprise.quarters.len(), 4);
+        assert_eq!(surprise.latest().unwrap().report_date, date("2024-02-01"));
+        assert_eq!(surprise.days_since_report, 30);
+        assert!((surprise.sue.unwrap() - 5.0).abs() < 1e-9);
+        assert!((surprise.latest().unwrap().abnormal_return_3d.unwrap() - 0.08).abs() < 1e-12);
+        assert!((surprise.latest_surprise_percent().unwrap() - 10.0).abs() < 1e-9);
+
+        // Full SUE and reaction, two thirds of the drift window left
+        assert!((pead_score(&surprise) - 2.0 / 3.0).abs() < 1e-9);
+
+        let faded = earnings_surprise(&calendar, &stock, &market, date("2024-05-01")).unwrap();
+        assert_eq!(pead_score(&faded), 0.0);
+    }
+}
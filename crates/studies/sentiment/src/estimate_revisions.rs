@@ -0,0 +1,289 @@
+// Estimate revisions
+// Stores a consensus estimate snapshot per run and measures revision momentum, breadth and dispersion from the stored history
+
+use super::calculator::json_date;
+use super::estimate_snapshot_storage::EstimateSnapshotStorage;
+use super::models::{EstimateChange, EstimateData, EstimateRevisions};
+use async_trait::async_trait;
+use buenotea_core::market_data::as_of_timestamp;
+use buenotea_core::Result;
+use chrono::{Duration, NaiveDate};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Horizons in calendar days over which consensus changes are measured
+pub const REVISION_HORIZONS_DAYS: [i64; 3] = [7, 30, 90];
+
+/// Calendar days a snapshot may trail a horizon (or `as_of`) and still be used
+pub const SNAPSHOT_SLACK_DAYS: i64 = 7;
+
+/// Calendar days of snapshots loaded per calculation
+pub const SNAPSHOT_LOOKBACK_DAYS: i64 = 90 + SNAPSHOT_SLACK_DAYS;
+
+/// Calendar days over which consensus raises and cuts are counted
+pub const BREADTH_WINDOW_DAYS: i64 = 30;
+
+/// Relative consensus moves smaller than this are not counted as raises or cuts
+const MIN_REVISION: f64 = 0.001;
+
+/// EPS and revenue consensus changes that score a full ±1
+const EPS_REVISION_SCALE: f64 = 0.05;
+const REVENUE_REVISION_SCALE: f64 = 0.03;
+
+/// Store of consensus estimate snapshots
+#[async_trait]
+pub trait EstimateHistory: Send + Sync {
+    /// Snapshots of `symbol` dated from `from` to `to` inclusive, oldest first
+    async fn snapshots(&self, symbol: &str, from: NaiveDate, to: NaiveDate) -> Result<Vec<EstimateData>>;
+
+    /// Store a snapshot, replacing any with the same date and fiscal period
+    async fn record(&self, symbol: &str, snapshot: &EstimateData) -> Result<()>;
+}
+
+/// Snapshots kept in memory (tests and dry runs)
+#[derive(Default)]
+pub struct InMemoryEstimateHistory {
+    snapshots: Mutex<HashMap<String, Vec<EstimateData>>>,
+}
+
+#[async_trait]
+impl EstimateHistory for InMemoryEstimateHistory {
+    async fn snapshots(&self, symbol: &str, from: NaiveDate, to: NaiveDate) -> Result<Vec<EstimateData>> {
+        let snapshots = self.snapshots.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(snapshots
+            .get(symbol)
+            .map(|history| {
+                history
+                    .iter()
+                    .filter(|s| (from..=to).contains(&s.date.date_naive()))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn record(&self, symbol: &str, snapshot: &EstimateData) -> Result<()> {
+        let mut snapshots = self.snapshots.lock().unwrap_or_else(|e| e.into_inner());
+        let history = snapshots.entry(symbol.to_string()).or_default();
+        history.retain(|s| s.date.date_naive() != snapshot.date.date_naive() || !s.same_period(snapshot));
+        history.push(snapshot.clone());
+        history.sort_by_key(|s| s.date);
+        Ok(())
+    }
+}
+
+/// Snapshot store in Supabase (`estimate_snapshots`), `None` without database credentials
+pub fn estimate_history_from_env() -> Option<Arc<dyn EstimateHistory>> {
+    EstimateSnapshotStorage::from_env()
+        .ok()
+        .map(|storage| Arc::new(storage) as Arc<dyn EstimateHistory>)
+}
+
+/// Snapshot of the nearest fiscal period ending after `as_of` from FMP `analyst-estimates`
+///
+/// `recommendation` comes from a separate endpoint and is passed through.
+pub fn parse_fmp_estimate_snapshot(json: &Value, as_of: NaiveDate, recommendation: f64) -> Option<EstimateData> {
+    let next = json
+        .as_array()?
+        .iter()
+        .filter_map(|entry| Some((json_date(&entry["date"])?, entry)))
+        .filter(|(period, _)| *period > as_of)
+        .min_by_key(|(period, _)| *period)?;
+    let (period, entry) = next;
+    let number = |key: &str| entry.get(key).and_then(|v| v.as_f64());
+
+    Some(EstimateData {
+        date: as_of_timestamp(as_of),
+        fiscal_period: Some(period),
+        eps: number("estimatedEpsAvg")?,
+        eps_high: number("estimatedEpsHigh"),
+        eps_low: number("estimatedEpsLow"),
+        revenue: number("estimatedRevenueAvg")?,
+        revenue_high: number("estimatedRevenueHigh"),
+        revenue_low: number("estimatedRevenueLow"),
+        recommendation,
+        analyst_count: number("numberAnalystsEstimatedEps")
+            .or_else(|| number("numberAnalystEstimatedRevenue"))
+            .unwrap_or(0.0) as u32,
+    })
+}
+
+/// Net analyst rating (1=Strong Buy, 0=Hold, -1=Strong Sell) from an FMP `analyst-stock-recommendations` entry
+pub fn recommendation_score(entry: &Value) -> Option<f64> {
+    let count = |key: &str| entry.get(key).and_then(|v| v.as_f64()).unwrap_or(0.0);
+    let (strong_buy, buy, hold) = (count("analystRatingsStrongBuy"), count("analystRatingsbuy"), count("analystRatingsHold"));
+    let (sell, strong_sell) = (count("analystRatingsSell"), count("analystRatingsStrongSell"));
+    let total = strong_buy + buy + hold + sell + strong_sell;
+    (total > 0.0).then(|| (2.0 * strong_buy + buy - sell - 2.0 * strong_sell) / (2.0 * total))
+}
+
+/// Revision momentum, breadth and dispersion as of `as_of`
+///
+/// Only snapshots dated by `as_of` and targeting the same fiscal period as the
+/// latest one are compared, so a fiscal year rolling over restarts the history.
+/// Each horizon compares the latest snapshot with the newest one at least that
+/// many days older, within `SNAPSHOT_SLACK_DAYS`. `None` when the latest
+/// snapshot is stale or there is nothing to compare it with.
+pub fn estimate_revisions(history: &[EstimateData], as_of: NaiveDate) -> Option<EstimateRevisions> {
+    let current = history.iter().filter(|s| s.date.date_naive() <= as_of).max_by_key(|s| s.date)?;
+    let current_date = current.date.date_naive();
+    if (as_of - current_date).num_days() > SNAPSHOT_SLACK_DAYS {
+        return None;
+    }
+
+    let mut period: Vec<&EstimateData> = history
+        .iter()
+        .filter(|s| s.date.date_naive() <= current_date && s.same_period(current))
+        .collect();
+    period.sort_by_key(|s| s.date);
+
+    let change_over = |days: i64| -> Option<EstimateChange> {
+        let target = current_date - Duration::days(days);
+        period
+            .iter()
+            .rev()
+            .find(|s| s.date.date_naive() <= target)
+            .filter(|base| (target - base.date.date_naive()).num_days() <= SNAPSHOT_SLACK_DAYS)
+            .map(|base| current.change_from(base))
+    };
+    let [short, medium, long] = REVISION_HORIZONS_DAYS.map(change_over);
+    let eps = |change: &Option<EstimateChange>| change.as_ref().map(|c| c.eps_change).filter(|c| c.is_finite());
+
+    let window_start = current_date - Duration::days(BREADTH_WINDOW_DAYS);
+    let (mut revisions_up, mut revisions_down) = (0, 0);
+    for pair in period.windows(2).filter(|pair| pair[1].date.date_naive() > window_start) {
+        let change = pair[1].change_from(pair[0]).eps_change;
+        if change > MIN_REVISION {
+            revisions_up += 1;
+        } else if change < -MIN_REVISION {
+            revisions_down += 1;
+        }
+    }
+
+    let revisions = EstimateRevisions {
+        eps_change_7d: eps(&short),
+        eps_change_30d: eps(&medium),
+        eps_change_90d: eps(&long),
+        revenue_change_30d: medium.map(|c| c.revenue_change).filter(|c| c.is_finite()),
+        revisions_up,
+        revisions_down,
+        dispersion: current.eps_dispersion(),
+        snapshots: period.len(),
+    };
+    (period.len() > 1).then_some(revisions)
+}
+
+/// Score the earnings_revisions component between -1 and +1
+///
+/// EPS momentum (7/30/90 days weighted 20/40/40, ±5% is a full score), 30-day
+/// revenue momentum (±3%) and breadth are weighted 50/20/30 over whichever
+/// are available. The result is divided by one plus the dispersion, as a
+/// consensus move among disagreeing analysts says less.
+pub fn earnings_revisions_score(revisions: &EstimateRevisions) -> f64 {
+    let scaled = |change: Option<f64>, scale: f64| change.map(|c| (c / scale).clamp(-1.0, 1.0));
+    let eps_momentum = weighted_mean(&[
+        (scaled(revisions.eps_change_7d, EPS_REVISION_SCALE), 0.2),
+        (scaled(revisions.eps_change_30d, EPS_REVISION_SCALE), 0.4),
+        (scaled(revisions.eps_change_90d, EPS_REVISION_SCALE), 0.4),
+    ]);
+    let score = weighted_mean(&[
+        (eps_momentum, 0.5),
+        (scaled(revisions.revenue_change_30d, REVENUE_REVISION_SCALE), 0.2),
+        (revisions.breadth(), 0.3),
+    ])
+    .unwrap_or(0.0);
+
+    (score / (1.0 + revisions.dispersion.unwrap_or(0.0))).clamp(-1.0, 1.0)
+}
+
+/// Mean of the available values, reweighted over their weights
+pub(crate) fn weighted_mean(parts: &[(Option<f64>, f64)]) -> Option<f64> {
+    let (sum, weight) = parts
+        .iter()
+        .filter_map(|(value, weight)| Some((value.as_ref()? * weight, *weight)))
+        .fold((0.0, 0.0), |(sum, total), (value, weight)| (sum + value, total + weight));
+    (weight > 0.0).then(|| sum / weight)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(day: &str, eps: f64, revenue: f64) -> EstimateData {
+        EstimateData {
+            date: as_of_timestamp(NaiveDate::parse_from_str(day, "%Y-%m-%d").unwrap()),
+            fiscal_period: NaiveDate::from_ymd_opt(2024, 12, 31),
+            eps,
+            eps_high: Some(eps * 1.1),
+            eps_low: Some(eps * 0.9),
+            revenue,
+            revenue_high: None,
+            revenue_low: None,
+            recommendation: 0.5,
+            analyst_count: 12,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_revisions_from_stored_history() {
+        let history = InMemoryEstimateHistory::default();
+        for (day, eps, revenue) in [
+            ("2024-03-01", 2.00, 100.0),
+            ("2024-05-01", 2.00, 100.0),
+            ("2024-05-23", 2.04, 101.0),
+            ("2024-05-24", 2.06, 101.5),
+            ("2024-05-27", 2.05, 101.5),
+            ("2024-05-31", 2.10, 102.0),
+        ] {
+            history.record("ACME", &snapshot(day, eps, revenue)).await.unwrap();
+        }
+        // Re-recording a day replaces it
+        history.record("ACME", &snapshot("2024-05-31", 2.12, 102.0)).await.unwrap();
+
+        let as_of = NaiveDate::from_ymd_opt(2024, 6, 3).unwrap();
+        let stored = history.snapshots("ACME", as_of - Duration::days(SNAPSHOT_LOOKBACK_DAYS), as_of).await.unwrap();
+        assert_eq!(stored.len(), 6);
+
+        let revisions = estimate_revisions(&stored, as_of).unwrap();
+        assert!((revisions.eps_change_7d.unwrap() - (2.12 - 2.06) / 2.06).abs() < 1e-12);
+        assert!((revisions.eps_change_30d.unwrap() - 0.06).abs() < 1e-12);
+        assert!((revisions.eps_change_90d.unwrap() - 0.06).abs() < 1e-12);
+        assert!((revisions.revenue_change_30d.unwrap() - 0.02).abs() < 1e-12);
+        assert_eq!((revisions.revisions_up, revisions.revisions_down), (3, 1));
+        assert_eq!(revisions.breadth(), Some(0.5));
+
+        let score = earnings_revisions_score(&revisions);
+        assert!(score > 0.5 && score < 1.0);
+
+        // A stale latest snapshot or a single snapshot gives nothing to score
+        assert!(estimate_revisions(&stored, as_of + Duration::days(30)).is_none());
+        assert!(estimate_revisions(&stored[..1], as_of).is_none());
+    }
+
+    #[test]
+    fn test_parse_fmp_snapshot_picks_next_fiscal_period() {
+        let json = serde_json::json!([
+            {"symbol": "ACME", "date": "2025-12-31", "estimatedEpsAvg": 2.6, "estimatedEpsHigh": 2.9,
+             "estimatedEpsLow": 2.3, "estimatedRevenueAvg": 120.0, "numberAnalystsEstimatedEps": 9},
+            {"symbol": "ACME", "date": "2024-12-31", "estimatedEpsAvg": 2.1, "estimatedEpsHigh": 2.3,
+             "estimatedEpsLow": 1.9, "estimatedRevenueAvg": 102.0, "estimatedRevenueHigh": 105.0,
+             "estimatedRevenueLow": 99.0, "numberAnalystsEstimatedEps": 14},
+            {"symbol": "ACME", "date": "2023-12-31", "estimatedEpsAvg": 1.8, "estimatedRevenueAvg": 95.0}
+        ]);
+        let as_of = NaiveDate::from_ymd_opt(2024, 6, 3).unwrap();
+        let recommendation = recommendation_score(&serde_json::json!({
+            "analystRatingsStrongBuy": 4, "analystRatingsbuy": 4, "analystRatingsHold": 2,
+            "analystRatingsSell": 0, "analystRatingsStrongSell": 0
+        }))
+        .unwrap();
+        assert!((recommendation - 0.6).abs() < 1e-12);
+
+        let snapshot = parse_fmp_estimate_snapshot(&json, as_of, recommendation).unwrap();
+        assert_eq!(snapshot.fiscal_period, NaiveDate::from_ymd_opt(2024, 12, 31));
+        assert_eq!(snapshot.eps, 2.1);
+        assert_eq!(snapshot.revenue_low, Some(99.0));
+        assert_eq!(snapshot.analyst_count, 14);
+        assert!((snapshot.eps_dispersion().unwrap() - 0.4 / 2.1).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,81 @@
+// Estimate snapshot storage operations for Supabase
+// One consensus snapshot per symbol, day and fiscal period in the estimate_snapshots table
+
+use super::estimate_revisions::EstimateHistory;
+use super::models::EstimateData;
+use async_trait::async_trait;
+use buenotea_core::market_data::as_of_timestamp;
+use buenotea_core::Result;
+use buenotea_infrastructure::sentiment_models::{EstimateSnapshotInsert, EstimateSnapshotRecord};
+use buenotea_infrastructure::DatabaseClient;
+use chrono::NaiveDate;
+use tracing::info;
+
+/// Table holding the snapshots, with the upsert conflict target
+const SNAPSHOT_TABLE: &str = "estimate_snapshots";
+const SNAPSHOT_UPSERT: &str = "estimate_snapshots?on_conflict=symbol,snapshot_date,fiscal_period";
+
+/// Consensus estimate snapshot storage
+pub struct EstimateSnapshotStorage {
+    db_client: DatabaseClient,
+}
+
+impl EstimateSnapshotStorage {
+    /// Create a new estimate snapshot storage instance
+    pub fn new(db_client: DatabaseClient) -> Self {
+        Self { db_client }
+    }
+
+    /// Create from environment variables
+    pub fn from_env() -> Result<Self> {
+        let db_client = DatabaseClient::from_env()?;
+        Ok(Self::new(db_client))
+    }
+}
+
+#[async_trait]
+impl EstimateHistory for EstimateSnapshotStorage {
+    async fn snapshots(&self, symbol: &str, from: NaiveDate, to: NaiveDate) -> Result<Vec<EstimateData>> {
+        let filter = format!(
+            "symbol=eq.{}&snapshot_date=gte.{}&snapshot_date=lte.{}&order=snapshot_date.asc",
+            symbol, from, to
+        );
+        let records: Vec<EstimateSnapshotRecord> = self.db_client.query(SNAPSHOT_TABLE, Some(&filter)).await?;
+        info!("Retrieved {} estimate snapshots for {} from {} to {}", records.len(), symbol, from, to);
+        Ok(records.into_iter().map(snapshot_from_record).collect())
+    }
+
+    async fn record(&self, symbol: &str, snapshot: &EstimateData) -> Result<()> {
+        let insert = EstimateSnapshotInsert {
+            symbol: symbol.to_string(),
+            snapshot_date: snapshot.date.date_naive(),
+            fiscal_period: snapshot.fiscal_period,
+            eps_estimate: snapshot.eps,
+            eps_high: snapshot.eps_high,
+            eps_low: snapshot.eps_low,
+            revenue_estimate: snapshot.revenue,
+            revenue_high: snapshot.revenue_high,
+            revenue_low: snapshot.revenue_low,
+            recommendation: snapshot.recommendation,
+            analyst_count: snapshot.analyst_count as i32,
+        };
+        let _: EstimateSnapshotRecord = self.db_client.upsert(SNAPSHOT_UPSERT, &insert).await?;
+        info!("Stored estimate snapshot for {} on {}", symbol, insert.snapshot_date);
+        Ok(())
+    }
+}
+
+fn snapshot_from_record(record: EstimateSnapshotRecord) -> EstimateData {
+    EstimateData {
+        date: as_of_timestamp(record.snapshot_date),
+        fiscal_period: record.fiscal_period,
+        eps: record.eps_estimate,
+        eps_high: record.eps_high,
+        eps_low: record.eps_low,
+        revenue: record.revenue_estimate,
+        revenue_high: record.revenue_high,
+        revenue_low: record.revenue_low,
+        recommendation: record.recommendation,
+        analyst_count: record.analyst_count.max(0) as u32,
+    }
+}
@@ -3,15 +3,31 @@
 // based on multiple data sources and sentiment indicators.
 
 pub mod calculator;
+pub mod data_quality;
+pub mod earnings_surprise;
+pub mod estimate_revisions;
+pub mod estimate_snapshot_storage;
+pub mod llm_news;
 pub mod models;
+pub mod news_sentiment;
+pub mod options_flow;
+pub mod sector_benchmark;
 pub mod sentiment_models;
 pub mod sentiment_storage;
+pub mod short_volume;
 
 // Re-export main types for easy access
 pub use calculator::QSSCalculator;
+pub use data_quality::DataQualityFlags;
+pub use estimate_revisions::{EstimateHistory, InMemoryEstimateHistory};
+pub use estimate_snapshot_storage::EstimateSnapshotStorage;
+pub use llm_news::LlmNewsScorer;
 pub use models::*;
+pub use options_flow::{CboeOptionsProvider, LocalOptionsChainProvider, OptionsChainProvider};
+pub use sector_benchmark::SectorClassification;
 pub use sentiment_models::create_sentiment_record_with_tracking;
 pub use sentiment_storage::SentimentStorage;
+pub use short_volume::{FinraShortVolume, ShortVolumeSource};
 
 #[cfg(test)]
 mod tests {
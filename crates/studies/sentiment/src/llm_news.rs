@@ -0,0 +1,211 @@
+// LLM news sentiment
+// Batches headlines into one ChatGPT request with a strict JSON schema and aggregates the validated reply
+
+use super::models::{HeadlineAssessment, ScoredArticle};
+use buenotea_core::{ChatGPTService, Error, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tracing::warn;
+
+/// Most recent headlines sent in one request
+pub const MAX_HEADLINES: usize = 20;
+
+/// Requests made before an invalid reply is rejected
+const MAX_ATTEMPTS: usize = 2;
+
+/// Reply budget; roughly 40 tokens per assessed headline plus slack
+const MAX_TOKENS: u32 = 1200;
+
+const SCHEMA_NAME: &str = "headline_sentiment";
+
+/// Event types the model may assign, matching `NewsEventType`
+const EVENT_TYPES: [&str; 11] = [
+    "earnings",
+    "guidance_raise",
+    "guidance_cut",
+    "mergers_acquisitions",
+    "litigation",
+    "regulatory",
+    "analyst_rating",
+    "product",
+    "management",
+    "capital_return",
+    "other",
+];
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct HeadlineReply {
+    headlines: Vec<HeadlineAssessment>,
+}
+
+/// JSON schema of the reply, in the strict subset structured outputs accept
+pub fn headline_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "headlines": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "index": { "type": "integer" },
+                        "polarity": { "type": "number" },
+                        "relevance": { "type": "number" },
+                        "event_type": { "type": "string", "enum": EVENT_TYPES }
+                    },
+                    "required": ["index", "polarity", "relevance", "event_type"],
+                    "additionalProperties": false
+                }
+            }
+        },
+        "required": ["headlines"],
+        "additionalProperties": false
+    })
+}
+
+/// Prompt listing the headlines with 1-based indices
+pub fn headline_prompt(symbol: &str, headlines: &[&str]) -> String {
+    let listed: Vec<String> = headlines.iter().enumerate().map(|(i, h)| format!("{}. {}", i + 1, h)).collect();
+    format!(
+        "You are an equity analyst. For each news headline about {symbol}, assess its likely impact on {symbol}'s share price.\n\
+         Return one entry per headline with its index, polarity from -1 (very negative) to 1 (very positive), \
+         relevance from 0 (not about {symbol}) to 1 (directly about {symbol}) and the event type.\n\n\
+         Headlines:\n{}",
+        listed.join("\n")
+    )
+}
+
+/// Deserialize and validate a reply for `expected` headlines
+///
+/// Rejects unknown fields, out-of-range polarity or relevance, and replies
+/// that skip, repeat or invent headline indices.
+pub fn parse_headline_reply(text: &str, expected: usize) -> Result<Vec<HeadlineAssessment>> {
+    let invalid = |message: String| Error::InvalidResponseFormat("ChatGPT".to_string(), message);
+
+    let reply: HeadlineReply = serde_json::from_str(text.trim()).map_err(|e| invalid(format!("Invalid headline JSON: {}", e)))?;
+    let mut assessments = reply.headlines;
+    assessments.sort_by_key(|a| a.index);
+
+    if assessments.len() != expected || assessments.iter().enumerate().any(|(i, a)| a.index != i + 1) {
+        return Err(invalid(format!("Expected assessments for headlines 1..={}, got {}", expected, assessments.len())));
+    }
+    for a in &assessments {
+        if !(-1.0..=1.0).contains(&a.polarity) || !(0.0..=1.0).contains(&a.relevance) {
+            return Err(invalid(format!(
+                "Headline {} out of range: polarity {}, relevance {}",
+                a.index, a.polarity, a.relevance
+            )));
+        }
+    }
+    Ok(assessments)
+}
+
+/// Relevance- and recency-weighted polarity of the assessed articles, `None` when nothing is relevant
+pub fn llm_news_score(articles: &[ScoredArticle]) -> Option<f64> {
+    let (weighted, total_weight) = articles
+        .iter()
+        .filter_map(|article| article.llm.as_ref().map(|llm| (llm, llm.relevance * article.weight)))
+        .fold((0.0, 0.0), |(sum, weights), (llm, weight)| (sum + llm.polarity * weight, weights + weight));
+    (total_weight > 0.0).then(|| weighted / total_weight)
+}
+
+/// Scores headlines with ChatGPT
+pub struct LlmNewsScorer {
+    service: ChatGPTService,
+}
+
+impl LlmNewsScorer {
+    pub fn new(service: ChatGPTService) -> Self {
+        Self { service }
+    }
+
+    /// Create from OPENAI_API_KEY
+    pub fn from_env() -> Result<Self> {
+        Ok(Self::new(ChatGPTService::from_env()?))
+    }
+
+    /// Assess the `MAX_HEADLINES` most recent articles in place
+    ///
+    /// Invalid or truncated replies are retried once and then returned as an
+    /// error; articles are only updated from a fully validated reply.
+    pub async fn assess(&self, symbol: &str, articles: &mut [ScoredArticle]) -> Result<()> {
+        let mut recent: Vec<usize> = (0..articles.len()).collect();
+        recent.sort_by(|a, b| articles[*b].published_at.cmp(&articles[*a].published_at));
+        recent.truncate(MAX_HEADLINES);
+        if recent.is_empty() {
+            return Ok(());
+        }
+
+        let headlines: Vec<&str> = recent.iter().map(|i| articles[*i].headline.as_str()).collect();
+        let prompt = headline_prompt(symbol, &headlines);
+
+        let mut last_error = None;
+        for attempt in 1..=MAX_ATTEMPTS {
+            let reply = self
+                .service
+                .generate_json(&prompt, SCHEMA_NAME, headline_schema(), MAX_TOKENS)
+                .await
+                .and_then(|text| parse_headline_reply(&text, recent.len()));
+            match reply {
+                Ok(assessments) => {
+                    for (position, assessment) in recent.iter().zip(assessments) {
+                        articles[*position].llm = Some(assessment);
+                    }
+                    return Ok(());
+                }
+                Err(e @ (Error::InvalidResponseFormat(..) | Error::Json(_))) => {
+                    warn!("Headline assessment attempt {} for {} rejected: {}", attempt, symbol, e);
+                    last_error = Some(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| Error::InvalidResponseFormat("ChatGPT".to_string(), "No reply".to_string())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::NewsEventType;
+    use chrono::Utc;
+
+    #[test]
+    fn test_parse_headline_reply_validation() {
+        let valid = r#"{"headlines": [
+            {"index": 2, "polarity": -0.8, "relevance": 1.0, "event_type": "guidance_cut"},
+            {"index": 1, "polarity": 0.3, "relevance": 0.5, "event_type": "mergers_acquisitions"}
+        ]}"#;
+        let parsed = parse_headline_reply(valid, 2).unwrap();
+        assert_eq!(parsed[0].index, 1);
+        assert_eq!(parsed[1].event_type, NewsEventType::GuidanceCut);
+
+        // Truncated, missing, out-of-range, unknown fields and unknown events are all rejected
+        assert!(parse_headline_reply(&valid[..60], 2).is_err());
+        assert!(parse_headline_reply(valid, 3).is_err());
+        assert!(parse_headline_reply(&valid.replace("-0.8", "-3"), 2).is_err());
+        assert!(parse_headline_reply(&valid.replace("\"relevance\": 0.5", "\"relevance\": 0.5, \"x\": 1"), 2).is_err());
+        assert!(parse_headline_reply(&valid.replace("guidance_cut", "rumor"), 2).is_err());
+        assert!(parse_headline_reply(&valid.replace("\"index\": 2", "\"index\": 1"), 2).is_err());
+    }
+
+    #[test]
+    fn test_llm_news_score_weights() {
+        let article = |weight: f64, polarity: f64, relevance: f64| ScoredArticle {
+            headline: String::new(),
+            source: None,
+            url: None,
+            published_at: Utc::now(),
+            score: None,
+            positive_words: 0,
+            negative_words: 0,
+            weight,
+            llm: Some(HeadlineAssessment { index: 1, polarity, relevance, event_type: NewsEventType::Other }),
+        };
+        let articles = vec![article(1.0, -1.0, 1.0), article(0.5, 1.0, 1.0), article(1.0, 1.0, 0.0)];
+        assert!((llm_news_score(&articles).unwrap() - (-0.5 / 1.5)).abs() < 1e-12);
+        assert_eq!(llm_news_score(&articles[2..]), None);
+        assert!(headline_prompt("ACME", &["Acme beats"]).contains("1. Acme beats"));
+    }
+}
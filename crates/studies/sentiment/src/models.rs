@@ -1,6 +1,6 @@
 // Data models for sentiment analysis and QSS calculations
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
 /// Trading signal generated from QSS score
@@ -64,8 +64,8 @@ pub struct QSSResult {
     pub trading_signal: TradingSignal,
     /// Individual component scores
     pub components: QSSComponents,
-    /// Warning and context flags
-    pub flags: Vec<String>,
+    /// Data quality flags raised while collecting data
+    pub flags: Vec<DataQualityFlag>,
     /// Confidence score (0.0 to 1.0)
     pub confidence_score: f64,
     /// Timestamp of analysis
@@ -77,23 +77,38 @@ pub struct QSSResult {
 /// Individual component scores that make up the QSS
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QSSComponents {
-    /// Earnings revisions score (40% weight)
+    /// Earnings revisions score (30% weight)
     pub earnings_revisions: f64,
-    /// Relative strength score (30% weight)
+    /// Relative strength score (20% weight)
     pub relative_strength: f64,
-    /// Short interest score (20% weight)
+    /// Short interest score (15% weight)
     pub short_interest: f64,
     /// Options flow score (10% weight)
     pub options_flow: f64,
+    /// News sentiment score (15% weight)
+    #[serde(default)]
+    pub news: f64,
+    /// Post-earnings-announcement drift score (10% weight)
+    #[serde(default)]
+    pub pead: f64,
 }
 
 impl QSSComponents {
+    pub const EARNINGS_REVISIONS_WEIGHT: f64 = 0.30;
+    pub const RELATIVE_STRENGTH_WEIGHT: f64 = 0.20;
+    pub const SHORT_INTEREST_WEIGHT: f64 = 0.15;
+    pub const OPTIONS_FLOW_WEIGHT: f64 = 0.10;
+    pub const NEWS_WEIGHT: f64 = 0.15;
+    pub const PEAD_WEIGHT: f64 = 0.10;
+
     /// Calculate the weighted QSS score from components
     pub fn calculate_qss(&self) -> f64 {
-        0.40 * self.earnings_revisions +
-        0.30 * self.relative_strength +
-        0.20 * self.short_interest +
-        0.10 * self.options_flow
+        Self::EARNINGS_REVISIONS_WEIGHT * self.earnings_revisions +
+        Self::RELATIVE_STRENGTH_WEIGHT * self.relative_strength +
+        Self::SHORT_INTEREST_WEIGHT * self.short_interest +
+        Self::OPTIONS_FLOW_WEIGHT * self.options_flow +
+        Self::NEWS_WEIGHT * self.news +
+        Self::PEAD_WEIGHT * self.pead
     }
 
     /// Get the number of components with valid data
@@ -103,6 +118,8 @@ impl QSSComponents {
         if self.relative_strength != 0.0 { count += 1; }
         if self.short_interest != 0.0 { count += 1; }
         if self.options_flow != 0.0 { count += 1; }
+        if self.news != 0.0 { count += 1; }
+        if self.pead != 0.0 { count += 1; }
         count
     }
 }
@@ -133,11 +150,49 @@ pub struct QSSMeta {
     pub previous_revenue_estimate: Option<i64>,
     pub revenue_change_percentage: Option<f64>,
     pub analyst_count: Option<i32>,
+    /// Range of the next consensus EPS estimate over its absolute average
+    #[serde(default)]
+    pub estimate_dispersion: Option<f64>,
+    /// Consensus revision momentum, `None` until enough snapshots are stored
+    #[serde(default)]
+    pub estimate_revisions: Option<EstimateRevisions>,
+    /// EPS surprise history, SUE and post-report price reactions
+    #[serde(default)]
+    pub earnings_surprise: Option<EarningsSurpriseData>,
     pub market_benchmark_return: Option<f64>,
     pub sector_benchmark_return: Option<f64>,
+    /// ETF used for the sector benchmark, picked from the company sector and industry
+    #[serde(default)]
+    pub sector_benchmark: Option<String>,
     pub relative_to_market: Option<f64>,
     pub relative_to_sector: Option<f64>,
     pub volume_ratio: Option<f64>,
+    /// FINRA short volume ratio of the latest session
+    #[serde(default)]
+    pub short_volume_ratio: Option<f64>,
+    /// Short volume ratio z-score against the prior 20 sessions
+    #[serde(default)]
+    pub short_volume_zscore: Option<f64>,
+    #[serde(default)]
+    pub days_to_cover: Option<f64>,
+    /// Put/call volume ratio of the options chain
+    #[serde(default)]
+    pub put_call_ratio: Option<f64>,
+    /// Call share of total option premium (0.5 is balanced)
+    #[serde(default)]
+    pub options_premium_skew: Option<f64>,
+    #[serde(default)]
+    pub unusual_options_contracts: Option<usize>,
+    /// Recency-weighted lexicon tone of recent company news (-1 to 1)
+    #[serde(default)]
+    pub news_sentiment: Option<f64>,
+    #[serde(default)]
+    pub news_article_count: Option<usize>,
+    #[serde(default)]
+    pub news_articles: Vec<ScoredArticle>,
+    /// Relevance- and recency-weighted language model polarity (-1 to 1)
+    #[serde(default)]
+    pub news_llm_sentiment: Option<f64>,
 }
 
 /// Historical price data point
@@ -172,15 +227,28 @@ impl PriceData {
     }
 }
 
-/// Analyst estimate data point
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Analyst estimate data point (one consensus snapshot)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct EstimateData {
     /// Estimate date
     pub date: DateTime<Utc>,
+    /// End of the fiscal period the consensus targets
+    #[serde(default)]
+    pub fiscal_period: Option<NaiveDate>,
     /// Earnings per share estimate
     pub eps: f64,
+    /// Highest and lowest analyst EPS estimate
+    #[serde(default)]
+    pub eps_high: Option<f64>,
+    #[serde(default)]
+    pub eps_low: Option<f64>,
     /// Revenue estimate
     pub revenue: f64,
+    /// Highest and lowest analyst revenue estimate
+    #[serde(default)]
+    pub revenue_high: Option<f64>,
+    #[serde(default)]
+    pub revenue_low: Option<f64>,
     /// Analyst recommendation (1=Strong Buy, 0=Hold, -1=Strong Sell)
     pub recommendation: f64,
     /// Number of analysts providing estimates
@@ -189,13 +257,27 @@ pub struct EstimateData {
 
 impl EstimateData {
     /// Calculate the change from another estimate
+    ///
+    /// EPS and revenue changes are relative to the absolute earlier value, so
+    /// a smaller loss is a positive change; a zero earlier value gives a
+    /// non-finite change.
     pub fn change_from(&self, other: &EstimateData) -> EstimateChange {
         EstimateChange {
-            eps_change: (self.eps - other.eps) / other.eps,
-            revenue_change: (self.revenue - other.revenue) / other.revenue,
+            eps_change: (self.eps - other.eps) / other.eps.abs(),
+            revenue_change: (self.revenue - other.revenue) / other.revenue.abs(),
             recommendation_change: self.recommendation - other.recommendation,
         }
     }
+
+    /// EPS estimate range over the absolute consensus
+    pub fn eps_dispersion(&self) -> Option<f64> {
+        crate::data_quality::estimate_dispersion(self.eps_high?, self.eps_low?, self.eps)
+    }
+
+    /// Whether two snapshots target the same fiscal period
+    pub fn same_period(&self, other: &EstimateData) -> bool {
+        self.fiscal_period == other.fiscal_period
+    }
 }
 
 /// Changes in analyst estimates
@@ -209,6 +291,71 @@ pub struct EstimateChange {
     pub recommendation_change: f64,
 }
 
+/// Consensus revision momentum from stored estimate snapshots
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EstimateRevisions {
+    /// Relative change of the EPS consensus over 7, 30 and 90 days
+    pub eps_change_7d: Option<f64>,
+    pub eps_change_30d: Option<f64>,
+    pub eps_change_90d: Option<f64>,
+    /// Relative change of the revenue consensus over 30 days
+    pub revenue_change_30d: Option<f64>,
+    /// Snapshot-to-snapshot EPS consensus raises and cuts within 30 days
+    pub revisions_up: u32,
+    pub revisions_down: u32,
+    /// Current EPS estimate range over the absolute consensus
+    pub dispersion: Option<f64>,
+    /// Snapshots behind the figures
+    pub snapshots: usize,
+}
+
+impl EstimateRevisions {
+    /// Raises minus cuts over all moves (-1 to 1), `None` without moves
+    pub fn breadth(&self) -> Option<f64> {
+        let moves = self.revisions_up + self.revisions_down;
+        (moves > 0).then(|| (self.revisions_up as f64 - self.revisions_down as f64) / moves as f64)
+    }
+}
+
+/// One released report with its EPS surprise and price reaction
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuarterSurprise {
+    pub report_date: NaiveDate,
+    pub eps_actual: f64,
+    pub eps_estimate: f64,
+    /// Stock minus market return over the three sessions the report was public
+    pub abnormal_return_3d: Option<f64>,
+}
+
+impl QuarterSurprise {
+    pub fn eps_surprise(&self) -> f64 {
+        self.eps_actual - self.eps_estimate
+    }
+}
+
+/// Earnings surprise history behind the post-earnings drift component
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EarningsSurpriseData {
+    /// Released reports, newest first (up to eight quarters)
+    pub quarters: Vec<QuarterSurprise>,
+    /// Latest surprise over the standard deviation of the prior surprises
+    pub sue: Option<f64>,
+    /// Calendar days from the latest report to `as_of`
+    pub days_since_report: i64,
+}
+
+impl EarningsSurpriseData {
+    pub fn latest(&self) -> Option<&QuarterSurprise> {
+        self.quarters.first()
+    }
+
+    /// Latest EPS surprise relative to the absolute estimate, in percent
+    pub fn latest_surprise_percent(&self) -> Option<f64> {
+        let latest = self.latest().filter(|q| q.eps_estimate != 0.0)?;
+        Some(latest.eps_surprise() / latest.eps_estimate.abs() * 100.0)
+    }
+}
+
 /// Short interest data point
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ShortInterestData {
@@ -240,6 +387,76 @@ impl ShortInterestData {
     }
 }
 
+/// Short volume statistics behind the short_interest component
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShortVolumeMetrics {
+    /// Short volume over total FINRA-reported volume on the latest session
+    pub short_volume_ratio: f64,
+    /// Latest ratio against the prior 20 sessions, `None` with fewer than 10
+    pub short_volume_zscore: Option<f64>,
+    /// Short volume over the last 20 sessions divided by average daily volume
+    pub days_to_cover: Option<f64>,
+    /// Sessions of short volume found
+    pub sessions: usize,
+}
+
+/// A news article with its lexicon score
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoredArticle {
+    pub headline: String,
+    pub source: Option<String>,
+    pub url: Option<String>,
+    pub published_at: DateTime<Utc>,
+    /// Net tone (-1 to 1), `None` when no lexicon word was found
+    pub score: Option<f64>,
+    pub positive_words: usize,
+    pub negative_words: usize,
+    /// Recency weight in the aggregate (1 when just published)
+    pub weight: f64,
+    /// Language model assessment of the headline, when one was requested
+    #[serde(default)]
+    pub llm: Option<HeadlineAssessment>,
+}
+
+/// Kind of corporate event a headline reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NewsEventType {
+    Earnings,
+    GuidanceRaise,
+    GuidanceCut,
+    MergersAcquisitions,
+    Litigation,
+    Regulatory,
+    AnalystRating,
+    Product,
+    Management,
+    CapitalReturn,
+    Other,
+}
+
+/// Language model reading of one headline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HeadlineAssessment {
+    /// 1-based position of the headline in the prompt
+    pub index: usize,
+    /// Expected share price impact, -1 (very negative) to 1 (very positive)
+    pub polarity: f64,
+    /// How much the headline concerns the company itself, 0 to 1
+    pub relevance: f64,
+    pub event_type: NewsEventType,
+}
+
+/// News sentiment over the lookback window
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewsSentiment {
+    /// Recency-weighted average of the article scores, `None` without any tone
+    pub score: Option<f64>,
+    pub article_count: usize,
+    pub articles: Vec<ScoredArticle>,
+}
+
 /// Options flow data point
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OptionsFlowData {
@@ -255,6 +472,27 @@ pub struct OptionsFlowData {
     pub put_premium: f64,
     /// Days to expiration
     pub dte: u32,
+    /// Call open interest at the close
+    #[serde(default)]
+    pub call_open_interest: u64,
+    /// Put open interest at the close
+    #[serde(default)]
+    pub put_open_interest: u64,
+    /// Call open interest change since the previous chain, `None` without one
+    #[serde(default)]
+    pub call_oi_change: Option<i64>,
+    /// Put open interest change since the previous chain, `None` without one
+    #[serde(default)]
+    pub put_oi_change: Option<i64>,
+    /// Contracts trading more than their open interest
+    #[serde(default)]
+    pub unusual_contracts: usize,
+    /// Call premium of the unusual contracts
+    #[serde(default)]
+    pub unusual_call_premium: f64,
+    /// Put premium of the unusual contracts
+    #[serde(default)]
+    pub unusual_put_premium: f64,
 }
 
 impl OptionsFlowData {
@@ -307,7 +545,8 @@ pub struct PerformanceComparison {
 }
 
 /// Data quality flags
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
 pub enum DataQualityFlag {
     /// Earnings announcement within 2 days
     EarningsWindow,
@@ -317,10 +556,20 @@ pub enum DataQualityFlag {
     LowConfidence,
     /// Less than minimum required data
     InsufficientData,
+    /// No price history or quote available
+    NoPriceData,
     /// No short interest data available
     NoShortData,
     /// No options flow data available
     NoOptionsData,
+    /// No scored news available
+    NoNewsData,
+    /// Too few reported quarters with estimates for a SUE
+    NoSurpriseData,
+    /// Language model headline assessment rejected; lexicon score used
+    NewsModelRejected,
+    /// Past date scored from reported EPS instead of consensus estimates
+    ReportedEpsOnly,
     /// Data is stale or outdated
     StaleData,
     /// API rate limit exceeded
@@ -339,8 +588,13 @@ impl DataQualityFlag {
             DataQualityFlag::NoEstimates => "Insufficient analyst estimates available",
             DataQualityFlag::LowConfidence => "High dispersion in analyst estimates",
             DataQualityFlag::InsufficientData => "Less than minimum required data points",
+            DataQualityFlag::NoPriceData => "No price data available",
             DataQualityFlag::NoShortData => "No short interest data available",
             DataQualityFlag::NoOptionsData => "No options flow data available",
+            DataQualityFlag::NoNewsData => "No news sentiment available",
+            DataQualityFlag::NoSurpriseData => "Too few reported quarters for earnings surprise",
+            DataQualityFlag::NewsModelRejected => "Headline assessment rejected, lexicon score used",
+            DataQualityFlag::ReportedEpsOnly => "Consensus estimates unavailable for past dates, reported EPS used",
             DataQualityFlag::StaleData => "Data is stale or outdated",
             DataQualityFlag::RateLimited => "API rate limit exceeded",
             DataQualityFlag::NetworkIssues => "Network connectivity issues",
@@ -348,6 +602,40 @@ impl DataQualityFlag {
         }
     }
 
+    /// Stable snake_case code, as serialized and stored in `warning_flags`
+    pub fn code(&self) -> &'static str {
+        match self {
+            DataQualityFlag::EarningsWindow => "earnings_window",
+            DataQualityFlag::NoEstimates => "no_estimates",
+            DataQualityFlag::LowConfidence => "low_confidence",
+            DataQualityFlag::InsufficientData => "insufficient_data",
+            DataQualityFlag::NoPriceData => "no_price_data",
+            DataQualityFlag::NoShortData => "no_short_data",
+            DataQualityFlag::NoOptionsData => "no_options_data",
+            DataQualityFlag::NoNewsData => "no_news_data",
+            DataQualityFlag::NoSurpriseData => "no_surprise_data",
+            DataQualityFlag::NewsModelRejected => "news_model_rejected",
+            DataQualityFlag::ReportedEpsOnly => "reported_eps_only",
+            DataQualityFlag::StaleData => "stale_data",
+            DataQualityFlag::RateLimited => "rate_limited",
+            DataQualityFlag::NetworkIssues => "network_issues",
+            DataQualityFlag::FlowConflict => "flow_conflict",
+        }
+    }
+
+    /// QSS component left without data, as stored in `missing_data_components`
+    pub fn missing_component(&self) -> Option<&'static str> {
+        match self {
+            DataQualityFlag::NoEstimates => Some("earnings_revisions"),
+            DataQualityFlag::NoPriceData => Some("relative_strength"),
+            DataQualityFlag::NoShortData => Some("short_interest"),
+            DataQualityFlag::NoOptionsData => Some("options_flow"),
+            DataQualityFlag::NoNewsData => Some("news"),
+            DataQualityFlag::NoSurpriseData => Some("pead"),
+            _ => None,
+        }
+    }
+
     /// Get the severity level of the flag
     pub fn severity(&self) -> Severity {
         match self {
@@ -355,8 +643,13 @@ impl DataQualityFlag {
             DataQualityFlag::NoEstimates => Severity::Warning,
             DataQualityFlag::LowConfidence => Severity::Warning,
             DataQualityFlag::InsufficientData => Severity::Error,
+            DataQualityFlag::NoPriceData => Severity::Warning,
             DataQualityFlag::NoShortData => Severity::Warning,
             DataQualityFlag::NoOptionsData => Severity::Warning,
+            DataQualityFlag::NoNewsData => Severity::Warning,
+            DataQualityFlag::NoSurpriseData => Severity::Warning,
+            DataQualityFlag::NewsModelRejected => Severity::Info,
+            DataQualityFlag::ReportedEpsOnly => Severity::Info,
             DataQualityFlag::StaleData => Severity::Warning,
             DataQualityFlag::RateLimited => Severity::Error,
             DataQualityFlag::NetworkIssues => Severity::Error,
@@ -365,6 +658,12 @@ impl DataQualityFlag {
     }
 }
 
+impl std::fmt::Display for DataQualityFlag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
 /// Severity levels for data quality flags
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum Severity {
@@ -376,6 +675,17 @@ pub enum Severity {
     Error,
 }
 
+impl Severity {
+    /// Confidence deducted for each flag of this severity
+    pub fn confidence_penalty(&self) -> f64 {
+        match self {
+            Severity::Info => 0.0,
+            Severity::Warning => 0.05,
+            Severity::Error => 0.15,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -397,9 +707,11 @@ mod tests {
             relative_strength: 0.3,
             short_interest: -0.2,
             options_flow: 0.1,
+            news: 0.4,
+            pead: -0.6,
         };
 
-        let expected = 0.40 * 0.5 + 0.30 * 0.3 + 0.20 * (-0.2) + 0.10 * 0.1;
+        let expected = 0.30 * 0.5 + 0.20 * 0.3 + 0.15 * (-0.2) + 0.10 * 0.1 + 0.15 * 0.4 + 0.10 * (-0.6);
         assert_eq!(components.calculate_qss(), expected);
     }
 
@@ -407,16 +719,26 @@ mod tests {
     fn test_estimate_change_calculation() {
         let old = EstimateData {
             date: Utc::now(),
+            fiscal_period: None,
             eps: 1.0,
+            eps_high: None,
+            eps_low: None,
             revenue: 100.0,
+            revenue_high: None,
+            revenue_low: None,
             recommendation: 0.5,
             analyst_count: 5,
         };
 
         let new = EstimateData {
             date: Utc::now(),
+            fiscal_period: None,
             eps: 1.1,
+            eps_high: Some(1.3),
+            eps_low: Some(0.9),
             revenue: 105.0,
+            revenue_high: None,
+            revenue_low: None,
             recommendation: 0.7,
             analyst_count: 6,
         };
@@ -425,6 +747,8 @@ mod tests {
         assert!((change.eps_change - 0.1).abs() < 0.001);
         assert!((change.revenue_change - 0.05).abs() < 0.001);
         assert!((change.recommendation_change - 0.2).abs() < 0.001);
+        assert!((new.eps_dispersion().unwrap() - 0.4 / 1.1).abs() < 1e-12);
+        assert_eq!(old.eps_dispersion(), None);
     }
 
     #[test]
@@ -445,6 +769,13 @@ mod tests {
             call_premium: 1000.0,
             put_premium: 600.0,
             dte: 30,
+            call_open_interest: 0,
+            put_open_interest: 0,
+            call_oi_change: None,
+            put_oi_change: None,
+            unusual_contracts: 0,
+            unusual_call_premium: 0.0,
+            unusual_put_premium: 0.0,
         };
 
         assert_eq!(data.put_call_ratio(), 0.8);
@@ -0,0 +1,238 @@
+// News sentiment
+// Scores company news locally with a finance lexicon (Loughran–McDonald style) and recency decay
+
+use super::models::{NewsSentiment, ScoredArticle};
+use chrono::{DateTime, TimeZone, Utc};
+use serde_json::Value;
+
+/// Days of news scored before `as_of`
+pub const NEWS_LOOKBACK_DAYS: i64 = 7;
+
+/// Article weight halves every this many hours
+const HALF_LIFE_HOURS: f64 = 48.0;
+
+/// Preceding tokens searched for a negator
+const NEGATION_WINDOW: usize = 3;
+
+/// Headline words count this many times as often as summary words
+const HEADLINE_WEIGHT: usize = 2;
+
+/// Positive words, in the spirit of the Loughran–McDonald financial lexicon
+///
+/// Generic words that are neutral in filings ("growth", "increase") are left
+/// out, as are words that read both ways in headlines ("cut", "raise").
+const POSITIVE_WORDS: &[&str] = &[
+    "achieve", "achieved", "achievement", "advance", "advanced", "advantage", "attractive", "beat", "beats",
+    "benefit", "benefited", "boost", "boosted", "boosts", "breakthrough", "bullish", "exceed", "exceeded",
+    "exceeds", "excellent", "favorable", "gain", "gained", "gains", "improve", "improved", "improvement",
+    "improves", "innovative", "jump", "jumped", "jumps", "outperform", "outperformed", "outperforms",
+    "positive", "profitable", "profitability", "rally", "rallied", "rebound", "rebounded", "record",
+    "recover", "recovered", "recovery", "soar", "soared", "soars", "strength", "strong", "stronger",
+    "succeed", "success", "successful", "surge", "surged", "surges", "surpass", "surpassed", "tops",
+    "upbeat", "upgrade", "upgraded", "upgrades", "win", "wins", "won",
+];
+
+/// Negative words, in the spirit of the Loughran–McDonald financial lexicon
+const NEGATIVE_WORDS: &[&str] = &[
+    "adverse", "bankrupt", "bankruptcy", "bearish", "breach", "collapse", "collapsed", "concern", "concerns",
+    "decline", "declined", "declines", "default", "defaulted", "deficit", "delay", "delayed", "delays",
+    "disappoint", "disappointed", "disappointing", "disappoints", "downgrade", "downgraded", "downgrades",
+    "drop", "dropped", "drops", "fail", "failed", "failure", "fails", "fall", "fallen", "falls", "fell",
+    "fraud", "halt", "halted", "impairment", "investigation", "lawsuit", "lawsuits", "layoff", "layoffs",
+    "litigation", "loss", "losses", "miss", "missed", "misses", "negative", "penalty", "plunge", "plunged",
+    "plunges", "probe", "recall", "restatement", "slump", "slumped", "slowdown", "sue", "sued", "tumble",
+    "tumbled", "tumbles", "underperform", "underperformed", "warning", "warns", "weak", "weaker", "weakness",
+];
+
+const NEGATORS: &[&str] = &["no", "not", "never", "none", "neither", "nor", "without", "nobody", "cannot"];
+
+/// One news item as returned by the news source
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewsArticle {
+    pub headline: String,
+    pub summary: String,
+    pub source: Option<String>,
+    pub url: Option<String>,
+    pub published_at: DateTime<Utc>,
+}
+
+/// Lexicon hits in a text after negation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LexiconHits {
+    pub positive: usize,
+    pub negative: usize,
+}
+
+impl LexiconHits {
+    /// Net tone between -1 and +1, `None` when no lexicon word was found
+    pub fn score(&self) -> Option<f64> {
+        let total = self.positive + self.negative;
+        (total > 0).then(|| (self.positive as f64 - self.negative as f64) / total as f64)
+    }
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .replace('\u{2019}', "'")
+        .split(|c: char| !(c.is_alphanumeric() || c == '\''))
+        .map(|token| token.trim_matches('\'').to_string())
+        .filter(|token| !token.is_empty())
+        .collect()
+}
+
+fn is_negator(token: &str) -> bool {
+    NEGATORS.contains(&token) || token.ends_with("n't")
+}
+
+/// Count lexicon words, flipping words preceded by a negator within three tokens
+///
+/// "did not miss" counts as positive and "no improvement" as negative.
+pub fn lexicon_hits(text: &str) -> LexiconHits {
+    let tokens = tokenize(text);
+    let mut hits = LexiconHits::default();
+    for (i, token) in tokens.iter().enumerate() {
+        let polarity = if POSITIVE_WORDS.contains(&token.as_str()) {
+            1
+        } else if NEGATIVE_WORDS.contains(&token.as_str()) {
+            -1
+        } else {
+            continue;
+        };
+        let negated = tokens[i.saturating_sub(NEGATION_WINDOW)..i].iter().any(|t| is_negator(t));
+        if (polarity > 0) != negated {
+            hits.positive += 1;
+        } else {
+            hits.negative += 1;
+        }
+    }
+    hits
+}
+
+/// Lexicon hits of an article, counting the headline twice
+pub fn article_hits(article: &NewsArticle) -> LexiconHits {
+    let headline = lexicon_hits(&article.headline);
+    let summary = lexicon_hits(&article.summary);
+    LexiconHits {
+        positive: headline.positive * HEADLINE_WEIGHT + summary.positive,
+        negative: headline.negative * HEADLINE_WEIGHT + summary.negative,
+    }
+}
+
+/// Recency weight of an article published `published_at`, seen at `as_of`
+pub fn recency_weight(published_at: DateTime<Utc>, as_of: DateTime<Utc>) -> f64 {
+    let age_hours = (as_of - published_at).num_minutes().max(0) as f64 / 60.0;
+    0.5f64.powf(age_hours / HALF_LIFE_HOURS)
+}
+
+/// Score each article and combine them into a recency-weighted news sentiment
+///
+/// Articles published after `as_of` are dropped. Articles without lexicon
+/// words are counted and listed but do not move the aggregate, which is `None`
+/// when no article carries any tone.
+pub fn news_sentiment(articles: &[NewsArticle], as_of: DateTime<Utc>) -> NewsSentiment {
+    let scored: Vec<ScoredArticle> = articles
+        .iter()
+        .filter(|article| article.published_at <= as_of)
+        .map(|article| {
+            let hits = article_hits(article);
+            ScoredArticle {
+                headline: article.headline.clone(),
+                source: article.source.clone(),
+                url: article.url.clone(),
+                published_at: article.published_at,
+                score: hits.score(),
+                positive_words: hits.positive,
+                negative_words: hits.negative,
+                weight: recency_weight(article.published_at, as_of),
+                llm: None,
+            }
+        })
+        .collect();
+
+    let (weighted, total_weight) = scored
+        .iter()
+        .filter_map(|article| article.score.map(|score| (score * article.weight, article.weight)))
+        .fold((0.0, 0.0), |(sum, weights), (value, weight)| (sum + value, weights + weight));
+
+    NewsSentiment {
+        score: (total_weight > 0.0).then(|| weighted / total_weight),
+        article_count: scored.len(),
+        articles: scored,
+    }
+}
+
+/// Parse a Finnhub company-news response (`headline`, `summary`, `datetime` in Unix seconds)
+pub fn parse_finnhub_news(json: &Value) -> Vec<NewsArticle> {
+    let text = |item: &Value, key: &str| item.get(key).and_then(Value::as_str).map(str::to_string);
+    json.as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    let published_at = Utc.timestamp_opt(item.get("datetime")?.as_i64()?, 0).single()?;
+                    Some(NewsArticle {
+                        headline: text(item, "headline")?,
+                        summary: text(item, "summary").unwrap_or_default(),
+                        source: text(item, "source").filter(|s| !s.is_empty()),
+                        url: text(item, "url").filter(|s| !s.is_empty()),
+                        published_at,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn article(headline: &str, published_at: DateTime<Utc>) -> NewsArticle {
+        NewsArticle {
+            headline: headline.to_string(),
+            summary: String::new(),
+            source: None,
+            url: None,
+            published_at,
+        }
+    }
+
+    #[test]
+    fn test_lexicon_and_negation() {
+        assert_eq!(lexicon_hits("Acme beats estimates, shares surge"), LexiconHits { positive: 2, negative: 0 });
+        assert_eq!(lexicon_hits("Acme did not miss estimates"), LexiconHits { positive: 1, negative: 0 });
+        assert_eq!(lexicon_hits("No improvement expected; guidance isn't strong"), LexiconHits { positive: 0, negative: 2 });
+        // Negators only reach three tokens back
+        assert_eq!(lexicon_hits("not one of the analysts saw weakness").negative, 1);
+        assert_eq!(lexicon_hits("Acme schedules annual meeting").score(), None);
+    }
+
+    #[test]
+    fn test_recency_weighted_aggregate() {
+        let now = Utc::now();
+        let articles = vec![
+            article("Acme shares plunge after fraud probe", now - Duration::hours(1)),
+            article("Acme beats estimates", now - Duration::hours(96)),
+            article("Acme to present at conference", now - Duration::hours(2)),
+            article("Acme upgraded", now + Duration::hours(1)),
+        ];
+        assert!((recency_weight(now - Duration::hours(48), now) - 0.5).abs() < 1e-9);
+
+        let sentiment = news_sentiment(&articles, now);
+        assert_eq!(sentiment.article_count, 3);
+        assert_eq!(sentiment.articles[0].score, Some(-1.0));
+        assert_eq!(sentiment.articles[2].score, None);
+        // The fresh negative story outweighs the four-day-old positive one
+        assert!(sentiment.score.unwrap() < -0.5);
+        assert_eq!(news_sentiment(&[], now).score, None);
+
+        let parsed = parse_finnhub_news(&serde_json::json!([
+            { "headline": "Acme beats", "summary": "", "datetime": 1704200000, "source": "Reuters", "url": "" },
+            { "summary": "no headline", "datetime": 1704200000 }
+        ]));
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].source.as_deref(), Some("Reuters"));
+        assert_eq!(parsed[0].url, None);
+    }
+}
@@ -0,0 +1,410 @@
+// Options flow
+// Options-chain providers and the aggregation of a chain into the options_flow component
+
+use super::models::OptionsFlowData;
+use async_trait::async_trait;
+use buenotea_core::market_data::is_historical;
+use buenotea_core::{Error, Result};
+use chrono::{NaiveDate, NaiveDateTime, TimeZone, Utc};
+use reqwest::Client;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// CBOE delayed quotes, one JSON document per underlying (`AAPL.json`)
+pub const CBOE_OPTIONS_URL: &str = "https://cdn.cboe.com/api/global/delayed_quotes/options";
+
+/// Shares per equity option contract
+const CONTRACT_MULTIPLIER: f64 = 100.0;
+
+/// Contracts need this much volume before volume above open interest counts as unusual
+pub const MIN_UNUSUAL_VOLUME: u64 = 500;
+
+/// Equity put/call volume ratio treated as neutral
+const NEUTRAL_PUT_CALL_RATIO: f64 = 0.7;
+
+/// Put/call distance from neutral that scores ±1
+const PUT_CALL_RANGE: f64 = 0.5;
+
+/// Call premium share distance from 50% that scores ±1
+const PREMIUM_SKEW_RANGE: f64 = 0.3;
+
+/// Net call-minus-put open interest change, as a share of open interest, that scores ±1
+const OI_CHANGE_RANGE: f64 = 0.05;
+
+/// Options component and price trend must both be at least this strong to conflict
+pub const FLOW_CONFLICT_THRESHOLD: f64 = 0.3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptionType {
+    Call,
+    Put,
+}
+
+/// One contract of an options chain at the close
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionContract {
+    /// OCC contract symbol (`AAPL240119C00150000`)
+    pub contract: String,
+    pub option_type: OptionType,
+    pub strike: f64,
+    pub expiration: NaiveDate,
+    pub volume: u64,
+    pub open_interest: u64,
+    /// Mid price when quoted, else the last trade
+    pub price: f64,
+}
+
+/// Options chain of an underlying on one date
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionsChain {
+    pub symbol: String,
+    pub date: NaiveDate,
+    pub underlying_price: Option<f64>,
+    pub contracts: Vec<OptionContract>,
+}
+
+/// Source of options chains
+#[async_trait]
+pub trait OptionsChainProvider: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Chain of `symbol` at the close of `as_of`
+    async fn options_chain(&self, symbol: &str, as_of: NaiveDate) -> Result<OptionsChain>;
+}
+
+/// Parse an OCC contract symbol into type, expiration and strike
+///
+/// The last fifteen characters are `YYMMDD`, `C`/`P` and the strike times 1000.
+pub fn parse_occ_symbol(contract: &str) -> Option<(OptionType, NaiveDate, f64)> {
+    let contract = contract.trim();
+    let tail = contract.get(contract.len().checked_sub(15)?..)?;
+    let expiration = NaiveDate::parse_from_str(&tail[..6], "%y%m%d").ok()?;
+    let option_type = match &tail[6..7] {
+        "C" => OptionType::Call,
+        "P" => OptionType::Put,
+        _ => return None,
+    };
+    let strike = tail[7..].parse::<f64>().ok()? / 1000.0;
+    Some((option_type, expiration, strike))
+}
+
+/// Parse a CBOE delayed quotes document (`{"timestamp": ..., "data": {"options": [...]}}`)
+pub fn parse_cboe_chain(json: &Value, symbol: &str) -> Option<OptionsChain> {
+    let data = json.get("data")?;
+    let number = |option: &Value, key: &str| option.get(key).and_then(Value::as_f64).filter(|v| v.is_finite());
+
+    let contracts = data
+        .get("options")?
+        .as_array()?
+        .iter()
+        .filter_map(|option| {
+            let contract = option.get("option")?.as_str()?;
+            let (option_type, expiration, strike) = parse_occ_symbol(contract)?;
+            let price = match (number(option, "bid"), number(option, "ask")) {
+                (Some(bid), Some(ask)) if bid > 0.0 && ask >= bid => (bid + ask) / 2.0,
+                _ => number(option, "last_trade_price").unwrap_or(0.0),
+            };
+            Some(OptionContract {
+                contract: contract.trim().to_string(),
+                option_type,
+                strike,
+                expiration,
+                volume: number(option, "volume").unwrap_or(0.0).max(0.0) as u64,
+                open_interest: number(option, "open_interest").unwrap_or(0.0).max(0.0) as u64,
+                price,
+            })
+        })
+        .collect();
+
+    let date = json
+        .get("timestamp")
+        .and_then(Value::as_str)
+        .and_then(|s| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").ok())
+        .map(|t| t.date())
+        .unwrap_or_else(|| Utc::now().date_naive());
+
+    Some(OptionsChain {
+        symbol: symbol.to_uppercase(),
+        date,
+        underlying_price: number(data, "current_price"),
+        contracts,
+    })
+}
+
+/// Live chains from the CBOE delayed quotes feed (no API key, current day only)
+pub struct CboeOptionsProvider {
+    client: Client,
+    base_url: String,
+}
+
+impl CboeOptionsProvider {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+impl Default for CboeOptionsProvider {
+    fn default() -> Self {
+        Self::new(CBOE_OPTIONS_URL)
+    }
+}
+
+#[async_trait]
+impl OptionsChainProvider for CboeOptionsProvider {
+    fn name(&self) -> &str {
+        "CBOE"
+    }
+
+    async fn options_chain(&self, symbol: &str, as_of: NaiveDate) -> Result<OptionsChain> {
+        if is_historical(as_of) {
+            return Err(Error::ApiError(
+                self.name().to_string(),
+                format!("Delayed quotes have no chain for {} on {}", symbol, as_of),
+            ));
+        }
+
+        let url = format!("{}/{}.json", self.base_url, symbol.to_uppercase());
+        let response = self.client.get(&url).send().await?;
+        let status = response.status();
+        if status.as_u16() == 429 {
+            return Err(Error::RateLimitExceeded(self.name().to_string()));
+        }
+        if !status.is_success() {
+            return Err(Error::ApiError(self.name().to_string(), format!("HTTP {} for {}", status, url)));
+        }
+
+        let json: Value = response.json().await?;
+        parse_cboe_chain(&json, symbol)
+            .ok_or_else(|| Error::InvalidResponseFormat(self.name().to_string(), format!("No options chain for {}", symbol)))
+    }
+}
+
+/// Archived chains in the CBOE format, stored as `<dir>/<SYMBOL>/<YYYY-MM-DD>.json`
+///
+/// Stands in for a historical options vendor: saving the daily CBOE document
+/// builds the archive that backfills and open interest changes read from.
+pub struct LocalOptionsChainProvider {
+    dir: PathBuf,
+}
+
+impl LocalOptionsChainProvider {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+#[async_trait]
+impl OptionsChainProvider for LocalOptionsChainProvider {
+    fn name(&self) -> &str {
+        "Local options archive"
+    }
+
+    async fn options_chain(&self, symbol: &str, as_of: NaiveDate) -> Result<OptionsChain> {
+        let path = self.dir.join(symbol.to_uppercase()).join(format!("{}.json", as_of));
+        let text = tokio::fs::read_to_string(&path).await.map_err(|e| {
+            Error::ApiError(self.name().to_string(), format!("Cannot read {}: {}", path.display(), e))
+        })?;
+        let json: Value = serde_json::from_str(&text)?;
+        let mut chain = parse_cboe_chain(&json, symbol)
+            .ok_or_else(|| Error::InvalidResponseFormat(self.name().to_string(), format!("No options chain in {}", path.display())))?;
+        // The file name is authoritative; snapshots may be saved after midnight
+        chain.date = as_of;
+        Ok(chain)
+    }
+}
+
+/// Options chain provider from OPTIONS_CHAIN_DIR, else CBOE delayed quotes (OPTIONS_CHAIN_URL overrides the base URL)
+pub fn options_provider_from_env() -> Box<dyn OptionsChainProvider> {
+    if let Ok(dir) = std::env::var("OPTIONS_CHAIN_DIR") {
+        return Box::new(LocalOptionsChainProvider::new(dir));
+    }
+    match std::env::var("OPTIONS_CHAIN_URL") {
+        Ok(url) => Box::new(CboeOptionsProvider::new(url)),
+        Err(_) => Box::new(CboeOptionsProvider::default()),
+    }
+}
+
+/// Aggregate a chain into call/put volume, premium and open interest
+///
+/// Expired contracts are ignored. Open interest changes are measured against
+/// `previous` contract by contract, and are `None` without a previous chain.
+/// A contract is unusual when it trades at least `MIN_UNUSUAL_VOLUME` and more
+/// than its open interest, i.e. mostly new positions. Chains report the open
+/// interest settled before the session, so changes lag volume by a day.
+pub fn aggregate_flow(chain: &OptionsChain, previous: Option<&OptionsChain>) -> Option<OptionsFlowData> {
+    let previous_oi: Option<HashMap<&str, u64>> =
+        previous.map(|p| p.contracts.iter().map(|c| (c.contract.as_str(), c.open_interest)).collect());
+
+    let mut flow = OptionsFlowData {
+        date: Utc.from_utc_datetime(&chain.date.and_hms_opt(0, 0, 0)?),
+        call_volume: 0,
+        put_volume: 0,
+        call_premium: 0.0,
+        put_premium: 0.0,
+        dte: 0,
+        call_open_interest: 0,
+        put_open_interest: 0,
+        call_oi_change: previous_oi.as_ref().map(|_| 0),
+        put_oi_change: previous_oi.as_ref().map(|_| 0),
+        unusual_contracts: 0,
+        unusual_call_premium: 0.0,
+        unusual_put_premium: 0.0,
+    };
+    let mut weighted_dte = 0.0;
+
+    for contract in chain.contracts.iter().filter(|c| c.expiration >= chain.date) {
+        let premium = contract.volume as f64 * contract.price * CONTRACT_MULTIPLIER;
+        let unusual = contract.volume >= MIN_UNUSUAL_VOLUME && contract.volume > contract.open_interest;
+        let oi_change = previous_oi.as_ref().map(|previous| {
+            contract.open_interest as i64 - previous.get(contract.contract.as_str()).copied().unwrap_or(0) as i64
+        });
+        weighted_dte += contract.volume as f64 * (contract.expiration - chain.date).num_days() as f64;
+        if unusual {
+            flow.unusual_contracts += 1;
+        }
+
+        let (volume, total_premium, open_interest, change, unusual_premium) = match contract.option_type {
+            OptionType::Call => (
+                &mut flow.call_volume,
+                &mut flow.call_premium,
+                &mut flow.call_open_interest,
+                &mut flow.call_oi_change,
+                &mut flow.unusual_call_premium,
+            ),
+            OptionType::Put => (
+                &mut flow.put_volume,
+                &mut flow.put_premium,
+                &mut flow.put_open_interest,
+                &mut flow.put_oi_change,
+                &mut flow.unusual_put_premium,
+            ),
+        };
+        *volume += contract.volume;
+        *total_premium += premium;
+        *open_interest += contract.open_interest;
+        if let (Some(total), Some(delta)) = (change.as_mut(), oi_change) {
+            *total += delta;
+        }
+        if unusual {
+            *unusual_premium += premium;
+        }
+    }
+
+    let total_volume = flow.call_volume + flow.put_volume;
+    if total_volume == 0 {
+        return None;
+    }
+    flow.dte = (weighted_dte / total_volume as f64).round() as u32;
+    Some(flow)
+}
+
+/// Score the options_flow component between -1 and +1
+///
+/// Averages the put/call ratio against a neutral 0.7, the call share of
+/// premium, the net call-minus-put open interest change and, when present,
+/// the call/put balance of unusual premium. Bullish flow scores positive.
+pub fn options_flow_score(flow: &OptionsFlowData) -> f64 {
+    let mut scores = Vec::new();
+    if flow.call_volume > 0 {
+        scores.push(-((flow.put_call_ratio() - NEUTRAL_PUT_CALL_RATIO) / PUT_CALL_RANGE).clamp(-1.0, 1.0));
+    }
+    if flow.call_premium + flow.put_premium > 0.0 {
+        scores.push(((flow.premium_skew() - 0.5) / PREMIUM_SKEW_RANGE).clamp(-1.0, 1.0));
+    }
+    let open_interest = (flow.call_open_interest + flow.put_open_interest) as f64;
+    if let (Some(calls), Some(puts)) = (flow.call_oi_change, flow.put_oi_change) {
+        if open_interest > 0.0 {
+            scores.push(((calls - puts) as f64 / open_interest / OI_CHANGE_RANGE).clamp(-1.0, 1.0));
+        }
+    }
+    let unusual_premium = flow.unusual_call_premium + flow.unusual_put_premium;
+    if unusual_premium > 0.0 {
+        scores.push((flow.unusual_call_premium - flow.unusual_put_premium) / unusual_premium);
+    }
+
+    if scores.is_empty() {
+        0.0
+    } else {
+        scores.iter().sum::<f64>() / scores.len() as f64
+    }
+}
+
+/// Options flow and price trend point strongly in opposite directions
+pub fn is_flow_conflict(options_score: f64, price_trend_score: f64) -> bool {
+    options_score.abs() >= FLOW_CONFLICT_THRESHOLD
+        && price_trend_score.abs() >= FLOW_CONFLICT_THRESHOLD
+        && options_score.signum() != price_trend_score.signum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn chain(date: NaiveDate, options: Value) -> OptionsChain {
+        parse_cboe_chain(&json!({ "timestamp": format!("{} 16:15:00", date), "data": { "current_price": 150.0, "options": options } }), "aapl").unwrap()
+    }
+
+    #[test]
+    fn test_parse_cboe_chain() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+        let parsed = chain(
+            date,
+            json!([
+                { "option": "AAPL240119C00150000", "bid": 2.0, "ask": 2.2, "volume": 1200.0, "open_interest": 800.0 },
+                { "option": "AAPL240119P00140000", "bid": 0.0, "ask": 0.1, "last_trade_price": 0.05, "volume": 10.0, "open_interest": 50.0 },
+                { "option": "garbage", "volume": 10.0 }
+            ]),
+        );
+        assert_eq!(parsed.symbol, "AAPL");
+        assert_eq!(parsed.date, date);
+        assert_eq!(parsed.contracts.len(), 2);
+        assert_eq!(parsed.contracts[0].option_type, OptionType::Call);
+        assert_eq!(parsed.contracts[0].strike, 150.0);
+        assert!((parsed.contracts[0].price - 2.1).abs() < 1e-12);
+        assert_eq!(parsed.contracts[1].price, 0.05);
+        assert_eq!(parse_occ_symbol("AAPL  240119P00142500"), Some((OptionType::Put, NaiveDate::from_ymd_opt(2024, 1, 19).unwrap(), 142.5)));
+    }
+
+    #[test]
+    fn test_aggregate_flow_and_score() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+        let previous = chain(
+            date.pred_opt().unwrap(),
+            json!([
+                { "option": "AAPL240119C00150000", "volume": 0.0, "open_interest": 800.0 },
+                { "option": "AAPL240119P00140000", "volume": 0.0, "open_interest": 500.0 }
+            ]),
+        );
+        let today = chain(
+            date,
+            json!([
+                // Unusual call buying: volume above open interest
+                { "option": "AAPL240119C00150000", "bid": 2.0, "ask": 2.0, "volume": 1200.0, "open_interest": 1000.0 },
+                { "option": "AAPL240119P00140000", "bid": 1.0, "ask": 1.0, "volume": 300.0, "open_interest": 450.0 },
+                // Expired contracts are ignored
+                { "option": "AAPL231229P00140000", "bid": 1.0, "ask": 1.0, "volume": 9000.0, "open_interest": 0.0 }
+            ]),
+        );
+
+        let flow = aggregate_flow(&today, Some(&previous)).unwrap();
+        assert_eq!((flow.call_volume, flow.put_volume), (1200, 300));
+        assert_eq!(flow.put_call_ratio(), 0.25);
+        assert_eq!(flow.call_premium, 240_000.0);
+        assert_eq!((flow.call_oi_change, flow.put_oi_change), (Some(200), Some(-50)));
+        assert_eq!(flow.unusual_contracts, 1);
+        assert_eq!(flow.dte, 17);
+        assert!(options_flow_score(&flow) > 0.8);
+
+        let without_history = aggregate_flow(&today, None).unwrap();
+        assert_eq!(without_history.call_oi_change, None);
+
+        assert!(is_flow_conflict(0.5, -0.4));
+        assert!(!is_flow_conflict(0.5, 0.4));
+        assert!(!is_flow_conflict(0.1, -0.9));
+    }
+}
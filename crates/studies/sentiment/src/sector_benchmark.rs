@@ -0,0 +1,133 @@
+// Sector benchmark selection
+// Maps a company's sector and industry classification onto the ETF used for relative_to_sector
+
+use serde::{Deserialize, Serialize};
+
+/// Benchmark used when a company cannot be classified
+pub const MARKET_BENCHMARK: &str = "SPY";
+
+/// Sector and industry of a company, as reported by the invite list or a provider profile
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SectorClassification {
+    pub sector: Option<String>,
+    pub industry: Option<String>,
+}
+
+impl SectorClassification {
+    pub fn new(sector: Option<String>, industry: Option<String>) -> Self {
+        Self { sector, industry }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sector.is_none() && self.industry.is_none()
+    }
+
+    /// ETF that best tracks this classification, `SPY` when nothing matches
+    pub fn benchmark_etf(&self) -> &'static str {
+        sector_benchmark_etf(self.sector.as_deref(), self.industry.as_deref())
+    }
+}
+
+/// Industry ETFs, checked in order against the lowercased industry name
+///
+/// Covers the FMP/Yahoo industry names ("Banks - Regional") as well as the
+/// broader Finnhub industries ("Banking"). REITs are matched first so that
+/// "REIT - Retail" does not land in the retail fund.
+const INDUSTRY_ETFS: [(&str, &str); 22] = [
+    ("reit", "XLRE"),
+    ("semiconductor", "SMH"),
+    ("banks - regional", "KRE"),
+    ("banks—regional", "KRE"),
+    ("bank", "KBE"),
+    ("biotech", "XBI"),
+    ("software", "IGV"),
+    ("exploration & production", "XOP"),
+    ("oil & gas e&p", "XOP"),
+    ("oil & gas equipment", "OIH"),
+    ("insurance", "KIE"),
+    ("capital markets", "KCE"),
+    ("aerospace", "ITA"),
+    ("homebuild", "XHB"),
+    ("residential construction", "XHB"),
+    ("retail", "XRT"),
+    ("medical devices", "IHI"),
+    ("medical instruments", "IHI"),
+    ("drug manufacturers", "XPH"),
+    ("pharmaceutical", "XPH"),
+    ("gold", "GDX"),
+    ("metals & mining", "XME"),
+];
+
+/// SPDR sector ETFs keyed by sector name fragments (FMP and GICS spellings)
+///
+/// The same table is used on industry names when a provider reports no
+/// sector, e.g. Finnhub's "Technology" or "Utilities" industries.
+const SECTOR_ETFS: [(&str, &str); 22] = [
+    ("consumer cyclical", "XLY"),
+    ("consumer discretionary", "XLY"),
+    ("consumer defensive", "XLP"),
+    ("consumer staples", "XLP"),
+    ("communication", "XLC"),
+    ("telecommunication", "XLC"),
+    ("media", "XLC"),
+    ("technology", "XLK"),
+    ("financial", "XLF"),
+    ("health", "XLV"),
+    ("energy", "XLE"),
+    ("oil & gas", "XLE"),
+    ("industrials", "XLI"),
+    ("machinery", "XLI"),
+    ("materials", "XLB"),
+    ("chemicals", "XLB"),
+    ("utilities", "XLU"),
+    ("real estate", "XLRE"),
+    ("food", "XLP"),
+    ("beverages", "XLP"),
+    ("hotels", "XLY"),
+    ("automobiles", "XLY"),
+];
+
+fn lookup(table: &[(&str, &'static str)], name: Option<&str>) -> Option<&'static str> {
+    let name = name?.trim().to_lowercase();
+    if name.is_empty() {
+        return None;
+    }
+    table.iter().find(|(fragment, _)| name.contains(fragment)).map(|(_, etf)| *etf)
+}
+
+/// Pick the benchmark ETF for a sector and industry
+///
+/// An industry fund wins when one exists (SMH for semiconductors, KRE for
+/// regional banks), then the SPDR fund for the sector, then a sector guessed
+/// from the industry name, and finally `SPY`.
+pub fn sector_benchmark_etf(sector: Option<&str>, industry: Option<&str>) -> &'static str {
+    lookup(&INDUSTRY_ETFS, industry)
+        .or_else(|| lookup(&SECTOR_ETFS, sector))
+        .or_else(|| lookup(&SECTOR_ETFS, industry))
+        .unwrap_or(MARKET_BENCHMARK)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_industry_funds_take_precedence() {
+        assert_eq!(sector_benchmark_etf(Some("Technology"), Some("Semiconductors")), "SMH");
+        assert_eq!(sector_benchmark_etf(Some("Financial Services"), Some("Banks - Regional")), "KRE");
+        assert_eq!(sector_benchmark_etf(Some("Financial Services"), Some("Banks - Diversified")), "KBE");
+        assert_eq!(sector_benchmark_etf(Some("Healthcare"), Some("Biotechnology")), "XBI");
+        assert_eq!(sector_benchmark_etf(Some("Real Estate"), Some("REIT - Retail")), "XLRE");
+    }
+
+    #[test]
+    fn test_sector_fallbacks() {
+        assert_eq!(sector_benchmark_etf(Some("Technology"), Some("Consumer Electronics")), "XLK");
+        assert_eq!(sector_benchmark_etf(Some("Consumer Defensive"), None), "XLP");
+        assert_eq!(sector_benchmark_etf(Some("Communication Services"), Some("Internet Content & Information")), "XLC");
+        // Finnhub reports only an industry
+        assert_eq!(sector_benchmark_etf(None, Some("Utilities")), "XLU");
+        assert_eq!(sector_benchmark_etf(None, None), MARKET_BENCHMARK);
+        assert_eq!(SectorClassification::default().benchmark_etf(), MARKET_BENCHMARK);
+    }
+}
@@ -1,5 +1,6 @@
 // Helper models and functions for converting sentiment analysis to database records
 
+use crate::models::QSSComponents;
 use chrono::Utc;
 
 /// Helper function to create sentiment record from QSSResult with API tracking
@@ -9,6 +10,8 @@ pub fn create_sentiment_record_with_tracking(
     api_urls: buenotea_infrastructure::sentiment_models::ApiUrls,
     gpt_explanation: String,
 ) -> buenotea_infrastructure::sentiment_models::CreateSentimentRecord {
+    let revisions = result.meta.estimate_revisions.as_ref();
+    let surprise = result.meta.earnings_surprise.as_ref();
     buenotea_infrastructure::sentiment_models::CreateSentimentRecord {
         symbol: result.symbol.clone(),
         analysis_date: result.timestamp,
@@ -19,10 +22,14 @@ pub fn create_sentiment_record_with_tracking(
         relative_strength_score: result.components.relative_strength,
         short_interest_score: result.components.short_interest,
         options_flow_score: result.components.options_flow,
-        earnings_weight: 0.40,
-        relative_strength_weight: 0.30,
-        short_interest_weight: 0.20,
-        options_flow_weight: 0.10,
+        news_score: result.components.news,
+        pead_score: result.components.pead,
+        earnings_weight: QSSComponents::EARNINGS_REVISIONS_WEIGHT,
+        relative_strength_weight: QSSComponents::RELATIVE_STRENGTH_WEIGHT,
+        short_interest_weight: QSSComponents::SHORT_INTEREST_WEIGHT,
+        options_flow_weight: QSSComponents::OPTIONS_FLOW_WEIGHT,
+        news_weight: QSSComponents::NEWS_WEIGHT,
+        pead_weight: QSSComponents::PEAD_WEIGHT,
         earnings_api_url: api_urls.earnings_api_url,
         earnings_api_source: api_urls.earnings_api_source,
         earnings_data_available: api_urls.earnings_data_available,
@@ -44,14 +51,20 @@ pub fn create_sentiment_record_with_tracking(
         data_points_count: result.meta.data_points_count as i32,
         trend_direction: result.meta.trend_direction,
         data_freshness_score: result.meta.data_freshness,
-        warning_flags: result.flags.clone(),
-        missing_data_components: vec![], // Could be calculated from flags
+        warning_flags: result.flags.iter().map(|flag| flag.code().to_string()).collect(),
+        missing_data_components: result
+            .flags
+            .iter()
+            .filter_map(|flag| flag.missing_component())
+            .map(str::to_string)
+            .collect(),
         gpt_explanation,
         gpt_explanation_timestamp: Some(Utc::now()),
         rsi_14: result.meta.rsi_14,
         rsi_source: result.meta.rsi_source,
         market_benchmark_return: result.meta.market_benchmark_return,
         sector_benchmark_return: result.meta.sector_benchmark_return,
+        sector_benchmark: result.meta.sector_benchmark,
         relative_to_market: result.meta.relative_to_market,
         relative_to_sector: result.meta.relative_to_sector,
         current_eps_estimate: result.meta.current_eps_estimate,
@@ -61,12 +74,36 @@ pub fn create_sentiment_record_with_tracking(
         previous_revenue_estimate: result.meta.previous_revenue_estimate,
         revenue_change_percentage: result.meta.revenue_change_percentage,
         analyst_count: result.meta.analyst_count,
+        estimate_dispersion: result.meta.estimate_dispersion,
+        eps_revision_7d: revisions.and_then(|r| r.eps_change_7d),
+        eps_revision_30d: revisions.and_then(|r| r.eps_change_30d),
+        eps_revision_90d: revisions.and_then(|r| r.eps_change_90d),
+        revenue_revision_30d: revisions.and_then(|r| r.revenue_change_30d),
+        estimate_revisions_up: revisions.map(|r| r.revisions_up as i32),
+        estimate_revisions_down: revisions.map(|r| r.revisions_down as i32),
+        standardized_unexpected_earnings: surprise.and_then(|s| s.sue),
+        eps_surprise_percent: surprise.and_then(|s| s.latest_surprise_percent()),
+        earnings_abnormal_return: surprise.and_then(|s| s.latest()).and_then(|q| q.abnormal_return_3d),
+        days_since_earnings: surprise.map(|s| s.days_since_report as i32),
+        earnings_surprises: surprise.and_then(|s| serde_json::to_value(&s.quarters).ok()),
         current_price: result.meta.current_price,
         price_15d_ago: result.meta.price_15d_ago,
         price_30d_ago: result.meta.price_30d_ago,
         return_15d: result.meta.return_15d,
         return_30d: result.meta.return_30d,
         volume_ratio: result.meta.volume_ratio,
+        short_volume_ratio: result.meta.short_volume_ratio,
+        short_volume_zscore: result.meta.short_volume_zscore,
+        days_to_cover: result.meta.days_to_cover,
+        put_call_ratio: result.meta.put_call_ratio,
+        options_premium_skew: result.meta.options_premium_skew,
+        unusual_options_contracts: result.meta.unusual_options_contracts.map(|n| n as i32),
+        news_sentiment_score: result.meta.news_sentiment,
+        news_article_count: result.meta.news_article_count.map(|n| n as i32),
+        news_articles: (!result.meta.news_articles.is_empty())
+            .then(|| serde_json::to_value(&result.meta.news_articles).ok())
+            .flatten(),
+        news_llm_score: result.meta.news_llm_sentiment,
     }
 }
 
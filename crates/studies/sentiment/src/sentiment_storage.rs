@@ -2,6 +2,8 @@
 // Uses time-series approach to track sentiment (QSS) over time
 
 use buenotea_core::Result;
+use chrono::NaiveDate;
+use std::collections::BTreeSet;
 use buenotea_infrastructure::DatabaseClient;
 use buenotea_infrastructure::sentiment_models::{CreateSentimentRecord, SentimentInsert, SentimentRecord};
 use tracing::info;
@@ -109,6 +111,12 @@ impl SentimentStorage {
         Ok(ids)
     }
 
+    /// Dates between `from` and `to` that already have a sentiment record for a symbol
+    pub async fn get_analysis_dates(&self, symbol: &str, from: NaiveDate, to: NaiveDate) -> Result<BTreeSet<NaiveDate>> {
+        info!("Getting stored sentiment dates for {} from {} to {}", symbol, from, to);
+        self.db_client.analysis_dates("sentiment_history", Some(symbol), from, to).await
+    }
+
     /// Get the latest sentiment record for a specific symbol (from sentiment view)
     pub async fn get_latest_sentiment(&self, symbol: &str) -> Result<Option<SentimentRecord>> {
         info!("Getting latest sentiment record for {}", symbol);
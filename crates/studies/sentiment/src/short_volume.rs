@@ -0,0 +1,276 @@
+// FINRA daily short-sale volume
+// Loads the consolidated Reg SHO short volume files and derives the short_interest component
+
+use super::models::{ShortInterestData, ShortVolumeMetrics};
+use buenotea_core::{Error, Result};
+use chrono::{Datelike, Duration, NaiveDate, TimeZone, Utc, Weekday};
+use reqwest::Client;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+const SOURCE_NAME: &str = "FINRA";
+
+/// Public location of the daily files (`CNMSshvol20240102.txt`)
+pub const FINRA_SHORT_VOLUME_URL: &str = "https://cdn.finra.org/equity/regsho/daily";
+
+/// Sessions of short volume ratios behind the z-score
+pub const ZSCORE_SESSIONS: usize = 20;
+
+/// Fewest prior sessions needed for a z-score
+const MIN_ZSCORE_SESSIONS: usize = 10;
+
+/// Calendar days searched for `ZSCORE_SESSIONS + 1` files (covers holiday weeks)
+const LOOKBACK_DAYS: i64 = 45;
+
+/// Parsed files kept in memory; a one-year backfill plus its lookback fits
+const MAX_CACHED_FILES: usize = 320;
+
+/// Typical short volume ratio for S&P 500 names; roughly half of TRF volume is short
+const NEUTRAL_SHORT_RATIO: f64 = 0.45;
+
+/// Ratio distance from neutral that scores ±1
+const SHORT_RATIO_RANGE: f64 = 0.15;
+
+/// Where the daily short volume files come from
+#[derive(Debug, Clone)]
+pub enum ShortVolumeSource {
+    /// Base URL the `CNMSshvolYYYYMMDD.txt` file names are appended to
+    Url(String),
+    /// Directory holding downloaded `CNMSshvolYYYYMMDD.txt` files
+    Directory(PathBuf),
+}
+
+/// Daily short volume of one symbol, keyed by symbol for one file
+type ShortVolumeFile = HashMap<String, ShortInterestData>;
+
+/// Reader for FINRA consolidated NMS short sale volume files
+pub struct FinraShortVolume {
+    client: Client,
+    source: ShortVolumeSource,
+    /// Parsed files by trade date; missing days are cached as empty files
+    files: Mutex<BTreeMap<NaiveDate, Arc<ShortVolumeFile>>>,
+}
+
+impl FinraShortVolume {
+    pub fn new(source: ShortVolumeSource) -> Self {
+        Self {
+            client: Client::new(),
+            source,
+            files: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Create from FINRA_SHORT_VOLUME_DIR, else FINRA_SHORT_VOLUME_URL, else the FINRA CDN
+    pub fn from_env() -> Self {
+        if let Ok(dir) = std::env::var("FINRA_SHORT_VOLUME_DIR") {
+            return Self::new(ShortVolumeSource::Directory(PathBuf::from(dir)));
+        }
+        let url = std::env::var("FINRA_SHORT_VOLUME_URL").unwrap_or_else(|_| FINRA_SHORT_VOLUME_URL.to_string());
+        Self::new(ShortVolumeSource::Url(url.trim_end_matches('/').to_string()))
+    }
+
+    /// Location of the file for `date`, used for logging and tracking
+    pub fn location(&self, date: NaiveDate) -> String {
+        let file = file_name(date);
+        match &self.source {
+            ShortVolumeSource::Url(base) => format!("{}/{}", base, file),
+            ShortVolumeSource::Directory(dir) => dir.join(file).display().to_string(),
+        }
+    }
+
+    /// Daily short volume for `symbol` on trading days up to `as_of`, newest first
+    ///
+    /// Returns at most `sessions` days found within the lookback window.
+    pub async fn history(&self, symbol: &str, sessions: usize, as_of: NaiveDate) -> Result<Vec<ShortInterestData>> {
+        let symbol = symbol.to_uppercase();
+        let mut history = Vec::new();
+        let mut day = as_of;
+        while history.len() < sessions && as_of - day <= Duration::days(LOOKBACK_DAYS) {
+            if !matches!(day.weekday(), Weekday::Sat | Weekday::Sun) {
+                if let Some(data) = self.file(day).await?.get(&symbol) {
+                    history.push(data.clone());
+                }
+            }
+            day -= Duration::days(1);
+        }
+        Ok(history)
+    }
+
+    async fn file(&self, date: NaiveDate) -> Result<Arc<ShortVolumeFile>> {
+        if let Some(file) = self.files.lock().await.get(&date) {
+            return Ok(file.clone());
+        }
+
+        let file = match self.read(date).await? {
+            Some(text) => Arc::new(parse_short_volume_file(&text)),
+            // Holidays have no file; today's file is published after the close, so don't cache its absence
+            None if date >= Utc::now().date_naive() => return Ok(Arc::new(HashMap::new())),
+            None => Arc::new(HashMap::new()),
+        };
+
+        let mut files = self.files.lock().await;
+        files.insert(date, file.clone());
+        while files.len() > MAX_CACHED_FILES {
+            files.pop_first();
+        }
+        Ok(file)
+    }
+
+    /// Raw file text, `None` when no file exists for the day
+    async fn read(&self, date: NaiveDate) -> Result<Option<String>> {
+        match &self.source {
+            ShortVolumeSource::Directory(dir) => {
+                let path = dir.join(file_name(date));
+                if !path.exists() {
+                    return Ok(None);
+                }
+                let text = tokio::fs::read_to_string(&path).await.map_err(|e| {
+                    Error::ApiError(SOURCE_NAME.to_string(), format!("Cannot read {}: {}", path.display(), e))
+                })?;
+                Ok(Some(text))
+            }
+            ShortVolumeSource::Url(_) => {
+                let url = self.location(date);
+                let response = self.client.get(&url).send().await?;
+                let status = response.status();
+                if status.as_u16() == 429 {
+                    return Err(Error::RateLimitExceeded(SOURCE_NAME.to_string()));
+                }
+                // The CDN answers 403 rather than 404 for days without a file
+                if matches!(status.as_u16(), 403 | 404) {
+                    return Ok(None);
+                }
+                if !status.is_success() {
+                    warn!("FINRA short volume request failed: HTTP {} for {}", status, url);
+                    return Err(Error::ApiError(SOURCE_NAME.to_string(), format!("HTTP {} for {}", status, url)));
+                }
+                Ok(Some(response.text().await?))
+            }
+        }
+    }
+}
+
+fn file_name(date: NaiveDate) -> String {
+    format!("CNMSshvol{}.txt", date.format("%Y%m%d"))
+}
+
+/// Parse a pipe-delimited FINRA file: `Date|Symbol|ShortVolume|ShortExemptVolume|TotalVolume|Market`
+///
+/// The header and the trailing record-count line are skipped. Recent files
+/// report fractional share volumes, which are rounded.
+pub fn parse_short_volume_file(text: &str) -> ShortVolumeFile {
+    let volume = |field: &str| field.trim().parse::<f64>().ok().filter(|v| *v >= 0.0).map(|v| v.round() as u64);
+
+    text.lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('|').collect();
+            if fields.len() < 5 {
+                return None;
+            }
+            let date = NaiveDate::parse_from_str(fields[0].trim(), "%Y%m%d").ok()?;
+            let timestamp = Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0)?);
+            let data = ShortInterestData::from_volumes(timestamp, volume(fields[2])?, volume(fields[4])?);
+            Some((fields[1].trim().to_uppercase(), data))
+        })
+        .collect()
+}
+
+/// Short volume ratio, its z-score against the prior sessions and days to cover
+///
+/// `history` is newest first. Days to cover divides the short volume of the
+/// window by `average_volume` (consolidated volume from daily bars), falling
+/// back to the average FINRA total volume when bars are unavailable.
+pub fn short_volume_metrics(history: &[ShortInterestData], average_volume: Option<f64>) -> Option<ShortVolumeMetrics> {
+    let latest = history.first().filter(|d| d.total_volume > 0)?;
+
+    let prior: Vec<f64> = history
+        .iter()
+        .skip(1)
+        .take(ZSCORE_SESSIONS)
+        .filter(|d| d.total_volume > 0)
+        .map(|d| d.short_ratio)
+        .collect();
+    let short_volume_zscore = if prior.len() >= MIN_ZSCORE_SESSIONS {
+        let mean = prior.iter().sum::<f64>() / prior.len() as f64;
+        let variance = prior.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (prior.len() - 1) as f64;
+        let std_dev = variance.sqrt();
+        (std_dev > 0.0).then(|| (latest.short_ratio - mean) / std_dev)
+    } else {
+        None
+    };
+
+    let window = &history[..history.len().min(ZSCORE_SESSIONS)];
+    let short_volume: u64 = window.iter().map(|d| d.short_volume).sum();
+    let average_volume = average_volume
+        .filter(|v| *v > 0.0)
+        .unwrap_or_else(|| window.iter().map(|d| d.total_volume as f64).sum::<f64>() / window.len() as f64);
+    let days_to_cover = (average_volume > 0.0).then(|| short_volume as f64 / average_volume);
+
+    Some(ShortVolumeMetrics {
+        short_volume_ratio: latest.short_ratio,
+        short_volume_zscore,
+        days_to_cover,
+        sessions: history.len(),
+    })
+}
+
+/// Score the short_interest component between -1 and +1
+///
+/// Rising or unusually high short selling is bearish: the z-score (±2 maps to
+/// ∓1) and the ratio's distance from a neutral 45% are averaged. Days to cover
+/// is reported but not scored, as a crowded short is also squeeze fuel.
+pub fn short_interest_score(metrics: &ShortVolumeMetrics) -> f64 {
+    let level = -((metrics.short_volume_ratio - NEUTRAL_SHORT_RATIO) / SHORT_RATIO_RANGE).clamp(-1.0, 1.0);
+    match metrics.short_volume_zscore {
+        Some(z) => (level + (-z / 2.0).clamp(-1.0, 1.0)) / 2.0,
+        None => level,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(short_volume: u64, total_volume: u64) -> ShortInterestData {
+        ShortInterestData::from_volumes(Utc::now(), short_volume, total_volume)
+    }
+
+    #[test]
+    fn test_parse_short_volume_file() {
+        let text = "Date|Symbol|ShortVolume|ShortExemptVolume|TotalVolume|Market\n\
+                    20240102|AAPL|4000000.5|12000|9000000|B,Q,N\n\
+                    20240102|brk.b|100|0|400|Q,N\n\
+                    20240102|BAD|x|0|400|Q\n\
+                    8954\n";
+        let file = parse_short_volume_file(text);
+        assert_eq!(file.len(), 2);
+        let aapl = &file["AAPL"];
+        assert_eq!(aapl.short_volume, 4_000_001);
+        assert_eq!(aapl.total_volume, 9_000_000);
+        assert_eq!(aapl.date.date_naive(), NaiveDate::from_ymd_opt(2024, 1, 2).unwrap());
+        assert_eq!(file["BRK.B"].short_ratio, 0.25);
+    }
+
+    #[test]
+    fn test_metrics_and_score() {
+        // Latest day spikes to 70% short after alternating 40%/50% sessions
+        let mut history = vec![day(700, 1000)];
+        history.extend((0..20).map(|i| if i % 2 == 0 { day(400, 1000) } else { day(500, 1000) }));
+
+        let metrics = short_volume_metrics(&history, Some(2000.0)).unwrap();
+        assert_eq!(metrics.short_volume_ratio, 0.7);
+        assert!(metrics.short_volume_zscore.unwrap() > 2.0);
+        // 20 sessions of short volume over 2000 shares a day
+        assert_eq!(metrics.days_to_cover, Some((700 + 10 * 400 + 9 * 500) as f64 / 2000.0));
+        assert_eq!(short_interest_score(&metrics), -1.0);
+
+        // Too little history for a z-score scores the level alone
+        let short = short_volume_metrics(&history[1..5], None).unwrap();
+        assert_eq!(short.short_volume_zscore, None);
+        assert!((short_interest_score(&short) - 1.0 / 3.0).abs() < 1e-12);
+        assert!(short_volume_metrics(&[], None).is_none());
+    }
+}
@@ -4,15 +4,24 @@
 use buenotea_core::Result;
 use super::models::*;
 use super::indicators::*;
-use chrono::Utc;
+use super::config::TTSConfig;
+use chrono::{DateTime, NaiveDate, Utc};
 use std::time::Instant;
 use tracing::{info, warn};
-use reqwest::Client;
-use serde_json::Value;
+use buenotea_core::market_data::{
+    as_of_timestamp, earnings_calendar_from_env, EarningsCalendar, EarningsCalendarProvider, MarketDataProvider,
+    ProviderChain,
+};
+use std::sync::Arc;
+
+/// Calendar days ahead of an earnings report during which risk is widened
+pub const EARNINGS_RISK_DAYS: i64 = 7;
 
 /// Main TTS calculator that combines technical indicators
 pub struct TTSCalculator {
-    client: Client,
+    provider: Arc<dyn MarketDataProvider>,
+    earnings_calendar: Option<Arc<dyn EarningsCalendarProvider>>,
+    config: TTSConfig,
     raw_api_responses: std::collections::HashMap<String, serde_json::Value>,
     api_endpoints_used: Vec<String>,
     primary_api_source: String,
@@ -20,10 +29,17 @@ pub struct TTSCalculator {
 }
 
 impl TTSCalculator {
-    /// Create a new TTS calculator
+    /// Create a new TTS calculator using the providers configured in the environment
     pub fn new() -> Self {
+        Self::with_provider(Arc::new(ProviderChain::from_env()))
+    }
+
+    /// Create a TTS calculator backed by a specific market data provider
+    pub fn with_provider(provider: Arc<dyn MarketDataProvider>) -> Self {
         Self {
-            client: Client::new(),
+            provider,
+            earnings_calendar: earnings_calendar_from_env(),
+            config: TTSConfig::default(),
             raw_api_responses: std::collections::HashMap::new(),
             api_endpoints_used: Vec::new(),
             primary_api_source: String::new(),
@@ -31,8 +47,40 @@ impl TTSCalculator {
         }
     }
 
+    /// Read report dates from a specific earnings calendar instead of the environment
+    pub fn with_earnings_calendar(mut self, earnings_calendar: Arc<dyn EarningsCalendarProvider>) -> Self {
+        self.earnings_calendar = Some(earnings_calendar);
+        self
+    }
+
+    /// Use a custom scoring configuration (weights, periods and thresholds)
+    ///
+    /// The configuration is validated first, so zero periods or all-zero weights
+    /// are rejected instead of producing NaN scores under a `config_hash`.
+    pub fn try_with_config(mut self, config: TTSConfig) -> Result<Self> {
+        config.validate()?;
+        self.config = config;
+        Ok(self)
+    }
+
+    /// Scoring configuration used by this calculator
+    pub fn config(&self) -> &TTSConfig {
+        &self.config
+    }
+
     /// Calculate TTS score for a given stock symbol with API tracking
     pub async fn calculate_tts_with_tracking(&mut self, symbol: &str) -> Result<(TTSResult, TTSApiTracking)> {
+        self.calculate_with_tracking(symbol, None).await
+    }
+
+    /// Calculate TTS score using only bars dated on or before `as_of`
+    ///
+    /// The result is stamped with `as_of`, so it can be stored as history without lookahead.
+    pub async fn calculate_tts_as_of(&mut self, symbol: &str, as_of: NaiveDate) -> Result<(TTSResult, TTSApiTracking)> {
+        self.calculate_with_tracking(symbol, Some(as_of)).await
+    }
+
+    async fn calculate_with_tracking(&mut self, symbol: &str, as_of: Option<NaiveDate>) -> Result<(TTSResult, TTSApiTracking)> {
         let start_time = Instant::now();
         info!("Starting TTS calculation for {}", symbol);
 
@@ -43,16 +91,58 @@ impl TTSCalculator {
         self.fallback_api_source = None;
 
         // Collect price data from multiple sources
-        let price_data = self.collect_price_data(symbol).await?;
+        let price_data = self.collect_price_data(symbol, as_of).await?;
+        
+        let earnings = self.collect_earnings_calendar(symbol).await;
+        
+        let as_of_time = as_of.map(as_of_timestamp).unwrap_or_else(Utc::now);
+        let result = self.score_prices_with_earnings(symbol, &price_data, as_of_time, earnings.as_ref())?;
+        
+        let computation_time = start_time.elapsed().as_millis() as u64;
+
+        info!("TTS calculation completed for {} in {}ms", symbol, computation_time);
+        
+        // Create API tracking information
+        let api_tracking = TTSApiTracking {
+            primary_api_source: self.primary_api_source.clone(),
+            fallback_api_source: self.fallback_api_source.clone(),
+            api_endpoints_used: self.api_endpoints_used.clone(),
+            raw_api_responses: if self.raw_api_responses.is_empty() { None } else { Some(self.raw_api_responses.clone()) },
+            price_data_points: price_data.len() as i32,
+            analysis_period_days: 30, // Approximate
+            current_price: price_data.last().map(|p| p.close).unwrap_or(0.0),
+        };
         
+        Ok((result, api_tracking))
+    }
+
+    /// Score a caller-supplied price series without touching the network
+    ///
+    /// Bars dated after `as_of` are ignored, so the same series can be replayed
+    /// day by day. The result is stamped with `as_of`.
+    pub fn score_prices(&self, symbol: &str, prices: &[PricePoint], as_of: DateTime<Utc>) -> Result<TTSResult> {
+        self.score_prices_with_earnings(symbol, prices, as_of, None)
+    }
+
+    /// Score a caller-supplied price series, widening risk ahead of the next earnings report
+    pub fn score_prices_with_earnings(
+        &self,
+        symbol: &str,
+        prices: &[PricePoint],
+        as_of: DateTime<Utc>,
+        earnings: Option<&EarningsCalendar>,
+    ) -> Result<TTSResult> {
+        let mut price_data: Vec<PricePoint> = prices.iter().filter(|p| p.date <= as_of).cloned().collect();
+        price_data.sort_by_key(|p| p.date);
+
         if price_data.is_empty() {
             return Err(buenotea_core::Error::ValidationError { 
-                message: format!("No price data available for {}", symbol) 
+                message: format!("No price data available for {} as of {}", symbol, as_of.format("%Y-%m-%d")) 
             });
         }
 
         // Calculate technical indicators
-        let indicators = calculate_all_indicators(&price_data)?;
+        let indicators = calculate_indicators_with_periods(&price_data, &self.config.periods)?;
         
         // Calculate individual indicator scores
         let indicator_scores = self.calculate_indicator_scores(&price_data, &indicators)?;
@@ -67,7 +157,8 @@ impl TTSCalculator {
         let volume_analysis = self.calculate_volume_analysis(&price_data)?;
         
         // Calculate risk assessment
-        let risk_assessment = self.calculate_risk_assessment(&price_data, &indicators)?;
+        let days_to_earnings = earnings.and_then(|calendar| calendar.days_until_next(as_of.date_naive()));
+        let risk_assessment = self.calculate_risk_assessment(&price_data, &indicators, days_to_earnings)?;
         
         // Calculate final TTS score
         let tts_score = self.calculate_final_tts_score(&indicator_scores, &trend_analysis)?;
@@ -79,11 +170,12 @@ impl TTSCalculator {
         let confidence_score = self.calculate_confidence_score(&price_data, &indicators);
         
         // Generate flags
-        let flags = self.generate_flags(&price_data, &indicators);
-        
-        let computation_time = start_time.elapsed().as_millis() as u64;
+        let mut flags = self.generate_flags(&price_data, &indicators);
+        if let Some(days) = days_to_earnings.filter(|days| *days <= EARNINGS_RISK_DAYS) {
+            flags.push(format!("Earnings report in {} days", days));
+        }
 
-        let result = TTSResult {
+        Ok(TTSResult {
             symbol: symbol.to_string(),
             tts_score,
             trading_signal,
@@ -92,150 +184,58 @@ impl TTSCalculator {
             support_resistance,
             volume_analysis,
             risk_assessment,
-            timestamp: Utc::now(),
+            timestamp: as_of,
             confidence_score,
             flags,
-        };
-
-        info!("TTS calculation completed for {} in {}ms", symbol, computation_time);
-        
-        // Create API tracking information
-        let api_tracking = TTSApiTracking {
-            primary_api_source: self.primary_api_source.clone(),
-            fallback_api_source: self.fallback_api_source.clone(),
-            api_endpoints_used: self.api_endpoints_used.clone(),
-            raw_api_responses: if self.raw_api_responses.is_empty() { None } else { Some(self.raw_api_responses.clone()) },
-            price_data_points: price_data.len() as i32,
-            analysis_period_days: 30, // Approximate
-            current_price: price_data.last().map(|p| p.close).unwrap_or(0.0),
-        };
-        
-        Ok((result, api_tracking))
+            config_hash: self.config.config_hash(),
+        })
     }
 
     /// Calculate TTS score for a given stock symbol (backward compatibility)
     pub async fn calculate_tts(&self, symbol: &str) -> Result<TTSResult> {
-        let mut calculator = TTSCalculator::new();
+        let mut calculator = TTSCalculator {
+            earnings_calendar: self.earnings_calendar.clone(),
+            config: self.config.clone(),
+            ..TTSCalculator::with_provider(self.provider.clone())
+        };
         let (result, _) = calculator.calculate_tts_with_tracking(symbol).await?;
         Ok(result)
     }
 
-    /// Collect price data from multiple API sources
-    async fn collect_price_data(&mut self, symbol: &str) -> Result<Vec<PricePoint>> {
-        let mut price_data = Vec::new();
-
-        // Try FMP first (better data quality)
-        if let Ok(data) = self.fetch_fmp_price_data(symbol).await {
-            price_data = data;
-            self.primary_api_source = "FMP".to_string();
-            info!("Collected {} price points from FMP for {}", price_data.len(), symbol);
-        } else {
-            // Fallback to Alpha Vantage
-            if let Ok(data) = self.fetch_alpha_vantage_price_data(symbol).await {
-                price_data = data;
-                self.primary_api_source = "Alpha Vantage".to_string();
-                info!("Collected {} price points from Alpha Vantage for {}", price_data.len(), symbol);
-            } else {
-                warn!("Failed to collect price data from both FMP and Alpha Vantage for {}", symbol);
+    /// Past and scheduled earnings reports, `None` when no calendar is configured or it fails
+    async fn collect_earnings_calendar(&mut self, symbol: &str) -> Option<EarningsCalendar> {
+        let provider = self.earnings_calendar.as_ref()?;
+        match provider.earnings_calendar(symbol).await {
+            Ok(response) => {
+                self.api_endpoints_used.push(response.url);
+                Some(response.data)
             }
-        }
-
-        Ok(price_data)
-    }
-
-    /// Fetch price data from FMP
-    async fn fetch_fmp_price_data(&mut self, symbol: &str) -> Result<Vec<PricePoint>> {
-        let api_key = std::env::var("FMP_API_KEY")
-            .map_err(|_| buenotea_core::Error::MissingApiKey("FMP".to_string()))?;
-
-        let url = format!(
-            "https://financialmodelingprep.com/api/v3/historical-price-full/{}?apikey={}",
-            symbol, api_key
-        );
-
-        let response = self.client.get(&url).send().await?;
-        let json: Value = response.json().await?;
-        
-        // Store raw API response
-        self.api_endpoints_used.push(url.clone());
-        self.raw_api_responses.insert("FMP".to_string(), json.clone());
-
-        let mut price_points = Vec::new();
-        
-        if let Some(historical_data) = json["historical"].as_array() {
-            for item in historical_data.iter().take(200) { // Limit to 200 days
-                if let (Some(date_str), Some(open), Some(high), Some(low), Some(close), Some(volume)) = (
-                    item["date"].as_str(),
-                    item["open"].as_f64(),
-                    item["high"].as_f64(),
-                    item["low"].as_f64(),
-                    item["close"].as_f64(),
-                    item["volume"].as_u64(),
-                ) {
-                    if let Ok(date) = chrono::DateTime::parse_from_rfc3339(&format!("{}T00:00:00Z", date_str)) {
-                        price_points.push(PricePoint {
-                            date: date.with_timezone(&Utc),
-                            open,
-                            high,
-                            low,
-                            close,
-                            volume,
-                        });
-                    }
-                }
+            Err(e) => {
+                warn!("Failed to collect earnings calendar for {}: {}", symbol, e);
+                None
             }
         }
+    }
 
-        // Sort by date (oldest first)
-        price_points.sort_by_key(|p| p.date);
-        Ok(price_points)
-    }
-
-    /// Fetch price data from Alpha Vantage
-    async fn fetch_alpha_vantage_price_data(&mut self, symbol: &str) -> Result<Vec<PricePoint>> {
-        let api_key = std::env::var("ALPHA_VANTAGE_API_KEY")
-            .map_err(|_| buenotea_core::Error::MissingApiKey("Alpha Vantage".to_string()))?;
-
-        let url = format!(
-            "https://www.alphavantage.co/query?function=TIME_SERIES_DAILY&symbol={}&apikey={}&outputsize=full",
-            symbol, api_key
-        );
-
-        let response = self.client.get(&url).send().await?;
-        let json: Value = response.json().await?;
-        
-        // Store raw API response
-        self.api_endpoints_used.push(url.clone());
-        self.raw_api_responses.insert("Alpha Vantage".to_string(), json.clone());
-
-        let mut price_points = Vec::new();
-        
-        if let Some(time_series) = json["Time Series (Daily)"].as_object() {
-            for (date_str, data) in time_series.iter().take(200) { // Limit to 200 days
-                if let (Some(open), Some(high), Some(low), Some(close), Some(volume)) = (
-                    data["1. open"].as_str().and_then(|s| s.parse::<f64>().ok()),
-                    data["2. high"].as_str().and_then(|s| s.parse::<f64>().ok()),
-                    data["3. low"].as_str().and_then(|s| s.parse::<f64>().ok()),
-                    data["4. close"].as_str().and_then(|s| s.parse::<f64>().ok()),
-                    data["5. volume"].as_str().and_then(|s| s.parse::<u64>().ok()),
-                ) {
-                    if let Ok(date) = chrono::DateTime::parse_from_rfc3339(&format!("{}T00:00:00Z", date_str)) {
-                        price_points.push(PricePoint {
-                            date: date.with_timezone(&Utc),
-                            open,
-                            high,
-                            low,
-                            close,
-                            volume,
-                        });
-                    }
-                }
+    /// Collect price data through the market data provider (FMP first, Alpha Vantage fallback)
+    async fn collect_price_data(&mut self, symbol: &str, as_of: Option<NaiveDate>) -> Result<Vec<PricePoint>> {
+        let response = match as_of {
+            Some(date) => self.provider.daily_bars_as_of(symbol, 200, date).await,
+            None => self.provider.daily_bars(symbol, 200).await,
+        };
+        match response {
+            Ok(response) => {
+                self.primary_api_source = response.source.clone();
+                self.api_endpoints_used.push(response.url);
+                self.raw_api_responses.insert(response.source.clone(), response.raw);
+                info!("Collected {} price points from {} for {}", response.data.len(), response.source, symbol);
+                Ok(response.data.iter().map(PricePoint::from).collect())
+            }
+            Err(e) => {
+                warn!("Failed to collect price data for {}: {}", symbol, e);
+                Ok(Vec::new())
             }
         }
-
-        // Sort by date (oldest first)
-        price_points.sort_by_key(|p| p.date);
-        Ok(price_points)
     }
 
     /// Calculate scores for individual technical indicators using -1.0 to +1.0 scale
@@ -253,7 +253,13 @@ impl TTSCalculator {
 
         // MACD Score (-1.0 to +1.0)
         let macd_score = if let (Some(macd), Some(signal)) = (indicators.macd, indicators.macd_signal) {
-            score_macd(macd, signal)
+            score_macd(
+                macd,
+                signal,
+                current_price,
+                indicators.macd_histogram_slope.unwrap_or(0.0),
+                indicators.macd_crossover,
+            )
         } else {
             0.0 // Neutral when no data
         };
@@ -276,7 +282,7 @@ impl TTSCalculator {
 
         // Stochastic Score (-1.0 to +1.0)
         let stochastic_score = if let (Some(k), Some(d)) = (indicators.stochastic_k, indicators.stochastic_d) {
-            score_stochastic(k, d)
+            score_stochastic(k, d, indicators.stochastic_crossover)
         } else {
             0.0 // Neutral when no data
         };
@@ -453,7 +459,7 @@ impl TTSCalculator {
             });
         }
 
-        let recent_data = &price_data[price_data.len() - 50..]; // Use last 50 days
+        let recent_data = &price_data[price_data.len().saturating_sub(50)..]; // Use last 50 days
         
         // Find support (recent lows)
         let support_level = recent_data.iter().map(|p| p.low).fold(f64::INFINITY, f64::min);
@@ -530,7 +536,7 @@ impl TTSCalculator {
         };
 
         // Determine volume-price relationship
-        let vp_relationship = if price_data.len() >= 5 {
+        let vp_relationship = if price_data.len() >= 10 {
             let recent_prices = &price_data[price_data.len() - 5..];
             let recent_volumes = &price_data[price_data.len() - 5..];
             
@@ -559,7 +565,15 @@ impl TTSCalculator {
     }
 
     /// Calculate risk assessment
-    fn calculate_risk_assessment(&self, price_data: &[PricePoint], indicators: &IndicatorValues) -> Result<RiskAssessment> {
+    ///
+    /// Within `EARNINGS_RISK_DAYS` of a report the risk level is raised one step
+    /// and the stop loss widened to absorb the earnings gap.
+    fn calculate_risk_assessment(
+        &self,
+        price_data: &[PricePoint],
+        indicators: &IndicatorValues,
+        days_to_earnings: Option<i64>,
+    ) -> Result<RiskAssessment> {
         let current_price = price_data.last().map(|p| p.close).unwrap_or(0.0);
         
         // Calculate volatility score
@@ -581,6 +595,8 @@ impl TTSCalculator {
             50.0
         };
 
+        let earnings_ahead = days_to_earnings.is_some_and(|days| days <= EARNINGS_RISK_DAYS);
+
         // Determine risk level
         let risk_level = if volatility_score >= 75.0 {
             RiskLevel::VeryHigh
@@ -591,15 +607,17 @@ impl TTSCalculator {
         } else {
             RiskLevel::Low
         };
+        let risk_level = if earnings_ahead { risk_level.raised() } else { risk_level };
 
         // Calculate maximum drawdown risk
         let max_drawdown_risk = self.calculate_max_drawdown_risk(price_data)?;
 
-        // Calculate stop loss recommendation
+        // Calculate stop loss recommendation, wider ahead of earnings
+        let (atr_multiple, fallback_stop) = if earnings_ahead { (3.0, 0.12) } else { (2.0, 0.08) };
         let stop_loss = if let Some(atr) = indicators.atr_14 {
-            current_price - (atr * 2.0) // 2x ATR stop loss
+            current_price - (atr * atr_multiple) // 2x ATR stop loss (3x ahead of earnings)
         } else {
-            current_price * 0.92 // 8% stop loss as fallback
+            current_price * (1.0 - fallback_stop) // 8% stop loss as fallback (12% ahead of earnings)
         };
 
         // Calculate risk-reward ratio
@@ -611,6 +629,7 @@ impl TTSCalculator {
             max_drawdown_risk,
             stop_loss,
             risk_reward_ratio,
+            days_to_earnings,
         })
     }
 
@@ -658,40 +677,14 @@ impl TTSCalculator {
     }
 
     /// Calculate final TTS score from indicator scores and trend analysis (-1.0 to +1.0)
+    /// Component and indicator weights come from the calculator's `TTSConfig`
     fn calculate_final_tts_score(&self, indicators: &TTSIndicators, trend_analysis: &TrendAnalysis) -> Result<f64> {
-        // Weight the different components
-        let mut weighted_score = 0.0;
-
-        // Technical indicators (70% weight)
-        let indicators_avg = (
-            indicators.rsi_score +
-            indicators.macd_score +
-            indicators.bollinger_score +
-            indicators.ma_score +
-            indicators.stochastic_score +
-            indicators.williams_score +
-            indicators.atr_score +
-            indicators.volume_score
-        ) / 8.0;
-        
-        weighted_score += indicators_avg * 0.7;
-
-        // Trend analysis (30% weight)
-        let trend_score = (
-            trend_analysis.short_term.score() +
-            trend_analysis.medium_term.score() +
-            trend_analysis.long_term.score()
-        ) / 3.0;
-        
-        weighted_score += trend_score * 0.3;
-
-        // Ensure score is within bounds
-        Ok(weighted_score.max(-1.0).min(1.0))
+        Ok(self.config.final_score(indicators, trend_analysis))
     }
 
     /// Generate trading signal from TTS score (-1.0 to +1.0)
     fn generate_trading_signal(&self, tts_score: f64) -> TTSSignal {
-        TTSSignal::from_score(tts_score)
+        self.config.signal(tts_score)
     }
 
     /// Calculate confidence score
@@ -763,3 +756,129 @@ impl Default for TTSCalculator {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use buenotea_core::market_data::ProviderChain;
+    use chrono::{Duration, TimeZone};
+
+    fn offline_calculator() -> TTSCalculator {
+        TTSCalculator::with_provider(Arc::new(ProviderChain::default()))
+    }
+
+    fn series(days: usize, start: f64, daily_change: f64) -> Vec<PricePoint> {
+        let first_day = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        (0..days)
+            .map(|i| {
+                // Small oscillation so ranges and volumes are not degenerate
+                let close = start * (1.0 + daily_change).powi(i as i32) + if i % 2 == 0 { 0.3 } else { -0.3 };
+                PricePoint {
+                    date: first_day + Duration::days(i as i64),
+                    open: close - 0.2,
+                    high: close + 1.0,
+                    low: close - 1.0,
+                    close,
+                    volume: 1_000_000 + (i as u64 % 5) * 50_000,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_score_prices_uptrend_is_bullish() {
+        let prices = series(120, 100.0, 0.01);
+        let as_of = prices.last().unwrap().date;
+        let result = offline_calculator().score_prices("TEST", &prices, as_of).unwrap();
+
+        assert!(result.tts_score > 0.0);
+        assert_eq!(result.timestamp, as_of);
+        assert_eq!(result.trading_signal, TTSSignal::from_score(result.tts_score));
+        assert!(result.risk_assessment.stop_loss < prices.last().unwrap().close);
+    }
+
+    #[test]
+    fn test_score_prices_ignores_bars_after_as_of() {
+        let calculator = offline_calculator();
+        let prices = series(120, 100.0, -0.01);
+        let as_of = prices[59].date;
+
+        let replayed = calculator.score_prices("TEST", &prices, as_of).unwrap();
+        let truncated = calculator.score_prices("TEST", &prices[..60], as_of).unwrap();
+
+        assert_eq!(replayed.tts_score, truncated.tts_score);
+        assert_eq!(replayed.support_resistance.support_level, truncated.support_resistance.support_level);
+    }
+
+    #[test]
+    fn test_score_prices_short_and_empty_series() {
+        let calculator = offline_calculator();
+        let prices = series(30, 50.0, 0.0);
+        assert!(calculator.score_prices("TEST", &prices, prices.last().unwrap().date).is_ok());
+
+        let before_history = prices[0].date - Duration::days(1);
+        assert!(calculator.score_prices("TEST", &prices, before_history).is_err());
+    }
+
+    #[test]
+    fn test_invalid_config_rejected() {
+        let mut config = TTSConfig::default();
+        config.periods.rsi = 0;
+        assert!(offline_calculator().try_with_config(config).is_err());
+    }
+
+    #[test]
+    fn test_score_prices_uses_config() {
+        let prices = series(220, 100.0, 0.004);
+        let as_of = prices.last().unwrap().date;
+        let default_result = offline_calculator().score_prices("TEST", &prices, as_of).unwrap();
+
+        let config = TTSConfig {
+            indicator_weight: 0.0,
+            ..TTSConfig::default()
+        };
+        let result = offline_calculator()
+            .try_with_config(config.clone())
+            .unwrap()
+            .score_prices("TEST", &prices, as_of)
+            .unwrap();
+
+        // Trend-only scoring ignores the indicator component entirely
+        assert!((result.tts_score - config.trend_score(&result.trend_analysis)).abs() < 1e-12);
+        assert!(result.tts_score > 0.0);
+        assert_eq!(result.config_hash, config.config_hash());
+        assert_ne!(result.config_hash, default_result.config_hash);
+    }
+
+    #[test]
+    fn test_score_prices_widens_risk_ahead_of_earnings() {
+        use buenotea_core::market_data::EarningsReport;
+
+        let prices = series(120, 100.0, 0.01);
+        let as_of = prices.last().unwrap().date;
+        let report = |days: i64| EarningsReport {
+            date: as_of.date_naive() + Duration::days(days),
+            time: None,
+            fiscal_period_end: None,
+            eps_actual: None,
+            eps_estimate: Some(1.0),
+            revenue_actual: None,
+            revenue_estimate: None,
+        };
+        let calculator = offline_calculator();
+        let plain = calculator.score_prices("TEST", &prices, as_of).unwrap();
+
+        let soon = EarningsCalendar::new("TEST", vec![report(3)]);
+        let result = calculator.score_prices_with_earnings("TEST", &prices, as_of, Some(&soon)).unwrap();
+        assert_eq!(result.risk_assessment.days_to_earnings, Some(3));
+        assert_eq!(result.risk_assessment.risk_level, plain.risk_assessment.risk_level.raised());
+        assert!(result.risk_assessment.stop_loss < plain.risk_assessment.stop_loss);
+        assert!(result.flags.iter().any(|flag| flag == "Earnings report in 3 days"));
+        assert_eq!(result.tts_score, plain.tts_score);
+
+        let later = EarningsCalendar::new("TEST", vec![report(40)]);
+        let result = calculator.score_prices_with_earnings("TEST", &prices, as_of, Some(&later)).unwrap();
+        assert_eq!(result.risk_assessment.days_to_earnings, Some(40));
+        assert_eq!(result.risk_assessment.stop_loss, plain.risk_assessment.stop_loss);
+    }
+}
@@ -0,0 +1,358 @@
+// TTS scoring configuration
+// Weights, indicator periods and signal thresholds, loadable from TOML or JSON
+
+use buenotea_core::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::models::{TTSIndicators, TTSSignal, TrendAnalysis};
+
+/// Complete TTS scoring methodology
+///
+/// The default reproduces the original scoring: an equal-weight average of the
+/// eight indicator scores blended 70/30 with the average trend score.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TTSConfig {
+    /// Weight of the indicator component in the final score
+    pub indicator_weight: f64,
+    /// Weight of the trend component in the final score
+    pub trend_weight: f64,
+    /// Relative weights of the individual indicator scores
+    pub indicator_weights: IndicatorWeights,
+    /// Relative weights of the short/medium/long trend scores
+    pub trend_weights: TrendWeights,
+    /// Lookback periods used when calculating indicators
+    pub periods: IndicatorPeriods,
+    /// Score thresholds used to derive the trading signal
+    pub thresholds: SignalThresholds,
+}
+
+/// Relative weights of the eight indicator scores (normalized by their sum)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IndicatorWeights {
+    pub rsi: f64,
+    pub macd: f64,
+    pub bollinger: f64,
+    pub moving_averages: f64,
+    pub stochastic: f64,
+    pub williams_r: f64,
+    pub atr: f64,
+    pub volume: f64,
+}
+
+/// Relative weights of the trend horizons (normalized by their sum)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrendWeights {
+    pub short_term: f64,
+    pub medium_term: f64,
+    pub long_term: f64,
+}
+
+/// Indicator lookback periods, in trading sessions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IndicatorPeriods {
+    pub rsi: usize,
+    pub macd_fast: usize,
+    pub macd_slow: usize,
+    pub macd_signal: usize,
+    pub bollinger: usize,
+    pub bollinger_std_dev: f64,
+    pub sma_short: usize,
+    pub sma_medium: usize,
+    pub sma_long: usize,
+    pub stochastic_k: usize,
+    pub stochastic_d: usize,
+    pub williams_r: usize,
+    pub atr: usize,
+}
+
+/// Lower bounds of each signal band on the -1.0 to +1.0 scale
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SignalThresholds {
+    pub strong_buy: f64,
+    pub buy: f64,
+    pub neutral: f64,
+    pub sell: f64,
+}
+
+impl Default for TTSConfig {
+    fn default() -> Self {
+        Self {
+            indicator_weight: 0.7,
+            trend_weight: 0.3,
+            indicator_weights: IndicatorWeights::default(),
+            trend_weights: TrendWeights::default(),
+            periods: IndicatorPeriods::default(),
+            thresholds: SignalThresholds::default(),
+        }
+    }
+}
+
+impl Default for IndicatorWeights {
+    fn default() -> Self {
+        Self {
+            rsi: 1.0,
+            macd: 1.0,
+            bollinger: 1.0,
+            moving_averages: 1.0,
+            stochastic: 1.0,
+            williams_r: 1.0,
+            atr: 1.0,
+            volume: 1.0,
+        }
+    }
+}
+
+impl Default for TrendWeights {
+    fn default() -> Self {
+        Self {
+            short_term: 1.0,
+            medium_term: 1.0,
+            long_term: 1.0,
+        }
+    }
+}
+
+impl Default for IndicatorPeriods {
+    fn default() -> Self {
+        Self {
+            rsi: 14,
+            macd_fast: 12,
+            macd_slow: 26,
+            macd_signal: 9,
+            bollinger: 20,
+            bollinger_std_dev: 2.0,
+            sma_short: 20,
+            sma_medium: 50,
+            sma_long: 200,
+            stochastic_k: 14,
+            stochastic_d: 3,
+            williams_r: 14,
+            atr: 14,
+        }
+    }
+}
+
+impl Default for SignalThresholds {
+    fn default() -> Self {
+        Self {
+            strong_buy: 0.6,
+            buy: 0.2,
+            neutral: -0.2,
+            sell: -0.6,
+        }
+    }
+}
+
+impl TTSConfig {
+    /// Parse a configuration from TOML; missing fields keep their defaults
+    pub fn from_toml_str(content: &str) -> Result<Self> {
+        let config: Self = toml::from_str(content).map_err(|e| Error::ValidationError {
+            message: format!("Invalid TTS config TOML: {}", e),
+        })?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Parse a configuration from JSON; missing fields keep their defaults
+    pub fn from_json_str(content: &str) -> Result<Self> {
+        let config: Self = serde_json::from_str(content)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Load a configuration file, choosing the format from its extension
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| Error::ValidationError {
+            message: format!("Failed to read TTS config {}: {}", path.display(), e),
+        })?;
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::from_json_str(&content),
+            _ => Self::from_toml_str(&content),
+        }
+    }
+
+    /// Check that weights, periods and thresholds are usable
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |message: &str| Err(Error::ValidationError { message: message.to_string() });
+
+        let w = &self.indicator_weights;
+        let indicator_weights = [w.rsi, w.macd, w.bollinger, w.moving_averages, w.stochastic, w.williams_r, w.atr, w.volume];
+        let t = &self.trend_weights;
+        let trend_weights = [t.short_term, t.medium_term, t.long_term];
+        let component_weights = [self.indicator_weight, self.trend_weight];
+
+        if indicator_weights
+            .iter()
+            .chain(&trend_weights)
+            .chain(&component_weights)
+            .any(|w| !w.is_finite() || *w < 0.0)
+        {
+            return invalid("TTS weights must be finite and non-negative");
+        }
+        if indicator_weights.iter().sum::<f64>() == 0.0 || trend_weights.iter().sum::<f64>() == 0.0 {
+            return invalid("TTS indicator and trend weights cannot all be zero");
+        }
+        if self.indicator_weight + self.trend_weight == 0.0 {
+            return invalid("TTS indicator_weight and trend_weight cannot both be zero");
+        }
+
+        let p = &self.periods;
+        if [p.rsi, p.macd_fast, p.macd_slow, p.macd_signal, p.bollinger, p.sma_short, p.sma_medium,
+            p.sma_long, p.stochastic_k, p.stochastic_d, p.williams_r, p.atr].contains(&0) {
+            return invalid("TTS indicator periods must be greater than zero");
+        }
+        if p.macd_fast >= p.macd_slow {
+            return invalid("TTS macd_fast period must be shorter than macd_slow");
+        }
+
+        let s = &self.thresholds;
+        if !(s.strong_buy >= s.buy && s.buy >= s.neutral && s.neutral >= s.sell) {
+            return invalid("TTS signal thresholds must be ordered strong_buy >= buy >= neutral >= sell");
+        }
+
+        Ok(())
+    }
+
+    /// Stable identifier of this methodology, stored with each timing record
+    ///
+    /// FNV-1a over the canonical JSON form, so the value does not change between
+    /// builds or Rust versions.
+    pub fn config_hash(&self) -> String {
+        let canonical = serde_json::to_string(self).unwrap_or_default();
+        let hash = canonical.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+        format!("{:016x}", hash)
+    }
+
+    /// Weighted average of the indicator scores
+    pub fn indicator_score(&self, indicators: &TTSIndicators) -> f64 {
+        let w = &self.indicator_weights;
+        weighted_average(&[
+            (indicators.rsi_score, w.rsi),
+            (indicators.macd_score, w.macd),
+            (indicators.bollinger_score, w.bollinger),
+            (indicators.ma_score, w.moving_averages),
+            (indicators.stochastic_score, w.stochastic),
+            (indicators.williams_score, w.williams_r),
+            (indicators.atr_score, w.atr),
+            (indicators.volume_score, w.volume),
+        ])
+    }
+
+    /// Weighted average of the trend horizon scores
+    pub fn trend_score(&self, trend_analysis: &TrendAnalysis) -> f64 {
+        let w = &self.trend_weights;
+        weighted_average(&[
+            (trend_analysis.short_term.score(), w.short_term),
+            (trend_analysis.medium_term.score(), w.medium_term),
+            (trend_analysis.long_term.score(), w.long_term),
+        ])
+    }
+
+    /// Final TTS score (-1.0 to +1.0) from the indicator and trend components
+    pub fn final_score(&self, indicators: &TTSIndicators, trend_analysis: &TrendAnalysis) -> f64 {
+        weighted_average(&[
+            (self.indicator_score(indicators), self.indicator_weight),
+            (self.trend_score(trend_analysis), self.trend_weight),
+        ])
+        .clamp(-1.0, 1.0)
+    }
+
+    /// Trading signal for a score using the configured thresholds
+    pub fn signal(&self, score: f64) -> TTSSignal {
+        TTSSignal::from_score_with_thresholds(score, &self.thresholds)
+    }
+}
+
+fn weighted_average(values: &[(f64, f64)]) -> f64 {
+    let total_weight: f64 = values.iter().map(|(_, weight)| weight).sum();
+    if total_weight == 0.0 {
+        return 0.0;
+    }
+    values.iter().map(|(value, weight)| value * weight).sum::<f64>() / total_weight
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TrendDirection;
+
+    #[test]
+    fn test_partial_toml_keeps_defaults() {
+        let config = TTSConfig::from_toml_str(
+            r#"
+            indicator_weight = 0.5
+            trend_weight = 0.5
+
+            [indicator_weights]
+            macd = 2.0
+
+            [thresholds]
+            strong_buy = 0.7
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.indicator_weight, 0.5);
+        assert_eq!(config.indicator_weights.macd, 2.0);
+        assert_eq!(config.indicator_weights.rsi, 1.0);
+        assert_eq!(config.thresholds.strong_buy, 0.7);
+        assert_eq!(config.thresholds.buy, 0.2);
+        assert_eq!(config.periods, IndicatorPeriods::default());
+    }
+
+    #[test]
+    fn test_json_and_validation() {
+        let config = TTSConfig::from_json_str(r#"{"periods": {"rsi": 21}}"#).unwrap();
+        assert_eq!(config.periods.rsi, 21);
+
+        assert!(TTSConfig::from_json_str(r#"{"periods": {"macd_fast": 30}}"#).is_err());
+        assert!(TTSConfig::from_json_str(r#"{"thresholds": {"buy": 0.9}}"#).is_err());
+        assert!(TTSConfig::from_toml_str("indicator_weight = -1.0").is_err());
+    }
+
+    #[test]
+    fn test_config_hash_tracks_methodology() {
+        let default = TTSConfig::default();
+        assert_eq!(default.config_hash(), TTSConfig::default().config_hash());
+        assert_eq!(default.config_hash().len(), 16);
+
+        let mut tweaked = TTSConfig::default();
+        tweaked.indicator_weights.volume = 0.5;
+        assert_ne!(default.config_hash(), tweaked.config_hash());
+    }
+
+    #[test]
+    fn test_default_matches_equal_weighting() {
+        let indicators = TTSIndicators {
+            rsi_score: 0.8,
+            macd_score: 0.4,
+            bollinger_score: 0.0,
+            ma_score: 0.8,
+            stochastic_score: -0.4,
+            williams_score: 0.0,
+            atr_score: 0.0,
+            volume_score: 0.0,
+        };
+        let trend = TrendAnalysis {
+            short_term: TrendDirection::Bullish,
+            medium_term: TrendDirection::Neutral,
+            long_term: TrendDirection::StrongBullish,
+            strength: 50.0,
+            consistency: 50.0,
+        };
+
+        let expected_trend = (TrendDirection::Bullish.score() + TrendDirection::StrongBullish.score()) / 3.0;
+        let expected = 0.7 * (1.6 / 8.0) + 0.3 * expected_trend;
+        assert!((TTSConfig::default().final_score(&indicators, &trend) - expected).abs() < 1e-12);
+    }
+}
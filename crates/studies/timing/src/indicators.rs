@@ -1,7 +1,8 @@
 // Technical indicators calculations for TTS
 // All indicators now return scores between -1.0 (strong sell) and +1.0 (strong buy)
 
-use super::models::{PricePoint, IndicatorValues};
+use super::models::{PricePoint, IndicatorValues, Crossover};
+use super::config::IndicatorPeriods;
 use buenotea_core::Result;
 
 /// Calculate RSI (Relative Strength Index)
@@ -52,21 +53,22 @@ pub fn calculate_rsi(prices: &[f64], period: usize) -> Result<f64> {
 }
 
 /// Calculate MACD (Moving Average Convergence Divergence)
-pub fn calculate_macd(prices: &[f64], fast_period: usize, slow_period: usize, _signal_period: usize) -> Result<(f64, f64, f64)> {
+/// Returns the latest (MACD line, signal line, histogram)
+pub fn calculate_macd(prices: &[f64], fast_period: usize, slow_period: usize, signal_period: usize) -> Result<(f64, f64, f64)> {
     if prices.len() < slow_period {
         return Ok((0.0, 0.0, 0.0));
     }
 
-    let ema_fast = calculate_ema(prices, fast_period)?;
-    let ema_slow = calculate_ema(prices, slow_period)?;
-    
-    let macd_line = ema_fast - ema_slow;
-    
-    // For signal line, we need MACD values over time, but we'll approximate
-    let signal_line = macd_line * 0.9; // Simplified signal line
-    let histogram = macd_line - signal_line;
+    Ok(latest_macd(&macd_series(prices, fast_period, slow_period, signal_period)))
+}
+
+/// Latest (MACD line, signal line, histogram) of a MACD series, zero before the slow EMA warms up
+fn latest_macd(series: &MacdSeries) -> (f64, f64, f64) {
+    let macd_line = last_value(&series.macd).unwrap_or(0.0);
 
-    Ok((macd_line, signal_line, histogram))
+    // Until the signal EMA has warmed up the histogram is flat
+    let signal_line = last_value(&series.signal).unwrap_or(macd_line);
+    (macd_line, signal_line, macd_line - signal_line)
 }
 
 /// Calculate EMA (Exponential Moving Average)
@@ -80,14 +82,7 @@ pub fn calculate_ema(prices: &[f64], period: usize) -> Result<f64> {
         return Ok(prices.iter().sum::<f64>() / prices.len() as f64);
     }
 
-    let multiplier = 2.0 / (period + 1) as f64;
-    let mut ema = prices[..period].iter().sum::<f64>() / period as f64;
-
-    for &price in &prices[period..] {
-        ema = (price * multiplier) + (ema * (1.0 - multiplier));
-    }
-
-    Ok(ema)
+    Ok(last_value(&ema_series(prices, period)).unwrap_or(0.0))
 }
 
 /// Calculate SMA (Simple Moving Average)
@@ -107,43 +102,28 @@ pub fn calculate_bollinger_bands(prices: &[f64], period: usize, std_dev: f64) ->
         return Ok((*current_price, *current_price, *current_price));
     }
 
-    let sma = calculate_sma(prices, period)?;
-    let recent_prices = &prices[prices.len() - period..];
-    
-    let variance: f64 = recent_prices.iter()
-        .map(|&price| (price - sma).powi(2))
-        .sum::<f64>() / period as f64;
-    
-    let standard_deviation = variance.sqrt();
-    
-    let upper_band = sma + (std_dev * standard_deviation);
-    let lower_band = sma - (std_dev * standard_deviation);
-
-    Ok((upper_band, sma, lower_band))
+    let bands = bollinger_series(prices, period, std_dev);
+    match (last_value(&bands.upper), last_value(&bands.middle), last_value(&bands.lower)) {
+        (Some(upper), Some(middle), Some(lower)) => Ok((upper, middle, lower)),
+        _ => Ok((0.0, 0.0, 0.0)),
+    }
 }
 
 /// Calculate Stochastic Oscillator
-pub fn calculate_stochastic(price_points: &[PricePoint], k_period: usize, _d_period: usize) -> Result<(f64, f64)> {
+/// Returns the latest (%K, %D) where %D is the SMA of %K over `d_period`
+pub fn calculate_stochastic(price_points: &[PricePoint], k_period: usize, d_period: usize) -> Result<(f64, f64)> {
     if price_points.len() < k_period {
         return Ok((50.0, 50.0));
     }
 
-    let recent_prices = &price_points[price_points.len() - k_period..];
-    let current_close = recent_prices.last().unwrap().close;
-    
-    let highest_high = recent_prices.iter().map(|p| p.high).fold(0.0, f64::max);
-    let lowest_low = recent_prices.iter().map(|p| p.low).fold(f64::INFINITY, f64::min);
-    
-    let k_percent = if highest_high != lowest_low {
-        100.0 * (current_close - lowest_low) / (highest_high - lowest_low)
-    } else {
-        50.0
-    };
-
-    // Simplified D calculation (normally would be SMA of K values)
-    let d_percent = k_percent * 0.8; // Simplified
+    Ok(latest_stochastic(&stochastic_series(price_points, k_period, d_period)))
+}
 
-    Ok((k_percent, d_percent))
+/// Latest (%K, %D) of a stochastic series, neutral 50 before %K warms up
+fn latest_stochastic(series: &StochasticSeries) -> (f64, f64) {
+    let k_percent = last_value(&series.k).unwrap_or(50.0);
+    let d_percent = last_value(&series.d).unwrap_or(k_percent);
+    (k_percent, d_percent)
 }
 
 /// Calculate Williams %R
@@ -152,18 +132,7 @@ pub fn calculate_williams_r(price_points: &[PricePoint], period: usize) -> Resul
         return Ok(-50.0);
     }
 
-    let recent_prices = &price_points[price_points.len() - period..];
-    let current_close = recent_prices.last().unwrap().close;
-    
-    let highest_high = recent_prices.iter().map(|p| p.high).fold(0.0, f64::max);
-    let lowest_low = recent_prices.iter().map(|p| p.low).fold(f64::INFINITY, f64::min);
-    
-    if highest_high != lowest_low {
-        let williams_r = -100.0 * (highest_high - current_close) / (highest_high - lowest_low);
-        Ok(williams_r)
-    } else {
-        Ok(-50.0)
-    }
+    Ok(last_value(&williams_r_series(price_points, period)).unwrap_or(-50.0))
 }
 
 /// Calculate ATR (Average True Range)
@@ -172,23 +141,267 @@ pub fn calculate_atr(price_points: &[PricePoint], period: usize) -> Result<f64>
         return Ok(0.0);
     }
 
-    let mut true_ranges = Vec::new();
-    
+    if let Some(atr) = last_value(&atr_series(price_points, period)) {
+        return Ok(atr);
+    }
+
+    // Not enough bars for a full window: average every true range we have
+    let true_ranges: Vec<f64> = price_points
+        .windows(2)
+        .map(|pair| pair[1].true_range(pair[0].close))
+        .collect();
+    Ok(true_ranges.iter().sum::<f64>() / true_ranges.len() as f64)
+}
+
+// ============================================================================
+// INDICATOR SERIES: one value per input bar, `None` until the indicator has
+// enough history. Index `i` always refers to the same bar as the input.
+// ============================================================================
+
+/// Aligned indicator series
+pub type Series = Vec<Option<f64>>;
+
+/// MACD line, signal line and histogram series
+#[derive(Debug, Clone)]
+pub struct MacdSeries {
+    pub macd: Series,
+    pub signal: Series,
+    pub histogram: Series,
+}
+
+/// Bollinger band series
+#[derive(Debug, Clone)]
+pub struct BollingerSeries {
+    pub upper: Series,
+    pub middle: Series,
+    pub lower: Series,
+}
+
+/// Stochastic %K and %D series
+#[derive(Debug, Clone)]
+pub struct StochasticSeries {
+    pub k: Series,
+    pub d: Series,
+}
+
+/// Every indicator series used by the TTS, aligned to the input bars
+#[derive(Debug, Clone)]
+pub struct IndicatorSeries {
+    pub sma_20: Series,
+    pub sma_50: Series,
+    pub sma_200: Series,
+    pub ema_12: Series,
+    pub ema_26: Series,
+    pub macd: MacdSeries,
+    pub bollinger: BollingerSeries,
+    pub stochastic: StochasticSeries,
+    pub williams_r: Series,
+    pub atr_14: Series,
+}
+
+/// Bars to look back when detecting crossovers and histogram slope
+const CROSSOVER_LOOKBACK: usize = 3;
+
+/// Latest defined value of a series
+pub fn last_value(series: &[Option<f64>]) -> Option<f64> {
+    series.last().copied().flatten()
+}
+
+/// Simple moving average series
+pub fn sma_series(values: &[f64], period: usize) -> Series {
+    let mut out = vec![None; values.len()];
+    if period == 0 {
+        return out;
+    }
+
+    let mut sum = 0.0;
+    for (i, value) in values.iter().enumerate() {
+        sum += value;
+        if i >= period {
+            sum -= values[i - period];
+        }
+        if i + 1 >= period {
+            out[i] = Some(sum / period as f64);
+        }
+    }
+    out
+}
+
+/// Exponential moving average series, seeded with the SMA of the first `period` values
+pub fn ema_series(values: &[f64], period: usize) -> Series {
+    let mut out = vec![None; values.len()];
+    if period == 0 || values.len() < period {
+        return out;
+    }
+
+    let multiplier = 2.0 / (period + 1) as f64;
+    let mut ema = values[..period].iter().sum::<f64>() / period as f64;
+    out[period - 1] = Some(ema);
+
+    for (i, &value) in values.iter().enumerate().skip(period) {
+        ema = (value * multiplier) + (ema * (1.0 - multiplier));
+        out[i] = Some(ema);
+    }
+    out
+}
+
+/// Apply a series function to the defined tail of another series (e.g. EMA of the MACD line)
+fn over_defined(series: &[Option<f64>], f: impl Fn(&[f64]) -> Series) -> Series {
+    let mut out = vec![None; series.len()];
+    if let Some(start) = series.iter().position(|v| v.is_some()) {
+        let values: Vec<f64> = series[start..].iter().map(|v| v.unwrap_or(0.0)).collect();
+        for (offset, value) in f(&values).into_iter().enumerate() {
+            out[start + offset] = value;
+        }
+    }
+    out
+}
+
+/// MACD series with a real signal line (EMA of the MACD line)
+pub fn macd_series(prices: &[f64], fast_period: usize, slow_period: usize, signal_period: usize) -> MacdSeries {
+    let fast = ema_series(prices, fast_period);
+    let slow = ema_series(prices, slow_period);
+
+    let macd: Series = fast
+        .iter()
+        .zip(&slow)
+        .map(|(f, s)| Some((*f)? - (*s)?))
+        .collect();
+    let signal = over_defined(&macd, |values| ema_series(values, signal_period));
+    let histogram = macd
+        .iter()
+        .zip(&signal)
+        .map(|(m, s)| Some((*m)? - (*s)?))
+        .collect();
+
+    MacdSeries { macd, signal, histogram }
+}
+
+/// Bollinger band series (population standard deviation)
+pub fn bollinger_series(prices: &[f64], period: usize, std_dev: f64) -> BollingerSeries {
+    let middle = sma_series(prices, period);
+    let mut upper = vec![None; prices.len()];
+    let mut lower = vec![None; prices.len()];
+
+    for (i, sma) in middle.iter().enumerate() {
+        if let Some(sma) = sma {
+            let window = &prices[i + 1 - period..=i];
+            let variance = window.iter().map(|&p| (p - sma).powi(2)).sum::<f64>() / period as f64;
+            let band = std_dev * variance.sqrt();
+            upper[i] = Some(sma + band);
+            lower[i] = Some(sma - band);
+        }
+    }
+
+    BollingerSeries { upper, middle, lower }
+}
+
+/// Highest high and lowest low over the window ending at `end`
+fn high_low(price_points: &[PricePoint], end: usize, period: usize) -> (f64, f64, f64) {
+    let window = &price_points[end + 1 - period..=end];
+    let highest_high = window.iter().map(|p| p.high).fold(f64::NEG_INFINITY, f64::max);
+    let lowest_low = window.iter().map(|p| p.low).fold(f64::INFINITY, f64::min);
+    (highest_high, lowest_low, price_points[end].close)
+}
+
+/// Stochastic %K series and %D (SMA of %K) series
+pub fn stochastic_series(price_points: &[PricePoint], k_period: usize, d_period: usize) -> StochasticSeries {
+    let mut k = vec![None; price_points.len()];
+    if k_period > 0 {
+        for (i, value) in k.iter_mut().enumerate().skip(k_period - 1) {
+            let (highest_high, lowest_low, close) = high_low(price_points, i, k_period);
+            *value = Some(if highest_high != lowest_low {
+                100.0 * (close - lowest_low) / (highest_high - lowest_low)
+            } else {
+                50.0
+            });
+        }
+    }
+    let d = over_defined(&k, |values| sma_series(values, d_period));
+
+    StochasticSeries { k, d }
+}
+
+/// Williams %R series
+pub fn williams_r_series(price_points: &[PricePoint], period: usize) -> Series {
+    let mut out = vec![None; price_points.len()];
+    if period == 0 {
+        return out;
+    }
+
+    for (i, value) in out.iter_mut().enumerate().skip(period - 1) {
+        let (highest_high, lowest_low, close) = high_low(price_points, i, period);
+        *value = Some(if highest_high != lowest_low {
+            -100.0 * (highest_high - close) / (highest_high - lowest_low)
+        } else {
+            -50.0
+        });
+    }
+    out
+}
+
+/// ATR series: mean true range over the last `period` bars
+pub fn atr_series(price_points: &[PricePoint], period: usize) -> Series {
+    let mut true_ranges = vec![None; price_points.len()];
     for i in 1..price_points.len() {
-        let tr = price_points[i].true_range(price_points[i - 1].close);
-        true_ranges.push(tr);
+        true_ranges[i] = Some(price_points[i].true_range(price_points[i - 1].close));
+    }
+    over_defined(&true_ranges, |values| sma_series(values, period))
+}
+
+/// Calculate every indicator series for a set of bars
+pub fn calculate_indicator_series(price_points: &[PricePoint], periods: &IndicatorPeriods) -> IndicatorSeries {
+    let closes: Vec<f64> = price_points.iter().map(|p| p.close).collect();
+
+    IndicatorSeries {
+        sma_20: sma_series(&closes, periods.sma_short),
+        sma_50: sma_series(&closes, periods.sma_medium),
+        sma_200: sma_series(&closes, periods.sma_long),
+        ema_12: ema_series(&closes, periods.macd_fast),
+        ema_26: ema_series(&closes, periods.macd_slow),
+        macd: macd_series(&closes, periods.macd_fast, periods.macd_slow, periods.macd_signal),
+        bollinger: bollinger_series(&closes, periods.bollinger, periods.bollinger_std_dev),
+        stochastic: stochastic_series(price_points, periods.stochastic_k, periods.stochastic_d),
+        williams_r: williams_r_series(price_points, periods.williams_r),
+        atr_14: atr_series(price_points, periods.atr),
     }
+}
 
-    if true_ranges.len() < period {
-        return Ok(true_ranges.iter().sum::<f64>() / true_ranges.len() as f64);
+/// Most recent crossover of `fast` over `slow` within the last `lookback` bars
+pub fn detect_crossover(fast: &[Option<f64>], slow: &[Option<f64>], lookback: usize) -> Option<Crossover> {
+    let diffs: Vec<Option<f64>> = fast.iter().zip(slow).map(|(f, s)| Some((*f)? - (*s)?)).collect();
+    let start = diffs.len().saturating_sub(lookback + 1);
+
+    for i in (start + 1..diffs.len()).rev() {
+        if let (Some(previous), Some(current)) = (diffs[i - 1], diffs[i]) {
+            if previous <= 0.0 && current > 0.0 {
+                return Some(Crossover::Bullish);
+            }
+            if previous >= 0.0 && current < 0.0 {
+                return Some(Crossover::Bearish);
+            }
+        }
     }
+    None
+}
 
-    let atr = true_ranges[true_ranges.len() - period..].iter().sum::<f64>() / period as f64;
-    Ok(atr)
+/// Average change per bar of a series over the last `lookback` bars
+pub fn series_slope(series: &[Option<f64>], lookback: usize) -> Option<f64> {
+    if lookback == 0 || series.len() <= lookback {
+        return None;
+    }
+    let current = series[series.len() - 1]?;
+    let earlier = series[series.len() - 1 - lookback]?;
+    Some((current - earlier) / lookback as f64)
 }
 
 /// Calculate all technical indicators for a series of price points
 pub fn calculate_all_indicators(price_points: &[PricePoint]) -> Result<IndicatorValues> {
+    calculate_indicators_with_periods(price_points, &IndicatorPeriods::default())
+}
+
+/// Calculate all technical indicators using custom lookback periods
+pub fn calculate_indicators_with_periods(price_points: &[PricePoint], periods: &IndicatorPeriods) -> Result<IndicatorValues> {
     if price_points.is_empty() {
         return Ok(IndicatorValues {
             rsi_14: None,
@@ -207,22 +420,26 @@ pub fn calculate_all_indicators(price_points: &[PricePoint]) -> Result<Indicator
             stochastic_d: None,
             williams_r: None,
             atr_14: None,
+            macd_histogram_slope: None,
+            macd_crossover: None,
+            stochastic_crossover: None,
         });
     }
 
     let closes: Vec<f64> = price_points.iter().map(|p| p.close).collect();
-
-    let rsi_14 = calculate_rsi(&closes, 14).ok();
-    let (macd, macd_signal, macd_histogram) = calculate_macd(&closes, 12, 26, 9).unwrap_or((0.0, 0.0, 0.0));
-    let (bb_upper, bb_middle, bb_lower) = calculate_bollinger_bands(&closes, 20, 2.0).unwrap_or((0.0, 0.0, 0.0));
-    let sma_20 = calculate_sma(&closes, 20).ok();
-    let sma_50 = calculate_sma(&closes, 50).ok();
-    let sma_200 = calculate_sma(&closes, 200).ok();
-    let ema_12 = calculate_ema(&closes, 12).ok();
-    let ema_26 = calculate_ema(&closes, 26).ok();
-    let (stoch_k, stoch_d) = calculate_stochastic(price_points, 14, 3).unwrap_or((50.0, 50.0));
-    let williams_r = calculate_williams_r(price_points, 14).ok();
-    let atr_14 = calculate_atr(price_points, 14).ok();
+    let series = calculate_indicator_series(price_points, periods);
+
+    let rsi_14 = calculate_rsi(&closes, periods.rsi).ok();
+    let (macd, macd_signal, macd_histogram) = latest_macd(&series.macd);
+    let (bb_upper, bb_middle, bb_lower) = calculate_bollinger_bands(&closes, periods.bollinger, periods.bollinger_std_dev).unwrap_or((0.0, 0.0, 0.0));
+    let sma_20 = calculate_sma(&closes, periods.sma_short).ok();
+    let sma_50 = calculate_sma(&closes, periods.sma_medium).ok();
+    let sma_200 = calculate_sma(&closes, periods.sma_long).ok();
+    let ema_12 = calculate_ema(&closes, periods.macd_fast).ok();
+    let ema_26 = calculate_ema(&closes, periods.macd_slow).ok();
+    let (stoch_k, stoch_d) = latest_stochastic(&series.stochastic);
+    let williams_r = calculate_williams_r(price_points, periods.williams_r).ok();
+    let atr_14 = calculate_atr(price_points, periods.atr).ok();
 
     Ok(IndicatorValues {
         rsi_14,
@@ -241,6 +458,9 @@ pub fn calculate_all_indicators(price_points: &[PricePoint]) -> Result<Indicator
         stochastic_d: Some(stoch_d),
         williams_r,
         atr_14,
+        macd_histogram_slope: series_slope(&series.macd.histogram, CROSSOVER_LOOKBACK),
+        macd_crossover: detect_crossover(&series.macd.macd, &series.macd.signal, CROSSOVER_LOOKBACK),
+        stochastic_crossover: detect_crossover(&series.stochastic.k, &series.stochastic.d, CROSSOVER_LOOKBACK),
     })
 }
 
@@ -320,6 +540,75 @@ mod tests {
         assert!(indicators.bollinger_upper.is_some());
         assert!(indicators.stochastic_k.is_some());
     }
+
+    fn create_wave_prices(len: usize) -> Vec<PricePoint> {
+        (0..len)
+            .map(|i| {
+                let close = 100.0 + (i as f64 / 6.0).sin() * 8.0 + i as f64 * 0.1;
+                PricePoint { date: Utc::now(), open: close - 0.5, high: close + 1.0, low: close - 1.0, close, volume: 1000000 }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_series_are_aligned() {
+        let price_points = create_wave_prices(60);
+        let series = calculate_indicator_series(&price_points, &IndicatorPeriods::default());
+
+        assert_eq!(series.macd.macd.len(), 60);
+        assert_eq!(series.stochastic.d.len(), 60);
+        assert!(series.macd.macd[24].is_none());
+        assert!(series.macd.macd[25].is_some());
+        // Signal needs 9 MACD values before it is defined
+        assert!(series.macd.signal[32].is_none());
+        assert!(series.macd.signal[33].is_some());
+        assert!(series.stochastic.k[13].is_some());
+        assert!(series.stochastic.d[14].is_none());
+        assert!(series.stochastic.d[15].is_some());
+    }
+
+    #[test]
+    fn test_macd_signal_is_ema_of_macd() {
+        let closes: Vec<f64> = create_wave_prices(80).iter().map(|p| p.close).collect();
+        let series = macd_series(&closes, 12, 26, 9);
+
+        let macd_values: Vec<f64> = series.macd.iter().flatten().copied().collect();
+        let expected_signal = calculate_ema(&macd_values, 9).unwrap();
+        let (macd, signal, histogram) = calculate_macd(&closes, 12, 26, 9).unwrap();
+
+        assert!((signal - expected_signal).abs() < 1e-9);
+        assert!((histogram - (macd - signal)).abs() < 1e-9);
+        assert!(histogram.abs() > 0.0);
+    }
+
+    #[test]
+    fn test_stochastic_d_is_sma_of_k() {
+        let price_points = create_wave_prices(40);
+        let series = stochastic_series(&price_points, 14, 3);
+        let k: Vec<f64> = series.k.iter().flatten().copied().collect();
+        let (_, d) = calculate_stochastic(&price_points, 14, 3).unwrap();
+
+        assert!((d - calculate_sma(&k, 3).unwrap()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_detect_crossover() {
+        let fast = vec![None, Some(1.0), Some(2.0), Some(4.0), Some(5.0)];
+        let slow = vec![None, Some(3.0), Some(3.0), Some(3.0), Some(3.0)];
+        assert_eq!(detect_crossover(&fast, &slow, 3), Some(Crossover::Bullish));
+        assert_eq!(detect_crossover(&slow, &fast, 3), Some(Crossover::Bearish));
+        // Crossover happened before the lookback window
+        assert_eq!(detect_crossover(&fast, &slow, 1), None);
+    }
+
+    #[test]
+    fn test_score_macd_rewards_bullish_momentum() {
+        let bullish = score_macd(1.0, 0.5, 100.0, 0.2, Some(Crossover::Bullish));
+        let bearish = score_macd(0.5, 1.0, 100.0, -0.2, Some(Crossover::Bearish));
+        assert!(bullish > 0.5);
+        assert!(bearish < -0.5);
+        assert_eq!(score_macd(1.0, 0.5, 0.0, 0.2, None), 0.0);
+    }
 }
 
 // ============================================================================
@@ -346,18 +635,24 @@ pub fn score_rsi(rsi: f64) -> f64 {
 }
 
 /// Convert MACD to -1.0 to +1.0 score
-/// Positive MACD = bullish (positive score)
-/// Negative MACD = bearish (negative score)
-pub fn score_macd(macd: f64, signal: f64) -> f64 {
-    let macd_diff = macd - signal;
-    
-    // Normalize MACD difference to -1.0 to +1.0
-    // This is a simplified approach - in practice you'd want to use historical volatility
-    match macd_diff {
-        d if d > 1.0 => 1.0,
-        d if d < -1.0 => -1.0,
-        _ => macd_diff
+/// Blends the MACD/signal spread (as % of price), the histogram slope and any
+/// recent signal-line crossover:
+/// MACD above a rising signal line = bullish (positive score)
+/// MACD below a falling signal line = bearish (negative score)
+pub fn score_macd(macd: f64, signal: f64, price: f64, histogram_slope: f64, crossover: Option<Crossover>) -> f64 {
+    if price <= 0.0 {
+        return 0.0;
     }
+
+    // A spread of 0.5% of price is treated as a full-strength reading
+    let spread_score = ((macd - signal) / price * 100.0 / 0.5).clamp(-1.0, 1.0);
+    
+    // Histogram growing by 0.1% of price per bar is full momentum
+    let slope_score = (histogram_slope / price * 100.0 / 0.1).clamp(-1.0, 1.0);
+    
+    let crossover_score = crossover.map(|c| c.score()).unwrap_or(0.0);
+
+    (0.4 * spread_score + 0.3 * slope_score + 0.3 * crossover_score).clamp(-1.0, 1.0)
 }
 
 /// Convert Bollinger Bands position to -1.0 to +1.0 score
@@ -397,10 +692,11 @@ pub fn score_moving_averages(price: f64, sma_20: f64, sma_50: f64, sma_200: f64)
 /// Convert Stochastic to -1.0 to +1.0 score
 /// %K > 80 = overbought (negative score)
 /// %K < 20 = oversold (positive score)
-pub fn score_stochastic(k: f64, d: f64) -> f64 {
+/// A recent %K/%D crossover adds to the score in its direction
+pub fn score_stochastic(k: f64, d: f64, crossover: Option<Crossover>) -> f64 {
     let avg_stoch = (k + d) / 2.0;
     
-    match avg_stoch {
+    let zone_score = match avg_stoch {
         s if s >= 80.0 => -((s - 80.0) / 20.0), // -1.0 at 100
         s if s <= 20.0 => (20.0 - s) / 20.0,    // +1.0 at 0
         _ => {
@@ -410,6 +706,11 @@ pub fn score_stochastic(k: f64, d: f64) -> f64 {
                 (50.0 - avg_stoch) / 30.0  // +1.0 at 20
             }
         }
+    };
+
+    match crossover {
+        Some(c) => (0.7 * zone_score + 0.3 * c.score()).clamp(-1.0, 1.0),
+        None => zone_score,
     }
 }
 
@@ -2,12 +2,14 @@
 // Provides comprehensive technical analysis for stock trading decisions
 
 pub mod calculator;
+pub mod config;
 pub mod models;
 pub mod indicators;
 pub mod timing_models;
 pub mod timing_storage;
 
 pub use calculator::TTSCalculator;
+pub use config::TTSConfig;
 pub use models::*;
 pub use timing_models::*;
 pub use timing_storage::TimingStorage;
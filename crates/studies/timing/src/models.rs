@@ -1,5 +1,7 @@
 // Data models for Technical Trading Score (TTS) calculations
 
+use buenotea_core::market_data::DailyBar;
+use crate::config::SignalThresholds;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -29,6 +31,9 @@ pub struct TTSResult {
     pub confidence_score: f64,
     /// Data quality flags
     pub flags: Vec<String>,
+    /// Hash of the `TTSConfig` used to produce this score
+    #[serde(default)]
+    pub config_hash: String,
 }
 
 /// TTS trading signal
@@ -49,11 +54,16 @@ pub enum TTSSignal {
 impl TTSSignal {
     /// Convert a TTS score (-1.0 to +1.0) to a trading signal
     pub fn from_score(score: f64) -> Self {
+        Self::from_score_with_thresholds(score, &SignalThresholds::default())
+    }
+
+    /// Create signal from TTS score using custom band thresholds
+    pub fn from_score_with_thresholds(score: f64, thresholds: &SignalThresholds) -> Self {
         match score {
-            s if s >= 0.6 => TTSSignal::StrongBuy,
-            s if s >= 0.2 => TTSSignal::Buy,
-            s if s >= -0.2 => TTSSignal::Neutral,
-            s if s >= -0.6 => TTSSignal::Sell,
+            s if s >= thresholds.strong_buy => TTSSignal::StrongBuy,
+            s if s >= thresholds.buy => TTSSignal::Buy,
+            s if s >= thresholds.neutral => TTSSignal::Neutral,
+            s if s >= thresholds.sell => TTSSignal::Sell,
             _ => TTSSignal::StrongSell,
         }
     }
@@ -211,6 +221,9 @@ pub struct RiskAssessment {
     pub stop_loss: f64,
     /// Risk-reward ratio
     pub risk_reward_ratio: f64,
+    /// Calendar days until the next earnings report, when the calendar is known
+    #[serde(default)]
+    pub days_to_earnings: Option<i64>,
 }
 
 /// Risk level
@@ -231,6 +244,15 @@ impl RiskLevel {
             RiskLevel::VeryHigh => 100.0,
         }
     }
+
+    /// One level higher, capped at `VeryHigh`
+    pub fn raised(&self) -> RiskLevel {
+        match self {
+            RiskLevel::Low => RiskLevel::Medium,
+            RiskLevel::Medium => RiskLevel::High,
+            RiskLevel::High | RiskLevel::VeryHigh => RiskLevel::VeryHigh,
+        }
+    }
 }
 
 /// Price data point for technical analysis
@@ -274,6 +296,19 @@ impl PricePoint {
     }
 }
 
+impl From<&DailyBar> for PricePoint {
+    fn from(bar: &DailyBar) -> Self {
+        Self {
+            date: bar.date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc(),
+            open: bar.open,
+            high: bar.high,
+            low: bar.low,
+            close: bar.close,
+            volume: bar.volume,
+        }
+    }
+}
+
 /// Technical indicator values
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IndicatorValues {
@@ -293,6 +328,30 @@ pub struct IndicatorValues {
     pub stochastic_d: Option<f64>,
     pub williams_r: Option<f64>,
     pub atr_14: Option<f64>,
+    /// Average change of the MACD histogram per bar over the last few bars
+    pub macd_histogram_slope: Option<f64>,
+    /// Most recent MACD / signal line crossover, if one happened recently
+    pub macd_crossover: Option<Crossover>,
+    /// Most recent %K / %D crossover, if one happened recently
+    pub stochastic_crossover: Option<Crossover>,
+}
+
+/// Direction of a line crossing its signal line
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum Crossover {
+    /// Fast line crossed above the slow line
+    Bullish,
+    /// Fast line crossed below the slow line
+    Bearish,
+}
+
+impl Crossover {
+    pub fn score(&self) -> f64 {
+        match self {
+            Crossover::Bullish => 1.0,
+            Crossover::Bearish => -1.0,
+        }
+    }
 }
 
 /// API tracking information for TTS calculations
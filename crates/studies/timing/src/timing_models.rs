@@ -73,5 +73,6 @@ pub fn create_timing_record_with_tracking(
         
         // Metadata
         flags: tts_result.flags,
+        config_hash: Some(tts_result.config_hash).filter(|hash| !hash.is_empty()),
     }
 }
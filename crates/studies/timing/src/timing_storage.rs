@@ -2,6 +2,8 @@
 // Uses time-series approach to track timing signals over time
 
 use buenotea_core::Result;
+use chrono::NaiveDate;
+use std::collections::BTreeSet;
 use buenotea_infrastructure::DatabaseClient;
 use buenotea_infrastructure::timing_models::{CreateTimingRecord, TimingInsert, TimingRecord};
 use tracing::info;
@@ -109,6 +111,12 @@ impl TimingStorage {
         Ok(ids)
     }
 
+    /// Dates between `from` and `to` that already have a timing record for a symbol
+    pub async fn get_analysis_dates(&self, symbol: &str, from: NaiveDate, to: NaiveDate) -> Result<BTreeSet<NaiveDate>> {
+        info!("Getting stored timing dates for {} from {} to {}", symbol, from, to);
+        self.db_client.analysis_dates("timing_history", Some(symbol), from, to).await
+    }
+
     /// Get the latest timing record for a symbol (from timing view)
     pub async fn get_latest_timing_record(&self, symbol: &str) -> Result<Option<TimingRecord>> {
         info!("Getting latest timing record for {}", symbol);